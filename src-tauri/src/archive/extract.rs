@@ -0,0 +1,298 @@
+/// 解压压缩包条目到本地目录
+///
+/// 按条目路径或通配符选择条目（`*`、`?` 不跨越 `/`，`**` 可跨越多级目录），选中目录时包含其全部子条目，
+/// 未指定任何模式时解压整个压缩包。条目从完整的条目索引中选择（不受分析条目数量上限限制），
+/// 再由格式处理器通过 `extract_entries_to_sink` 顺序解压一遍写入磁盘。
+///
+/// 写入前逐级检查目标路径：包含 `..`、盘符的条目路径（zip-slip）以及经过已有符号链接的路径都会被跳过，
+/// 压缩包中的符号链接、硬链接和设备文件不会被创建。
+use crate::archive::formats::common::{EntryEvent, EntrySink};
+use crate::archive::formats::{list_index, CompressionHandlerDispatcher};
+use crate::archive::limits::{self, OutputLimit};
+use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::io::AsyncWriteExt;
+
+/// 选中的文件条目
+struct SelectedFile {
+    relative_path: PathBuf,
+    size: u64,
+    modified_time: Option<SystemTime>,
+}
+
+/// 正在写入的文件
+struct OpenFile {
+    path: PathBuf,
+    file: tokio::fs::File,
+    modified_time: Option<SystemTime>,
+}
+
+/// 按选择模式解压条目到 `target_dir`，进度回调参数为（已写入字节数，选中条目的总字节数）
+#[allow(clippy::too_many_arguments)]
+pub async fn extract_entries_to_directory(
+    handler: &dyn CompressionHandlerDispatcher,
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    filename: &str,
+    patterns: &[String],
+    target_dir: &Path,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    password: Option<&str>,
+) -> Result<ExtractSummary, String> {
+    let mut summary = ExtractSummary::default();
+    let index = list_index::entry_index(handler, client.clone(), file_path, filename).await?;
+
+    let patterns: Vec<Vec<char>> = patterns.iter()
+        .map(|pattern| pattern.trim().trim_matches('/').chars().collect::<Vec<char>>())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+
+    // 先排除不会写入的条目，使总字节数与实际写入量一致
+    let mut directories = Vec::new();
+    let mut files: HashMap<String, SelectedFile> = HashMap::new();
+    for position in 0..index.len() {
+        let entry = index.entry(position);
+        if !patterns.is_empty() && !is_selected(&entry.path, &patterns) {
+            continue;
+        }
+
+        let skip_reason = match entry.metadata.get("entry_type").map(|t| t.as_str()) {
+            Some("symlink") | Some("hardlink") => Some("archive.extract.link"),
+            Some("char_device") | Some("block_device") | Some("fifo") => Some("archive.extract.special_file"),
+            _ => None,
+        };
+        let relative_path = match (skip_reason, safe_relative_path(&entry.path)) {
+            (None, Some(relative_path)) => relative_path,
+            (reason, _) => {
+                let reason = reason.unwrap_or("archive.extract.unsafe_path");
+                log::warn!("跳过条目 {}: {}", entry.path, reason);
+                summary.skipped.push(SkippedEntry { path: entry.path, reason: reason.to_string() });
+                continue;
+            }
+        };
+
+        let modified_time = entry.modified_time.as_deref()
+            .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+            .map(SystemTime::from);
+        if entry.is_dir {
+            directories.push((entry.path, relative_path, modified_time));
+        } else {
+            // 重复的路径只解压第一次出现的条目
+            files.entry(entry.path).or_insert(SelectedFile { relative_path, size: entry.size, modified_time });
+        }
+    }
+    let total_bytes: u64 = files.values().map(|file| file.size).sum();
+
+    // 按条目声明的大小预先检查：每个条目不超过单个解压流的上限，总输出不超过压缩比限制，
+    // 避免写满磁盘；实际解压时每个条目仍单独受限
+    limits::current_policy().check_entries((directories.len() + files.len()) as u64)?;
+    let archive_size = client.get_file_size(file_path).await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let output_limit = OutputLimit::for_compressed_size(archive_size);
+    for file in files.values() {
+        output_limit.check(file.size)?;
+    }
+    output_limit.check_ratio(total_bytes)?;
+
+    tokio::fs::create_dir_all(target_dir).await
+        .map_err(|e| format!("Failed to create target directory: {}", e))?;
+    let target_dir = tokio::fs::canonicalize(target_dir).await
+        .map_err(|e| format!("Failed to resolve target directory: {}", e))?;
+
+    log::debug!("开始解压 {} 个文件、{} 个目录到 {}", files.len(), directories.len(), target_dir.display());
+
+    let mut directory_times = Vec::new();
+    for (path, relative_path, modified_time) in directories {
+        if let Err(reason) = prepare_directories(&target_dir, &relative_path, true).await {
+            log::warn!("跳过条目 {}: {}", path, reason);
+            summary.skipped.push(SkippedEntry { path, reason });
+            continue;
+        }
+        summary.extracted_dirs += 1;
+        if let Some(time) = modified_time {
+            directory_times.push((target_dir.join(&relative_path), time));
+        }
+    }
+
+    let (sink, receiver) = EntrySink::new(files.keys().cloned());
+    let extract_task = handler.extract_entries_to_sink(client, file_path, sink, cancel_rx, password);
+
+    let mut current: Option<OpenFile> = None;
+    let mut written_bytes = 0u64;
+    let write_task = async {
+        // 接收端随写入任务一起结束，写入失败时解压端的发送随之失败
+        let mut receiver = receiver;
+        while let Some(event) = receiver.recv().await {
+            match event {
+                EntryEvent::Begin(path) => {
+                    let Some(selected) = files.get(&path) else {
+                        continue;
+                    };
+                    if let Err(reason) = prepare_directories(&target_dir, &selected.relative_path, false).await {
+                        log::warn!("跳过条目 {}: {}", path, reason);
+                        summary.skipped.push(SkippedEntry { path, reason });
+                        continue;
+                    }
+                    let output_path = target_dir.join(&selected.relative_path);
+                    let file = tokio::fs::File::create(&output_path).await
+                        .map_err(|e| format!("Failed to create file {}: {}", output_path.display(), e))?;
+                    current = Some(OpenFile { path: output_path, file, modified_time: selected.modified_time });
+                }
+                EntryEvent::Data(chunk) => {
+                    // 被跳过的条目的数据直接丢弃
+                    let Some(open) = current.as_mut() else {
+                        continue;
+                    };
+                    open.file.write_all(&chunk).await
+                        .map_err(|e| format!("Failed to write data: {}", e))?;
+                    written_bytes += chunk.len() as u64;
+                    if let Some(callback) = progress_callback {
+                        callback(written_bytes, total_bytes);
+                    }
+                }
+                EntryEvent::End => {
+                    let Some(mut open) = current.take() else {
+                        continue;
+                    };
+                    open.file.flush().await
+                        .map_err(|e| format!("Failed to flush file: {}", e))?;
+                    summary.extracted_files += 1;
+                    if let Some(time) = open.modified_time {
+                        let file = open.file.into_std().await;
+                        if let Err(e) = file.set_modified(time) {
+                            log::debug!("设置文件修改时间失败 {}: {}", open.path.display(), e);
+                        }
+                    }
+                }
+            }
+        }
+        Ok::<(), String>(())
+    };
+
+    // 解压失败时写入端在通道关闭后结束
+    let (extracted, written) = tokio::join!(extract_task, write_task);
+    if let Some(open) = current.take() {
+        // 删除部分写入的文件
+        drop(open.file);
+        let _ = tokio::fs::remove_file(&open.path).await;
+    }
+    written?;
+    extracted?;
+
+    // 目录的修改时间在写入子条目后才能设置，从最深的目录开始
+    directory_times.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+    for (path, time) in directory_times {
+        if let Err(e) = std::fs::File::open(&path).and_then(|dir| dir.set_modified(time)) {
+            log::debug!("设置目录修改时间失败 {}: {}", path.display(), e);
+        }
+    }
+
+    summary.total_bytes = written_bytes;
+    log::debug!(
+        "解压完成: {} 个文件, {} 个目录, {} 字节, 跳过 {} 个条目",
+        summary.extracted_files, summary.extracted_dirs, summary.total_bytes, summary.skipped.len()
+    );
+    Ok(summary)
+}
+
+/// 条目自身或其任一上级目录匹配某个选择模式
+fn is_selected(entry_path: &str, patterns: &[Vec<char>]) -> bool {
+    let path: Vec<char> = entry_path.trim_matches('/').chars().collect();
+    let prefix_ends = path.iter().enumerate()
+        .filter(|(_, &c)| c == '/')
+        .map(|(i, _)| i)
+        .chain(std::iter::once(path.len()));
+
+    for end in prefix_ends {
+        if patterns.iter().any(|pattern| glob_match(pattern, &path[..end])) {
+            return true;
+        }
+    }
+    false
+}
+
+/// 通配符匹配：`**` 匹配任意字符（`**/` 可匹配零层目录），`*` 和 `?` 不匹配 `/`
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            if rest.first() == Some(&'/') && glob_match(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('*') => {
+            for i in 0..=text.len() {
+                if glob_match(&pattern[1..], &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => text.first().is_some_and(|&c| c != '/') && glob_match(&pattern[1..], &text[1..]),
+        Some(&c) => text.first() == Some(&c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// 将条目路径转换为安全的相对路径；包含 `..` 或盘符（Windows）时返回 None
+fn safe_relative_path(entry_path: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in entry_path.split(['/', '\\']) {
+        match component {
+            // 开头的 `/` 按相对路径处理
+            "" | "." => continue,
+            ".." => return None,
+            component if cfg!(windows) && component.contains(':') => return None,
+            component => path.push(component),
+        }
+    }
+
+    if path.as_os_str().is_empty() {
+        None
+    } else {
+        Some(path)
+    }
+}
+
+/// 逐级创建条目所需的目录，拒绝经过符号链接或与已有文件冲突的路径
+async fn prepare_directories(target_dir: &Path, relative_path: &Path, is_dir: bool) -> Result<(), String> {
+    let components: Vec<_> = relative_path.components().collect();
+    let mut current = target_dir.to_path_buf();
+
+    for (i, component) in components.iter().enumerate() {
+        current.push(component);
+        let is_last = i + 1 == components.len();
+
+        match tokio::fs::symlink_metadata(&current).await {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err("archive.extract.symlink_in_path".to_string());
+            }
+            Ok(metadata) if metadata.is_dir() => {
+                if is_last && !is_dir {
+                    return Err("archive.extract.path_conflict".to_string());
+                }
+            }
+            Ok(_) => {
+                // 已有的普通文件会被条目覆盖，但不能作为目录使用
+                if !is_last || is_dir {
+                    return Err("archive.extract.path_conflict".to_string());
+                }
+            }
+            Err(_) if is_last && !is_dir => {}
+            Err(_) => {
+                tokio::fs::create_dir(&current).await
+                    .map_err(|e| format!("Failed to create directory {}: {}", current.display(), e))?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,231 @@
+/// BGZF（分块 gzip）随机访问
+///
+/// bgzip 生成的文件由一系列独立的 gzip 成员（块）组成，每块解压后不超过 64KB，块长度记录在头部 extra 字段中。
+/// 建立块索引后，读取任意解压偏移只需下载并解压覆盖该范围的几个块。
+/// 索引优先读取同目录下的 `.gzi` 文件，没有时顺序扫描块头部建立（只读取、不解压），并按文件缓存在内存中。
+use crate::archive::formats::common::*;
+use crate::archive::formats::gzip::GzipHandler;
+use crate::archive::limits::OutputLimit;
+use crate::storage::traits::{ProgressCallback, StorageClient};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// 最多缓存的块索引数量
+const MAX_CACHED_INDEXES: usize = 16;
+/// `.gzi` 中最后一个块之后的数据上限（最后一个数据块和 EOF 标记块），超过时认为索引与文件不匹配
+const MAX_GZI_TAIL_SIZE: u64 = 2 * 64 * 1024;
+
+type BgzfIndexCache = VecDeque<(String, Arc<BgzfIndex>)>;
+
+static BGZF_INDEX_CACHE: LazyLock<Mutex<BgzfIndexCache>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// BGZF 块的起始位置
+#[derive(Clone, Copy)]
+pub(crate) struct BgzfBlock {
+    pub compressed_offset: u64,
+    pub uncompressed_offset: u64,
+}
+
+/// BGZF 块索引
+pub(crate) struct BgzfIndex {
+    /// 按偏移排序的块，第一个块从 (0, 0) 开始
+    pub blocks: Vec<BgzfBlock>,
+    pub uncompressed_size: u64,
+}
+
+impl BgzfIndex {
+    /// 解析 bgzip 生成的 `.gzi` 文件：块数量（u64）后跟每个块（第一个块除外）的压缩偏移和解压偏移
+    fn from_gzi(data: &[u8]) -> Result<Self, String> {
+        let read_u64 = |offset: usize| -> Option<u64> {
+            data.get(offset..offset + 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        };
+
+        let count = read_u64(0).ok_or("Invalid GZI index: missing entry count")?;
+        let expected_size = count.checked_mul(16).and_then(|size| size.checked_add(8));
+        if expected_size != Some(data.len() as u64) {
+            return Err("Invalid GZI index: size does not match entry count".to_string());
+        }
+        let count = count as usize;
+
+        let mut blocks = vec![BgzfBlock { compressed_offset: 0, uncompressed_offset: 0 }];
+        for i in 0..count {
+            let block = BgzfBlock {
+                compressed_offset: read_u64(8 + i * 16).unwrap(),
+                uncompressed_offset: read_u64(16 + i * 16).unwrap(),
+            };
+            let previous = blocks[blocks.len() - 1];
+            if block.compressed_offset <= previous.compressed_offset || block.uncompressed_offset < previous.uncompressed_offset {
+                return Err("Invalid GZI index: offsets are not increasing".to_string());
+            }
+            blocks.push(block);
+        }
+
+        Ok(Self { blocks, uncompressed_size: 0 })
+    }
+
+    /// 包含 `offset` 的块以及覆盖 `[offset, offset + length)` 所需的块范围
+    fn block_range(&self, offset: u64, length: u64) -> (usize, usize) {
+        let first = self.blocks.partition_point(|block| block.uncompressed_offset <= offset).saturating_sub(1);
+        let end = self.blocks.partition_point(|block| block.uncompressed_offset < offset.saturating_add(length));
+        (first, end.max(first + 1))
+    }
+}
+
+fn get_cached_index(key: &str) -> Option<Arc<BgzfIndex>> {
+    let cache = BGZF_INDEX_CACHE.lock().ok()?;
+    cache.iter().find(|(cached_key, _)| cached_key == key).map(|(_, index)| index.clone())
+}
+
+fn cache_index(key: String, index: Arc<BgzfIndex>) {
+    let Ok(mut cache) = BGZF_INDEX_CACHE.lock() else {
+        return;
+    };
+
+    cache.retain(|(cached_key, _)| *cached_key != key);
+    log::debug!("缓存BGZF索引: {} ({} 个块, {} 字节)", key, index.blocks.len(), index.uncompressed_size);
+    cache.push_back((key, index));
+    while cache.len() > MAX_CACHED_INDEXES {
+        cache.pop_front();
+    }
+}
+
+/// 获取 BGZF 文件的块索引：缓存、`.gzi` 文件，最后退回到扫描块头部
+///
+/// 扫描块头部需要顺序读取整个文件，期间通过 `progress_callback` 报告读取进度并响应取消信号。
+pub(crate) async fn load_index(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    cache_key: String,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<Arc<BgzfIndex>, String> {
+    if let Some(index) = get_cached_index(&cache_key) {
+        return Ok(index);
+    }
+
+    let index = match load_gzi_index(client, file_path, file_size).await {
+        Ok(Some(index)) => index,
+        Ok(None) => scan_index(client, file_path, file_size, progress_callback, cancel_rx).await?,
+        Err(e) => {
+            log::warn!("GZI索引不可用，改为扫描BGZF块: {}", e);
+            scan_index(client, file_path, file_size, progress_callback, cancel_rx).await?
+        }
+    };
+
+    let index = Arc::new(index);
+    cache_index(cache_key, index.clone());
+    Ok(index)
+}
+
+/// 读取同目录下的 `.gzi` 索引；文件不存在时返回 None
+async fn load_gzi_index(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+) -> Result<Option<BgzfIndex>, String> {
+    let gzi_path = format!("{}.gzi", file_path);
+    let Ok(gzi_size) = client.get_file_size(&gzi_path).await else {
+        return Ok(None);
+    };
+
+    log::debug!("读取GZI索引: {} ({} 字节)", gzi_path, gzi_size);
+    let data = client.read_file_range(&gzi_path, 0, gzi_size).await
+        .map_err(|e| format!("Failed to read GZI index: {}", e))?;
+    let mut index = BgzfIndex::from_gzi(&data)?;
+
+    // `.gzi` 不记录总大小，扫描最后一个块之后的数据补齐
+    let last = index.blocks[index.blocks.len() - 1];
+    if last.compressed_offset > file_size || file_size - last.compressed_offset > MAX_GZI_TAIL_SIZE {
+        return Err("GZI index does not match file".to_string());
+    }
+    let tail = client.read_file_range(file_path, last.compressed_offset, file_size - last.compressed_offset).await
+        .map_err(|e| format!("Failed to read BGZF data: {}", e))?;
+    let scan = GzipHandler::scan_members(tail.as_slice(), OutputLimit::for_compressed_size(file_size))?;
+    if !scan.complete || (!scan.bgzf && !tail.is_empty()) {
+        return Err("GZI index does not match file".to_string());
+    }
+
+    index.uncompressed_size = last.uncompressed_offset + scan.uncompressed_read;
+    Ok(Some(index))
+}
+
+/// 顺序读取整个文件，按块头部中的块长度建立索引
+async fn scan_index(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<BgzfIndex, String> {
+    log::debug!("扫描BGZF块建立索引: {} ({} 字节)", file_path, file_size);
+
+    let scan = consume_file_stream_with_client(client, file_path, 0, file_size, progress_callback, cancel_rx, move |reader| {
+        GzipHandler::scan_members(reader, OutputLimit::for_compressed_size(file_size))
+    }).await?;
+    if !scan.complete {
+        return Err("Failed to scan BGZF blocks: unexpected end of data".to_string());
+    }
+
+    let blocks = scan.members.iter()
+        .map(|member| BgzfBlock {
+            compressed_offset: member.compressed_offset,
+            uncompressed_offset: member.uncompressed_offset,
+        })
+        .collect();
+    Ok(BgzfIndex { blocks, uncompressed_size: scan.uncompressed_read })
+}
+
+/// 从解压偏移 `offset` 开始读取最多 `max_size` 字节，只下载覆盖该范围的块
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn read_preview_at_offset(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    index: &BgzfIndex,
+    offset: u64,
+    max_size: usize,
+    progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<crate::archive::types::FilePreview, String> {
+    if offset >= index.uncompressed_size {
+        return Ok(PreviewBuilder::new()
+            .total_size(index.uncompressed_size)
+            .build());
+    }
+
+    let (first, end) = index.block_range(offset, max_size as u64);
+    let block = index.blocks[first];
+    let read_end = index.blocks.get(end).map_or(file_size, |block| block.compressed_offset);
+
+    log::debug!(
+        "BGZF定位: {} 解压偏移 {} 位于第 {} 块，读取压缩范围 {}..{}",
+        file_path, offset, first, block.compressed_offset, read_end
+    );
+
+    let progress_callback = progress_callback.map(|callback| Arc::from(callback) as ProgressCallback);
+    let compressed = client.read_file_range_with_progress(
+        file_path,
+        block.compressed_offset,
+        read_end - block.compressed_offset,
+        progress_callback,
+        cancel_rx,
+    ).await.map_err(|e| format!("Failed to read BGZF blocks: {}", e))?;
+
+    let mut decoder = flate2::read::MultiGzDecoder::new(compressed.as_slice());
+    let skip = offset - block.uncompressed_offset;
+    std::io::copy(&mut (&mut decoder).take(skip), &mut std::io::sink())
+        .map_err(|e| format!("Failed to decompress BGZF block: {}", e))?;
+
+    let mut content = Vec::new();
+    decoder.take(max_size as u64).read_to_end(&mut content)
+        .map_err(|e| format!("Failed to decompress BGZF block: {}", e))?;
+
+    Ok(PreviewBuilder::new()
+        .with_truncated(offset + (content.len() as u64) < index.uncompressed_size)
+        .content(content)
+        .total_size(index.uncompressed_size)
+        .build())
+}
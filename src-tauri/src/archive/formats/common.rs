@@ -62,7 +62,675 @@ pub fn is_text_content(data: &[u8]) -> bool {
     (non_text_count as f64 / total_checked as f64) < 0.1
 }
 
+/// 将 DOS 日期时间（高16位为日期，低16位为时间）转换为 RFC3339 字符串
+pub fn dos_datetime_to_rfc3339(dos_datetime: u32) -> Option<String> {
+    let date = (dos_datetime >> 16) as u16;
+    let time = dos_datetime as u16;
+
+    let year = 1980 + (date >> 9) as i32;
+    let month = ((date >> 5) & 0x0f) as u32;
+    let day = (date & 0x1f) as u32;
+    let hour = (time >> 11) as u32;
+    let minute = ((time >> 5) & 0x3f) as u32;
+    let second = ((time & 0x1f) * 2) as u32;
+
+    let datetime = chrono::NaiveDate::from_ymd_opt(year, month, day)?
+        .and_hms_opt(hour, minute, second)?;
+    Some(datetime.and_utc().to_rfc3339())
+}
+
+/// 分页列表默认每页条目数
+pub const DEFAULT_LIST_LIMIT: usize = 1000;
+/// 分页列表每页条目数上限
+pub const MAX_LIST_LIMIT: usize = 10000;
+
+/// 条目路径相对于目录前缀的层级关系
+pub enum DirectoryLevelMatch<'a> {
+    /// 位于前缀下更深的层级，值为当前层级的子目录名
+    Directory(&'a str),
+    /// 直接位于前缀下的文件
+    File,
+    /// 不在前缀下（或就是前缀目录本身）
+    Outside,
+}
+
+/// 规范化目录前缀：去掉开头的 `/`，非空时以 `/` 结尾
+pub fn normalize_directory_prefix(prefix: Option<&str>) -> String {
+    let prefix = prefix.unwrap_or_default().trim_start_matches('/');
+    if prefix.is_empty() || prefix.ends_with('/') {
+        prefix.to_string()
+    } else {
+        format!("{}/", prefix)
+    }
+}
+
+/// 判断条目路径在前缀目录下的层级
+pub fn match_directory_level<'a>(path: &'a str, prefix: &str) -> DirectoryLevelMatch<'a> {
+    let Some(rest) = path.strip_prefix(prefix) else {
+        return DirectoryLevelMatch::Outside;
+    };
+
+    match rest.find('/') {
+        Some(0) => DirectoryLevelMatch::Outside,
+        Some(position) => DirectoryLevelMatch::Directory(&rest[..position]),
+        None if rest.is_empty() => DirectoryLevelMatch::Outside,
+        None => DirectoryLevelMatch::File,
+    }
+}
+
+/// 返回（偏移，每页条目数），每页条目数限制在 [1, MAX_LIST_LIMIT]
+pub fn list_page_bounds(options: &crate::archive::types::ArchiveListOptions) -> (usize, usize) {
+    let offset = options.offset.unwrap_or(0);
+    let limit = options.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+    (offset, limit)
+}
+
+/// 为当前层级的子目录生成条目
+pub fn directory_entry(prefix: &str, name: &str, index: usize) -> crate::archive::types::ArchiveEntry {
+    crate::archive::types::ArchiveEntry {
+        path: format!("{}{}/", prefix, name),
+        size: 0,
+        compressed_size: None,
+        is_dir: true,
+        modified_time: None,
+        crc32: None,
+        index,
+        metadata: std::collections::HashMap::new(),
+    }
+}
+
+/// 根据本页条目和总数生成分页结果
+pub fn make_listing(
+    prefix: String,
+    offset: usize,
+    total_entries: usize,
+    archive_total_entries: usize,
+    entries: Vec<crate::archive::types::ArchiveEntry>,
+) -> crate::archive::types::ArchiveListing {
+    let end = offset + entries.len();
+    let has_more = end < total_entries;
+    crate::archive::types::ArchiveListing {
+        prefix,
+        entries,
+        total_entries,
+        archive_total_entries,
+        offset,
+        has_more,
+        next_offset: has_more.then_some(end),
+    }
+}
+
+/// 单文件压缩数据的前缀解压结果
+pub struct DecompressedPrefix {
+    /// 解压得到的数据（不超过请求的大小）
+    pub data: Vec<u8>,
+    /// 解压器实际消耗的压缩数据大小
+    pub compressed_read: u64,
+    /// 是否已解压到数据流末尾
+    pub reached_end: bool,
+}
+
+impl DecompressedPrefix {
+    /// 根据压缩比估算原始大小，返回 (大小, 是否为估算值)
+    pub fn estimate_total_size(&self, file_size: u64) -> (u64, bool) {
+        if self.reached_end {
+            return (self.data.len() as u64, false);
+        }
+        if self.data.is_empty() || self.compressed_read == 0 {
+            return (file_size, true);
+        }
+        let ratio = self.data.len() as f64 / self.compressed_read as f64;
+        ((file_size as f64 * ratio) as u64, true)
+    }
+}
+
+/// 从头顺序读取压缩数据并解压出前 `max_output` 字节
+///
+/// 单文件压缩格式（zstd、lz4、brotli）没有索引，只能从头顺序解压。压缩数据按块流式读取，
+/// 始终使用同一个解压器，得到足够的输出后立即停止读取。
+pub async fn decompress_prefix_with_client<F>(
+    client: &std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    max_output: usize,
+    create_decoder: F,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<DecompressedPrefix, String>
+where
+    F: FnOnce(CountingReader<ChannelReader>) -> std::io::Result<Box<dyn std::io::Read>> + Send + 'static,
+{
+    use std::io::Read;
+
+    consume_file_stream_with_client(client, file_path, 0, file_size, progress_callback, cancel_rx, move |reader| {
+        let consumed = std::rc::Rc::new(std::cell::Cell::new(0u64));
+        let input = CountingReader { inner: reader, consumed: consumed.clone() };
+        let mut decoder = create_decoder(input)
+            .map_err(|e| format!("Failed to create decoder: {}", e))?;
+
+        let mut output = Vec::with_capacity(max_output.min(1024 * 1024));
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut reached_end = false;
+        while output.len() < max_output {
+            let want = buffer.len().min(max_output - output.len());
+            match decoder.read(&mut buffer[..want]) {
+                Ok(0) => {
+                    reached_end = true;
+                    break;
+                }
+                Ok(n) => output.extend_from_slice(&buffer[..n]),
+                Err(e) => return Err(format!("Failed to decompress data: {}", e)),
+            }
+        }
+
+        Ok(DecompressedPrefix {
+            data: output,
+            compressed_read: consumed.get(),
+            reached_end,
+        })
+    }).await
+}
+
+/// 统计解压器消耗字节数的输入读取器，用于按压缩比估算原始大小
+pub struct CountingReader<R> {
+    inner: R,
+    consumed: std::rc::Rc<std::cell::Cell<u64>>,
+}
+
+impl<R: std::io::Read> std::io::Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.consumed.set(self.consumed.get() + n as u64);
+        Ok(n)
+    }
+}
+
+impl<R: std::io::BufRead> std::io::BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.consumed.set(self.consumed.get() + amt as u64);
+    }
+}
+
+/// 分析进度的最短上报间隔
+const ANALYSIS_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// 新条目累计到此数量时立即上报，不等待上报间隔
+const ANALYSIS_PROGRESS_BATCH: usize = 1000;
+
+/// 流式分析的进度上报
+///
+/// 读取端通过 `on_bytes_read` 更新读取进度，解析端通过 `on_entries` 按批次发送新条目；
+/// 两者共用上报间隔，避免大量细碎的更新。
+pub struct AnalysisReporter {
+    callback: crate::archive::types::AnalysisProgressCallback,
+    total_bytes: u64,
+    bytes_read: std::sync::atomic::AtomicU64,
+    state: std::sync::Mutex<AnalysisReporterState>,
+}
+
+struct AnalysisReporterState {
+    /// 已发送的条目数量
+    reported_entries: usize,
+    last_report: std::time::Instant,
+}
+
+impl AnalysisReporter {
+    pub fn new(callback: crate::archive::types::AnalysisProgressCallback, total_bytes: u64) -> std::sync::Arc<Self> {
+        std::sync::Arc::new(Self {
+            callback,
+            total_bytes,
+            bytes_read: std::sync::atomic::AtomicU64::new(0),
+            state: std::sync::Mutex::new(AnalysisReporterState {
+                reported_entries: 0,
+                last_report: std::time::Instant::now(),
+            }),
+        })
+    }
+
+    /// 更新已读取的字节数，距上次上报超过间隔时发送一次不含新条目的进度
+    pub fn on_bytes_read(&self, bytes_read: u64) {
+        self.bytes_read.store(bytes_read, std::sync::atomic::Ordering::Relaxed);
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.last_report.elapsed() < ANALYSIS_PROGRESS_INTERVAL {
+            return;
+        }
+        state.last_report = std::time::Instant::now();
+        let analyzed_entries = state.reported_entries;
+        drop(state);
+
+        self.send(Vec::new(), analyzed_entries);
+    }
+
+    /// 发送 `entries` 中尚未上报的条目（`entries` 为到目前为止解析出的全部条目）
+    ///
+    /// 新条目不足一批且距上次上报未超过间隔时暂不发送。
+    pub fn on_entries(&self, entries: &[crate::archive::types::ArchiveEntry]) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let pending = entries.len().saturating_sub(state.reported_entries);
+        if pending == 0
+            || (pending < ANALYSIS_PROGRESS_BATCH && state.last_report.elapsed() < ANALYSIS_PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        let batch = entries[state.reported_entries..].to_vec();
+        state.reported_entries = entries.len();
+        state.last_report = std::time::Instant::now();
+        drop(state);
+
+        self.send(batch, entries.len());
+    }
+
+    fn send(&self, entries: Vec<crate::archive::types::ArchiveEntry>, analyzed_entries: usize) {
+        (self.callback)(crate::archive::types::AnalysisProgress {
+            entries,
+            bytes_read: self.bytes_read.load(std::sync::atomic::Ordering::Relaxed),
+            total_bytes: self.total_bytes,
+            analysis_status: crate::archive::types::AnalysisStatus::Partial { analyzed_entries },
+        });
+    }
+}
+
+/// 顺序流式读取时首次范围请求的大小，之后逐次加倍
+const STREAM_INITIAL_CHUNK_SIZE: u64 = 64 * 1024;
+/// 顺序流式读取时每次范围请求的最大大小
+const STREAM_CHUNK_SIZE: u64 = 1024 * 1024;
+/// 读取端与解压端之间最多缓存的数据块数量，用于限制内存占用
+const STREAM_QUEUE_DEPTH: usize = 2;
+
+/// 将通道中的数据块转换为同步 `Read` 的读取器，供阻塞线程中的解压器使用
+pub struct ChannelReader {
+    receiver: tokio::sync::mpsc::Receiver<Vec<u8>>,
+    current: Vec<u8>,
+    position: usize,
+}
+
+impl std::io::Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = std::io::BufRead::fill_buf(self)?;
+        let n = buf.len().min(available.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+impl std::io::BufRead for ChannelReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        while self.position >= self.current.len() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.current = chunk;
+                    self.position = 0;
+                }
+                None => return Ok(&[]),
+            }
+        }
+        Ok(&self.current[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.current.len());
+    }
+}
+
+/// 从 `start_offset` 顺序读取到文件末尾，并在阻塞线程中以同步 `Read` 的方式交给 `consumer` 处理
+///
+/// 读取端按块发起范围请求，通过有界通道传给消费端，内存占用与文件大小无关。
+/// 消费端提前返回（例如已找到目标条目）时，读取端随之停止，不会读取剩余数据。
+pub async fn consume_file_stream_with_client<T, F>(
+    client: &std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    file_path: &str,
+    start_offset: u64,
+    file_size: u64,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    consumer: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(ChannelReader) -> Result<T, String> + Send + 'static,
+{
+    let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_QUEUE_DEPTH);
+    let reader = ChannelReader { receiver, current: Vec::new(), position: 0 };
+    let consumer_task = tokio::task::spawn_blocking(move || consumer(reader));
+
+    let mut offset = start_offset;
+    // 从较小的请求开始，消费端很快结束时（如定位到靠前的条目）避免多余读取
+    let mut chunk_size = STREAM_INITIAL_CHUNK_SIZE;
+    while offset < file_size {
+        if let Some(ref mut cancel_rx) = cancel_rx {
+            if cancel_rx.try_recv().is_ok() {
+                return Err("download.cancelled".to_string());
+            }
+        }
+
+        let length = chunk_size.min(file_size - offset);
+        chunk_size = (chunk_size * 2).min(STREAM_CHUNK_SIZE);
+        let chunk = client.read_file_range(file_path, offset, length).await
+            .map_err(|e| format!("Failed to read file data: {}", e))?;
+        if chunk.is_empty() {
+            break;
+        }
+        offset += chunk.len() as u64;
+
+        if let Some(callback) = progress_callback {
+            callback(offset, file_size);
+        }
+
+        if sender.send(chunk).await.is_err() {
+            // 消费端已结束，无需继续读取
+            log::debug!("流式读取提前结束: {} ({}/{} 字节)", file_path, offset, file_size);
+            break;
+        }
+    }
+    drop(sender);
+
+    consumer_task.await
+        .map_err(|e| format!("Stream consumer task failed: {}", e))?
+}
+
+/// 把阻塞线程中同步写入的数据按块转发给异步写入端的写入器
+pub struct ChannelWriter {
+    sender: tokio::sync::mpsc::Sender<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+impl ChannelWriter {
+    /// 发送剩余的缓冲数据，解压完成后必须调用
+    pub fn finish(mut self) -> Result<(), String> {
+        std::io::Write::flush(&mut self).map_err(|e| format!("Failed to write data: {}", e))
+    }
+}
+
+impl std::io::Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() as u64 >= STREAM_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::take(&mut self.buffer);
+        self.sender.blocking_send(chunk)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "output closed"))
+    }
+}
+
+/// 批量解压时解压端按压缩包内顺序发出的条目事件
+pub enum EntryEvent {
+    /// 开始写入一个条目，之后的数据都属于该条目
+    Begin(String),
+    Data(Vec<u8>),
+    /// 当前条目写入完成
+    End,
+}
+
+/// 批量解压的输出端：解压端依次写入选中的条目，接收端负责创建文件
+///
+/// 阻塞线程中的解压器使用 `begin_entry`/`end_entry` 和 `std::io::Write`（按块缓存后发送），
+/// 异步写入使用 `*_async` 方法和 `AsyncWrite`。二者之间只缓存少量数据块，内存占用与条目大小无关。
+/// 每个选中的条目只写入一次（TAR 中重复的路径以第一次出现的为准）。
+pub struct EntrySink {
+    sender: tokio_util::sync::PollSender<EntryEvent>,
+    pending: std::collections::HashSet<String>,
+    buffer: Vec<u8>,
+}
+
+impl EntrySink {
+    /// 创建输出端和对应的事件接收端，`selected` 为要写入的文件条目路径
+    pub fn new(selected: impl IntoIterator<Item = String>) -> (Self, tokio::sync::mpsc::Receiver<EntryEvent>) {
+        let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_QUEUE_DEPTH);
+        let sink = Self {
+            sender: tokio_util::sync::PollSender::new(sender),
+            pending: selected.into_iter().collect(),
+            buffer: Vec::new(),
+        };
+        (sink, receiver)
+    }
+
+    /// 条目被选中且尚未写入
+    pub fn wants(&self, path: &str) -> bool {
+        self.pending.contains(path)
+    }
+
+    /// 选中的条目都已写入，解压端可以提前结束
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// 尚未写入的条目路径（按路径排序）
+    pub fn pending_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.pending.iter().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    /// 开始写入条目（阻塞线程中使用）
+    pub fn begin_entry(&mut self, path: &str) -> Result<(), String> {
+        self.pending.remove(path);
+        self.blocking_send(EntryEvent::Begin(path.to_string()))
+    }
+
+    /// 发送缓存的数据并结束当前条目（阻塞线程中使用）
+    pub fn end_entry(&mut self) -> Result<(), String> {
+        std::io::Write::flush(self).map_err(|e| format!("Failed to write data: {}", e))?;
+        self.blocking_send(EntryEvent::End)
+    }
+
+    pub async fn begin_entry_async(&mut self, path: &str) -> Result<(), String> {
+        self.pending.remove(path);
+        self.send(EntryEvent::Begin(path.to_string())).await
+    }
+
+    pub async fn end_entry_async(&mut self) -> Result<(), String> {
+        self.send(EntryEvent::End).await
+    }
+
+    fn blocking_send(&self, event: EntryEvent) -> Result<(), String> {
+        match self.sender.get_ref() {
+            Some(sender) => sender.blocking_send(event).map_err(|_| "Failed to write data: output closed".to_string()),
+            None => Err("Failed to write data: output closed".to_string()),
+        }
+    }
+
+    async fn send(&self, event: EntryEvent) -> Result<(), String> {
+        match self.sender.get_ref() {
+            Some(sender) => sender.send(event).await.map_err(|_| "Failed to write data: output closed".to_string()),
+            None => Err("Failed to write data: output closed".to_string()),
+        }
+    }
+}
 
+impl std::io::Write for EntrySink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() as u64 >= STREAM_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::take(&mut self.buffer);
+        self.blocking_send(EntryEvent::Data(chunk))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::BrokenPipe, e))
+    }
+}
+
+impl tokio::io::AsyncWrite for EntrySink {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let closed = || std::io::Error::new(std::io::ErrorKind::BrokenPipe, "output closed");
+        match this.sender.poll_reserve(cx) {
+            std::task::Poll::Ready(Ok(())) => {}
+            std::task::Poll::Ready(Err(_)) => return std::task::Poll::Ready(Err(closed())),
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }
+        this.sender.send_item(EntryEvent::Data(buf.to_vec())).map_err(|_| closed())?;
+        std::task::Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: std::pin::Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: std::pin::Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// 丢弃数据只统计字节数的写入器，用于完整性测试
+#[derive(Default)]
+pub struct CountingSink {
+    pub written: u64,
+}
+
+impl std::io::Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.written += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 把解压任务写入 `ChannelWriter` 的数据写到异步的 `writer`，返回写入的字节数
+///
+/// `decode` 通常在 `consume_file_stream_with_client` 的消费端中解压并写入 `ChannelWriter`，
+/// 解压与写入并发进行，二者之间只缓存少量数据块，内存占用与条目大小无关。
+pub async fn pipe_to_async_writer<F, Fut>(
+    writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+    decode: F,
+) -> Result<u64, String>
+where
+    F: FnOnce(ChannelWriter) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let (sender, mut receiver) = tokio::sync::mpsc::channel::<Vec<u8>>(STREAM_QUEUE_DEPTH);
+    let decode_task = decode(ChannelWriter { sender, buffer: Vec::new() });
+    let write_task = async {
+        let mut written = 0u64;
+        while let Some(chunk) = receiver.recv().await {
+            writer.write_all(&chunk).await
+                .map_err(|e| format!("Failed to write data: {}", e))?;
+            written += chunk.len() as u64;
+        }
+        Ok::<u64, String>(written)
+    };
+
+    let ((), written) = tokio::try_join!(decode_task, write_task)?;
+    writer.flush().await
+        .map_err(|e| format!("Failed to write data: {}", e))?;
+    Ok(written)
+}
+
+/// 从头流式解压整个单文件压缩数据（gzip、zstd、lz4、brotli）并写入 `writer`，输出受解压限制约束
+pub async fn decompress_file_to_writer<F>(
+    client: &std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    file_path: &str,
+    writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    create_decoder: F,
+) -> Result<u64, String>
+where
+    F: FnOnce(ChannelReader) -> std::io::Result<Box<dyn std::io::Read>> + Send + 'static,
+{
+    let file_size = client.get_file_size(file_path).await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+    pipe_to_async_writer(writer, |mut output| consume_file_stream_with_client(
+        client,
+        file_path,
+        0,
+        file_size,
+        progress_callback,
+        cancel_rx,
+        move |reader| {
+            let decoder = create_decoder(reader)
+                .map_err(|e| format!("Failed to create decoder: {}", e))?;
+            let limit = crate::archive::limits::OutputLimit::for_compressed_size(file_size);
+            let mut decoder = crate::archive::limits::LimitedReader::new(decoder, limit);
+            std::io::copy(&mut decoder, &mut output)
+                .map_err(|e| crate::archive::limits::map_io_error(e, |e| format!("Failed to decompress data: {}", e)))?;
+            output.finish()
+        },
+    )).await
+}
+
+/// 将文件的 [start_offset, end_offset) 范围原样写入 `writer`（用于未压缩的条目数据）
+pub async fn copy_range_to_writer(
+    client: &std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    file_path: &str,
+    start_offset: u64,
+    end_offset: u64,
+    writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<u64, String> {
+    use tokio::io::AsyncWriteExt;
+
+    let total = end_offset.saturating_sub(start_offset);
+    let mut offset = start_offset;
+    while offset < end_offset {
+        if let Some(ref mut cancel_rx) = cancel_rx {
+            if cancel_rx.try_recv().is_ok() {
+                return Err("download.cancelled".to_string());
+            }
+        }
+
+        let length = STREAM_CHUNK_SIZE.min(end_offset - offset);
+        let chunk = client.read_file_range(file_path, offset, length).await
+            .map_err(|e| format!("Failed to read file data: {}", e))?;
+        if chunk.is_empty() {
+            return Err("Unexpected end of file data".to_string());
+        }
+        writer.write_all(&chunk).await
+            .map_err(|e| format!("Failed to write data: {}", e))?;
+        offset += chunk.len() as u64;
+
+        if let Some(callback) = progress_callback {
+            callback(offset - start_offset, total);
+        }
+    }
+
+    writer.flush().await
+        .map_err(|e| format!("Failed to write data: {}", e))?;
+    Ok(total)
+}
+
+/// 去掉单文件压缩格式的扩展名，得到原始文件名
+pub fn strip_compression_extension(filename: &str, extensions: &[&str]) -> String {
+    let basename = filename.rsplit('/').next().unwrap_or(filename);
+    let lower = basename.to_lowercase();
+    for extension in extensions {
+        if lower.ends_with(extension) && basename.len() > extension.len() {
+            return basename[..basename.len() - extension.len()].to_string();
+        }
+    }
+    "compressed_content".to_string()
+}
 
 /// 文件预览构建器
 #[derive(Debug, Clone)]
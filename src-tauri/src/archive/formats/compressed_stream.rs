@@ -0,0 +1,328 @@
+/// 单文件压缩格式处理器（zstd / lz4 / brotli）
+///
+/// 这些格式没有条目目录和索引，整个文件就是一个压缩数据流，只能从头顺序解压。
+/// 分析时解压少量样本估算原始大小；帧头记录的内容大小只描述第一个帧，
+/// 多帧（拼接）文件的总大小可能更大，因此除非样本已解压到末尾，否则都视为估算值。
+use crate::archive::types::*;
+use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
+use crate::storage::traits::StorageClient;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::sync::Arc;
+
+/// Zstandard 帧魔数（小端）
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+/// LZ4 帧魔数（小端）
+const LZ4_MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+/// Brotli 解码器的输入缓冲区大小（较小的缓冲区使压缩比估算更准确）
+const BROTLI_BUFFER_SIZE: usize = 4096;
+/// 分析时默认解压的样本大小
+const DEFAULT_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// 单文件压缩使用的压缩算法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamCodec {
+    Zstd,
+    Lz4,
+    Brotli,
+}
+
+impl StreamCodec {
+    pub fn compression_type(self) -> CompressionType {
+        match self {
+            StreamCodec::Zstd => CompressionType::Zstd,
+            StreamCodec::Lz4 => CompressionType::Lz4,
+            StreamCodec::Brotli => CompressionType::Brotli,
+        }
+    }
+
+    /// 日志和错误信息中使用的格式名称
+    fn name(self) -> &'static str {
+        match self {
+            StreamCodec::Zstd => "Zstd",
+            StreamCodec::Lz4 => "LZ4",
+            StreamCodec::Brotli => "Brotli",
+        }
+    }
+
+    /// 生成条目名称时去掉的扩展名
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            StreamCodec::Zstd => &[".zst", ".zstd"],
+            StreamCodec::Lz4 => &[".lz4"],
+            StreamCodec::Brotli => &[".br"],
+        }
+    }
+
+    /// 帧头最大长度；Brotli 没有帧头时返回 None
+    ///
+    /// zstd：魔数 4 + FHD 1 + 窗口描述符 1 + 字典 ID 4 + 内容大小 8；
+    /// lz4：魔数 4 + FLG 1 + BD 1 + 内容大小 8 + 字典 ID 4 + 校验 1。
+    fn frame_header_max_size(self) -> Option<u64> {
+        match self {
+            StreamCodec::Zstd => Some(18),
+            StreamCodec::Lz4 => Some(19),
+            StreamCodec::Brotli => None,
+        }
+    }
+
+    /// 检查压缩数据的魔数；Brotli 没有魔数，无法通过文件头识别
+    fn matches_magic(self, data: &[u8]) -> bool {
+        match self {
+            StreamCodec::Zstd => data.starts_with(&ZSTD_MAGIC),
+            StreamCodec::Lz4 => data.starts_with(&LZ4_MAGIC),
+            StreamCodec::Brotli => false,
+        }
+    }
+
+    /// 解析第一个帧头中记录的内容大小
+    fn parse_frame_content_size(self, header: &[u8]) -> Option<u64> {
+        match self {
+            StreamCodec::Zstd => Self::parse_zstd_content_size(header),
+            StreamCodec::Lz4 => Self::parse_lz4_content_size(header),
+            StreamCodec::Brotli => None,
+        }
+    }
+
+    /// 在输入流上创建对应的流式解压器
+    ///
+    /// zstd 直接使用输入的缓冲区，使统计到的压缩数据消耗量与解压进度一致。
+    fn decoder<'a, R: io::BufRead + 'a>(self, input: R) -> io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            StreamCodec::Zstd => Box::new(::zstd::stream::read::Decoder::with_buffer(input)?),
+            StreamCodec::Lz4 => Box::new(::lz4::Decoder::new(input)?),
+            StreamCodec::Brotli => Box::new(::brotli::Decompressor::new(input, BROTLI_BUFFER_SIZE)),
+        })
+    }
+
+    /// 解析 zstd 帧头中的 Frame_Content_Size 字段
+    fn parse_zstd_content_size(header: &[u8]) -> Option<u64> {
+        if header.len() < 5 || header[..4] != ZSTD_MAGIC {
+            return None;
+        }
+
+        let descriptor = header[4];
+        let fcs_flag = descriptor >> 6;
+        let single_segment = descriptor & 0x20 != 0;
+        let dict_id_size = match descriptor & 0x03 {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            _ => 4,
+        };
+        let fcs_size = match fcs_flag {
+            0 if single_segment => 1,
+            0 => return None,
+            1 => 2,
+            2 => 4,
+            _ => 8,
+        };
+
+        let offset = 5 + if single_segment { 0 } else { 1 } + dict_id_size;
+        let bytes = header.get(offset..offset + fcs_size)?;
+        let mut value = [0u8; 8];
+        value[..fcs_size].copy_from_slice(bytes);
+        let size = u64::from_le_bytes(value);
+
+        // 2 字节的内容大小字段有 256 的偏移
+        Some(if fcs_size == 2 { size + 256 } else { size })
+    }
+
+    /// 解析 lz4 帧头中的 Content Size 字段（FLG 第 3 位置位时存在）
+    fn parse_lz4_content_size(header: &[u8]) -> Option<u64> {
+        if header.len() < 6 || header[..4] != LZ4_MAGIC {
+            return None;
+        }
+
+        let flags = header[4];
+        if flags & 0x08 == 0 {
+            return None;
+        }
+
+        let bytes = header.get(6..14)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+pub struct CompressedStreamHandler {
+    codec: StreamCodec,
+}
+
+impl CompressedStreamHandler {
+    pub fn new(codec: StreamCodec) -> Self {
+        Self { codec }
+    }
+}
+
+#[async_trait::async_trait]
+impl CompressionHandlerDispatcher for CompressedStreamHandler {
+    async fn analyze_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        max_size: Option<usize>,
+    ) -> Result<ArchiveInfo, String> {
+        Self::analyze_stream(self.codec, client, file_path, filename, max_size).await
+    }
+
+    async fn extract_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_stream_preview(self.codec, client, file_path, max_size, progress_callback, cancel_rx).await
+    }
+
+    async fn extract_entry_to_writer(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<u64, String> {
+        let codec = self.codec;
+        decompress_file_to_writer(&client, file_path, writer, progress_callback.as_deref(), cancel_rx, move |reader| {
+            codec.decoder(reader)
+        }).await
+    }
+
+    fn compression_type(&self) -> CompressionType {
+        self.codec.compression_type()
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        self.codec.matches_magic(data)
+    }
+}
+
+impl CompressedStreamHandler {
+    /// 分析单文件压缩数据：读取帧头中的内容大小，并解压少量数据作为样本
+    async fn analyze_stream(
+        codec: StreamCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        max_size: Option<usize>,
+    ) -> Result<ArchiveInfo, String> {
+        log::debug!("开始分析{}文件: {}", codec.name(), file_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let content_size = Self::read_frame_content_size(codec, &client, file_path, file_size).await?;
+
+        let sample = decompress_prefix_with_client(
+            &client,
+            file_path,
+            file_size,
+            max_size.unwrap_or(DEFAULT_SAMPLE_SIZE),
+            move |input| codec.decoder(input),
+            None,
+            None,
+        ).await?;
+        let (uncompressed_size, size_estimated) = Self::total_size(&sample, content_size, file_size);
+
+        log::debug!("{}原始大小: {} 字节 (估算: {})", codec.name(), uncompressed_size, size_estimated);
+
+        let mut metadata = HashMap::new();
+        if let Some(size) = content_size {
+            metadata.insert("frame_content_size".to_string(), size.to_string());
+        }
+        if size_estimated {
+            metadata.insert("size_estimated".to_string(), "true".to_string());
+        }
+
+        let entry = ArchiveEntry {
+            path: strip_compression_extension(filename, codec.extensions()),
+            size: uncompressed_size,
+            compressed_size: Some(file_size),
+            is_dir: false,
+            modified_time: None,
+            crc32: None,
+            index: 0,
+            metadata,
+        };
+
+        Ok(ArchiveInfoBuilder::new(codec.compression_type())
+            .entries(vec![entry])
+            .total_entries(1)
+            .total_uncompressed_size(uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(false)
+            .analysis_status(AnalysisStatus::Complete)
+            .build())
+    }
+
+    /// 从文件开头解压出预览数据
+    async fn extract_stream_preview(
+        codec: StreamCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        log::debug!("开始提取{}预览: {}", codec.name(), file_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let content_size = Self::read_frame_content_size(codec, &client, file_path, file_size).await?;
+
+        let prefix = decompress_prefix_with_client(
+            &client,
+            file_path,
+            file_size,
+            max_size,
+            move |input| codec.decoder(input),
+            progress_callback.as_deref(),
+            cancel_rx,
+        ).await?;
+
+        let (total_size, _) = Self::total_size(&prefix, content_size, file_size);
+        let is_truncated = !prefix.reached_end;
+
+        Ok(PreviewBuilder::new()
+            .content(prefix.data)
+            .total_size(total_size)
+            .with_truncated(is_truncated)
+            .build())
+    }
+
+    /// 读取并校验帧头，返回第一个帧记录的内容大小
+    async fn read_frame_content_size(
+        codec: StreamCodec,
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+    ) -> Result<Option<u64>, String> {
+        let Some(header_size) = codec.frame_header_max_size() else {
+            return Ok(None);
+        };
+
+        let header = client.read_file_range(file_path, 0, header_size.min(file_size)).await
+            .map_err(|e| format!("Failed to read {} header: {}", codec.name(), e))?;
+        if !codec.matches_magic(&header) {
+            return Err(format!("Invalid {} header", codec.name()));
+        }
+        Ok(codec.parse_frame_content_size(&header))
+    }
+
+    /// 确定原始大小，返回 (大小, 是否为估算值)
+    ///
+    /// 样本已解压到末尾时大小是准确的；否则优先使用第一个帧记录的内容大小，
+    /// 但后面可能还有其他帧，所以仍标记为估算值。
+    fn total_size(prefix: &DecompressedPrefix, content_size: Option<u64>, file_size: u64) -> (u64, bool) {
+        match content_size {
+            Some(size) if !prefix.reached_end => (size.max(prefix.data.len() as u64), true),
+            _ => prefix.estimate_total_size(file_size),
+        }
+    }
+}
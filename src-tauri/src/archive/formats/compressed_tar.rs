@@ -0,0 +1,816 @@
+/// 压缩 TAR 格式处理器（tar.gz / tar.zst / tar.xz / tar.bz2 / tar.lz4）
+///
+/// 将顺序范围读取、流式解压器和 TAR 头部解析组合在一起：
+/// 压缩数据按块读取后直接送入解压器，TAR 头部逐个解析，文件内容只解压不保存，
+/// 因此内存占用与压缩包大小无关。
+use crate::archive::types::*;
+use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
+use crate::archive::formats::tar_header::{TarHeader, TarHeaderParser};
+use crate::archive::formats::gzip_index::{self, GzipStreamReader, TarHeaderIndex};
+use crate::archive::formats::list_index::EntryIndex;
+use crate::archive::limits::{self, OutputLimit};
+use crate::storage::traits::StorageClient;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+/// TAR 块大小
+const BLOCK_SIZE: u64 = 512;
+/// 列表分析的最大条目数量
+const MAX_ENTRIES: usize = 10000;
+
+/// TAR 外层使用的压缩算法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TarCodec {
+    Gzip,
+    Zstd,
+    Xz,
+    Bzip2,
+    Lz4,
+}
+
+impl TarCodec {
+    pub fn compression_type(self) -> CompressionType {
+        match self {
+            TarCodec::Gzip => CompressionType::TarGz,
+            TarCodec::Zstd => CompressionType::TarZst,
+            TarCodec::Xz => CompressionType::TarXz,
+            TarCodec::Bzip2 => CompressionType::TarBz2,
+            TarCodec::Lz4 => CompressionType::TarLz4,
+        }
+    }
+
+    /// 检查压缩数据的魔数
+    fn matches_magic(self, data: &[u8]) -> bool {
+        match self {
+            TarCodec::Gzip => data.starts_with(&[0x1f, 0x8b, 0x08]),
+            TarCodec::Zstd => data.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]),
+            TarCodec::Xz => data.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]),
+            TarCodec::Bzip2 => data.len() >= 4 && data.starts_with(b"BZh") && (b'1'..=b'9').contains(&data[3]),
+            TarCodec::Lz4 => data.starts_with(&[0x04, 0x22, 0x4D, 0x18]),
+        }
+    }
+
+    /// 在输入流上创建对应的流式解压器
+    fn decoder<'a, R: Read + 'a>(self, input: R) -> io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            TarCodec::Gzip => Box::new(flate2::read::MultiGzDecoder::new(input)),
+            TarCodec::Zstd => Box::new(::zstd::stream::read::Decoder::new(input)?),
+            TarCodec::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(input)),
+            TarCodec::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(input)),
+            TarCodec::Lz4 => Box::new(::lz4::Decoder::new(input)?),
+        })
+    }
+}
+
+/// 顺序遍历解压后 TAR 数据流的条目
+pub(crate) struct TarEntryWalker<R: Read> {
+    reader: R,
+    index: usize,
+    /// 当前条目尚未读取的数据大小（含 512 字节对齐填充）
+    pending: u64,
+    /// 已读取的解压数据偏移
+    position: u64,
+    /// 最近一个条目头部的偏移（有扩展头部时为第一个扩展头部的偏移）
+    header_offset: u64,
+    parser: TarHeaderParser,
+    /// 头部解析失败时返回错误，而不是跳过该块继续查找下一个头部
+    strict_headers: bool,
+    /// 解压数据流的输出限制，按条目头部声明的大小在解压数据前检查
+    output_limit: Option<OutputLimit>,
+}
+
+impl<R: Read> TarEntryWalker<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self::with_position(reader, 0, 0)
+    }
+
+    /// 从 TAR 数据中间的某个条目头部开始遍历
+    pub(crate) fn with_position(reader: R, position: u64, index: usize) -> Self {
+        Self {
+            reader,
+            index,
+            pending: 0,
+            position,
+            header_offset: position,
+            parser: TarHeaderParser::new(),
+            strict_headers: false,
+            output_limit: None,
+        }
+    }
+
+    /// 按压缩数据大小限制解压输出，超出时 `next_entry` 返回 `archive.limit.*` 错误
+    pub(crate) fn with_output_limit(mut self, compressed_size: u64) -> Self {
+        self.output_limit = Some(OutputLimit::for_compressed_size(compressed_size));
+        self
+    }
+
+    /// 头部校验和错误等解析失败时停止遍历（用于完整性测试）
+    pub(crate) fn with_strict_headers(mut self) -> Self {
+        self.strict_headers = true;
+        self
+    }
+
+    /// 最近一个条目头部在解压数据中的偏移
+    pub(crate) fn header_offset(&self) -> u64 {
+        self.header_offset
+    }
+
+    pub(crate) fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// 读取下一个条目的头部，自动跳过上一个条目未读取的数据
+    pub(crate) fn next_entry(&mut self) -> Result<Option<ArchiveEntry>, String> {
+        let mut extension_offset = None;
+        loop {
+            self.skip_pending()?;
+
+            let header_offset = self.position;
+            let mut header = [0u8; BLOCK_SIZE as usize];
+            if !Self::read_block(&mut self.reader, &mut header)? {
+                return Ok(None);
+            }
+            self.position += BLOCK_SIZE;
+
+            // 空块表示 TAR 数据结束
+            if header.iter().all(|&b| b == 0) {
+                return Ok(None);
+            }
+
+            match self.parser.parse(&header, self.index) {
+                Ok(TarHeader::Extension { type_flag, size }) => {
+                    // 扩展头部数据紧随其后，读取后应用到下一个条目
+                    let mut data = Vec::with_capacity(size as usize);
+                    (&mut self.reader).take(size).read_to_end(&mut data)
+                        .map_err(|e| format!("Failed to read TAR extension header: {}", e))?;
+                    if (data.len() as u64) < size {
+                        return Err("Unexpected end of TAR data".to_string());
+                    }
+                    self.position += size;
+                    self.pending = size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE - size;
+                    self.parser.apply_extension(type_flag, &data)?;
+                    extension_offset.get_or_insert(header_offset);
+                }
+                Ok(TarHeader::Entry(mut entry)) => {
+                    if let Some(limit) = self.output_limit {
+                        limit.check(self.position.saturating_add(entry.size))?;
+                    }
+                    entry.compressed_size = None;
+                    // 从记录的偏移恢复遍历时需要重新读取扩展头部
+                    self.header_offset = extension_offset.unwrap_or(header_offset);
+                    self.pending = entry.size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+                    self.index += 1;
+                    return Ok(Some(entry));
+                }
+                Err(e) if self.strict_headers => {
+                    return Err(format!("Invalid TAR header at offset {}: {}", header_offset, e));
+                }
+                Err(e) => {
+                    log::warn!("解析TAR头部失败，条目 {}: {}", self.index, e);
+                }
+            }
+        }
+    }
+
+    /// 读取当前条目的前 `max_size` 字节
+    pub(crate) fn read_entry_data(&mut self, entry: &ArchiveEntry, max_size: usize) -> Result<Vec<u8>, String> {
+        let read_size = entry.size.min(max_size as u64);
+        let mut data = Vec::with_capacity(read_size.min(16 * 1024 * 1024) as usize);
+        (&mut self.reader).take(read_size).read_to_end(&mut data)
+            .map_err(|e| format!("Failed to read TAR entry data: {}", e))?;
+        if (data.len() as u64) < read_size {
+            return Err("Unexpected end of TAR data".to_string());
+        }
+        self.pending -= data.len() as u64;
+        self.position += data.len() as u64;
+        Ok(data)
+    }
+
+    /// 将当前条目的全部数据写入 `output`
+    pub(crate) fn copy_entry_data<W: Write>(&mut self, entry: &ArchiveEntry, output: &mut W) -> Result<u64, String> {
+        let copied = io::copy(&mut (&mut self.reader).take(entry.size), output)
+            .map_err(|e| format!("Failed to copy TAR entry data: {}", e))?;
+        if copied < entry.size {
+            return Err("Unexpected end of TAR data".to_string());
+        }
+        self.pending -= copied;
+        self.position += copied;
+        Ok(copied)
+    }
+
+    /// 完整性测试：读取全部条目的数据，返回逐条目结果和导致测试中止的结构性错误
+    pub(crate) fn test_entries(&mut self) -> (Vec<EntryIntegrity>, Option<String>) {
+        let mut results = Vec::new();
+        loop {
+            let entry = match self.next_entry() {
+                Ok(Some(entry)) => entry,
+                Ok(None) => return (results, None),
+                Err(e) => return (results, Some(e)),
+            };
+
+            match self.copy_entry_data(&entry, &mut io::sink()) {
+                Ok(size) => results.push(EntryIntegrity::passed(entry.path, size)),
+                Err(e) => {
+                    results.push(EntryIntegrity::failed(entry.path, 0, e.clone()));
+                    return (results, Some(e));
+                }
+            }
+        }
+    }
+
+    /// 批量解压：顺序遍历条目，把 `sink` 选中的文件条目写入其中，选中的条目都写入后提前结束
+    pub(crate) fn extract_selected(&mut self, sink: &mut EntrySink) -> Result<(), String> {
+        while !sink.is_complete() {
+            let Some(entry) = self.next_entry()? else {
+                break;
+            };
+            if entry.is_dir || !sink.wants(&entry.path) {
+                continue;
+            }
+            sink.begin_entry(&entry.path)?;
+            self.copy_entry_data(&entry, sink)?;
+            sink.end_entry()?;
+        }
+        Ok(())
+    }
+
+    fn skip_pending(&mut self) -> Result<(), String> {
+        if self.pending > 0 {
+            let skipped = io::copy(&mut (&mut self.reader).take(self.pending), &mut io::sink())
+                .map_err(|e| format!("Failed to skip TAR entry data: {}", e))?;
+            if skipped < self.pending {
+                return Err("Unexpected end of TAR data".to_string());
+            }
+            self.position += self.pending;
+            self.pending = 0;
+        }
+        Ok(())
+    }
+
+    /// 读取完整的 512 字节块，数据流在块边界结束时返回 false
+    fn read_block(reader: &mut R, block: &mut [u8]) -> Result<bool, String> {
+        let mut filled = 0;
+        while filled < block.len() {
+            match reader.read(&mut block[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err("Unexpected end of TAR data".to_string()),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Failed to decompress TAR data: {}", e)),
+            }
+        }
+        Ok(true)
+    }
+}
+
+pub struct CompressedTarHandler {
+    codec: TarCodec,
+}
+
+impl CompressedTarHandler {
+    pub fn new(codec: TarCodec) -> Self {
+        Self { codec }
+    }
+}
+
+#[async_trait::async_trait]
+impl CompressionHandlerDispatcher for CompressedTarHandler {
+    async fn analyze_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<usize>,
+    ) -> Result<ArchiveInfo, String> {
+        Self::analyze_compressed_tar(self.codec, client, file_path, None).await
+    }
+
+    async fn analyze_with_progress(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<usize>,
+        progress_callback: Option<AnalysisProgressCallback>,
+    ) -> Result<ArchiveInfo, String> {
+        Self::analyze_compressed_tar(self.codec, client, file_path, progress_callback).await
+    }
+
+    async fn extract_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_compressed_tar_preview(self.codec, client, file_path, entry_path, max_size, progress_callback, cancel_rx).await
+    }
+
+    async fn extract_entry_to_writer(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<u64, String> {
+        Self::extract_compressed_tar_to_writer(self.codec, client, file_path, entry_path, writer, progress_callback, cancel_rx).await
+    }
+
+    async fn test_integrity_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<IntegrityReport, String> {
+        Self::test_compressed_tar(self.codec, client, file_path, progress_callback, cancel_rx).await
+    }
+
+    async fn extract_entries_to_sink(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<(), String> {
+        Self::extract_compressed_tar_entries(self.codec, client, file_path, sink, cancel_rx).await
+    }
+
+    async fn build_entry_index(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+    ) -> Result<EntryIndex, String> {
+        Self::build_compressed_tar_index(self.codec, client, file_path).await
+    }
+
+    fn compression_type(&self) -> CompressionType {
+        self.codec.compression_type()
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        if !self.codec.matches_magic(data) {
+            return false;
+        }
+
+        // 尝试解压第一个块来确认内部是 TAR 数据
+        let mut header = [0u8; BLOCK_SIZE as usize];
+        let mut filled = 0;
+        if let Ok(mut decoder) = self.codec.decoder(data) {
+            while filled < header.len() {
+                match decoder.read(&mut header[filled..]) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => filled += n,
+                }
+            }
+        }
+
+        if filled == header.len() {
+            return header[257..262] == *b"ustar";
+        }
+
+        // 样本数据不足以解压出第一个块时，xz 和 bzip2 没有对应的单文件处理器，按魔数识别；
+        // gzip、zstd 和 lz4 交给单文件处理器（例如 lz4 默认 4MB 的数据块无法从样本中解压）
+        matches!(self.codec, TarCodec::Xz | TarCodec::Bzip2)
+    }
+}
+
+impl CompressedTarHandler {
+    /// 流式分析压缩 TAR 文件，边读取边解压边解析头部
+    ///
+    /// TAR.GZ 在解压的同时建立 gzip 检查点索引并记录条目头部偏移，之后的预览可直接定位。
+    /// 提供 `progress_callback` 时，解析出的条目以 `AnalysisStatus::Partial` 按批次发送。
+    async fn analyze_compressed_tar(
+        codec: TarCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<AnalysisProgressCallback>,
+    ) -> Result<ArchiveInfo, String> {
+        log::debug!("开始流式分析压缩TAR文件 ({:?}): {}", codec, file_path);
+
+        let (file_size, cache_key) = gzip_index::file_size_and_cache_key(&client, file_path).await?;
+
+        let reporter = progress_callback.map(|callback| AnalysisReporter::new(callback, file_size));
+        let read_progress = reporter.clone().map(|reporter| {
+            Box::new(move |bytes_read: u64, _total: u64| reporter.on_bytes_read(bytes_read)) as Box<dyn Fn(u64, u64) + Send + Sync>
+        });
+
+        let (entries, complete) = if codec == TarCodec::Gzip {
+            consume_file_stream_with_client(&client, file_path, 0, file_size, read_progress.as_deref(), None, move |reader| {
+                let decoder = GzipStreamReader::with_index(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+                let mut headers = TarHeaderIndex::default();
+                let result = Self::collect_entries(&mut walker, Some(&mut headers), reporter.as_deref());
+
+                // 出错时也缓存已建立的部分索引，之后的预览可以从这里继续
+                let mut index = walker.into_inner().into_index();
+                index.tar_headers = headers.finish();
+                gzip_index::cache_index(cache_key, index);
+                result
+            }).await?
+        } else {
+            consume_file_stream_with_client(&client, file_path, 0, file_size, read_progress.as_deref(), None, move |reader| {
+                let decoder = codec.decoder(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+                Self::collect_entries(&mut walker, None, reporter.as_deref())
+            }).await?
+        };
+
+        log::debug!("压缩TAR流式分析完成，找到 {} 个条目 (完整: {})", entries.len(), complete);
+
+        let entry_count = entries.len();
+        let total_uncompressed_size = entries.iter().map(|entry| entry.size).sum();
+
+        Ok(ArchiveInfoBuilder::new(codec.compression_type())
+            .entries(entries)
+            .total_uncompressed_size(total_uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(codec == TarCodec::Gzip)
+            .analysis_status(if complete {
+                AnalysisStatus::Complete
+            } else {
+                AnalysisStatus::Partial { analyzed_entries: entry_count }
+            })
+            .build())
+    }
+
+    /// 完整遍历压缩 TAR 建立列表索引（不受分析条目数量上限限制）
+    ///
+    /// TAR.GZ 同时建立完整的 gzip 检查点索引和条目头部偏移，供之后的预览直接定位。
+    async fn build_compressed_tar_index(
+        codec: TarCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<EntryIndex, String> {
+        log::debug!("开始建立压缩TAR列表索引 ({:?}): {}", codec, file_path);
+
+        let (file_size, cache_key) = gzip_index::file_size_and_cache_key(&client, file_path).await?;
+
+        let (index, complete) = if codec == TarCodec::Gzip {
+            consume_file_stream_with_client(&client, file_path, 0, file_size, None, None, move |reader| {
+                let decoder = GzipStreamReader::with_index(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+                let mut headers = TarHeaderIndex::default();
+                let result = Self::index_entries(&mut walker, Some(&mut headers));
+
+                let mut index = walker.into_inner().into_index();
+                index.tar_headers = headers.finish();
+                gzip_index::cache_index(cache_key, index);
+                result
+            }).await?
+        } else {
+            consume_file_stream_with_client(&client, file_path, 0, file_size, None, None, move |reader| {
+                let decoder = codec.decoder(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+                Self::index_entries(&mut walker, None)
+            }).await?
+        };
+
+        if !complete {
+            log::warn!("压缩TAR数据不完整，列表索引只包含 {} 个条目: {}", index.len(), file_path);
+        }
+        Ok(index)
+    }
+
+    /// 流式提取压缩 TAR 中的文件预览，读到目标条目后立即停止
+    async fn extract_compressed_tar_preview(
+        codec: TarCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        log::debug!("开始流式提取压缩TAR预览 ({:?}): {} -> {}", codec, file_path, entry_path);
+
+        if codec == TarCodec::Gzip {
+            return Self::with_gzip_tar_entry(&client, file_path, entry_path, progress_callback.as_deref(), cancel_rx, move |walker, entry| {
+                Self::read_preview(walker, entry, max_size)
+            }).await;
+        }
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let target = entry_path.to_string();
+        consume_file_stream_with_client(&client, file_path, 0, file_size, progress_callback.as_deref(), cancel_rx, move |reader| {
+            let decoder = codec.decoder(reader)
+                .map_err(|e| format!("Failed to create decoder: {}", e))?;
+            let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+            Self::find_entry(&mut walker, &target, None, |walker, entry| Self::read_preview(walker, entry, max_size))
+        }).await
+    }
+
+    /// 流式解压压缩 TAR 中的条目并写入 `writer`；TAR.GZ 有索引时从最近的检查点开始
+    async fn extract_compressed_tar_to_writer(
+        codec: TarCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<u64, String> {
+        log::debug!("开始流式解压压缩TAR条目 ({:?}): {} -> {}", codec, file_path, entry_path);
+
+        if codec == TarCodec::Gzip {
+            return pipe_to_async_writer(writer, |mut output| Self::with_gzip_tar_entry(
+                &client,
+                file_path,
+                entry_path,
+                progress_callback.as_deref(),
+                cancel_rx,
+                move |walker, entry| {
+                    Self::copy_entry(walker, entry, &mut output)?;
+                    output.finish()
+                },
+            )).await;
+        }
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let target = entry_path.to_string();
+        pipe_to_async_writer(writer, |mut output| consume_file_stream_with_client(
+            &client,
+            file_path,
+            0,
+            file_size,
+            progress_callback.as_deref(),
+            cancel_rx,
+            move |reader| {
+                let decoder = codec.decoder(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+                Self::find_entry(&mut walker, &target, None, |walker, entry| {
+                    Self::copy_entry(walker, entry, &mut output)?;
+                    output.finish()
+                })
+            },
+        )).await
+    }
+
+    /// 批量解压：从头解压一遍，把选中的条目依次写入 `sink`
+    async fn extract_compressed_tar_entries(
+        codec: TarCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        mut sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), String> {
+        log::debug!("开始批量解压压缩TAR条目 ({:?}): {}", codec, file_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        consume_file_stream_with_client(&client, file_path, 0, file_size, None, cancel_rx, move |reader| {
+            let decoder = codec.decoder(reader)
+                .map_err(|e| format!("Failed to create decoder: {}", e))?;
+            TarEntryWalker::new(decoder).with_output_limit(file_size).extract_selected(&mut sink)
+        }).await
+    }
+
+    /// 在 TAR.GZ 中定位条目并交给 `on_entry` 处理
+    ///
+    /// 索引记录了目标条目时从最近的检查点直接定位；索引没有覆盖到目标时，
+    /// 从最后记录的条目头部继续解压并延长索引，没有索引时从头解压并建立索引。
+    async fn with_gzip_tar_entry<T, F>(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        on_entry: F,
+    ) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut TarEntryWalker<GzipStreamReader<ChannelReader>>, &ArchiveEntry) -> Result<T, String> + Send + 'static,
+    {
+        let (file_size, cache_key) = gzip_index::file_size_and_cache_key(client, file_path).await?;
+        let target = entry_path.to_string();
+        let cached = gzip_index::get_cached_index(&cache_key);
+
+        if let Some(index) = cached.as_ref() {
+            if let Some((header_offset, entry_index)) = index.tar_headers.get(entry_path) {
+                return gzip_index::consume_from_offset_with_index(
+                    client,
+                    file_path,
+                    file_size,
+                    index.clone(),
+                    header_offset,
+                    progress_callback,
+                    cancel_rx,
+                    move |decoder| {
+                        let mut walker = TarEntryWalker::with_position(decoder, header_offset, entry_index).with_output_limit(file_size);
+                        match walker.next_entry()? {
+                            Some(entry) if entry.path == target => on_entry(&mut walker, &entry),
+                            _ => Err("Gzip index does not match archive content".to_string()),
+                        }
+                    },
+                ).await;
+            }
+            if index.complete && !index.tar_headers.is_empty() {
+                return Err("File not found in archive".to_string());
+            }
+        }
+
+        if let Some((index, (header_offset, entry_index))) = cached.and_then(|index| index.tar_headers.last().map(|last| (index, last))) {
+            log::debug!("gzip索引未包含目标条目，从解压偏移 {} 继续建立索引: {}", header_offset, file_path);
+            let headers = index.tar_headers.clone();
+            return gzip_index::resume_index_from_offset(
+                client,
+                file_path,
+                file_size,
+                index,
+                header_offset,
+                progress_callback,
+                cancel_rx,
+                move |decoder| {
+                    let walker = TarEntryWalker::with_position(decoder, header_offset, entry_index).with_output_limit(file_size);
+                    Self::find_entry_with_index(walker, headers, &target, cache_key, on_entry)
+                },
+            ).await;
+        }
+
+        // 没有可用的索引时从头解压，同时建立索引供之后的预览使用
+        consume_file_stream_with_client(client, file_path, 0, file_size, progress_callback, cancel_rx, move |reader| {
+            let decoder = GzipStreamReader::with_index(reader)
+                .map_err(|e| format!("Failed to create decoder: {}", e))?;
+            let walker = TarEntryWalker::new(decoder).with_output_limit(file_size);
+            Self::find_entry_with_index(walker, TarHeaderIndex::default(), &target, cache_key, on_entry)
+        }).await
+    }
+
+    /// 顺序查找目标条目并记录途经的条目头部，结束后（包括出错和取消时）缓存建立的索引
+    fn find_entry_with_index<R: Read, T>(
+        mut walker: TarEntryWalker<GzipStreamReader<R>>,
+        mut headers: TarHeaderIndex,
+        target: &str,
+        cache_key: String,
+        on_entry: impl FnOnce(&mut TarEntryWalker<GzipStreamReader<R>>, &ArchiveEntry) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let result = Self::find_entry(&mut walker, target, Some(&mut headers), on_entry);
+
+        let mut index = walker.into_inner().into_index();
+        index.tar_headers = headers.finish();
+        gzip_index::cache_index(cache_key, index);
+        result
+    }
+
+    /// 完整性测试：解压全部数据，校验 TAR 头部以及压缩流自身的校验（gzip 尾部 CRC32 和长度等）
+    async fn test_compressed_tar(
+        codec: TarCodec,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<IntegrityReport, String> {
+        log::debug!("开始测试压缩TAR完整性 ({:?}): {}", codec, file_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        consume_file_stream_with_client(
+            &client,
+            file_path,
+            0,
+            file_size,
+            progress_callback.as_deref(),
+            cancel_rx,
+            move |reader| {
+                let decoder = codec.decoder(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let mut walker = TarEntryWalker::new(decoder).with_output_limit(file_size).with_strict_headers();
+                let (entries, archive_error) = walker.test_entries();
+
+                // TAR 结束标记之后继续读到压缩流末尾，触发尾部校验
+                let archive_error = archive_error.or_else(|| {
+                    io::copy(&mut walker.into_inner(), &mut io::sink()).err()
+                        .map(|e| format!("Compressed stream verification failed: {}", e))
+                });
+                Ok(IntegrityReport::new(entries, archive_error))
+            },
+        ).await
+    }
+
+    fn copy_entry<R: Read, W: Write>(walker: &mut TarEntryWalker<R>, entry: &ArchiveEntry, output: &mut W) -> Result<u64, String> {
+        if entry.is_dir {
+            return Err("Cannot preview directory".to_string());
+        }
+        walker.copy_entry_data(entry, output)
+    }
+
+    /// 遍历条目直到达到分析上限，可选记录每个条目头部的偏移，返回（条目，是否完整）
+    fn collect_entries<R: Read>(
+        walker: &mut TarEntryWalker<R>,
+        headers: Option<&mut TarHeaderIndex>,
+        reporter: Option<&AnalysisReporter>,
+    ) -> Result<(Vec<ArchiveEntry>, bool), String> {
+        let mut entries = Vec::new();
+
+        let complete = Self::walk_entries(walker, headers, |entry| {
+            entries.push(entry);
+            if let Some(reporter) = reporter {
+                reporter.on_entries(&entries);
+            }
+            if entries.len() >= MAX_ENTRIES {
+                log::warn!("压缩TAR条目数量达到限制({})，停止分析", MAX_ENTRIES);
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok((entries, complete))
+    }
+
+    /// 遍历全部条目建立列表索引，返回（索引，是否完整）
+    fn index_entries<R: Read>(
+        walker: &mut TarEntryWalker<R>,
+        headers: Option<&mut TarHeaderIndex>,
+    ) -> Result<(EntryIndex, bool), String> {
+        let mut index = EntryIndex::default();
+        let complete = Self::walk_entries(walker, headers, |entry| {
+            index.push_entry(entry)?;
+            Ok(true)
+        })?;
+        Ok((index, complete))
+    }
+
+    /// 把条目逐个交给 `visitor`，可选记录每个条目头部的偏移；`visitor` 返回 false 时停止
+    ///
+    /// 返回是否读到了 TAR 结束位置。数据损坏或被截断时，如果已经读到条目则保留它们并返回 false。
+    fn walk_entries<R: Read>(
+        walker: &mut TarEntryWalker<R>,
+        mut headers: Option<&mut TarHeaderIndex>,
+        mut visitor: impl FnMut(ArchiveEntry) -> Result<bool, String>,
+    ) -> Result<bool, String> {
+        let mut count = 0usize;
+
+        loop {
+            match walker.next_entry() {
+                Ok(Some(entry)) => {
+                    if let Some(ref mut headers) = headers {
+                        headers.insert(&entry.path, walker.header_offset(), entry.index);
+                    }
+                    count += 1;
+                    if !visitor(entry)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(None) => return Ok(true),
+                Err(e) if count > 0 && !limits::is_limit_error(&e) => {
+                    log::warn!("压缩TAR数据读取中断，保留已解析的 {} 个条目: {}", count, e);
+                    return Ok(false);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// 顺序查找目标条目并交给 `on_entry` 处理，可选记录途经条目头部的偏移
+    fn find_entry<R: Read, T>(
+        walker: &mut TarEntryWalker<R>,
+        target: &str,
+        mut headers: Option<&mut TarHeaderIndex>,
+        on_entry: impl FnOnce(&mut TarEntryWalker<R>, &ArchiveEntry) -> Result<T, String>,
+    ) -> Result<T, String> {
+        while let Some(entry) = walker.next_entry()? {
+            if let Some(ref mut headers) = headers {
+                headers.insert(&entry.path, walker.header_offset(), entry.index);
+            }
+            if entry.path == target {
+                return on_entry(walker, &entry);
+            }
+        }
+
+        Err("File not found in archive".to_string())
+    }
+
+    fn read_preview<R: Read>(walker: &mut TarEntryWalker<R>, entry: &ArchiveEntry, max_size: usize) -> Result<FilePreview, String> {
+        if entry.is_dir {
+            return Err("Cannot preview directory".to_string());
+        }
+
+        let content = walker.read_entry_data(entry, max_size)?;
+        let is_truncated = (content.len() as u64) < entry.size;
+
+        Ok(PreviewBuilder::new()
+            .content(content)
+            .total_size(entry.size)
+            .with_truncated(is_truncated)
+            .build())
+    }
+}
@@ -1,15 +1,75 @@
 /// GZIP 格式处理器
 use crate::archive::types::*;
-use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
-use crate::storage::traits::{StorageClient, ProgressCallback};
+use crate::archive::formats::{CompressionHandlerDispatcher, bgzf, common::*};
+use crate::archive::formats::gzip_index::{self, GzipStreamReader};
+use crate::archive::limits::{self, LimitExceeded, LimitedReader, OutputLimit};
+use crate::storage::traits::StorageClient;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::io::{Cursor, Read};
-use flate2::read::GzDecoder;
-
+use std::io::{BufRead, Read};
+
+/// 头部 FLG 字段的标志位
+const FLAG_FHCRC: u8 = 0x02;
+const FLAG_FEXTRA: u8 = 0x04;
+const FLAG_FNAME: u8 = 0x08;
+const FLAG_FCOMMENT: u8 = 0x10;
+/// 成员尾部（CRC32 + ISIZE）长度
+const GZIP_TRAILER_SIZE: u64 = 8;
+/// 最小的完整成员长度（10 字节头部 + 空 deflate 块 + 尾部）
+const MIN_MEMBER_SIZE: u64 = 20;
+/// 分析时样本的最小长度，保证能读到较长的文件名和注释
+const MIN_SAMPLE_SIZE: u64 = 64 * 1024;
+/// 不超过此大小的文件在分析时完整扫描成员边界，更大的文件只扫描样本
+const MEMBER_SCAN_LIMIT: u64 = 16 * 1024 * 1024;
+/// 元数据中最多列出的成员偏移数量（BGZF 文件每 64KB 一个成员）
+const MAX_REPORTED_MEMBERS: usize = 1024;
+/// 成员扫描的读取和解压缓冲区大小
+const SCAN_BUFFER_SIZE: usize = 64 * 1024;
 
 pub struct GzipHandler;
 
+/// 解析后的 gzip 成员头部
+struct GzipHeader {
+    /// 头部长度，即 deflate 数据的起始偏移
+    header_size: usize,
+    /// 修改时间（Unix 时间戳，0 表示未记录）
+    mtime: u32,
+    os: u8,
+    filename: Option<String>,
+    comment: Option<String>,
+    /// BGZF 块总长度，不是 BGZF 块时为 None
+    bgzf_block_size: Option<u64>,
+}
+
+/// gzip 成员的起始位置
+pub(crate) struct GzipMember {
+    pub compressed_offset: u64,
+    pub uncompressed_offset: u64,
+}
+
+/// 成员扫描结果
+#[derive(Default)]
+pub(crate) struct MemberScan {
+    pub members: Vec<GzipMember>,
+    /// 已扫描的压缩数据长度
+    pub compressed_read: u64,
+    /// 已扫描部分对应的原始数据长度
+    pub uncompressed_read: u64,
+    /// 是否扫描到数据末尾
+    pub complete: bool,
+    pub bgzf: bool,
+}
+
+impl MemberScan {
+    /// 按已扫描部分的压缩比估算原始大小
+    fn estimate_total_size(&self, file_size: u64) -> u64 {
+        if self.compressed_read == 0 || self.uncompressed_read == 0 {
+            return file_size;
+        }
+        (file_size as f64 * self.uncompressed_read as f64 / self.compressed_read as f64) as u64
+    }
+}
+
 #[async_trait::async_trait]
 impl CompressionHandlerDispatcher for GzipHandler {
     async fn analyze_with_client(
@@ -19,7 +79,18 @@ impl CompressionHandlerDispatcher for GzipHandler {
         _filename: &str,
         max_size: Option<usize>,
     ) -> Result<ArchiveInfo, String> {
-        Self::analyze_with_storage_client(client, file_path, max_size).await
+        Self::analyze_with_storage_client(client, file_path, max_size, None).await
+    }
+
+    async fn analyze_with_progress(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        max_size: Option<usize>,
+        progress_callback: Option<AnalysisProgressCallback>,
+    ) -> Result<ArchiveInfo, String> {
+        Self::analyze_with_storage_client(client, file_path, max_size, progress_callback).await
     }
 
     async fn extract_preview_with_client(
@@ -34,6 +105,45 @@ impl CompressionHandlerDispatcher for GzipHandler {
         Self::extract_gzip_preview_streaming(client, file_path, max_size, progress_callback, cancel_rx).await
     }
 
+    async fn extract_preview_at_offset(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        offset: u64,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_gzip_preview_at_offset(client, file_path, offset, max_size, progress_callback, cancel_rx).await
+    }
+
+    async fn extract_entry_to_writer(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<u64, String> {
+        decompress_file_to_writer(&client, file_path, writer, progress_callback.as_deref(), cancel_rx, |reader| {
+            Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)) as Box<dyn Read>)
+        }).await
+    }
+
+    async fn test_integrity_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<IntegrityReport, String> {
+        Self::test_gzip_integrity(client, file_path, progress_callback, cancel_rx).await
+    }
+
     fn compression_type(&self) -> CompressionType {
         CompressionType::Gzip
     }
@@ -49,6 +159,7 @@ impl GzipHandler {
         client: Arc<dyn StorageClient>,
         file_path: &str,
         max_size: Option<usize>,
+        progress_callback: Option<AnalysisProgressCallback>,
     ) -> Result<ArchiveInfo, String> {
         log::debug!("使用storage client分析GZIP文件: {}", file_path);
 
@@ -58,186 +169,555 @@ impl GzipHandler {
 
         log::debug!("GZIP文件大小: {} 字节", file_size);
 
+        // 完整扫描成员边界时通过分析进度报告读取进度
+        let reporter = progress_callback.map(|callback| AnalysisReporter::new(callback, file_size));
+        let read_progress = reporter.map(|reporter| {
+            Box::new(move |bytes_read: u64, _total: u64| reporter.on_bytes_read(bytes_read)) as Box<dyn Fn(u64, u64) + Send + Sync>
+        });
+
         // 使用流式分析，只读取必要的部分
-        Self::analyze_gzip_streaming(client, file_path, file_size, max_size).await
+        Self::analyze_gzip_streaming(client, file_path, file_size, max_size, read_progress.as_deref(), None).await
     }
 
-    /// 使用存储客户端提取GZIP文件预览
+    /// 完整性测试：解压全部数据，由解压器校验每个 gzip 成员尾部的 CRC32 和长度
+    async fn test_gzip_integrity(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<IntegrityReport, String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let header_data = client.read_file_range(file_path, 0, 1024.min(file_size)).await
+            .map_err(|e| format!("Failed to read GZIP header: {}", e))?;
+        if !Self::validate_gzip_header(&header_data) {
+            return Err("Invalid GZIP header".to_string());
+        }
+        let path = Self::extract_original_filename(&header_data)
+            .unwrap_or_else(|| "compressed_content".to_string());
 
+        // 解压错误属于条目结果，读取失败和取消才作为错误返回
+        let (size, error) = consume_file_stream_with_client(
+            &client,
+            file_path,
+            0,
+            file_size,
+            progress_callback.as_deref(),
+            cancel_rx,
+            move |reader| {
+                let decoder = flate2::read::MultiGzDecoder::new(reader);
+                let mut decoder = LimitedReader::new(decoder, OutputLimit::for_compressed_size(file_size));
+                let mut sink = CountingSink::default();
+                let error = std::io::copy(&mut decoder, &mut sink).err()
+                    .map(|e| limits::map_io_error(e, |e| format!("GZIP verification failed: {}", e)));
+                // 超出解压限制时中止测试，不作为条目的校验结果
+                match error {
+                    Some(error) if limits::is_limit_error(&error) => Err(error),
+                    error => Ok((sink.written, error)),
+                }
+            },
+        ).await?;
+
+        let entry = match error {
+            Some(error) => EntryIntegrity::failed(path, size, error),
+            None => EntryIntegrity::passed(path, size),
+        };
+        Ok(IntegrityReport::new(vec![entry], None))
+    }
 
-    /// 流式分析GZIP文件，只读取必要的头部和少量内容
+    /// 流式分析GZIP文件，只读取必要的头部、样本和尾部
+    ///
+    /// 原始大小优先取自成员扫描；只扫描了样本时无法确认后面没有其他成员，
+    /// 原始大小标记为估算：样本内只有一个成员时使用尾部 ISIZE，否则按样本压缩比估算。
+    /// 尾部 CRC32 只在完整扫描确认只有一个成员时报告。
+    ///
+    /// 完整扫描（不超过 `MEMBER_SCAN_LIMIT` 的文件）时通过 `progress_callback` 报告读取进度，
+    /// 并响应取消信号。
     async fn analyze_gzip_streaming(
         client: Arc<dyn StorageClient>,
         file_path: &str,
         file_size: u64,
         max_sample_size: Option<usize>,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<ArchiveInfo, String> {
         log::debug!("开始流式分析GZIP文件: {} ({} 字节)", file_path, file_size);
 
-        // 读取GZIP头部用于验证和提取元数据
-        const HEADER_SIZE: u64 = 1024; // 读取前1KB用于头部分析
-        let header_size = HEADER_SIZE.min(file_size);
+        // 样本同时用于解析头部（文件名和注释可能较长）和估算压缩比
+        let sample_size = max_sample_size.unwrap_or(64 * 1024) as u64;
+        let read_size = (sample_size * 2).max(MIN_SAMPLE_SIZE).min(file_size);
 
-        let header_data = client.read_file_range(file_path, 0, header_size).await
-            .map_err(|e| format!("Failed to read GZIP header: {}", e))?;
+        let sample = client.read_file_range(file_path, 0, read_size).await
+            .map_err(|e| format!("Failed to read GZIP data for analysis: {}", e))?;
 
-        if !Self::validate_gzip_header(&header_data) {
+        if !Self::validate_gzip_header(&sample) {
             return Err("Invalid GZIP header".to_string());
         }
+        let header = Self::read_header(&mut sample.as_slice())
+            .map_err(|e| format!("Invalid GZIP header: {}", e))?;
+
+        // 小文件完整扫描成员边界，大文件只扫描样本
+        let whole_file = read_size == file_size || file_size <= MEMBER_SCAN_LIMIT;
+        let limit = OutputLimit::for_compressed_size(file_size);
+        let scan = if read_size == file_size || file_size > MEMBER_SCAN_LIMIT {
+            Self::scan_members(sample.as_slice(), limit)?
+        } else {
+            consume_file_stream_with_client(&client, file_path, 0, file_size, progress_callback, cancel_rx, move |reader| {
+                Self::scan_members(reader, limit)
+            }).await?
+        };
+        let scan_complete = whole_file && scan.complete;
+        let multi_member = scan.members.len() > 1 || scan.bgzf;
+
+        // 尾部：CRC32 + ISIZE（最后一个成员）
+        let trailer = if file_size < MIN_MEMBER_SIZE {
+            None
+        } else if read_size == file_size {
+            Some(sample[sample.len() - GZIP_TRAILER_SIZE as usize..].to_vec())
+        } else {
+            Some(client.read_file_range(file_path, file_size - GZIP_TRAILER_SIZE, GZIP_TRAILER_SIZE).await
+                .map_err(|e| format!("Failed to read GZIP trailer: {}", e))?)
+        };
+        let trailer = trailer.filter(|data| data.len() == GZIP_TRAILER_SIZE as usize).map(|data| {
+            let crc32 = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+            let isize = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+            (crc32, isize)
+        });
 
-        // 提取原始文件名
-        let original_filename = Self::extract_original_filename(&header_data)
-            .unwrap_or_else(|| "compressed_content".to_string());
-
-        // 确定要读取的样本大小用于内容分析
-        let sample_size = max_sample_size.unwrap_or(64 * 1024); // 默认64KB
-        let read_size = (sample_size * 2).min(file_size as usize); // 考虑压缩比，读取2倍大小
-
-        let compressed_data = client.read_file_range(file_path, 0, read_size as u64).await
-            .map_err(|e| format!("Failed to read GZIP data for analysis: {}", e))?;
+        let (uncompressed_size, size_estimated) = match trailer {
+            _ if scan_complete => (scan.uncompressed_read, false),
+            // 完整扫描却未到达末尾说明数据截断或损坏，尾部不可信
+            _ if whole_file => (scan.estimate_total_size(file_size), true),
+            // 只扫描了样本：后面可能还有其他成员，ISIZE 只作为估算
+            Some((_, isize)) if !multi_member => {
+                let estimate = scan.estimate_total_size(file_size);
+                let size = Self::resolve_isize(isize, estimate);
+                if size < scan.uncompressed_read {
+                    // 样本解压出的数据已超过 ISIZE，说明后面还有其他成员
+                    (estimate, true)
+                } else {
+                    (size, true)
+                }
+            }
+            _ => (scan.estimate_total_size(file_size), true),
+        };
 
-        // 流式解压缩样本数据来估算大小
-        let uncompressed_sample = Self::decompress_sample(&compressed_data, sample_size)?;
+        log::debug!(
+            "GZIP原始大小: {} 字节 (估算: {}), 已扫描 {} 个成员 (完整: {})",
+            uncompressed_size, size_estimated, scan.members.len(), scan_complete
+        );
 
-        // 估算解压后的总大小（基于样本压缩比）
-        let compression_ratio = compressed_data.len() as f64 / uncompressed_sample.len() as f64;
-        let estimated_uncompressed_size = (file_size as f64 / compression_ratio) as u64;
+        let mut metadata = HashMap::new();
+        if header.mtime != 0 {
+            metadata.insert("mtime".to_string(), header.mtime.to_string());
+        }
+        metadata.insert("os".to_string(), Self::os_name(header.os).to_string());
+        if let Some(ref comment) = header.comment {
+            metadata.insert("comment".to_string(), comment.clone());
+        }
+        if let Some((_, isize)) = trailer {
+            metadata.insert("isize".to_string(), isize.to_string());
+        }
+        metadata.insert("multi_member".to_string(), multi_member.to_string());
+        if scan.bgzf {
+            metadata.insert("bgzf".to_string(), "true".to_string());
+        }
+        if scan_complete {
+            metadata.insert("members".to_string(), scan.members.len().to_string());
+        }
+        let reported = &scan.members[..scan.members.len().min(MAX_REPORTED_MEMBERS)];
+        let join_offsets = |offset: fn(&GzipMember) -> u64| {
+            reported.iter().map(|member| offset(member).to_string()).collect::<Vec<_>>().join(",")
+        };
+        metadata.insert("member_offsets".to_string(), join_offsets(|member| member.compressed_offset));
+        metadata.insert("member_uncompressed_offsets".to_string(), join_offsets(|member| member.uncompressed_offset));
+        if !scan_complete || reported.len() < scan.members.len() {
+            metadata.insert("member_offsets_truncated".to_string(), "true".to_string());
+        }
+        if size_estimated {
+            metadata.insert("size_estimated".to_string(), "true".to_string());
+        }
 
         let entry = ArchiveEntry {
-            path: original_filename.clone(),
-            size: estimated_uncompressed_size,
+            path: header.filename.unwrap_or_else(|| "compressed_content".to_string()),
+            size: uncompressed_size,
             compressed_size: Some(file_size),
             is_dir: false,
-            modified_time: None,
-            crc32: None,
+            modified_time: Some(header.mtime)
+                .filter(|&mtime| mtime != 0)
+                .and_then(|mtime| chrono::DateTime::from_timestamp(mtime as i64, 0))
+                .map(|datetime| datetime.to_rfc3339()),
+            // 多成员文件的尾部 CRC32 只覆盖最后一个成员，只扫描样本时无法确认只有一个成员
+            crc32: trailer.filter(|_| scan_complete && !multi_member).map(|(crc32, _)| crc32),
             index: 0,
-            metadata: HashMap::new(),
+            metadata,
         };
 
         Ok(ArchiveInfoBuilder::new(CompressionType::Gzip)
             .entries(vec![entry])
             .total_entries(1)
-            .total_uncompressed_size(estimated_uncompressed_size)
+            .total_uncompressed_size(uncompressed_size)
             .total_compressed_size(file_size)
             .supports_streaming(true)
-            .supports_random_access(false)
+            .supports_random_access(scan.bgzf)
             .analysis_status(AnalysisStatus::Complete)
             .build())
     }
 
+    /// ISIZE 只记录原始大小对 2^32 取模的值，取与估算大小最接近的同余值
+    fn resolve_isize(isize: u32, estimate: u64) -> u64 {
+        const MODULUS: u64 = 1 << 32;
+        let wraps = (estimate.saturating_sub(isize as u64) + MODULUS / 2) / MODULUS;
+        isize as u64 + wraps * MODULUS
+    }
+
+    /// 顺序扫描 gzip 成员边界
+    ///
+    /// 普通成员需要解压才能找到 deflate 流的结尾；BGZF 块在头部记录了块大小，直接跳过压缩数据并从尾部读取原始大小。
+    /// 数据截断或损坏时返回已扫描的部分；解压输出超出 `limit` 时返回 `archive.limit.*` 错误。
+    pub(crate) fn scan_members<R: Read>(reader: R, limit: OutputLimit) -> Result<MemberScan, String> {
+        let mut reader = std::io::BufReader::with_capacity(SCAN_BUFFER_SIZE, reader);
+        let mut scan = MemberScan::default();
+        if let Err(e) = Self::scan_next_members(&mut reader, &mut scan, limit) {
+            if let Some(limit) = e.get_ref().and_then(|inner| inner.downcast_ref::<LimitExceeded>()) {
+                return Err(limit.to_string());
+            }
+            log::debug!("GZIP成员扫描在偏移 {} 处结束: {}", scan.compressed_read, e);
+        }
+        Ok(scan)
+    }
+
+    fn scan_next_members<R: BufRead>(reader: &mut R, scan: &mut MemberScan, limit: OutputLimit) -> std::io::Result<()> {
+        let mut output = vec![0u8; SCAN_BUFFER_SIZE];
+
+        loop {
+            // 与解压时一致，成员之后不是 gzip 魔数的数据（如填充）视为文件结束
+            let next = reader.fill_buf()?;
+            if next.first() != Some(&0x1f) {
+                scan.complete = true;
+                return Ok(());
+            }
+
+            let header = Self::read_header(reader)?;
+            scan.members.push(GzipMember {
+                compressed_offset: scan.compressed_read,
+                uncompressed_offset: scan.uncompressed_read,
+            });
+            scan.compressed_read += header.header_size as u64;
+
+            match header.bgzf_block_size {
+                Some(block_size) => {
+                    scan.bgzf = true;
+                    let data_size = block_size.checked_sub(header.header_size as u64 + GZIP_TRAILER_SIZE)
+                        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid BGZF block size"))?;
+                    let skipped = std::io::copy(&mut reader.by_ref().take(data_size), &mut std::io::sink())?;
+                    if skipped < data_size {
+                        return Err(std::io::ErrorKind::UnexpectedEof.into());
+                    }
+                    scan.compressed_read += data_size;
+                }
+                None => {
+                    let mut inflater = flate2::Decompress::new(false);
+                    loop {
+                        let input = reader.fill_buf()?;
+                        if input.is_empty() {
+                            return Err(std::io::ErrorKind::UnexpectedEof.into());
+                        }
+                        let (total_in, total_out) = (inflater.total_in(), inflater.total_out());
+                        let status = inflater.decompress(input, &mut output, flate2::FlushDecompress::None)
+                            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                        let consumed = inflater.total_in() - total_in;
+                        let produced = inflater.total_out() - total_out;
+                        reader.consume(consumed as usize);
+                        scan.compressed_read += consumed;
+                        scan.uncompressed_read += produced;
+                        limit.check(scan.uncompressed_read)?;
+
+                        if status == flate2::Status::StreamEnd {
+                            break;
+                        }
+                        if consumed == 0 && produced == 0 {
+                            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "deflate stream made no progress"));
+                        }
+                    }
+                }
+            }
+
+            let mut trailer = [0u8; GZIP_TRAILER_SIZE as usize];
+            reader.read_exact(&mut trailer)?;
+            scan.compressed_read += GZIP_TRAILER_SIZE;
+            if header.bgzf_block_size.is_some() {
+                scan.uncompressed_read += u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]) as u64;
+                limit.check(scan.uncompressed_read)?;
+            }
+        }
+    }
+
     /// 流式提取GZIP预览，只读取和解压必要的部分
+    ///
+    /// 按需顺序读取并解压（支持多成员文件），得到预览数据后停止读取；
+    /// 没有读到末尾时原始大小取自成员扫描或尾部 ISIZE。
     async fn extract_gzip_preview_streaming(
         client: Arc<dyn StorageClient>,
         file_path: &str,
         max_size: usize,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
-        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
         log::debug!("开始流式提取GZIP预览: {}", file_path);
 
         let file_size = client.get_file_size(file_path).await
             .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        // 估算需要读取的压缩数据大小（考虑压缩比）
-        // 通常文本压缩比在3-5倍，二进制文件1.5-2倍
-        let estimated_compressed_size = (max_size * 3).max(4096); // 至少读取4KB
-        let read_size = std::cmp::min(estimated_compressed_size as u64, file_size);
+        let (content, reached_end) = consume_file_stream_with_client(
+            &client,
+            file_path,
+            0,
+            file_size,
+            progress_callback.as_deref(),
+            cancel_rx.as_deref_mut(),
+            move |mut reader| {
+                let header = reader.fill_buf()
+                    .map_err(|e| format!("Failed to read GZIP header: {}", e))?;
+                if !Self::validate_gzip_header(header) {
+                    return Err("Invalid GZIP header".to_string());
+                }
+                Self::read_preview_data(&mut flate2::read::MultiGzDecoder::new(reader), max_size)
+            },
+        ).await?;
+
+        let total_size = if reached_end {
+            content.len() as u64
+        } else {
+            Self::analyze_gzip_streaming(client, file_path, file_size, None, progress_callback.as_deref(), cancel_rx).await?
+                .total_uncompressed_size
+                .max(content.len() as u64)
+        };
 
-        // 直接读取全部数据，避免人为分块导致的性能问题
-        let progress_cb = progress_callback.map(|cb| {
-            Arc::new(move |current: u64, total: u64| {
-                cb(current, total);
-            }) as ProgressCallback
-        });
-        
-        let compressed_data = client.read_file_range_with_progress(file_path, 0, read_size, progress_cb, cancel_rx).await
-            .map_err(|e| format!("Failed to read GZIP data: {}", e))?;
+        Ok(PreviewBuilder::new()
+            .with_truncated(!reached_end)
+            .content(content)
+            .total_size(total_size)
+            .build())
+    }
 
-        if !Self::validate_gzip_header(&compressed_data) {
-            return Err("Invalid GZIP header".to_string());
+    /// 从解压偏移 `offset` 开始提取预览
+    ///
+    /// BGZF 文件按块索引只下载覆盖该范围的块；普通 gzip 只能顺序解压，
+    /// 已有检查点索引时从最近的检查点开始，否则从头解压并建立索引供之后使用。
+    async fn extract_gzip_preview_at_offset(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        offset: u64,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let (file_size, cache_key) = gzip_index::file_size_and_cache_key(&client, file_path).await?;
+
+        // 先读取较小的头部，文件名或注释较长时再扩大读取范围
+        let mut header = None;
+        for read_size in [1024, MIN_SAMPLE_SIZE] {
+            let header_data = client.read_file_range(file_path, 0, read_size.min(file_size)).await
+                .map_err(|e| format!("Failed to read GZIP header: {}", e))?;
+            if !Self::validate_gzip_header(&header_data) {
+                return Err("Invalid GZIP header".to_string());
+            }
+            match Self::read_header(&mut header_data.as_slice()) {
+                Ok(parsed) => {
+                    header = Some(parsed);
+                    break;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof && read_size < file_size => continue,
+                Err(e) => return Err(format!("Invalid GZIP header: {}", e)),
+            }
+        }
+        let header = header.ok_or("Invalid GZIP header: header too long")?;
+
+        if header.bgzf_block_size.is_some() {
+            let index = bgzf::load_index(
+                &client, file_path, file_size, cache_key, progress_callback.as_deref(), cancel_rx.as_deref_mut(),
+            ).await?;
+            return bgzf::read_preview_at_offset(
+                &client, file_path, file_size, &index, offset, max_size, progress_callback, cancel_rx,
+            ).await;
+        }
+        if offset == 0 {
+            return Self::extract_gzip_preview_streaming(client, file_path, max_size, progress_callback, cancel_rx).await;
         }
 
-        // 流式解压缩预览数据
-        let preview_data = Self::decompress_sample(&compressed_data, max_size)?;
-        
-        // 基于样本数据估算总文件大小
-        let compression_ratio = if preview_data.len() > 0 {
-            compressed_data.len() as f64 / preview_data.len() as f64
-        } else {
-            3.0 // 默认压缩比
+        let (content, reached_end) = match gzip_index::get_cached_index(&cache_key) {
+            // 索引没有覆盖到目标偏移时从最后一个检查点继续建立
+            Some(index) if !index.complete && offset > index.indexed_size => gzip_index::resume_index_from_offset(
+                &client,
+                file_path,
+                file_size,
+                index,
+                offset,
+                progress_callback.as_deref(),
+                cancel_rx.as_deref_mut(),
+                move |mut decoder| {
+                    let result = Self::read_preview_data(&mut decoder, max_size);
+                    gzip_index::cache_index(cache_key, decoder.into_index());
+                    result
+                },
+            ).await?,
+            Some(index) => gzip_index::consume_from_offset_with_index(
+                &client,
+                file_path,
+                file_size,
+                index,
+                offset,
+                progress_callback.as_deref(),
+                cancel_rx.as_deref_mut(),
+                move |mut decoder| Self::read_preview_data(&mut decoder, max_size),
+            ).await?,
+            None => consume_file_stream_with_client(&client, file_path, 0, file_size, progress_callback.as_deref(), cancel_rx.as_deref_mut(), move |reader| {
+                let mut decoder = GzipStreamReader::with_index(reader)
+                    .map_err(|e| format!("Failed to create decoder: {}", e))?;
+                let result = match std::io::copy(&mut (&mut decoder).take(offset), &mut std::io::sink()) {
+                    Ok(skipped) if skipped < offset => Err("Unexpected end of gzip data".to_string()),
+                    Ok(_) => Self::read_preview_data(&mut decoder, max_size),
+                    Err(e) => Err(format!("Failed to decompress gzip data: {}", e)),
+                };
+                // 出错或取消时也缓存已建立的部分索引
+                gzip_index::cache_index(cache_key, decoder.into_index());
+                result
+            }).await?,
         };
-        
-        // 估算完整解压后的文件大小
-        let estimated_total_size = (file_size as f64 / compression_ratio) as u64;
-        
-        // 判断是否被截断
-        let is_truncated = preview_data.len() >= max_size || estimated_total_size > preview_data.len() as u64;
 
-        // 检测内容类型
-        let _mime_type = detect_mime_type(&preview_data);
+        // 没有读到末尾时使用分析得到的原始大小
+        let total_size = if reached_end {
+            offset + content.len() as u64
+        } else {
+            Self::analyze_gzip_streaming(client, file_path, file_size, None, progress_callback.as_deref(), cancel_rx).await?
+                .total_uncompressed_size
+                .max(offset + content.len() as u64)
+        };
 
         Ok(PreviewBuilder::new()
-            .content(preview_data)
-            .total_size(estimated_total_size)
-            .with_truncated(is_truncated)
+            .with_truncated(!reached_end)
+            .content(content)
+            .total_size(total_size)
             .build())
     }
 
-    /// 解压缩样本数据
-    fn decompress_sample(compressed_data: &[u8], max_output_size: usize) -> Result<Vec<u8>, String> {
-        let mut decoder = GzDecoder::new(Cursor::new(compressed_data));
-        let mut buffer = vec![0u8; max_output_size];
-
-        let bytes_read = decoder.read(&mut buffer)
+    /// 读取最多 `max_size` 字节解压数据，并返回是否已到达数据末尾
+    fn read_preview_data<R: Read>(decoder: &mut R, max_size: usize) -> Result<(Vec<u8>, bool), String> {
+        let mut content = Vec::new();
+        decoder.take(max_size as u64 + 1).read_to_end(&mut content)
             .map_err(|e| format!("Failed to decompress data: {}", e))?;
-
-        buffer.truncate(bytes_read);
-        Ok(buffer)
+        let reached_end = content.len() <= max_size;
+        content.truncate(max_size);
+        Ok((content, reached_end))
     }
 
-
-
     // 辅助方法
     fn validate_gzip_header(data: &[u8]) -> bool {
         data.len() >= 3 && data[0] == 0x1f && data[1] == 0x8b && data[2] == 0x08
     }
 
     fn extract_original_filename(data: &[u8]) -> Option<String> {
-        if data.len() < 10 {
-            return None;
-        }
+        Self::read_header(&mut &data[..]).ok()?.filename
+    }
 
-        // 检查FLG字段中的FNAME位
-        let flg = data[3];
-        if (flg & 0x08) == 0 {
-            return None; // 没有文件名
+    /// 读取一个 gzip 成员头部（RFC 1952）
+    fn read_header<R: Read>(reader: &mut R) -> std::io::Result<GzipHeader> {
+        let mut fixed = [0u8; 10];
+        reader.read_exact(&mut fixed)?;
+        if !Self::validate_gzip_header(&fixed) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid gzip member header"));
         }
 
-        // 跳过固定头部 (10 bytes)
-        let mut offset = 10;
+        let flags = fixed[3];
+        let mut header = GzipHeader {
+            header_size: fixed.len(),
+            mtime: u32::from_le_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]),
+            os: fixed[9],
+            filename: None,
+            comment: None,
+            bgzf_block_size: None,
+        };
 
-        // 如果有FEXTRA标志，跳过额外字段
-        if (flg & 0x04) != 0 {
-            if offset + 2 > data.len() {
-                return None;
-            }
-            let xlen = u16::from_le_bytes([data[offset], data[offset + 1]]);
-            offset += 2 + xlen as usize;
+        if flags & FLAG_FEXTRA != 0 {
+            let mut xlen = [0u8; 2];
+            reader.read_exact(&mut xlen)?;
+            let mut extra = vec![0u8; u16::from_le_bytes(xlen) as usize];
+            reader.read_exact(&mut extra)?;
+            header.header_size += 2 + extra.len();
+            header.bgzf_block_size = Self::parse_bgzf_block_size(&extra);
+        }
+        if flags & FLAG_FNAME != 0 {
+            let bytes = Self::read_zero_terminated(reader)?;
+            header.header_size += bytes.len() + 1;
+            header.filename = Some(Self::decode_header_string(bytes));
+        }
+        if flags & FLAG_FCOMMENT != 0 {
+            let bytes = Self::read_zero_terminated(reader)?;
+            header.header_size += bytes.len() + 1;
+            header.comment = Some(Self::decode_header_string(bytes));
+        }
+        if flags & FLAG_FHCRC != 0 {
+            let mut crc16 = [0u8; 2];
+            reader.read_exact(&mut crc16)?;
+            header.header_size += crc16.len();
         }
 
-        // 读取文件名（以null结尾）
-        if offset >= data.len() {
-            return None;
+        Ok(header)
+    }
+
+    /// 从 extra 字段的 BC 子字段读取 BGZF 块总长度（BSIZE + 1）
+    fn parse_bgzf_block_size(extra: &[u8]) -> Option<u64> {
+        let mut offset = 0;
+        while offset + 4 <= extra.len() {
+            let subfield_size = u16::from_le_bytes([extra[offset + 2], extra[offset + 3]]) as usize;
+            let data = extra.get(offset + 4..offset + 4 + subfield_size)?;
+            if extra[offset] == b'B' && extra[offset + 1] == b'C' && subfield_size == 2 {
+                return Some(u16::from_le_bytes([data[0], data[1]]) as u64 + 1);
+            }
+            offset += 4 + subfield_size;
         }
+        None
+    }
 
-        let mut filename_bytes = Vec::new();
-        for &byte in &data[offset..] {
-            if byte == 0 {
-                break;
+    fn read_zero_terminated<R: Read>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let mut byte = [0u8; 1];
+        loop {
+            reader.read_exact(&mut byte)?;
+            if byte[0] == 0 {
+                return Ok(bytes);
             }
-            filename_bytes.push(byte);
+            bytes.push(byte[0]);
         }
+    }
 
-        String::from_utf8(filename_bytes).ok()
+    /// 头部字符串按规范为 ISO 8859-1，但很多工具直接写入 UTF-8
+    fn decode_header_string(bytes: Vec<u8>) -> String {
+        String::from_utf8(bytes)
+            .unwrap_or_else(|e| e.into_bytes().into_iter().map(char::from).collect())
+    }
+
+    /// 头部 OS 字段对应的系统名称
+    fn os_name(os: u8) -> &'static str {
+        match os {
+            0 => "FAT",
+            1 => "Amiga",
+            2 => "VMS",
+            3 => "Unix",
+            4 => "VM/CMS",
+            5 => "Atari TOS",
+            6 => "HPFS",
+            7 => "Macintosh",
+            8 => "Z-System",
+            9 => "CP/M",
+            10 => "TOPS-20",
+            11 => "NTFS",
+            12 => "QDOS",
+            13 => "Acorn RISCOS",
+            _ => "unknown",
+        }
     }
 }
+
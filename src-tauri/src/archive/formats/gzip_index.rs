@@ -0,0 +1,618 @@
+/// zran 风格的 gzip 随机访问索引
+///
+/// 首次顺序解压时，每隔一段解压数据就在 deflate 块边界记录一个检查点：
+/// 压缩数据偏移、边界处未对齐的位数，以及此前 32KB 的解压窗口。
+/// 之后从最近的检查点恢复解压器即可直接定位到任意解压偏移，无需从头解压。
+/// 索引按存储协议、文件路径、大小和 ETag 缓存在内存中；
+/// 没有解压到末尾的索引可以从最后一个检查点继续建立。
+use crate::archive::formats::common::*;
+use crate::storage::traits::StorageClient;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use libz_rs_sys as zlib;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// deflate 最大回溯距离
+const WINDOW_SIZE: usize = 32 * 1024;
+/// 相邻检查点之间的解压数据间隔
+const CHECKPOINT_SPAN: u64 = 4 * 1024 * 1024;
+/// 单个索引最多保留的检查点数量，超过时间隔加倍并丢弃一半检查点
+const MAX_CHECKPOINTS: usize = 1024;
+/// 最多缓存的索引数量
+const MAX_CACHED_INDEXES: usize = 16;
+/// 压缩数据输入缓冲区大小
+const INPUT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 自动识别 gzip/zlib 头部的窗口参数
+const WINDOW_BITS_GZIP: i32 = 15 + 32;
+/// 不带头部的 raw deflate 窗口参数（从检查点恢复时使用）
+const WINDOW_BITS_RAW: i32 = -15;
+/// gzip 成员尾部（CRC32 + ISIZE）长度
+const GZIP_TRAILER_SIZE: usize = 8;
+
+/// 按插入顺序淘汰的索引缓存
+type GzipIndexCache = VecDeque<(String, Arc<GzipIndex>)>;
+
+static GZIP_INDEX_CACHE: LazyLock<Mutex<GzipIndexCache>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// 解压检查点
+#[derive(Clone)]
+pub(crate) struct GzipCheckpoint {
+    /// 检查点对应的解压数据偏移
+    pub uncompressed_offset: u64,
+    /// 检查点之后第一个完整字节的压缩数据偏移
+    pub compressed_offset: u64,
+    /// 检查点位于字节中间时，前一个字节中属于下一个块的位数
+    pub bits: u8,
+    /// 压缩保存的解压窗口
+    window: Vec<u8>,
+}
+
+impl GzipCheckpoint {
+    /// 恢复解压时需要开始读取的压缩数据偏移
+    pub fn read_offset(&self) -> u64 {
+        self.compressed_offset - if self.bits > 0 { 1 } else { 0 }
+    }
+
+    fn window(&self) -> io::Result<Vec<u8>> {
+        let mut window = Vec::with_capacity(WINDOW_SIZE);
+        DeflateDecoder::new(self.window.as_slice()).read_to_end(&mut window)?;
+        Ok(window)
+    }
+}
+
+/// gzip 检查点索引
+pub(crate) struct GzipIndex {
+    pub checkpoints: Vec<GzipCheckpoint>,
+    /// 建立索引时使用的检查点间隔
+    span: u64,
+    /// 已建立索引的解压数据大小
+    pub indexed_size: u64,
+    /// 是否已解压到文件末尾
+    pub complete: bool,
+    /// TAR.GZ 条目头部在解压数据中的偏移和条目索引
+    pub tar_headers: TarHeaderIndex,
+}
+
+impl GzipIndex {
+    /// 查找不超过 `offset` 的最近检查点
+    pub fn checkpoint_before(&self, offset: u64) -> Option<&GzipCheckpoint> {
+        let position = self.checkpoints.partition_point(|checkpoint| checkpoint.uncompressed_offset <= offset);
+        position.checked_sub(1).map(|i| &self.checkpoints[i])
+    }
+}
+
+/// TAR.GZ 条目头部索引
+///
+/// 只保存路径哈希、头部偏移和条目索引（每个条目 24 字节），条目很多时也能记录全部头部；
+/// 哈希冲突由调用方读取头部后比较路径确认。
+#[derive(Clone, Default)]
+pub(crate) struct TarHeaderIndex {
+    /// 按 (路径哈希, 头部偏移) 排序
+    headers: Vec<(u64, u64, usize)>,
+    /// 偏移最大的头部，续建索引时从这里继续
+    last: Option<(u64, usize)>,
+}
+
+impl TarHeaderIndex {
+    fn path_hash(path: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 记录一个条目头部；全部记录完成后需调用 `finish`
+    pub fn insert(&mut self, path: &str, header_offset: u64, entry_index: usize) {
+        self.headers.push((Self::path_hash(path), header_offset, entry_index));
+        if self.last.is_none_or(|(offset, _)| header_offset > offset) {
+            self.last = Some((header_offset, entry_index));
+        }
+    }
+
+    /// 排序并去掉重复记录的头部
+    pub fn finish(mut self) -> Self {
+        self.headers.sort_unstable_by_key(|&(hash, offset, _)| (hash, offset));
+        self.headers.dedup_by_key(|&mut (hash, offset, _)| (hash, offset));
+        self
+    }
+
+    /// 查找路径对应的头部偏移和条目索引；同名条目出现多次时返回最后一个
+    pub fn get(&self, path: &str) -> Option<(u64, usize)> {
+        let hash = Self::path_hash(path);
+        let end = self.headers.partition_point(|&(h, _, _)| h <= hash);
+        match end.checked_sub(1).map(|i| self.headers[i]) {
+            Some((h, offset, index)) if h == hash => Some((offset, index)),
+            _ => None,
+        }
+    }
+
+    pub fn last(&self) -> Option<(u64, usize)> {
+        self.last
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
+
+/// 生成索引缓存键，ETag 不可用时仅依赖文件大小判断变化
+pub(crate) fn index_cache_key(protocol: &str, file_path: &str, file_size: u64, etag: Option<&str>) -> String {
+    format!("{}:{}:{}:{}", protocol, file_path, file_size, etag.unwrap_or(""))
+}
+
+/// 通过一次元数据请求获取文件大小和对应的索引缓存键
+pub(crate) async fn file_size_and_cache_key(client: &Arc<dyn StorageClient>, file_path: &str) -> Result<(u64, String), String> {
+    let info = client.get_file_info(file_path).await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    if info.etag.is_none() {
+        log::debug!("文件没有ETag，仅使用文件大小作为缓存键: {}", file_path);
+    }
+    let key = index_cache_key(client.protocol(), file_path, info.size, info.etag.as_deref());
+    Ok((info.size, key))
+}
+
+pub(crate) fn get_cached_index(key: &str) -> Option<Arc<GzipIndex>> {
+    let cache = GZIP_INDEX_CACHE.lock().ok()?;
+    cache.iter().find(|(cached_key, _)| cached_key == key).map(|(_, index)| index.clone())
+}
+
+/// 缓存索引；已有覆盖范围更大的索引时保留原索引
+pub(crate) fn cache_index(key: String, index: GzipIndex) {
+    let Ok(mut cache) = GZIP_INDEX_CACHE.lock() else {
+        return;
+    };
+
+    if let Some(position) = cache.iter().position(|(cached_key, _)| *cached_key == key) {
+        if cache[position].1.indexed_size >= index.indexed_size {
+            return;
+        }
+        cache.remove(position);
+    }
+
+    log::debug!("缓存gzip索引: {} ({} 个检查点, {} 字节)", key, index.checkpoints.len(), index.indexed_size);
+    cache.push_back((key, Arc::new(index)));
+    while cache.len() > MAX_CACHED_INDEXES {
+        cache.pop_front();
+    }
+}
+
+/// 从检查点恢复解压，在阻塞线程中把定位到 `offset` 的解压数据流交给 `consumer`
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn consume_from_offset_with_index<T, F>(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    index: Arc<GzipIndex>,
+    offset: u64,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    consumer: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(GzipStreamReader<ChannelReader>) -> Result<T, String> + Send + 'static,
+{
+    consume_from_checkpoint(client, file_path, file_size, index, offset, false, progress_callback, cancel_rx, consumer).await
+}
+
+/// 与 `consume_from_offset_with_index` 相同，但恢复解压后继续记录检查点，
+/// `consumer` 可以通过 `into_index` 取出延长后的索引
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn resume_index_from_offset<T, F>(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    index: Arc<GzipIndex>,
+    offset: u64,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    consumer: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(GzipStreamReader<ChannelReader>) -> Result<T, String> + Send + 'static,
+{
+    consume_from_checkpoint(client, file_path, file_size, index, offset, true, progress_callback, cancel_rx, consumer).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn consume_from_checkpoint<T, F>(
+    client: &Arc<dyn StorageClient>,
+    file_path: &str,
+    file_size: u64,
+    index: Arc<GzipIndex>,
+    offset: u64,
+    continue_index: bool,
+    progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    consumer: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(GzipStreamReader<ChannelReader>) -> Result<T, String> + Send + 'static,
+{
+    let read_offset = index.checkpoint_before(offset)
+        .ok_or_else(|| "No gzip checkpoint before offset".to_string())?
+        .read_offset();
+
+    log::debug!("通过gzip索引定位: {} 解压偏移 {}，从压缩偏移 {} 开始读取", file_path, offset, read_offset);
+
+    consume_file_stream_with_client(client, file_path, read_offset, file_size, progress_callback, cancel_rx, move |reader| {
+        let checkpoint = index.checkpoint_before(offset)
+            .ok_or_else(|| "No gzip checkpoint before offset".to_string())?;
+        let mut decoder = if continue_index {
+            GzipStreamReader::resume_indexing(reader, &index, checkpoint)
+        } else {
+            GzipStreamReader::resume(reader, checkpoint)
+        }.map_err(|e| format!("Failed to resume gzip stream: {}", e))?;
+
+        let skip = offset - checkpoint.uncompressed_offset;
+        let skipped = io::copy(&mut (&mut decoder).take(skip), &mut io::sink())
+            .map_err(|e| format!("Failed to decompress gzip data: {}", e))?;
+        if skipped < skip {
+            return Err("Unexpected end of gzip data".to_string());
+        }
+
+        consumer(decoder)
+    }).await
+}
+
+/// zlib 解压流的安全封装
+struct Inflater {
+    // z_stream 初始化后不能移动，因此放在堆上
+    stream: Box<zlib::z_stream>,
+}
+
+impl Inflater {
+    fn new(window_bits: i32) -> io::Result<Self> {
+        let mut stream = Box::new(zlib::z_stream::default());
+        // SAFETY: stream 是有效的 z_stream，初始化后由 Drop 负责释放
+        let ret = unsafe {
+            zlib::inflateInit2_(
+                &mut *stream,
+                window_bits,
+                zlib::zlibVersion(),
+                std::mem::size_of::<zlib::z_stream>() as i32,
+            )
+        };
+        if ret != zlib::Z_OK {
+            return Err(io::Error::other(format!("inflateInit2 failed: {}", ret)));
+        }
+        Ok(Self { stream })
+    }
+
+    /// 解压数据，返回 (返回码, 消耗的输入字节数, 产生的输出字节数)
+    fn inflate(&mut self, input: &[u8], output: &mut [u8], flush: i32) -> (i32, usize, usize) {
+        let avail_in = input.len().min(u32::MAX as usize);
+        let avail_out = output.len().min(u32::MAX as usize);
+        self.stream.next_in = input.as_ptr();
+        self.stream.avail_in = avail_in as u32;
+        self.stream.next_out = output.as_mut_ptr();
+        self.stream.avail_out = avail_out as u32;
+
+        // SAFETY: 输入输出指针在本次调用期间有效，长度与缓冲区一致
+        let ret = unsafe { zlib::inflate(&mut *self.stream, flush) };
+
+        let consumed = avail_in - self.stream.avail_in as usize;
+        let produced = avail_out - self.stream.avail_out as usize;
+        self.stream.next_in = std::ptr::null();
+        self.stream.avail_in = 0;
+        self.stream.next_out = std::ptr::null_mut();
+        self.stream.avail_out = 0;
+        (ret, consumed, produced)
+    }
+
+    /// 数据流状态：第 7 位表示位于块边界，第 6 位表示最后一个块之后，低 3 位为未对齐的位数
+    fn data_type(&self) -> i32 {
+        self.stream.data_type
+    }
+
+    fn prime(&mut self, bits: u8, value: u8) -> io::Result<()> {
+        // SAFETY: stream 已初始化
+        let ret = unsafe { zlib::inflatePrime(&mut *self.stream, bits as i32, value as i32) };
+        Self::check(ret, "inflatePrime")
+    }
+
+    fn set_dictionary(&mut self, dictionary: &[u8]) -> io::Result<()> {
+        // SAFETY: stream 已初始化，dictionary 在调用期间有效
+        let ret = unsafe { zlib::inflateSetDictionary(&mut *self.stream, dictionary.as_ptr(), dictionary.len() as u32) };
+        Self::check(ret, "inflateSetDictionary")
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        // SAFETY: stream 已初始化
+        let ret = unsafe { zlib::inflateReset(&mut *self.stream) };
+        Self::check(ret, "inflateReset")
+    }
+
+    fn check(ret: i32, operation: &str) -> io::Result<()> {
+        if ret == zlib::Z_OK {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("{} failed: {}", operation, ret)))
+        }
+    }
+}
+
+impl Drop for Inflater {
+    fn drop(&mut self) {
+        // SAFETY: stream 已初始化且只释放一次
+        unsafe {
+            zlib::inflateEnd(&mut *self.stream);
+        }
+    }
+}
+
+/// 记录检查点所需的状态
+struct CheckpointRecorder {
+    span: u64,
+    last_checkpoint: Option<u64>,
+    /// 最近的解压数据，用于保存检查点窗口
+    window: Vec<u8>,
+    checkpoints: Vec<GzipCheckpoint>,
+}
+
+impl CheckpointRecorder {
+    fn new() -> Self {
+        Self {
+            span: CHECKPOINT_SPAN,
+            last_checkpoint: None,
+            window: Vec::with_capacity(WINDOW_SIZE * 2),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// 从已有索引的检查点继续记录，丢弃该检查点之后的检查点
+    fn resume(index: &GzipIndex, checkpoint: &GzipCheckpoint) -> io::Result<Self> {
+        let checkpoints = index.checkpoints.iter()
+            .take_while(|c| c.uncompressed_offset <= checkpoint.uncompressed_offset)
+            .cloned()
+            .collect();
+        Ok(Self {
+            span: index.span,
+            last_checkpoint: Some(checkpoint.uncompressed_offset),
+            window: checkpoint.window()?,
+            checkpoints,
+        })
+    }
+
+    fn push_output(&mut self, data: &[u8]) {
+        if data.len() >= WINDOW_SIZE {
+            self.window.clear();
+            self.window.extend_from_slice(&data[data.len() - WINDOW_SIZE..]);
+            return;
+        }
+        self.window.extend_from_slice(data);
+        if self.window.len() > WINDOW_SIZE * 2 {
+            self.window.drain(..self.window.len() - WINDOW_SIZE);
+        }
+    }
+
+    fn should_record(&self, uncompressed_offset: u64) -> bool {
+        match self.last_checkpoint {
+            None => true,
+            Some(last) => uncompressed_offset - last >= self.span,
+        }
+    }
+
+    fn record(&mut self, uncompressed_offset: u64, compressed_offset: u64, bits: u8) -> io::Result<()> {
+        let window_start = self.window.len().saturating_sub(WINDOW_SIZE);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&self.window[window_start..])?;
+
+        self.checkpoints.push(GzipCheckpoint {
+            uncompressed_offset,
+            compressed_offset,
+            bits,
+            window: encoder.finish()?,
+        });
+        self.last_checkpoint = Some(uncompressed_offset);
+
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            // 间隔加倍，只保留偶数位置的检查点
+            self.span *= 2;
+            let mut position = 0;
+            self.checkpoints.retain(|_| {
+                position += 1;
+                position % 2 == 1
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 支持多成员的 gzip 解压读取器，可在解压过程中建立检查点索引，也可从检查点恢复
+pub(crate) struct GzipStreamReader<R: Read> {
+    input: R,
+    buffer: Box<[u8]>,
+    position: usize,
+    length: usize,
+    input_finished: bool,
+    inflater: Inflater,
+    /// 当前成员以 raw deflate 方式解压（从检查点恢复时）
+    raw_member: bool,
+    /// 刚结束一个成员，需要确认后面是否还有 gzip 成员
+    between_members: bool,
+    finished: bool,
+    compressed_offset: u64,
+    uncompressed_offset: u64,
+    recorder: Option<CheckpointRecorder>,
+}
+
+impl<R: Read> GzipStreamReader<R> {
+    /// 从文件开头解压，同时建立检查点索引
+    pub fn with_index(input: R) -> io::Result<Self> {
+        let mut reader = Self::new(input, Inflater::new(WINDOW_BITS_GZIP)?, 0, 0);
+        reader.recorder = Some(CheckpointRecorder::new());
+        Ok(reader)
+    }
+
+    /// 从检查点恢复解压，`input` 需从 `checkpoint.read_offset()` 开始
+    pub fn resume(mut input: R, checkpoint: &GzipCheckpoint) -> io::Result<Self> {
+        let mut inflater = Inflater::new(WINDOW_BITS_RAW)?;
+        let mut compressed_offset = checkpoint.read_offset();
+
+        if checkpoint.bits > 0 {
+            let mut byte = [0u8; 1];
+            input.read_exact(&mut byte)?;
+            compressed_offset += 1;
+            inflater.prime(checkpoint.bits, byte[0] >> (8 - checkpoint.bits))?;
+        }
+        inflater.set_dictionary(&checkpoint.window()?)?;
+
+        let mut reader = Self::new(input, inflater, compressed_offset, checkpoint.uncompressed_offset);
+        reader.raw_member = true;
+        Ok(reader)
+    }
+
+    /// 从检查点恢复解压并继续建立索引，`input` 需从 `checkpoint.read_offset()` 开始
+    pub fn resume_indexing(input: R, index: &GzipIndex, checkpoint: &GzipCheckpoint) -> io::Result<Self> {
+        let mut reader = Self::resume(input, checkpoint)?;
+        reader.recorder = Some(CheckpointRecorder::resume(index, checkpoint)?);
+        Ok(reader)
+    }
+
+    fn new(input: R, inflater: Inflater, compressed_offset: u64, uncompressed_offset: u64) -> Self {
+        Self {
+            input,
+            buffer: vec![0u8; INPUT_BUFFER_SIZE].into_boxed_slice(),
+            position: 0,
+            length: 0,
+            input_finished: false,
+            inflater,
+            raw_member: false,
+            between_members: false,
+            finished: false,
+            compressed_offset,
+            uncompressed_offset,
+            recorder: None,
+        }
+    }
+
+    /// 结束读取并取出建立的索引
+    pub fn into_index(self) -> GzipIndex {
+        let (checkpoints, span) = self.recorder
+            .map(|recorder| (recorder.checkpoints, recorder.span))
+            .unwrap_or((Vec::new(), CHECKPOINT_SPAN));
+        GzipIndex {
+            checkpoints,
+            span,
+            indexed_size: self.uncompressed_offset,
+            complete: self.finished,
+            tar_headers: TarHeaderIndex::default(),
+        }
+    }
+
+    /// 保证缓冲区中至少有 `count` 字节（或输入已结束），返回可用字节数
+    fn fill_buffer(&mut self, count: usize) -> io::Result<usize> {
+        if self.length - self.position >= count || self.input_finished {
+            return Ok(self.length - self.position);
+        }
+
+        self.buffer.copy_within(self.position..self.length, 0);
+        self.length -= self.position;
+        self.position = 0;
+
+        while self.length < count.max(1) && !self.input_finished {
+            let n = self.input.read(&mut self.buffer[self.length..])?;
+            if n == 0 {
+                self.input_finished = true;
+            }
+            self.length += n;
+        }
+        Ok(self.length)
+    }
+
+    fn consume_input(&mut self, count: usize) {
+        self.position += count;
+        self.compressed_offset += count as u64;
+    }
+
+    /// 一个成员结束后，跳过尾部并准备解压下一个成员
+    fn finish_member(&mut self) -> io::Result<()> {
+        if self.raw_member {
+            // raw deflate 不处理 gzip 尾部，需要手动跳过
+            if self.fill_buffer(GZIP_TRAILER_SIZE)? < GZIP_TRAILER_SIZE {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated gzip trailer"));
+            }
+            self.consume_input(GZIP_TRAILER_SIZE);
+            self.inflater = Inflater::new(WINDOW_BITS_GZIP)?;
+            self.raw_member = false;
+        } else {
+            self.inflater.reset()?;
+        }
+        self.between_members = true;
+        Ok(())
+    }
+
+    /// 检查下一个成员是否存在；文件末尾的填充或其他数据视为结束
+    fn check_next_member(&mut self) -> io::Result<()> {
+        let available = self.fill_buffer(2)?;
+        let data = &self.buffer[self.position..self.length];
+        if available < 2 || data[0] != 0x1f || data[1] != 0x8b {
+            self.finished = true;
+        }
+        self.between_members = false;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for GzipStreamReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if self.between_members {
+                self.check_next_member()?;
+            }
+            if self.finished {
+                return Ok(0);
+            }
+
+            if self.fill_buffer(1)? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated gzip data"));
+            }
+
+            let flush = if self.recorder.is_some() { zlib::Z_BLOCK } else { zlib::Z_NO_FLUSH };
+            let (ret, consumed, produced) = self.inflater.inflate(&self.buffer[self.position..self.length], buf, flush);
+            self.consume_input(consumed);
+            self.uncompressed_offset += produced as u64;
+
+            if let Some(ref mut recorder) = self.recorder {
+                recorder.push_output(&buf[..produced]);
+
+                // 位于块边界且不在最后一个块之后时记录检查点
+                let data_type = self.inflater.data_type();
+                if ret == zlib::Z_OK
+                    && data_type & 128 != 0
+                    && data_type & 64 == 0
+                    && recorder.should_record(self.uncompressed_offset)
+                {
+                    recorder.record(self.uncompressed_offset, self.compressed_offset, (data_type & 7) as u8)?;
+                }
+            }
+
+            match ret {
+                zlib::Z_OK => {}
+                zlib::Z_STREAM_END => self.finish_member()?,
+                // 输入已全部消耗，下一轮继续读取
+                zlib::Z_BUF_ERROR => {}
+                _ => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, format!("invalid gzip data (zlib error {})", ret)));
+                }
+            }
+
+            if produced > 0 {
+                return Ok(produced);
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+/// 分页列表使用的紧凑条目索引
+///
+/// 首次列出压缩包时完整遍历一次全部条目，路径连续保存在一个字符串中，并按压缩包版本
+/// （协议、路径、大小、ETag）缓存；之后任意目录层级的翻页都只在索引上计算，
+/// 不受分析时的条目数量限制，也不需要重新读取压缩包。批量解压也在同一索引上选择条目。
+use crate::archive::formats::common::*;
+use crate::archive::formats::CompressionHandlerDispatcher;
+use crate::archive::limits;
+use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// 最多缓存的条目索引数量
+const MAX_CACHED_INDEXES: usize = 4;
+
+/// 按插入顺序淘汰的条目索引缓存
+type EntryIndexCache = VecDeque<(String, Arc<EntryIndex>)>;
+
+static ENTRY_INDEX_CACHE: LazyLock<Mutex<EntryIndexCache>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// 路径连续保存的条目索引，`T` 为每个条目附带的数据（按压缩包内顺序）
+pub struct PathIndex<T> {
+    paths: String,
+    /// （路径在 `paths` 中的结束位置，附带数据）
+    items: Vec<(usize, T)>,
+}
+
+impl<T> Default for PathIndex<T> {
+    fn default() -> Self {
+        Self { paths: String::new(), items: Vec::new() }
+    }
+}
+
+impl<T> PathIndex<T> {
+    /// 添加一个条目；条目总数超过解压限制时返回错误
+    pub fn push(&mut self, path: &str, item: T) -> Result<(), String> {
+        limits::current_policy().check_entries(self.items.len() as u64 + 1)?;
+        self.paths.push_str(path);
+        self.items.push((self.paths.len(), item));
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn path(&self, position: usize) -> &str {
+        let start = position.checked_sub(1).map_or(0, |previous| self.items[previous].0);
+        &self.paths[start..self.items[position].0]
+    }
+
+    pub fn item(&self, position: usize) -> &T {
+        &self.items[position].1
+    }
+
+    /// 计算某一目录层级的一页：子目录按名称排序在前，直接位于该层级的条目按压缩包内顺序在后
+    pub fn page(&self, options: &ArchiveListOptions) -> LevelPage {
+        let prefix = normalize_directory_prefix(options.prefix.as_deref());
+        let (offset, limit) = list_page_bounds(options);
+
+        let mut directories = BTreeSet::new();
+        let mut files = Vec::new();
+        for position in 0..self.items.len() {
+            match match_directory_level(self.path(position), &prefix) {
+                DirectoryLevelMatch::Directory(name) => {
+                    directories.insert(name);
+                }
+                DirectoryLevelMatch::File => files.push(position),
+                DirectoryLevelMatch::Outside => {}
+            }
+        }
+
+        let directory_count = directories.len();
+        let directory_entries: Vec<_> = directories
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(limit)
+            .map(|(index, name)| directory_entry(&prefix, name, index))
+            .collect();
+        let file_start = offset.saturating_sub(directory_count).min(files.len());
+        let file_end = (file_start + limit - directory_entries.len()).min(files.len());
+
+        LevelPage {
+            total_entries: directory_count + files.len(),
+            archive_total_entries: self.items.len(),
+            file_positions: files[file_start..file_end].to_vec(),
+            directory_entries,
+            prefix,
+            offset,
+        }
+    }
+}
+
+/// 某一目录层级的一页，文件条目由调用方按 `file_positions` 补全
+pub struct LevelPage {
+    prefix: String,
+    offset: usize,
+    total_entries: usize,
+    archive_total_entries: usize,
+    directory_entries: Vec<ArchiveEntry>,
+    /// 本页文件条目在索引中的位置
+    pub file_positions: Vec<usize>,
+}
+
+impl LevelPage {
+    /// 加上本页的文件条目，生成分页结果
+    pub fn into_listing(self, files: Vec<ArchiveEntry>) -> ArchiveListing {
+        let mut entries = self.directory_entries;
+        entries.extend(files);
+        make_listing(self.prefix, self.offset, self.total_entries, self.archive_total_entries, entries)
+    }
+}
+
+/// 索引中保存的条目信息（路径保存在 `PathIndex` 中）
+pub struct IndexedEntry {
+    size: u64,
+    compressed_size: Option<u64>,
+    modified_time: Option<Box<str>>,
+    crc32: Option<u32>,
+    is_dir: bool,
+    index: usize,
+    metadata: Option<Box<[(String, String)]>>,
+}
+
+/// 保存完整条目信息的索引，用于没有中央目录可按需读取的格式
+pub type EntryIndex = PathIndex<IndexedEntry>;
+
+impl EntryIndex {
+    pub fn push_entry(&mut self, entry: ArchiveEntry) -> Result<(), String> {
+        self.push(&entry.path, IndexedEntry {
+            size: entry.size,
+            compressed_size: entry.compressed_size,
+            modified_time: entry.modified_time.map(String::into_boxed_str),
+            crc32: entry.crc32,
+            is_dir: entry.is_dir,
+            index: entry.index,
+            metadata: (!entry.metadata.is_empty()).then(|| entry.metadata.into_iter().collect()),
+        })
+    }
+
+    pub fn entry(&self, position: usize) -> ArchiveEntry {
+        let item = self.item(position);
+        ArchiveEntry {
+            path: self.path(position).to_string(),
+            size: item.size,
+            compressed_size: item.compressed_size,
+            is_dir: item.is_dir,
+            modified_time: item.modified_time.as_deref().map(str::to_string),
+            crc32: item.crc32,
+            index: item.index,
+            metadata: item.metadata.iter().flat_map(|metadata| metadata.iter().cloned()).collect(),
+        }
+    }
+}
+
+/// 使用（缓存的）条目索引分页列出某一目录层级
+pub async fn list_with_entry_index<H: CompressionHandlerDispatcher + ?Sized>(
+    handler: &H,
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    filename: &str,
+    options: &ArchiveListOptions,
+) -> Result<ArchiveListing, String> {
+    let index = entry_index(handler, client, file_path, filename).await?;
+    let page = index.page(options);
+    let files = page.file_positions.iter().map(|&position| index.entry(position)).collect();
+    Ok(page.into_listing(files))
+}
+
+/// 获取压缩包当前版本的完整条目索引，没有缓存时遍历一次全部条目建立
+pub async fn entry_index<H: CompressionHandlerDispatcher + ?Sized>(
+    handler: &H,
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    filename: &str,
+) -> Result<Arc<EntryIndex>, String> {
+    let file_info = client.get_file_info(file_path).await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let cache_key = format!(
+        "{}:{}:{}:{}",
+        client.protocol(), file_path, file_info.size, file_info.etag.unwrap_or_default()
+    );
+
+    if let Some(index) = get_cached_index(&cache_key) {
+        return Ok(index);
+    }
+
+    let index = Arc::new(handler.build_entry_index(client, file_path, filename).await?);
+    log::debug!("建立压缩包列表索引: {} ({} 个条目)", file_path, index.len());
+    cache_index(cache_key, index.clone());
+    Ok(index)
+}
+
+/// 清空条目索引缓存（例如 ZIP 文件名编码改变后，已缓存的路径不再有效）
+pub fn clear_cache() {
+    ENTRY_INDEX_CACHE.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+fn get_cached_index(key: &str) -> Option<Arc<EntryIndex>> {
+    let cache = ENTRY_INDEX_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.iter().find(|(cached_key, _)| cached_key == key).map(|(_, index)| index.clone())
+}
+
+fn cache_index(key: String, index: Arc<EntryIndex>) {
+    let mut cache = ENTRY_INDEX_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(cached_key, _)| *cached_key != key);
+    cache.push_back((key, index));
+    while cache.len() > MAX_CACHED_INDEXES {
+        cache.pop_front();
+    }
+}
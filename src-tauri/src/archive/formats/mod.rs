@@ -3,12 +3,24 @@
 /// 此模块将不同压缩格式的处理逻辑分离到独立的子模块中，
 /// 提供统一的接口和共享的工具函数。
 pub mod zip;
+pub mod zip_crypto;
+pub mod zip_encoding;
 pub mod gzip;
+pub mod gzip_index;
+pub mod bgzf;
 pub mod tar;
-pub mod tar_gz;
+pub mod tar_header;
+pub mod compressed_tar;
+pub mod seven_zip;
+pub mod rar;
+pub mod unrar_stream;
+pub mod compressed_stream;
 pub mod common;
+pub mod list_index;
 
 use crate::archive::types::*;
+use crate::archive::formats::compressed_tar::{CompressedTarHandler, TarCodec};
+use crate::archive::formats::compressed_stream::{CompressedStreamHandler, StreamCodec};
 use crate::storage::traits::StorageClient;
 use std::sync::Arc;
 
@@ -24,6 +36,20 @@ pub trait CompressionHandlerDispatcher: Send + Sync {
         max_size: Option<usize>,
     ) -> Result<ArchiveInfo, String>;
 
+    /// 分析压缩文件，解析过程中通过 `progress_callback` 按批次发送已解析的条目
+    ///
+    /// 默认不发送中间结果，等同于 `analyze_with_client`；需要顺序读取整个压缩包的格式应覆盖此方法。
+    async fn analyze_with_progress(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        max_size: Option<usize>,
+        _progress_callback: Option<AnalysisProgressCallback>,
+    ) -> Result<ArchiveInfo, String> {
+        self.analyze_with_client(client, file_path, filename, max_size).await
+    }
+
     /// 通过存储客户端提取文件预览（统一接口，支持流式提取、进度回调和取消信号）
     async fn extract_preview_with_client(
         &self,
@@ -35,6 +61,116 @@ pub trait CompressionHandlerDispatcher: Send + Sync {
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String>;
 
+    /// 使用密码提取加密条目的预览；不支持加密的格式忽略密码
+    #[allow(clippy::too_many_arguments)]
+    async fn extract_preview_with_password(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<FilePreview, String> {
+        self.extract_preview_with_client(client, file_path, entry_path, max_size, progress_callback, cancel_rx).await
+    }
+
+    /// 从条目的指定解压偏移开始提取预览；默认只支持从开头预览
+    #[allow(clippy::too_many_arguments)]
+    async fn extract_preview_at_offset(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        offset: u64,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        if offset > 0 {
+            return Err("archive.preview.offset_unsupported".to_string());
+        }
+        self.extract_preview_with_client(client, file_path, entry_path, max_size, progress_callback, cancel_rx).await
+    }
+
+    /// 将条目完整解压并流式写入 `writer`，返回写入的字节数（用于下载，内存占用与条目大小无关）
+    #[allow(clippy::too_many_arguments)]
+    async fn extract_entry_to_writer(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String>;
+
+    /// 把 `sink` 中选中的条目按压缩包内顺序依次解压写入 `sink`（用于批量解压到目录）
+    ///
+    /// 默认逐个调用 `extract_entry_to_writer`；需要顺序解压或每次定位条目代价较高的格式
+    /// 应覆盖此方法，只遍历一遍压缩包。
+    async fn extract_entries_to_sink(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        mut sink: common::EntrySink,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        for path in sink.pending_paths() {
+            sink.begin_entry_async(&path).await?;
+            self.extract_entry_to_writer(client.clone(), file_path, &path, &mut sink, None, cancel_rx.as_deref_mut(), password).await?;
+            sink.end_entry_async().await?;
+        }
+        Ok(())
+    }
+
+    /// 完整性测试：流式读取所有条目，校验 CRC32、压缩流尾部和头部校验和（不保存解压数据）
+    ///
+    /// 进度回调参数为（已读取的压缩包字节数，压缩包大小）。默认不支持。
+    async fn test_integrity_with_client(
+        &self,
+        _client: Arc<dyn StorageClient>,
+        _file_path: &str,
+        _progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<IntegrityReport, String> {
+        Err("archive.test.unsupported".to_string())
+    }
+
+    /// 遍历全部条目建立列表索引（不受分析条目数量上限限制）
+    ///
+    /// 默认使用完整分析结果；分析时会截断条目列表的格式应覆盖此方法。
+    async fn build_entry_index(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+    ) -> Result<list_index::EntryIndex, String> {
+        let info = self.analyze_with_client(client, file_path, filename, None).await?;
+        let mut index = list_index::EntryIndex::default();
+        for entry in info.entries {
+            index.push_entry(entry)?;
+        }
+        Ok(index)
+    }
+
+    /// 分页列出某一目录层级的条目
+    ///
+    /// 默认实现在按文件版本缓存的条目索引上分页；可以按需读取目录的格式应覆盖此方法。
+    async fn list_entries_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        options: &ArchiveListOptions,
+    ) -> Result<ArchiveListing, String> {
+        list_index::list_with_entry_index(self, client, file_path, filename, options).await
+    }
+
     /// 获取压缩类型
     #[allow(dead_code)] // API 保留方法，保持接口完整性
     fn compression_type(&self) -> CompressionType;
@@ -49,12 +185,16 @@ pub fn get_handler(compression_type: &CompressionType) -> Option<Box<dyn Compres
         CompressionType::Zip => Some(Box::new(zip::ZipHandler)),
         CompressionType::Gzip => Some(Box::new(gzip::GzipHandler)),
         CompressionType::Tar => Some(Box::new(tar::TarHandler)),
-        CompressionType::TarGz => Some(Box::new(tar_gz::TarGzHandler)),
-        CompressionType::SevenZip => None, // 7Z 格式不支持流式处理
-        CompressionType::Rar => None, // RAR 格式不支持流式处理
-        CompressionType::Brotli => None, // Brotli 格式暂不支持
-        CompressionType::Lz4 => None, // LZ4 格式暂不支持
-        CompressionType::Zstd => None, // Zstd 格式暂不支持
+        CompressionType::TarGz => Some(Box::new(CompressedTarHandler::new(TarCodec::Gzip))),
+        CompressionType::TarZst => Some(Box::new(CompressedTarHandler::new(TarCodec::Zstd))),
+        CompressionType::TarXz => Some(Box::new(CompressedTarHandler::new(TarCodec::Xz))),
+        CompressionType::TarBz2 => Some(Box::new(CompressedTarHandler::new(TarCodec::Bzip2))),
+        CompressionType::TarLz4 => Some(Box::new(CompressedTarHandler::new(TarCodec::Lz4))),
+        CompressionType::SevenZip => Some(Box::new(seven_zip::SevenZipHandler)),
+        CompressionType::Rar => Some(Box::new(rar::RarHandler)),
+        CompressionType::Brotli => Some(Box::new(CompressedStreamHandler::new(StreamCodec::Brotli))),
+        CompressionType::Lz4 => Some(Box::new(CompressedStreamHandler::new(StreamCodec::Lz4))),
+        CompressionType::Zstd => Some(Box::new(CompressedStreamHandler::new(StreamCodec::Zstd))),
         CompressionType::Unknown => None,
     }
 }
@@ -63,9 +203,19 @@ pub fn get_handler(compression_type: &CompressionType) -> Option<Box<dyn Compres
 pub fn detect_format_and_get_handler(data: &[u8]) -> Option<Box<dyn CompressionHandlerDispatcher>> {
     let handlers: Vec<Box<dyn CompressionHandlerDispatcher>> = vec![
         Box::new(zip::ZipHandler),
+        Box::new(CompressedTarHandler::new(TarCodec::Gzip)), // TAR.GZ 需要在 GZIP 和 TAR 之前检查
         Box::new(gzip::GzipHandler),
-        Box::new(tar_gz::TarGzHandler), // TAR.GZ 需要在 TAR 之前检查
         Box::new(tar::TarHandler),
+        Box::new(seven_zip::SevenZipHandler),
+        Box::new(rar::RarHandler),
+        // 压缩 TAR 需要在对应的单文件压缩格式之前检查
+        Box::new(CompressedTarHandler::new(TarCodec::Zstd)),
+        Box::new(CompressedTarHandler::new(TarCodec::Xz)),
+        Box::new(CompressedTarHandler::new(TarCodec::Bzip2)),
+        Box::new(CompressedTarHandler::new(TarCodec::Lz4)),
+        Box::new(CompressedStreamHandler::new(StreamCodec::Zstd)),
+        Box::new(CompressedStreamHandler::new(StreamCodec::Lz4)),
+        // Brotli 没有魔数，只能通过扩展名识别
     ];
 
     handlers.into_iter().find(|handler| handler.validate_format(data))
@@ -1,58 +1,131 @@
-/// RAR 格式处理器
+/// RAR 格式处理器（支持 RAR 4.x 和 RAR 5.x）
+///
+/// 列表通过 `read_file_range` 逐块读取块头完成，跳过文件数据，无需下载整个压缩包。
+/// 预览时只下载目标条目所需的块，重组成一个最小的 RAR 文件后交给内置的 unrar 库解压；
+/// 对于 solid 压缩的条目，需要从压缩包开头读取到目标条目末尾。
+///
+/// 分卷压缩包（由 `archive::volumes` 拼接）按卷依次解析，跨卷条目的各段合并为一个条目；
+/// 解压时为涉及的每一卷分别重组临时分卷，由 unrar 按文件名依次打开。
 use crate::archive::types::*;
-use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
+use crate::archive::formats::{CompressionHandlerDispatcher, common::*, unrar_stream};
+use crate::archive::formats::list_index::EntryIndex;
+use crate::archive::limits::{self, OutputLimit};
+use crate::storage::traits::StorageClient;
 use std::collections::HashMap;
-use unrar::Archive as RarArchive;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const RAR4_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x00";
+const RAR5_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x01\x00";
+
+/// 每次读取块头时的预读大小
+const BLOCK_READ_SIZE: u64 = 4096;
+/// 条目数量限制
+const MAX_ENTRIES: usize = 10000;
+
+// RAR 4.x 块类型和标志
+const RAR4_BLOCK_MAIN: u8 = 0x73;
+const RAR4_BLOCK_FILE: u8 = 0x74;
+const RAR4_BLOCK_END: u8 = 0x7b;
+const RAR4_MAIN_ENCRYPTED_HEADERS: u16 = 0x0080;
+const RAR4_MAIN_SOLID: u16 = 0x0008;
+const RAR4_MAIN_NEW_NUMBERING: u16 = 0x0010;
+const RAR4_LONG_BLOCK: u16 = 0x8000;
+const RAR4_FILE_SPLIT_BEFORE: u16 = 0x0001;
+const RAR4_FILE_SPLIT_AFTER: u16 = 0x0002;
+const RAR4_FILE_ENCRYPTED: u16 = 0x0004;
+const RAR4_FILE_SOLID: u16 = 0x0010;
+const RAR4_FILE_DIRECTORY: u16 = 0x00e0;
+const RAR4_FILE_LARGE: u16 = 0x0100;
+const RAR4_FILE_UNICODE: u16 = 0x0200;
+const RAR4_END_NEXT_VOLUME: u16 = 0x0001;
+
+// RAR 5.x 块类型和标志
+const RAR5_BLOCK_MAIN: u64 = 1;
+const RAR5_BLOCK_FILE: u64 = 2;
+const RAR5_BLOCK_ENCRYPTION: u64 = 4;
+const RAR5_BLOCK_END: u64 = 5;
+const RAR5_HEADER_EXTRA: u64 = 0x01;
+const RAR5_HEADER_DATA: u64 = 0x02;
+const RAR5_HEADER_SPLIT_BEFORE: u64 = 0x08;
+const RAR5_HEADER_SPLIT_AFTER: u64 = 0x10;
+const RAR5_MAIN_SOLID: u64 = 0x04;
+const RAR5_FILE_DIRECTORY: u64 = 0x01;
+const RAR5_FILE_MTIME: u64 = 0x02;
+const RAR5_FILE_CRC32: u64 = 0x04;
+const RAR5_EXTRA_ENCRYPTION: u64 = 0x01;
+const RAR5_END_NEXT_VOLUME: u64 = 0x01;
 
 pub struct RarHandler;
 
 #[async_trait::async_trait]
 impl CompressionHandlerDispatcher for RarHandler {
-    async fn analyze_complete(&self, data: &[u8]) -> Result<ArchiveInfo, String> {
-        Self::analyze_rar_complete(data)
-    }
-
-    async fn analyze_streaming(
+    async fn analyze_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<usize>,
     ) -> Result<ArchiveInfo, String> {
-        // RAR 格式不支持流式分析，需要下载完整文件
-        Self::analyze_rar_by_download(url, headers, filename, file_size).await
+        Self::analyze_with_storage_client(client, file_path).await
     }
 
-    async fn analyze_streaming_without_size(
+    async fn extract_preview_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        // RAR 格式不支持流式分析，需要下载完整文件
-        Self::analyze_rar_by_download_no_size(url, headers, filename).await
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_rar_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx, None).await
     }
 
-    async fn extract_preview(
+    async fn extract_preview_with_password(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
         entry_path: &str,
         max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
     ) -> Result<FilePreview, String> {
-        Self::extract_rar_preview(url, headers, entry_path, max_size).await
+        Self::extract_rar_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx, password).await
     }
 
-    async fn extract_preview_with_client(
+    async fn extract_entry_to_writer(
         &self,
-        _client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
-        _file_path: &str,
-        _entry_path: &str,
-        _max_size: usize,
-        _progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
-        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
-    ) -> Result<FilePreview, String> {
-        Err("RAR format does not support client-based extraction yet".to_string())
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String> {
+        Self::extract_rar_entry_to_writer(client, file_path, entry_path, writer, progress_callback, cancel_rx, password).await
+    }
+
+    async fn extract_entries_to_sink(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        Self::extract_rar_entries(client, file_path, sink, cancel_rx, password).await
+    }
+
+    async fn build_entry_index(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+    ) -> Result<EntryIndex, String> {
+        Self::build_rar_entry_index(client, file_path).await
     }
 
     fn compression_type(&self) -> CompressionType {
@@ -64,214 +137,1096 @@ impl CompressionHandlerDispatcher for RarHandler {
     }
 }
 
-impl RarHandler {
-    /// 完整RAR文件分析
-    fn analyze_rar_complete(data: &[u8]) -> Result<ArchiveInfo, String> {
-        println!("开始分析RAR文件，数据长度: {} 字节", data.len());
+/// RAR 块头解析结果中的文件条目
+#[derive(Debug, Clone)]
+struct RarFileBlock {
+    path: String,
+    size: u64,
+    packed_size: u64,
+    is_dir: bool,
+    is_solid: bool,
+    is_encrypted: bool,
+    crc32: Option<u32>,
+    modified_time: Option<String>,
+    /// 块头在文件中的位置
+    header_offset: u64,
+    /// 块的结束位置；跨卷条目为最后一段的结束位置
+    block_end: u64,
+}
+
+/// 分卷中的一卷，普通 RAR 文件只有一卷
+#[derive(Debug, Clone)]
+struct RarVolume {
+    start: u64,
+    end: u64,
+    /// 签名和主块头的结束位置
+    main_header_end: u64,
+    /// 结束块的位置和长度
+    end_block: Option<(u64, u64)>,
+}
+
+/// 读取块头的范围
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LayoutScope<'a> {
+    /// 分析：最多读取 `MAX_ENTRIES` 个条目
+    Analysis,
+    /// 列表索引：读取全部条目
+    All,
+    /// 读到指定条目为止
+    Until(&'a str),
+}
+
+impl LayoutScope<'_> {
+    fn stops_at(self, path: &str) -> bool {
+        matches!(self, LayoutScope::Until(target) if target == path)
+    }
+}
 
-        if !Self::validate_rar_header(data) {
-            return Err("Invalid RAR header".to_string());
+/// 批量解压时把 unrar 输出写入 `EntrySink` 的访问者
+struct SinkVisitor<'a> {
+    sink: &'a mut EntrySink,
+    cancelled: &'a AtomicBool,
+    /// 写入失败的原因（回调中无法直接返回错误）
+    error: Option<String>,
+}
+
+impl unrar_stream::EntryVisitor for SinkVisitor<'_> {
+    fn enter(&mut self, path: &str) -> Result<bool, String> {
+        if !self.sink.wants(path) {
+            return Ok(false);
         }
+        self.sink.begin_entry(path)?;
+        Ok(true)
+    }
 
-        // 创建临时文件，因为unrar库需要文件路径
-        let temp_path = format!("/tmp/temp_rar_{}.rar", uuid::Uuid::new_v4());
-        std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+    fn data(&mut self, data: &[u8]) -> bool {
+        use std::io::Write;
 
-        let result = Self::analyze_rar_file(&temp_path);
+        if let Err(e) = self.sink.write_all(data) {
+            self.error = Some(format!("Failed to write data: {}", e));
+            return false;
+        }
+        !self.cancelled.load(Ordering::Relaxed)
+    }
 
-        // 清理临时文件
-        let _ = std::fs::remove_file(&temp_path);
+    fn leave(&mut self) -> Result<bool, String> {
+        self.sink.end_entry()?;
+        Ok(!self.sink.is_complete())
+    }
+}
 
-        result
+/// 整个压缩包的块结构
+#[derive(Debug, Clone)]
+struct RarLayout {
+    version: u8,
+    /// 已解析的分卷
+    volumes: Vec<RarVolume>,
+    /// 分卷总数
+    volume_count: usize,
+    /// RAR 4.x 分卷使用 `.partN.rar` 命名
+    new_numbering: bool,
+    is_solid: bool,
+    files: Vec<RarFileBlock>,
+    /// 是否因条目数量限制提前停止
+    truncated: bool,
+}
+
+impl RarLayout {
+    fn new(version: u8, volume_count: usize) -> Self {
+        Self {
+            version,
+            volumes: Vec::new(),
+            volume_count,
+            new_numbering: false,
+            is_solid: false,
+            files: Vec::new(),
+            truncated: false,
+        }
     }
 
-    /// 分析RAR文件
-    fn analyze_rar_file(file_path: &str) -> Result<ArchiveInfo, String> {
-        let archive = RarArchive::new(file_path.to_string());
-        let mut entries = Vec::new();
-        let mut total_uncompressed_size = 0;
-        let mut total_compressed_size = 0;
-
-        for (index, entry_result) in archive.iter().enumerate() {
-            match entry_result {
-                Ok(entry) => {
-                    let path = entry.filename.clone();
-                    let size = entry.unpacked_size;
-                    let compressed_size = entry.packed_size;
-                    let is_dir = entry.is_directory();
-
-                    total_uncompressed_size += size;
-                    total_compressed_size += compressed_size;
-
-                    // 处理修改时间
-                    let modified_time = entry.file_time.and_then(|_ft| {
-                        // DOS时间转换为Unix时间戳（简化处理）
-                        // 这里需要具体的时间转换逻辑
-                        None // 暂时返回None，可以后续完善
-                    });
-
-                    entries.push(ArchiveEntry {
-                        path,
-                        size,
-                        compressed_size: Some(compressed_size),
-                        is_dir,
-                        modified_time,
-                        crc32: Some(entry.crc),
-                        index,
-                        metadata: HashMap::new(),
-                    });
-                }
-                Err(e) => {
-                    println!("Warning: Failed to read RAR entry {}: {}", index, e);
-                    continue;
+    /// 添加文件块；接续上一卷的数据段合并到上一个条目
+    fn push_file(&mut self, file: RarFileBlock, split_before: bool) {
+        if split_before {
+            if let Some(last) = self.files.last_mut().filter(|last| last.path == file.path) {
+                last.packed_size += file.packed_size;
+                last.block_end = file.block_end;
+                // 完整文件的 CRC32 记录在最后一段中
+                if file.crc32.is_some() {
+                    last.crc32 = file.crc32;
                 }
+                return;
             }
         }
+        self.files.push(file);
+    }
+}
+
+impl RarHandler {
+    /// 使用存储客户端分析RAR文件（逐块读取块头）
+    async fn analyze_with_storage_client(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<ArchiveInfo, String> {
+        log::debug!("使用storage client分析RAR文件: {}", file_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let layout = Self::read_layout(client, file_path, file_size, LayoutScope::Analysis).await?;
+
+        let entries: Vec<_> = Self::layout_entries(&layout).collect();
+        let total_uncompressed_size = entries.iter().map(|entry| entry.size).sum();
+
+        let entry_count = entries.len();
+        limits::current_policy().check_entries(entry_count as u64)?;
 
         Ok(ArchiveInfoBuilder::new(CompressionType::Rar)
             .entries(entries)
             .total_uncompressed_size(total_uncompressed_size)
-            .total_compressed_size(total_compressed_size)
+            .total_compressed_size(file_size)
             .supports_streaming(false)
-            .supports_random_access(true)
-            .analysis_status(AnalysisStatus::Complete)
+            .supports_random_access(!layout.is_solid)
+            .analysis_status(if layout.truncated {
+                AnalysisStatus::Partial { analyzed_entries: entry_count }
+            } else {
+                AnalysisStatus::Complete
+            })
             .build())
     }
 
-    /// 通过下载完整文件分析RAR（有文件大小）
-    async fn analyze_rar_by_download(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
-    ) -> Result<ArchiveInfo, String> {
-        println!("开始下载RAR文件进行分析: {} (大小: {} 字节)", filename, file_size);
+    /// 读取全部块头建立列表索引（不受分析条目数量上限限制）
+    async fn build_rar_entry_index(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<EntryIndex, String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        // 限制最大下载大小（比如100MB）
-        const MAX_DOWNLOAD_SIZE: u64 = 100 * 1024 * 1024;
-        if file_size > MAX_DOWNLOAD_SIZE {
-            return Err(format!("RAR file is too large for analysis: {} bytes (max: {} bytes)",
-                             file_size, MAX_DOWNLOAD_SIZE));
+        let layout = Self::read_layout(client, file_path, file_size, LayoutScope::All).await?;
+        let mut index = EntryIndex::default();
+        for entry in Self::layout_entries(&layout) {
+            index.push_entry(entry)?;
         }
+        Ok(index)
+    }
 
-        let data = HttpClient::download_file(url, headers).await?;
-        Self::analyze_rar_complete(&data)
+    /// 把压缩包结构中的文件转换为条目
+    fn layout_entries(layout: &RarLayout) -> impl Iterator<Item = ArchiveEntry> + '_ {
+        layout.files.iter().enumerate().map(|(index, file)| {
+            let mut metadata = HashMap::new();
+            metadata.insert("rar_version".to_string(), layout.version.to_string());
+            if file.is_solid {
+                metadata.insert("solid".to_string(), "true".to_string());
+            }
+            if file.is_encrypted {
+                metadata.insert("encrypted".to_string(), "true".to_string());
+            }
+
+            ArchiveEntry {
+                path: file.path.clone(),
+                size: file.size,
+                compressed_size: Some(file.packed_size),
+                is_dir: file.is_dir,
+                modified_time: file.modified_time.clone(),
+                crc32: file.crc32,
+                index,
+                metadata,
+            }
+        })
     }
 
-    /// 通过下载完整文件分析RAR（无文件大小）
-    async fn analyze_rar_by_download_no_size(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        println!("开始下载RAR文件进行分析: {}", filename);
+    /// 提取RAR文件预览（支持进度回调和取消信号）
+    async fn extract_rar_preview_with_progress(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<FilePreview, String> {
+        log::debug!("开始提取RAR文件预览: {} -> {}", file_path, entry_path);
 
-        let data = HttpClient::download_file(url, headers).await?;
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        // 检查下载的文件大小
-        const MAX_DOWNLOAD_SIZE: usize = 100 * 1024 * 1024;
-        if data.len() > MAX_DOWNLOAD_SIZE {
-            return Err(format!("RAR file is too large for analysis: {} bytes (max: {} bytes)",
-                             data.len(), MAX_DOWNLOAD_SIZE));
+        let layout = Self::read_layout(client.clone(), file_path, file_size, LayoutScope::Until(entry_path)).await?;
+        let target = Self::find_target(&layout, entry_path, password)?;
+
+        if target.size == 0 {
+            return Ok(PreviewBuilder::new()
+                .content(Vec::new())
+                .with_truncated(false)
+                .total_size(0)
+                .build());
         }
 
-        Self::analyze_rar_complete(&data)
+        Self::check_output_limit(&layout, &target)?;
+
+        let temp_id = uuid::Uuid::new_v4();
+        let mut volume_paths = Vec::new();
+        let result = async {
+            Self::write_temp_volumes(
+                &client,
+                file_path,
+                &layout,
+                Self::data_blocks_range(&layout, &target),
+                &temp_id,
+                &mut volume_paths,
+                progress_callback.as_deref(),
+                cancel_rx.as_deref_mut(),
+            ).await?;
+
+            let (archive_path, target_path) = (volume_paths[0].clone(), target.path.clone());
+            let password = password.map(str::to_string);
+            Self::run_unrar(cancel_rx, move |cancelled| {
+                let mut content = Vec::new();
+                unrar_stream::process_entry(&archive_path, &target_path, password.as_deref(), Self::normalize_path, &mut |data| {
+                    let remaining = max_size - content.len();
+                    content.extend_from_slice(&data[..data.len().min(remaining)]);
+                    content.len() < max_size && !cancelled.load(Ordering::Relaxed)
+                })?;
+                Ok(content)
+            }).await
+        }.await;
+
+        // 清理临时文件
+        for path in &volume_paths {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        let content = result?;
+
+        let is_truncated = (content.len() as u64) < target.size;
+
+        Ok(PreviewBuilder::new()
+            .content(content)
+            .with_truncated(is_truncated)
+            .total_size(target.size)
+            .build())
     }
 
-    /// 提取RAR文件预览
-    async fn extract_rar_preview(
-        url: &str,
-        headers: &HashMap<String, String>,
+    /// 解压RAR条目并流式写入 `writer`
+    ///
+    /// unrar 只能处理磁盘上的文件：重组的RAR数据按块写入临时分卷，解压输出在回调中
+    /// 逐块转发给 `writer`，内存占用与条目大小无关。
+    async fn extract_rar_entry_to_writer(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
         entry_path: &str,
-        max_size: usize,
-    ) -> Result<FilePreview, String> {
-        println!("开始从RAR文件提取预览: {}", entry_path);
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String> {
+        log::debug!("开始流式解压RAR条目: {} -> {}", file_path, entry_path);
 
-        // 下载完整文件
-        let data = HttpClient::download_file(url, headers).await?;
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        // 创建临时文件
-        let temp_path = format!("/tmp/temp_rar_{}.rar", uuid::Uuid::new_v4());
-        std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+        let layout = Self::read_layout(client.clone(), file_path, file_size, LayoutScope::Until(entry_path)).await?;
+        let target = Self::find_target(&layout, entry_path, password)?;
 
-        let result = Self::extract_from_rar_file(&temp_path, entry_path, max_size);
+        if target.size == 0 {
+            return Ok(0);
+        }
+
+        Self::check_output_limit(&layout, &target)?;
+
+        let temp_id = uuid::Uuid::new_v4();
+        let mut volume_paths = Vec::new();
+
+        let result = async {
+            Self::write_temp_volumes(
+                &client,
+                file_path,
+                &layout,
+                Self::data_blocks_range(&layout, &target),
+                &temp_id,
+                &mut volume_paths,
+                progress_callback.as_deref(),
+                cancel_rx.as_deref_mut(),
+            ).await?;
+
+            let (archive_path, target_path) = (volume_paths[0].clone(), target.path.clone());
+            let password = password.map(str::to_string);
+            pipe_to_async_writer(writer, |mut output| Self::run_unrar(cancel_rx, move |cancelled| {
+                use std::io::Write;
+
+                let mut write_error = None;
+                unrar_stream::process_entry(&archive_path, &target_path, password.as_deref(), Self::normalize_path, &mut |data| {
+                    if let Err(e) = output.write_all(data) {
+                        write_error = Some(e);
+                        return false;
+                    }
+                    !cancelled.load(Ordering::Relaxed)
+                })?;
+                if let Some(e) = write_error {
+                    return Err(format!("Failed to write data: {}", e));
+                }
+                output.finish()
+            })).await
+        }.await;
 
         // 清理临时文件
-        let _ = std::fs::remove_file(&temp_path);
+        for path in &volume_paths {
+            let _ = tokio::fs::remove_file(path).await;
+        }
 
         result
     }
 
-    /// 从RAR文件提取内容
-    fn extract_from_rar_file(
+    /// 批量解压：把选中条目所需的块一次性重组为临时分卷，由 unrar 顺序解压一遍，
+    /// 选中的条目依次写入 `sink`
+    async fn extract_rar_entries(
+        client: Arc<dyn StorageClient>,
         file_path: &str,
-        entry_path: &str,
-        max_size: usize,
-    ) -> Result<FilePreview, String> {
-        let archive = RarArchive::new(file_path.to_string());
+        mut sink: EntrySink,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let layout = Self::read_layout(client.clone(), file_path, file_size, LayoutScope::All).await?;
+
+        // 空条目不需要解压数据
+        let mut range: Option<(u64, u64)> = None;
+        for file in &layout.files {
+            if file.is_dir || !sink.wants(&file.path) {
+                continue;
+            }
+            if file.size == 0 {
+                sink.begin_entry_async(&file.path).await?;
+                sink.end_entry_async().await?;
+                continue;
+            }
+            if file.is_encrypted && password.is_none() {
+                return Err(unrar_stream::PASSWORD_REQUIRED_ERROR.to_string());
+            }
+            let (start, length) = Self::data_blocks_range(&layout, file);
+            range = Some(match range {
+                Some((offset, end)) => (offset.min(start), end.max(start + length)),
+                None => (start, start + length),
+            });
+        }
+        let Some((start, end)) = range else {
+            return Ok(());
+        };
+        Self::check_range_output_limit(&layout, (start, end - start))?;
+
+        log::debug!("开始批量解压RAR条目: {} (数据范围 {}..{})", file_path, start, end);
+
+        let temp_id = uuid::Uuid::new_v4();
+        let mut volume_paths = Vec::new();
+        let result = async {
+            Self::write_temp_volumes(
+                &client,
+                file_path,
+                &layout,
+                (start, end - start),
+                &temp_id,
+                &mut volume_paths,
+                None,
+                cancel_rx.as_deref_mut(),
+            ).await?;
+
+            let archive_path = volume_paths[0].clone();
+            let password = password.map(str::to_string);
+            Self::run_unrar(cancel_rx, move |cancelled| {
+                let mut visitor = SinkVisitor { sink: &mut sink, cancelled: &cancelled, error: None };
+                unrar_stream::process_entries(&archive_path, password.as_deref(), Self::normalize_path, &mut visitor)?;
+                match visitor.error {
+                    Some(e) => Err(e),
+                    None => Ok(()),
+                }
+            }).await
+        }.await;
+
+        // 清理临时文件
+        for path in &volume_paths {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+
+        result
+    }
+
+    /// 在布局中查找要解压的条目；加密条目只有提供了密码才能继续
+    fn find_target(layout: &RarLayout, entry_path: &str, password: Option<&str>) -> Result<RarFileBlock, String> {
+        let target = layout.files.iter()
+            .find(|file| file.path == entry_path)
+            .cloned()
+            .ok_or_else(|| "File not found in archive".to_string())?;
+
+        if target.is_dir {
+            return Err("Cannot preview directory".to_string());
+        }
+
+        if target.is_encrypted && password.is_none() {
+            return Err(unrar_stream::PASSWORD_REQUIRED_ERROR.to_string());
+        }
+
+        Ok(target)
+    }
+
+    /// 在阻塞线程中运行 unrar 解压任务，收到取消信号时通知任务在下一块输出时中止
+    async fn run_unrar<T, F>(
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        task: F,
+    ) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<AtomicBool>) -> Result<T, String> + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let task_cancelled = cancelled.clone();
+        let mut handle = tokio::task::spawn_blocking(move || task(task_cancelled));
+
+        let result = match cancel_rx {
+            Some(cancel_rx) => tokio::select! {
+                result = &mut handle => result,
+                Ok(()) = cancel_rx.recv() => {
+                    cancelled.store(true, Ordering::Relaxed);
+                    let _ = handle.await;
+                    return Err("download.cancelled".to_string());
+                }
+            },
+            None => handle.await,
+        };
+        result.map_err(|e| format!("RAR extraction task failed: {}", e))?
+    }
+
+    /// 按解压目标条目时的全部输出（solid 条目包括之前的条目）检查解压限制
+    ///
+    /// solid 条目需要先解压之前的所有条目，因此在下载数据之前按块头中的大小检查。
+    fn check_output_limit(layout: &RarLayout, target: &RarFileBlock) -> Result<(), String> {
+        Self::check_range_output_limit(layout, Self::data_blocks_range(layout, target))
+    }
+
+    /// 按块头中的大小检查解压 [start, start + length) 范围内全部条目的输出
+    fn check_range_output_limit(layout: &RarLayout, (start, length): (u64, u64)) -> Result<(), String> {
+        let output = layout.files.iter()
+            .filter(|file| file.header_offset >= start && file.header_offset < start + length)
+            .fold(0u64, |total, file| total.saturating_add(file.size));
+        OutputLimit::for_compressed_size(length).check(output)?;
+        Ok(())
+    }
+
+    /// 目标条目所需的数据块范围（solid 条目需要之前的所有块）
+    fn data_blocks_range(layout: &RarLayout, target: &RarFileBlock) -> (u64, u64) {
+        let start = if target.is_solid {
+            layout.volumes[0].main_header_end
+        } else {
+            target.header_offset
+        };
+        (start, target.block_end - start)
+    }
+
+    /// 把 [blocks_offset, blocks_offset + blocks_size) 范围内的块重组为临时RAR文件，
+    /// 路径按卷的顺序追加到 `paths`（出错时调用方据此清理）
+    ///
+    /// 涉及的每一卷都写成一个临时分卷：签名和主块头 + 所需的块，除最后一卷外再加上结束块，
+    /// 使 unrar 解压到卷末时按文件名打开下一卷。
+    #[allow(clippy::too_many_arguments)]
+    async fn write_temp_volumes(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        layout: &RarLayout,
+        (blocks_offset, blocks_size): (u64, u64),
+        temp_id: &uuid::Uuid,
+        paths: &mut Vec<std::path::PathBuf>,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), String> {
+        let blocks_end = blocks_offset + blocks_size;
+
+        // 每卷中需要的块范围（不含签名、主块头和结束块）
+        let parts: Vec<(usize, u64, u64)> = layout.volumes.iter()
+            .enumerate()
+            .filter_map(|(index, volume)| {
+                let data_end = volume.end_block.map_or(volume.end, |(offset, _)| offset);
+                let start = blocks_offset.max(volume.main_header_end);
+                let end = blocks_end.min(data_end);
+                (start < end).then_some((index, start, end))
+            })
+            .collect();
+        if parts.is_empty() {
+            return Err("Invalid RAR data range".to_string());
+        }
+
+        let total: u64 = parts.iter().map(|(_, start, end)| end - start).sum();
+        log::debug!(
+            "读取RAR数据块: 偏移 {}, 大小 {} 字节, {} 卷 (solid: {})",
+            blocks_offset, total, parts.len(), layout.is_solid
+        );
 
-        for entry_result in archive.iter() {
-            match entry_result {
-                Ok(entry) => {
-                    if entry.filename == entry_path && !entry.is_directory() {
-                        // 提取文件到内存
-                        let mut output = Vec::new();
-                        entry.extract_to_writer(&mut output).map_err(|e| e.to_string())?;
+        let mut copied = 0u64;
+        for (position, &(index, start, end)) in parts.iter().enumerate() {
+            let volume = &layout.volumes[index];
+            let path = std::env::temp_dir().join(Self::temp_volume_name(layout, temp_id, index));
+            paths.push(path.clone());
 
+            let mut file = tokio::fs::File::create(&path).await
+                .map_err(|e| format!("Failed to write temporary RAR file: {}", e))?;
+            copy_range_to_writer(client, file_path, volume.start, volume.main_header_end, &mut file, None, None).await?;
 
-                        let total_size = entry.unpacked_size;
-                        let preview_size = max_size.min(output.len());
+            let progress = progress_callback.map(|callback| move |current: u64, _: u64| callback(copied + current, total));
+            copy_range_to_writer(
+                client,
+                file_path,
+                start,
+                end,
+                &mut file,
+                progress.as_ref().map(|progress| progress as &(dyn Fn(u64, u64) + Send + Sync)),
+                cancel_rx.as_deref_mut(),
+            ).await?;
+            copied += end - start;
 
-                        // 限制预览大小
-                        if output.len() > preview_size {
-                            output.truncate(preview_size);
+            if let Some((end_offset, end_size)) = volume.end_block.filter(|_| position + 1 < parts.len()) {
+                copy_range_to_writer(client, file_path, end_offset, end_offset + end_size, &mut file, None, None).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 临时分卷的文件名，按 unrar 查找下一卷的规则编号：
+    /// RAR 5.x 和使用新命名方式的 RAR 4.x 为 `.partN.rar`，否则为 `.rar`、`.r00`、`.r01`…
+    fn temp_volume_name(layout: &RarLayout, temp_id: &uuid::Uuid, index: usize) -> String {
+        if layout.volume_count <= 1 {
+            format!("dataset_viewer_rar_{}.rar", temp_id)
+        } else if layout.version == 5 || layout.new_numbering {
+            let width = layout.volume_count.to_string().len();
+            format!("dataset_viewer_rar_{}.part{:0width$}.rar", temp_id, index + 1, width = width)
+        } else if index == 0 {
+            format!("dataset_viewer_rar_{}.rar", temp_id)
+        } else {
+            format!("dataset_viewer_rar_{}.r{:02}", temp_id, index - 1)
+        }
+    }
+
+    /// 逐块读取RAR块头，构建压缩包结构
+    ///
+    /// 范围为 `LayoutScope::Until` 时，找到该条目（跨卷条目为最后一段）后立即停止，避免读取其后的块头。
+    async fn read_layout(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        scope: LayoutScope<'_>,
+    ) -> Result<RarLayout, String> {
+        let signature = client.read_file_range(file_path, 0, RAR5_SIGNATURE.len().min(file_size as usize) as u64)
+            .await
+            .map_err(|e| format!("Failed to read RAR signature: {}", e))?;
+
+        if signature.starts_with(RAR5_SIGNATURE) {
+            Self::read_rar5_layout(client, file_path, file_size, scope).await
+        } else if signature.starts_with(RAR4_SIGNATURE) {
+            Self::read_rar4_layout(client, file_path, file_size, scope).await
+        } else {
+            Err("Invalid RAR header".to_string())
+        }
+    }
+
+    /// 各分卷在文件中的范围；普通 RAR 文件只有一卷
+    fn volume_ranges(client: &Arc<dyn StorageClient>, file_path: &str, file_size: u64) -> Vec<(u64, u64)> {
+        let starts = client.volume_offsets(file_path).unwrap_or_else(|| vec![0]);
+        starts.iter()
+            .enumerate()
+            .map(|(index, &start)| (start, starts.get(index + 1).copied().unwrap_or(file_size)))
+            .collect()
+    }
+
+    /// 检查后续分卷的签名与第一卷一致
+    async fn check_volume_signature(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        start: u64,
+        end: u64,
+        signature: &[u8],
+    ) -> Result<(), String> {
+        let data = client.read_file_range(file_path, start, (signature.len() as u64).min(end - start))
+            .await
+            .map_err(|e| format!("Failed to read RAR signature: {}", e))?;
+        if data != signature {
+            return Err(format!("Invalid RAR volume header at {}", start));
+        }
+        Ok(())
+    }
+
+    /// 读取从指定位置开始的块头数据，块头长度超出预读大小时补充读取（不超过 `end`）
+    async fn read_block(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        end: u64,
+        offset: u64,
+        min_length: u64,
+    ) -> Result<Vec<u8>, String> {
+        let length = min_length.max(BLOCK_READ_SIZE).min(end - offset);
+        client.read_file_range(file_path, offset, length)
+            .await
+            .map_err(|e| format!("Failed to read RAR block at {}: {}", offset, e))
+    }
+
+    /// 解析 RAR 4.x 块结构
+    async fn read_rar4_layout(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        scope: LayoutScope<'_>,
+    ) -> Result<RarLayout, String> {
+        let volume_ranges = Self::volume_ranges(&client, file_path, file_size);
+        let mut layout = RarLayout::new(4, volume_ranges.len());
+
+        for (volume_index, &(start, end)) in volume_ranges.iter().enumerate() {
+            if volume_index > 0 {
+                Self::check_volume_signature(&client, file_path, start, end, RAR4_SIGNATURE).await?;
+            }
+
+            let mut volume = RarVolume {
+                start,
+                end,
+                main_header_end: start + RAR4_SIGNATURE.len() as u64,
+                end_block: None,
+            };
+            let mut next_volume = true;
+            let mut stop = false;
+            let mut offset = volume.main_header_end;
+
+            while offset.saturating_add(7) <= end {
+                let mut block = Self::read_block(&client, file_path, end, offset, 7).await?;
+                if block.len() < 7 {
+                    break;
+                }
+
+                let block_type = block[2];
+                let flags = u16::from_le_bytes([block[3], block[4]]);
+                let header_size = u16::from_le_bytes([block[5], block[6]]) as u64;
+                if header_size < 7 {
+                    return Err(format!("Invalid RAR block header size {} at {}", header_size, offset));
+                }
+
+                if (block.len() as u64) < header_size {
+                    block = Self::read_block(&client, file_path, end, offset, header_size).await?;
+                    if (block.len() as u64) < header_size {
+                        log::warn!("RAR块头不完整，位置 {}", offset);
+                        break;
+                    }
+                }
+                let header = &block[..header_size as usize];
+
+                let mut data_size = if flags & RAR4_LONG_BLOCK != 0 && header.len() >= 11 {
+                    u32::from_le_bytes([header[7], header[8], header[9], header[10]]) as u64
+                } else {
+                    0
+                };
+
+                match block_type {
+                    RAR4_BLOCK_MAIN => {
+                        if flags & RAR4_MAIN_ENCRYPTED_HEADERS != 0 {
+                            return Err("archive.rar.password.required".to_string());
                         }
+                        if volume_index == 0 {
+                            layout.is_solid = flags & RAR4_MAIN_SOLID != 0;
+                            layout.new_numbering = flags & RAR4_MAIN_NEW_NUMBERING != 0;
+                        }
+                        volume.main_header_end = Self::block_end(offset, header_size, 0)?;
+                    }
+                    RAR4_BLOCK_FILE => {
+                        let mut file = Self::parse_rar4_file_header(header, flags, offset)?;
+                        data_size = file.packed_size;
+                        file.block_end = Self::block_end(offset, header_size, data_size)?;
+                        let found = scope.stops_at(&file.path);
+                        layout.push_file(file, flags & RAR4_FILE_SPLIT_BEFORE != 0);
+                        if found && flags & RAR4_FILE_SPLIT_AFTER == 0 {
+                            stop = true;
+                            break;
+                        }
+                    }
+                    RAR4_BLOCK_END => {
+                        volume.end_block = Some((offset, Self::block_end(offset, header_size, data_size)? - offset));
+                        next_volume = flags & RAR4_END_NEXT_VOLUME != 0;
+                        break;
+                    }
+                    _ => {}
+                }
+
+                offset = Self::block_end(offset, header_size, data_size)?;
+
+                if scope == LayoutScope::Analysis && layout.files.len() >= MAX_ENTRIES {
+                    log::warn!("RAR条目数量达到限制({})，停止分析", MAX_ENTRIES);
+                    layout.truncated = true;
+                    stop = true;
+                    break;
+                }
+                if scope == LayoutScope::All {
+                    limits::current_policy().check_entries(layout.files.len() as u64)?;
+                }
+            }
+
+            layout.volumes.push(volume);
+            if stop || !next_volume {
+                break;
+            }
+        }
+
+        Ok(layout)
+    }
+
+    /// 解析 RAR 4.x 文件块头
+    fn parse_rar4_file_header(header: &[u8], flags: u16, offset: u64) -> Result<RarFileBlock, String> {
+        const FIXED_SIZE: usize = 32;
+        if header.len() < FIXED_SIZE {
+            return Err(format!("RAR file header too short at {}", offset));
+        }
+
+        let mut packed_size = u32::from_le_bytes([header[7], header[8], header[9], header[10]]) as u64;
+        let mut size = u32::from_le_bytes([header[11], header[12], header[13], header[14]]) as u64;
+        let crc32 = u32::from_le_bytes([header[16], header[17], header[18], header[19]]);
+        let dos_time = u32::from_le_bytes([header[20], header[21], header[22], header[23]]);
+        let name_size = u16::from_le_bytes([header[26], header[27]]) as usize;
+
+        let mut name_offset = FIXED_SIZE;
+        if flags & RAR4_FILE_LARGE != 0 {
+            if header.len() < FIXED_SIZE + 8 {
+                return Err(format!("RAR file header too short at {}", offset));
+            }
+            let high_packed = u32::from_le_bytes([header[32], header[33], header[34], header[35]]) as u64;
+            let high_size = u32::from_le_bytes([header[36], header[37], header[38], header[39]]) as u64;
+            packed_size |= high_packed << 32;
+            size |= high_size << 32;
+            name_offset += 8;
+        }
+
+        if name_offset + name_size > header.len() {
+            return Err(format!("RAR file name exceeds header at {}", offset));
+        }
+        let name_bytes = &header[name_offset..name_offset + name_size];
+        let name = if flags & RAR4_FILE_UNICODE != 0 {
+            Self::decode_rar4_unicode_name(name_bytes)
+        } else {
+            String::from_utf8_lossy(name_bytes).to_string()
+        };
+
+        let is_dir = flags & RAR4_FILE_DIRECTORY == RAR4_FILE_DIRECTORY;
+
+        Ok(RarFileBlock {
+            path: Self::normalize_path(&name),
+            size,
+            packed_size,
+            is_dir,
+            is_solid: flags & RAR4_FILE_SOLID != 0,
+            is_encrypted: flags & RAR4_FILE_ENCRYPTED != 0,
+            crc32: if is_dir { None } else { Some(crc32) },
+            modified_time: dos_datetime_to_rfc3339(dos_time),
+            header_offset: offset,
+            block_end: 0,
+        })
+    }
+
+    /// 解码 RAR 4.x 的 Unicode 文件名（ASCII 名称 + 0 + 压缩的 UTF-16 名称）
+    fn decode_rar4_unicode_name(data: &[u8]) -> String {
+        let Some(separator) = data.iter().position(|&b| b == 0) else {
+            return String::from_utf8_lossy(data).to_string();
+        };
+
+        let name = &data[..separator];
+        let encoded = &data[separator + 1..];
+        if encoded.is_empty() {
+            return String::from_utf8_lossy(name).to_string();
+        }
+
+        let mut result: Vec<u16> = Vec::with_capacity(name.len());
+        let high_byte = encoded[0] as u16;
+        let mut enc_pos = 1;
+        let mut dec_pos = 0;
+        let mut flags = 0u8;
+        let mut flag_bits = 0;
 
-                        let content = if file_type.is_text() {
-                            match String::from_utf8(output.clone()) {
-                                Ok(text) => text,
-                                Err(_) => TextDecoder::try_decode_text(output)?,
-                            }
+        while enc_pos < encoded.len() {
+            if flag_bits == 0 {
+                flags = encoded[enc_pos];
+                enc_pos += 1;
+                flag_bits = 8;
+            }
+
+            match flags >> 6 {
+                0 => {
+                    let Some(&low) = encoded.get(enc_pos) else { break };
+                    result.push(low as u16);
+                    enc_pos += 1;
+                    dec_pos += 1;
+                }
+                1 => {
+                    let Some(&low) = encoded.get(enc_pos) else { break };
+                    result.push(low as u16 | (high_byte << 8));
+                    enc_pos += 1;
+                    dec_pos += 1;
+                }
+                2 => {
+                    if enc_pos + 1 >= encoded.len() {
+                        break;
+                    }
+                    result.push(u16::from_le_bytes([encoded[enc_pos], encoded[enc_pos + 1]]));
+                    enc_pos += 2;
+                    dec_pos += 1;
+                }
+                _ => {
+                    let Some(&length) = encoded.get(enc_pos) else { break };
+                    enc_pos += 1;
+                    if length & 0x80 != 0 {
+                        let Some(&correction) = encoded.get(enc_pos) else { break };
+                        enc_pos += 1;
+                        for _ in 0..(length & 0x7f) as usize + 2 {
+                            let Some(&byte) = name.get(dec_pos) else { break };
+                            result.push((byte.wrapping_add(correction) as u16) | (high_byte << 8));
+                            dec_pos += 1;
+                        }
+                    } else {
+                        for _ in 0..length as usize + 2 {
+                            let Some(&byte) = name.get(dec_pos) else { break };
+                            result.push(byte as u16);
+                            dec_pos += 1;
+                        }
+                    }
+                }
+            }
+
+            flags <<= 2;
+            flag_bits -= 2;
+        }
+
+        String::from_utf16_lossy(&result)
+    }
+
+    /// 解析 RAR 5.x 块结构
+    async fn read_rar5_layout(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        scope: LayoutScope<'_>,
+    ) -> Result<RarLayout, String> {
+        let volume_ranges = Self::volume_ranges(&client, file_path, file_size);
+        let mut layout = RarLayout::new(5, volume_ranges.len());
+
+        for (volume_index, &(start, end)) in volume_ranges.iter().enumerate() {
+            if volume_index > 0 {
+                Self::check_volume_signature(&client, file_path, start, end, RAR5_SIGNATURE).await?;
+            }
+
+            let mut volume = RarVolume {
+                start,
+                end,
+                main_header_end: start + RAR5_SIGNATURE.len() as u64,
+                end_block: None,
+            };
+            let mut next_volume = true;
+            let mut stop = false;
+            let mut offset = volume.main_header_end;
+
+            while offset.saturating_add(7) <= end {
+                let mut block = Self::read_block(&client, file_path, end, offset, 7).await?;
+
+                // CRC32(4) + 块头大小(vint)，块头大小从块类型字段开始计算
+                let (header_size, size_len) = read_vint(&block, 4)
+                    .ok_or_else(|| format!("Invalid RAR5 block header at {}", offset))?;
+                let total_header_size = header_size.checked_add(4 + size_len as u64)
+                    .ok_or_else(|| format!("Invalid RAR5 block header size at {}", offset))?;
+
+                if (block.len() as u64) < total_header_size {
+                    block = Self::read_block(&client, file_path, end, offset, total_header_size).await?;
+                    if (block.len() as u64) < total_header_size {
+                        log::warn!("RAR5块头不完整，位置 {}", offset);
+                        break;
+                    }
+                }
+                let header = &block[..total_header_size as usize];
+                let mut reader = VintReader::new(header, 4 + size_len);
+
+                let block_type = reader.vint().ok_or("Invalid RAR5 block type")?;
+                let header_flags = reader.vint().ok_or("Invalid RAR5 block flags")?;
+                let extra_size = if header_flags & RAR5_HEADER_EXTRA != 0 {
+                    reader.vint().ok_or("Invalid RAR5 extra area size")?
+                } else {
+                    0
+                };
+                let data_size = if header_flags & RAR5_HEADER_DATA != 0 {
+                    reader.vint().ok_or("Invalid RAR5 data size")?
+                } else {
+                    0
+                };
+
+                match block_type {
+                    RAR5_BLOCK_MAIN => {
+                        let archive_flags = reader.vint().unwrap_or(0);
+                        if volume_index == 0 {
+                            layout.is_solid = archive_flags & RAR5_MAIN_SOLID != 0;
+                        }
+                        volume.main_header_end = Self::block_end(offset, total_header_size, data_size)?;
+                    }
+                    RAR5_BLOCK_ENCRYPTION => {
+                        // 块头被加密，必须提供密码才能列出文件
+                        return Err("archive.rar.password.required".to_string());
+                    }
+                    RAR5_BLOCK_FILE => {
+                        let extra_area = if extra_size > 0 && extra_size <= header.len() as u64 {
+                            &header[header.len() - extra_size as usize..]
                         } else {
-                            TextDecoder::format_binary_preview(output)
+                            &[]
                         };
-
-                        return Ok(PreviewBuilder::new()
-                            .content(content)
-                            .with_truncated(preview_size < total_size as usize)
-                            .total_size(total_size)
-                
-                            .build());
+                        let mut file = Self::parse_rar5_file_header(&mut reader, extra_area, offset)?;
+                        file.packed_size = data_size;
+                        file.block_end = Self::block_end(offset, total_header_size, data_size)?;
+                        let found = scope.stops_at(&file.path);
+                        layout.push_file(file, header_flags & RAR5_HEADER_SPLIT_BEFORE != 0);
+                        if found && header_flags & RAR5_HEADER_SPLIT_AFTER == 0 {
+                            stop = true;
+                            break;
+                        }
+                    }
+                    RAR5_BLOCK_END => {
+                        let end_flags = reader.vint().unwrap_or(0);
+                        volume.end_block = Some((offset, Self::block_end(offset, total_header_size, data_size)? - offset));
+                        next_volume = end_flags & RAR5_END_NEXT_VOLUME != 0;
+                        break;
                     }
+                    _ => {}
                 }
-                Err(e) => {
-                    println!("Warning: Failed to read RAR entry: {}", e);
-                    continue;
+
+                offset = Self::block_end(offset, total_header_size, data_size)?;
+
+                if scope == LayoutScope::Analysis && layout.files.len() >= MAX_ENTRIES {
+                    log::warn!("RAR条目数量达到限制({})，停止分析", MAX_ENTRIES);
+                    layout.truncated = true;
+                    stop = true;
+                    break;
+                }
+                if scope == LayoutScope::All {
+                    limits::current_policy().check_entries(layout.files.len() as u64)?;
                 }
             }
+
+            layout.volumes.push(volume);
+            if stop || !next_volume {
+                break;
+            }
         }
 
-        Err(format!("File '{}' not found in RAR archive", entry_path))
+        Ok(layout)
+    }
+
+    /// 解析 RAR 5.x 文件块头中类型相关的字段
+    fn parse_rar5_file_header(reader: &mut VintReader, extra_area: &[u8], offset: u64) -> Result<RarFileBlock, String> {
+        let error = || format!("Invalid RAR5 file header at {}", offset);
+
+        let file_flags = reader.vint().ok_or_else(error)?;
+        let size = reader.vint().ok_or_else(error)?;
+        let _attributes = reader.vint().ok_or_else(error)?;
+        let mtime = if file_flags & RAR5_FILE_MTIME != 0 {
+            Some(reader.u32().ok_or_else(error)?)
+        } else {
+            None
+        };
+        let crc32 = if file_flags & RAR5_FILE_CRC32 != 0 {
+            Some(reader.u32().ok_or_else(error)?)
+        } else {
+            None
+        };
+        let compression_info = reader.vint().ok_or_else(error)?;
+        let _host_os = reader.vint().ok_or_else(error)?;
+        let name_length = reader.vint().ok_or_else(error)? as usize;
+        let name_bytes = reader.bytes(name_length).ok_or_else(error)?;
+
+        Ok(RarFileBlock {
+            path: Self::normalize_path(&String::from_utf8_lossy(name_bytes)),
+            size,
+            packed_size: 0,
+            is_dir: file_flags & RAR5_FILE_DIRECTORY != 0,
+            is_solid: compression_info & 0x40 != 0,
+            is_encrypted: Self::rar5_extra_has_record(extra_area, RAR5_EXTRA_ENCRYPTION),
+            crc32,
+            modified_time: mtime.filter(|&t| t > 0).map(|t| {
+                use std::time::{Duration, UNIX_EPOCH};
+                use chrono::{DateTime, Utc};
+                let datetime: DateTime<Utc> = (UNIX_EPOCH + Duration::from_secs(t as u64)).into();
+                datetime.to_rfc3339()
+            }),
+            header_offset: offset,
+            block_end: 0,
+        })
+    }
+
+    /// 检查 RAR 5.x 扩展区是否包含指定类型的记录
+    fn rar5_extra_has_record(extra_area: &[u8], record_type: u64) -> bool {
+        let mut reader = VintReader::new(extra_area, 0);
+        while let Some(record_size) = reader.vint() {
+            let record_start = reader.position;
+            match reader.vint() {
+                Some(current_type) if current_type == record_type => return true,
+                Some(_) => {}
+                None => return false,
+            }
+            let Some(next) = usize::try_from(record_size).ok().and_then(|size| record_start.checked_add(size)) else {
+                return false;
+            };
+            reader.position = next;
+        }
+        false
+    }
+
+    /// 块的结束位置（块头 + 数据区）；大小来自块头，溢出说明块头损坏
+    fn block_end(offset: u64, header_size: u64, data_size: u64) -> Result<u64, String> {
+        offset.checked_add(header_size)
+            .and_then(|end| end.checked_add(data_size))
+            .ok_or_else(|| format!("Invalid RAR block size at {}", offset))
+    }
+
+    /// 统一路径分隔符（RAR 4.x 在 Windows 上使用反斜杠）
+    fn normalize_path(path: &str) -> String {
+        path.replace('\\', "/")
     }
 
     /// 验证RAR文件头
     fn validate_rar_header(data: &[u8]) -> bool {
-        if data.len() < 7 {
-            return false;
-        }
+        data.starts_with(RAR4_SIGNATURE) || data.starts_with(RAR5_SIGNATURE)
+    }
+}
 
-        // RAR 4.x 签名: "Rar!\x1a\x07\x00"
-        if data[0] == 0x52 && data[1] == 0x61 && data[2] == 0x72 &&
-           data[3] == 0x21 && data[4] == 0x1a && data[5] == 0x07 && data[6] == 0x00 {
-            return true;
+/// 读取 RAR 5.x 的变长整数，返回值和占用的字节数
+fn read_vint(data: &[u8], offset: usize) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in data.get(offset..)?.iter().take(10).enumerate() {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
         }
+    }
+    None
+}
 
-        // RAR 5.x 签名: "Rar!\x1a\x07\x01"
-        if data[0] == 0x52 && data[1] == 0x61 && data[2] == 0x72 &&
-           data[3] == 0x21 && data[4] == 0x1a && data[5] == 0x07 && data[6] == 0x01 {
-            return true;
-        }
+/// RAR 5.x 块头字段读取器
+struct VintReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
 
-        false
+impl<'a> VintReader<'a> {
+    fn new(data: &'a [u8], position: usize) -> Self {
+        Self { data, position }
+    }
+
+    fn vint(&mut self) -> Option<u64> {
+        let (value, length) = read_vint(self.data, self.position)?;
+        self.position += length;
+        Some(value)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.position..self.position.checked_add(length)?)?;
+        self.position += length;
+        Some(bytes)
     }
 }
@@ -1,58 +1,85 @@
 /// 7z 格式处理器
+///
+/// 7z 的目录信息（End Header）位于文件末尾，文件开头的 32 字节签名头记录了它的偏移和大小。
+/// 分析时只通过 `read_file_range` 读取签名头和尾部头信息；预览时只下载目标条目所在的
+/// 数据块（solid block），而不是整个文件。
 use crate::archive::types::*;
 use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
+use crate::archive::limits::{self, OutputLimit};
+use crate::storage::traits::{StorageClient, ProgressCallback};
+use sevenz_rust::{Archive, BlockDecoder};
 use std::collections::HashMap;
-use std::io::Cursor;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::Arc;
+
+/// 7z 签名头大小
+const SIGNATURE_HEADER_SIZE: u64 = 32;
+/// 解析头部时按需补充读取的最小块大小
+const HEADER_FETCH_CHUNK: u64 = 64 * 1024;
+/// 解析头部时按需补充读取的最大轮数，防止损坏文件导致无限请求
+const MAX_HEADER_FETCH_ROUNDS: usize = 32;
 
 pub struct SevenZipHandler;
 
 #[async_trait::async_trait]
 impl CompressionHandlerDispatcher for SevenZipHandler {
-    async fn analyze_complete(&self, data: &[u8]) -> Result<ArchiveInfo, String> {
-        Self::analyze_7z_complete(data)
-    }
-
-    async fn analyze_streaming(
+    async fn analyze_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<usize>,
     ) -> Result<ArchiveInfo, String> {
-        // 7z 格式不支持流式分析，需要下载完整文件
-        Self::analyze_7z_by_download(url, headers, filename, file_size).await
+        Self::analyze_with_storage_client(client, file_path).await
     }
 
-    async fn analyze_streaming_without_size(
+    async fn extract_preview_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        // 7z 格式不支持流式分析，需要下载完整文件
-        Self::analyze_7z_by_download_no_size(url, headers, filename).await
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_7z_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx, None).await
     }
 
-    async fn extract_preview(
+    async fn extract_preview_with_password(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
         entry_path: &str,
         max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
     ) -> Result<FilePreview, String> {
-        Self::extract_7z_preview(url, headers, entry_path, max_size).await
+        Self::extract_7z_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx, password).await
     }
 
-    async fn extract_preview_with_client(
+    async fn extract_entry_to_writer(
         &self,
-        _client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
-        _file_path: &str,
-        _entry_path: &str,
-        _max_size: usize,
-        _progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
-        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
-    ) -> Result<FilePreview, String> {
-        Err("7z format does not support client-based extraction yet".to_string())
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String> {
+        Self::extract_7z_entry_to_writer(client, file_path, entry_path, writer, progress_callback, cancel_rx, password).await
+    }
+
+    async fn extract_entries_to_sink(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        Self::extract_7z_entries(client, file_path, sink, cancel_rx, password).await
     }
 
     fn compression_type(&self) -> CompressionType {
@@ -65,150 +92,547 @@ impl CompressionHandlerDispatcher for SevenZipHandler {
 }
 
 impl SevenZipHandler {
-    /// 完整7z文件分析
-    fn analyze_7z_complete(data: &[u8]) -> Result<ArchiveInfo, String> {
-        println!("开始分析7z文件，数据长度: {} 字节", data.len());
+    /// 使用存储客户端分析7z文件（只读取签名头和尾部头信息）
+    async fn analyze_with_storage_client(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<ArchiveInfo, String> {
+        log::debug!("使用storage client分析7z文件: {}", file_path);
 
-        if !Self::validate_7z_header(data) {
-            return Err("Invalid 7z header".to_string());
-        }
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        let cursor = Cursor::new(data);
-        let mut archive = SevenZReader::new(cursor, data.len() as u64).map_err(|e| e.to_string())?;
-        let mut entries = Vec::new();
-        let mut total_uncompressed_size = 0;
+        let (archive, _reader) = Self::read_archive_header(client, file_path, file_size).await?;
 
-        for (index, entry) in archive.entries().iter().enumerate() {
-            let path = entry.name().to_string();
-            let size = entry.size();
-            let is_dir = entry.is_directory();
-            let compressed_size = Some(entry.size()); // 7z 没有单独的压缩大小概念
+        let mut entries = Vec::with_capacity(archive.files.len());
+        let mut total_uncompressed_size = 0u64;
 
-            total_uncompressed_size += size;
+        for (index, file) in archive.files.iter().enumerate() {
+            if file.name.is_empty() {
+                // 跳过没有文件名的条目
+                continue;
+            }
 
-            // 处理修改时间
-            let modified_time = entry.last_write_time().and_then(|timestamp| {
-                use std::time::{UNIX_EPOCH, Duration};
-                use chrono::{DateTime, Utc};
+            total_uncompressed_size += file.size;
 
-                // Windows FILETIME 转换为 Unix 时间戳
-                let unix_timestamp = (timestamp - 116444736000000000) / 10000000;
-                let duration = Duration::from_secs(unix_timestamp);
-                let datetime = UNIX_EPOCH + duration;
-                let datetime: DateTime<Utc> = datetime.into();
-                Some(datetime.to_rfc3339())
-            });
+            let mut metadata = HashMap::new();
+            if let Some(Some(block_index)) = archive.stream_map.file_folder_index.get(index) {
+                metadata.insert("block_index".to_string(), block_index.to_string());
+            }
+            if file.has_windows_attributes {
+                metadata.insert("windows_attributes".to_string(), format!("0x{:08x}", file.windows_attributes));
+            }
 
             entries.push(ArchiveEntry {
-                path,
-                size,
-                compressed_size: Some(compressed_size),
-                is_dir,
-                modified_time,
-                crc32: entry.crc32(),
+                path: file.name.clone(),
+                size: file.size,
+                compressed_size: if file.compressed_size > 0 { Some(file.compressed_size) } else { None },
+                is_dir: file.is_directory,
+                modified_time: if file.has_last_modified_date {
+                    Self::format_file_time(file.last_modified_date.to_unix_time())
+                } else {
+                    None
+                },
+                crc32: if file.has_crc { Some(file.crc as u32) } else { None },
                 index,
-                metadata: HashMap::new(),
+                metadata,
             });
         }
 
+        log::debug!("7z分析完成，找到 {} 个条目，{} 个数据块", entries.len(), archive.folders.len());
+
         Ok(ArchiveInfoBuilder::new(CompressionType::SevenZip)
             .entries(entries)
             .total_uncompressed_size(total_uncompressed_size)
-            .total_compressed_size(data.len() as u64)
+            .total_compressed_size(file_size)
             .supports_streaming(false)
             .supports_random_access(true)
             .analysis_status(AnalysisStatus::Complete)
             .build())
     }
 
-    /// 通过下载完整文件分析7z（有文件大小）
-    async fn analyze_7z_by_download(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
-    ) -> Result<ArchiveInfo, String> {
-        println!("开始下载7z文件进行分析: {} (大小: {} 字节)", filename, file_size);
+    /// 提取7z文件预览（支持进度回调和取消信号）
+    ///
+    /// 只下载目标条目所在数据块的压缩数据；对于 solid 压缩，需要从数据块开头解压到目标条目。
+    async fn extract_7z_preview_with_progress(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<FilePreview, String> {
+        log::debug!("开始提取7z文件预览: {} -> {}", file_path, entry_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        // 限制最大下载大小（比如100MB）
-        const MAX_DOWNLOAD_SIZE: u64 = 100 * 1024 * 1024;
-        if file_size > MAX_DOWNLOAD_SIZE {
-            return Err(format!("7z file is too large for analysis: {} bytes (max: {} bytes)",
-                             file_size, MAX_DOWNLOAD_SIZE));
+        let (archive, mut reader) = Self::read_archive_header(client.clone(), file_path, file_size).await?;
+
+        let (index, entry) = archive.files.iter()
+            .enumerate()
+            .find(|(_, file)| file.name == entry_path)
+            .ok_or_else(|| "File not found in archive".to_string())?;
+
+        if entry.is_directory {
+            return Err("Cannot preview directory".to_string());
         }
 
-        let data = HttpClient::download_file(url, headers).await?;
-        Self::analyze_7z_complete(&data)
+        if !entry.has_stream || entry.size == 0 {
+            return Ok(PreviewBuilder::new()
+                .content(Vec::new())
+                .with_truncated(false)
+                .total_size(0)
+                .build());
+        }
+
+        let block_index = archive.stream_map.file_folder_index
+            .get(index)
+            .copied()
+            .flatten()
+            .ok_or_else(|| format!("No data block found for entry: {}", entry_path))?;
+
+        let (block_offset, block_size) = Self::block_range(&archive, block_index, file_size)?;
+
+        log::debug!("读取7z数据块 {}: 偏移 {}, 大小 {} 字节", block_index, block_offset, block_size);
+
+        let progress_cb = progress_callback.map(|cb| {
+            Arc::new(move |current: u64, total: u64| {
+                cb(current, total);
+            }) as ProgressCallback
+        });
+
+        let block_data = client.read_file_range_with_progress(file_path, block_offset, block_size, progress_cb, cancel_rx)
+            .await
+            .map_err(|e| format!("Failed to read 7z data block: {}", e))?;
+
+        if block_data.len() as u64 != block_size {
+            return Err(format!("Read data length ({}) does not match expected length ({})", block_data.len(), block_size));
+        }
+
+        reader.add_range(block_offset, block_data);
+
+        let total_size = entry.size;
+        let password = Self::password_bytes(password);
+        let content = Self::decode_entry(&archive, block_index, block_size, &mut reader, &password, entry_path, max_size)?;
+        let is_truncated = (content.len() as u64) < total_size;
+
+        Ok(PreviewBuilder::new()
+            .content(content)
+            .with_truncated(is_truncated)
+            .total_size(total_size)
+            .build())
     }
 
-    /// 通过下载完整文件分析7z（无文件大小）
-    async fn analyze_7z_by_download_no_size(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        println!("开始下载7z文件进行分析: {}", filename);
+    /// 流式解压7z条目并写入 `writer`
+    ///
+    /// 只有一个打包流的数据块边下载边解压；多个打包流（如 BCJ2）需要随机访问，
+    /// 整块压缩数据先按块写入临时文件再解压，内存占用与数据块大小无关。
+    async fn extract_7z_entry_to_writer(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String> {
+        log::debug!("开始流式解压7z条目: {} -> {}", file_path, entry_path);
+
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let (archive, _reader) = Self::read_archive_header(client.clone(), file_path, file_size).await?;
+
+        let (index, entry) = archive.files.iter()
+            .enumerate()
+            .find(|(_, file)| file.name == entry_path)
+            .ok_or_else(|| "File not found in archive".to_string())?;
+
+        if entry.is_directory {
+            return Err("Cannot preview directory".to_string());
+        }
+
+        if !entry.has_stream || entry.size == 0 {
+            return Ok(0);
+        }
+
+        let block_index = archive.stream_map.file_folder_index
+            .get(index)
+            .copied()
+            .flatten()
+            .ok_or_else(|| format!("No data block found for entry: {}", entry_path))?;
+
+        let (block_offset, block_size) = Self::block_range(&archive, block_index, file_size)?;
+
+        let target = entry_path.to_string();
+        let password = Self::password_bytes(password);
+        let archive = Arc::new(archive);
+        let progress = progress_callback.as_deref();
+
+        pipe_to_async_writer(writer, |mut output| Self::with_block_source(
+            &client,
+            file_path,
+            &archive,
+            block_index,
+            block_offset,
+            block_size,
+            progress,
+            cancel_rx,
+            move |archive, mut source| {
+                Self::copy_entry(archive, block_index, block_size, &mut source, &password, &target, &mut output)?;
+                output.finish()
+            },
+        )).await
+    }
+
+    /// 批量解压：读取一次头部，选中条目所在的每个数据块只下载并解压一次，按块内顺序写入 `sink`
+    async fn extract_7z_entries(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        mut sink: EntrySink,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let (archive, _reader) = Self::read_archive_header(client.clone(), file_path, file_size).await?;
+        let archive = Arc::new(archive);
+
+        // 没有数据的条目直接写入，其余条目按所在数据块分组
+        let mut blocks = std::collections::BTreeSet::new();
+        for (index, file) in archive.files.iter().enumerate() {
+            if file.is_directory || !sink.wants(&file.name) {
+                continue;
+            }
+            match archive.stream_map.file_folder_index.get(index).copied().flatten() {
+                Some(block_index) if file.has_stream && file.size > 0 => {
+                    blocks.insert(block_index);
+                }
+                _ => {
+                    sink.begin_entry_async(&file.name).await?;
+                    sink.end_entry_async().await?;
+                }
+            }
+        }
+
+        log::debug!("开始批量解压7z条目: {} ({} 个数据块)", file_path, blocks.len());
+
+        let password = Arc::new(Self::password_bytes(password));
+        for block_index in blocks {
+            let (block_offset, block_size) = Self::block_range(&archive, block_index, file_size)?;
+            let password = password.clone();
+            sink = Self::with_block_source(
+                &client,
+                file_path,
+                &archive,
+                block_index,
+                block_offset,
+                block_size,
+                None,
+                cancel_rx.as_deref_mut(),
+                move |archive, mut source| {
+                    Self::copy_selected_entries(archive, block_index, block_size, &mut source, &password, &mut sink)?;
+                    Ok(sink)
+                },
+            ).await?;
+        }
 
-        let data = HttpClient::download_file(url, headers).await?;
+        Ok(())
+    }
 
-        // 检查下载的文件大小
-        const MAX_DOWNLOAD_SIZE: usize = 100 * 1024 * 1024;
-        if data.len() > MAX_DOWNLOAD_SIZE {
-            return Err(format!("7z file is too large for analysis: {} bytes (max: {} bytes)",
-                             data.len(), MAX_DOWNLOAD_SIZE));
+    /// 准备数据块的压缩数据，并在阻塞线程中交给 `consumer` 解压
+    ///
+    /// 只有一个打包流的数据块边下载边解压；多个打包流（如 BCJ2）需要随机访问，
+    /// 整块压缩数据先按块写入临时文件再解压，内存占用与数据块大小无关。
+    /// 进度回调参数为（已下载的数据块字节数，数据块大小）。
+    #[allow(clippy::too_many_arguments)]
+    async fn with_block_source<T, F>(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        archive: &Arc<Archive>,
+        block_index: usize,
+        block_offset: u64,
+        block_size: u64,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        consumer: F,
+    ) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Archive, &mut dyn BlockSource) -> Result<T, String> + Send + 'static,
+    {
+        let archive = archive.clone();
+        let single_pack_stream = archive.folders[block_index].packed_streams.len() <= 1;
+
+        if single_pack_stream {
+            let progress = progress_callback.map(|cb| {
+                move |current: u64, _total: u64| cb(current.saturating_sub(block_offset), block_size)
+            });
+            return consume_file_stream_with_client(
+                client,
+                file_path,
+                block_offset,
+                block_offset + block_size,
+                progress.as_ref().map(|cb| cb as &(dyn Fn(u64, u64) + Send + Sync)),
+                cancel_rx,
+                move |stream| {
+                    let mut source = ForwardReader { inner: stream, position: block_offset };
+                    consumer(&archive, &mut source)
+                },
+            ).await;
         }
 
-        Self::analyze_7z_complete(&data)
+        let block_path = std::env::temp_dir().join(format!("dataset_viewer_7z_{}.block", uuid::Uuid::new_v4()));
+        let result = async {
+            let mut block_file = tokio::fs::File::create(&block_path).await
+                .map_err(|e| format!("Failed to write temporary 7z block: {}", e))?;
+            copy_range_to_writer(client, file_path, block_offset, block_offset + block_size, &mut block_file, progress_callback, cancel_rx).await?;
+            drop(block_file);
+
+            let source_path = block_path.clone();
+            tokio::task::spawn_blocking(move || {
+                let file = std::fs::File::open(&source_path)
+                    .map_err(|e| format!("Failed to open temporary 7z block: {}", e))?;
+                let mut source = OffsetReader { inner: std::io::BufReader::new(file), base: block_offset };
+                consumer(&archive, &mut source)
+            })
+            .await
+            .map_err(|e| format!("7z extraction task failed: {}", e))?
+        }.await;
+
+        let _ = tokio::fs::remove_file(&block_path).await;
+        result
     }
 
-    /// 提取7z文件预览
-    async fn extract_7z_preview(
-        url: &str,
-        headers: &HashMap<String, String>,
+    /// 7z 使用 UTF-16LE 编码的密码
+    fn password_bytes(password: Option<&str>) -> Vec<u8> {
+        password.map(|password| sevenz_rust::Password::from(password).to_vec()).unwrap_or_default()
+    }
+
+    /// 读取并解析7z头部信息
+    ///
+    /// 先读取签名头和尾部头信息；若头部本身被压缩（Encoded Header）或签名头损坏，
+    /// 解析器访问到尚未下载的区间时会记录下来，再按需补充读取后重新解析。
+    async fn read_archive_header(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+    ) -> Result<(Archive, RangeReader), String> {
+        if file_size < SIGNATURE_HEADER_SIZE {
+            return Err(format!("File too small to be a valid 7z file ({} bytes < {} bytes)", file_size, SIGNATURE_HEADER_SIZE));
+        }
+
+        let signature_header = client.read_file_range(file_path, 0, SIGNATURE_HEADER_SIZE).await
+            .map_err(|e| format!("Failed to read 7z signature header: {}", e))?;
+
+        if !Self::validate_7z_header(&signature_header) || signature_header.len() < SIGNATURE_HEADER_SIZE as usize {
+            return Err("Invalid 7z header".to_string());
+        }
+
+        let next_header_offset = u64::from_le_bytes(signature_header[12..20].try_into().unwrap());
+        let next_header_size = u64::from_le_bytes(signature_header[20..28].try_into().unwrap());
+
+        let mut reader = RangeReader::new(file_size);
+        reader.add_range(0, signature_header);
+
+        let header_start = SIGNATURE_HEADER_SIZE.checked_add(next_header_offset);
+        match header_start {
+            Some(start) if next_header_size > 0 && start.saturating_add(next_header_size) <= file_size => {
+                let header_data = client.read_file_range(file_path, start, next_header_size).await
+                    .map_err(|e| format!("Failed to read 7z end header: {}", e))?;
+                reader.add_range(start, header_data);
+            }
+            _ => {
+                // 签名头损坏时由解析器从文件末尾搜索尾部头信息
+                log::warn!("7z签名头中的尾部头位置无效: offset={}, size={}", next_header_offset, next_header_size);
+            }
+        }
+
+        for _ in 0..MAX_HEADER_FETCH_ROUNDS {
+            reader.rewind();
+            match Archive::read(&mut reader, file_size, &[]) {
+                Ok(archive) => {
+                    limits::current_policy().check_entries(archive.files.len() as u64)?;
+                    return Ok((archive, reader));
+                }
+                Err(e) => {
+                    let (offset, length) = match reader.take_missing() {
+                        Some(missing) => missing,
+                        None => return Err(Self::map_7z_error(e)),
+                    };
+                    let fetch_length = length.max(HEADER_FETCH_CHUNK).min(file_size - offset);
+                    log::debug!("7z头部解析需要补充读取: 偏移 {}, 大小 {} 字节", offset, fetch_length);
+
+                    let data = client.read_file_range(file_path, offset, fetch_length).await
+                        .map_err(|e| format!("Failed to read 7z header data: {}", e))?;
+                    reader.add_range(offset, data);
+                }
+            }
+        }
+
+        Err("Failed to parse 7z header: too many header reads".to_string())
+    }
+
+    /// 计算数据块的压缩数据在文件中的位置和大小
+    ///
+    /// 偏移来自文件头部，使用 checked 运算并校验不超出文件范围，防止构造的文件导致溢出。
+    fn block_range(archive: &Archive, block_index: usize, file_size: u64) -> Result<(u64, u64), String> {
+        let folder = archive.folders.get(block_index)
+            .ok_or_else(|| format!("Invalid 7z block index: {}", block_index))?;
+        let first_pack_index = *archive.stream_map.folder_first_pack_stream_index.get(block_index)
+            .ok_or_else(|| format!("Invalid 7z block index: {}", block_index))?;
+        let pack_count = folder.packed_streams.len().max(1);
+        let last_pack_index = first_pack_index.checked_add(pack_count - 1)
+            .filter(|&index| index < archive.pack_sizes.len() && index < archive.stream_map.pack_stream_offsets.len())
+            .ok_or_else(|| format!("Invalid 7z pack stream index for block {}", block_index))?;
+
+        let invalid_range = || format!("7z data block {} exceeds file range ({} bytes)", block_index, file_size);
+        let pack_base = SIGNATURE_HEADER_SIZE.checked_add(archive.pack_pos).ok_or_else(invalid_range)?;
+        let start = pack_base.checked_add(archive.stream_map.pack_stream_offsets[first_pack_index])
+            .ok_or_else(invalid_range)?;
+        let end = pack_base.checked_add(archive.stream_map.pack_stream_offsets[last_pack_index])
+            .and_then(|offset| offset.checked_add(archive.pack_sizes[last_pack_index]))
+            .filter(|&end| end >= start && end <= file_size)
+            .ok_or_else(invalid_range)?;
+
+        Ok((start, end - start))
+    }
+
+    /// 解压数据块直到目标条目，并读取最多 max_size 字节
+    fn decode_entry(
+        archive: &Archive,
+        block_index: usize,
+        block_size: u64,
+        reader: &mut RangeReader,
+        password: &[u8],
         entry_path: &str,
         max_size: usize,
-    ) -> Result<FilePreview, String> {
-        println!("开始从7z文件提取预览: {}", entry_path);
+    ) -> Result<Vec<u8>, String> {
+        let mut content = None;
+        let mut output_check = Self::block_output_check(block_size);
+
+        let decoder = BlockDecoder::new(block_index, archive, password, reader);
+        decoder.for_each_entries(&mut |entry, entry_reader| {
+            output_check(entry.size())?;
+            if entry.name() == entry_path {
+                let mut buffer = Vec::with_capacity(max_size.min(entry.size() as usize));
+                entry_reader.take(max_size as u64).read_to_end(&mut buffer)?;
+                content = Some(buffer);
+                return Ok(false);
+            }
+            // solid 数据块中目标条目之前的数据必须解压后丢弃
+            std::io::copy(entry_reader, &mut std::io::sink())?;
+            Ok(true)
+        }).map_err(Self::map_7z_error)?;
 
-        // 下载完整文件
-        let data = HttpClient::download_file(url, headers).await?;
-        let cursor = Cursor::new(data);
-        let mut archive = SevenZArchive::read(cursor).map_err(|e| e.to_string())?;
+        content.ok_or_else(|| "File not found in archive".to_string())
+    }
 
-        // 查找目标文件
-        for entry in archive.entries() {
-            if entry.name() == entry_path && !entry.is_directory() {
-                let mut output = Vec::new();
-                archive.extract_to_writer(entry, &mut output).map_err(|e| e.to_string())?;
+    /// 解压数据块直到目标条目，并把目标条目的全部数据写入 `output`
+    fn copy_entry<R: Read + Seek, W: Write>(
+        archive: &Archive,
+        block_index: usize,
+        block_size: u64,
+        source: &mut R,
+        password: &[u8],
+        entry_path: &str,
+        output: &mut W,
+    ) -> Result<u64, String> {
+        let mut written = None;
+        let mut output_check = Self::block_output_check(block_size);
+
+        let decoder = BlockDecoder::new(block_index, archive, password, source);
+        decoder.for_each_entries(&mut |entry, entry_reader| {
+            output_check(entry.size())?;
+            if entry.name() == entry_path {
+                written = Some(std::io::copy(entry_reader, output)?);
+                return Ok(false);
+            }
+            // solid 数据块中目标条目之前的数据必须解压后丢弃
+            std::io::copy(entry_reader, &mut std::io::sink())?;
+            Ok(true)
+        }).map_err(Self::map_7z_error)?;
 
+        written.ok_or_else(|| "File not found in archive".to_string())
+    }
 
-                let total_size = entry.size();
-                let preview_size = max_size.min(output.len());
+    /// 解压整个数据块，把 `sink` 选中的条目写入其中；块内选中的条目都写入后提前结束
+    fn copy_selected_entries<R: Read + Seek>(
+        archive: &Archive,
+        block_index: usize,
+        block_size: u64,
+        source: &mut R,
+        password: &[u8],
+        sink: &mut EntrySink,
+    ) -> Result<(), String> {
+        let mut remaining = archive.files.iter()
+            .enumerate()
+            .filter(|(index, file)| {
+                archive.stream_map.file_folder_index.get(*index).copied().flatten() == Some(block_index)
+                    && !file.is_directory
+                    && sink.wants(&file.name)
+            })
+            .count();
+        let mut output_check = Self::block_output_check(block_size);
+
+        let decoder = BlockDecoder::new(block_index, archive, password, source);
+        decoder.for_each_entries(&mut |entry, entry_reader| {
+            output_check(entry.size())?;
+            if entry.is_directory() || !sink.wants(entry.name()) {
+                // solid 数据块中未选中的条目也必须解压后丢弃
+                std::io::copy(entry_reader, &mut std::io::sink())?;
+                return Ok(true);
+            }
 
-                // 限制预览大小
-                if output.len() > preview_size {
-                    output.truncate(preview_size);
-                }
+            sink.begin_entry(entry.name()).map_err(std::io::Error::other)?;
+            std::io::copy(entry_reader, sink)?;
+            sink.end_entry().map_err(std::io::Error::other)?;
+            remaining -= 1;
+            Ok(remaining > 0)
+        }).map_err(Self::map_7z_error)?;
+        Ok(())
+    }
 
-                let content = if file_type.is_text() {
-                    match String::from_utf8(output.clone()) {
-                        Ok(text) => text,
-                        Err(_) => TextDecoder::try_decode_text(output)?,
-                    }
-                } else {
-                    TextDecoder::format_binary_preview(output)
-                };
+    /// 数据块解压输出的累计检查：按条目声明的大小累加（解码器按声明大小限制每个条目的读取）
+    fn block_output_check(block_size: u64) -> impl FnMut(u64) -> Result<(), std::io::Error> {
+        let limit = OutputLimit::for_compressed_size(block_size);
+        let mut decoded = 0u64;
+        move |entry_size: u64| {
+            decoded = decoded.saturating_add(entry_size);
+            limit.check(decoded).map_err(std::io::Error::from)
+        }
+    }
 
-                return Ok(PreviewBuilder::new()
-                    .content(content)
-                    .with_truncated(preview_size < total_size as usize)
-                    .total_size(total_size)
-    
-                    .build());
+    /// 将7z解析错误转换为前端可识别的错误信息
+    fn map_7z_error(error: sevenz_rust::Error) -> String {
+        match error {
+            sevenz_rust::Error::Io(e, context) => {
+                limits::map_io_error(e, |e| format!("Failed to parse 7z archive: {:?}", sevenz_rust::Error::Io(e, context)))
             }
+            sevenz_rust::Error::PasswordRequired => "archive.7z.password.required".to_string(),
+            // 只有提供了密码时解码器才会返回这个错误
+            sevenz_rust::Error::MaybeBadPassword(_) => "archive.7z.password.incorrect".to_string(),
+            sevenz_rust::Error::UnsupportedCompressionMethod(method) => {
+                format!("Unsupported 7z compression method: {}", method)
+            }
+            other => format!("Failed to parse 7z archive: {:?}", other),
         }
+    }
+
+    fn format_file_time(unix_timestamp: i64) -> Option<String> {
+        if unix_timestamp <= 0 {
+            return None;
+        }
+
+        use std::time::{Duration, UNIX_EPOCH};
+        use chrono::{DateTime, Utc};
 
-        Err(format!("File '{}' not found in 7z archive", entry_path))
+        let datetime = UNIX_EPOCH + Duration::from_secs(unix_timestamp as u64);
+        let datetime: DateTime<Utc> = datetime.into();
+        Some(datetime.to_rfc3339())
     }
 
     /// 验证7z文件头
@@ -222,3 +646,159 @@ impl SevenZipHandler {
         data[3] == 0xaf && data[4] == 0x27 && data[5] == 0x1c
     }
 }
+
+/// 由若干已下载区间组成的只读文件视图
+///
+/// 7z 解析器需要同步的 `Read + Seek`，而存储客户端是异步的。解析器只会访问头部和
+/// 目标数据块，因此预先下载这些区间即可；访问未下载的区间时记录位置并返回错误，
+/// 由调用方补充读取后重试。
+struct RangeReader {
+    len: u64,
+    position: u64,
+    ranges: Vec<(u64, Vec<u8>)>,
+    missing: Option<(u64, u64)>,
+}
+
+impl RangeReader {
+    fn new(len: u64) -> Self {
+        Self {
+            len,
+            position: 0,
+            ranges: Vec::new(),
+            missing: None,
+        }
+    }
+
+    fn add_range(&mut self, offset: u64, data: Vec<u8>) {
+        if !data.is_empty() {
+            self.ranges.push((offset, data));
+        }
+    }
+
+    fn rewind(&mut self) {
+        self.position = 0;
+        self.missing = None;
+    }
+
+    fn take_missing(&mut self) -> Option<(u64, u64)> {
+        self.missing.take()
+    }
+}
+
+impl Read for RangeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+
+        let position = self.position;
+        let range = self.ranges.iter().find(|(offset, data)| {
+            position >= *offset && position < *offset + data.len() as u64
+        });
+
+        match range {
+            Some((offset, data)) => {
+                let start = (position - offset) as usize;
+                let count = buf.len().min(data.len() - start);
+                buf[..count].copy_from_slice(&data[start..start + count]);
+                self.position += count as u64;
+                Ok(count)
+            }
+            None => {
+                let length = (buf.len() as u64).min(self.len - position);
+                self.missing = Some((position, length));
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("range {}+{} not loaded", position, length),
+                ))
+            }
+        }
+    }
+}
+
+impl Seek for RangeReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        match new_position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid seek position")),
+        }
+    }
+}
+
+/// 数据块压缩数据的读取器（边下载边读取的数据流或临时文件）
+trait BlockSource: Read + Seek + Send {}
+
+impl<R: Read + Seek + Send> BlockSource for R {}
+
+/// 只能向前定位的顺序读取器
+///
+/// 单个打包流的数据块解压时只会定位到数据块开头一次，之后顺序读取，
+/// 因此把从 `position` 开始的数据流包装成 `Seek` 即可边下载边解压；向后定位时返回错误。
+struct ForwardReader<R: Read> {
+    inner: R,
+    position: u64,
+}
+
+impl<R: Read> Read for ForwardReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read> Seek for ForwardReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+
+        match target {
+            Some(target) if target >= self.position => {
+                let skip = target - self.position;
+                let skipped = std::io::copy(&mut (&mut self.inner).take(skip), &mut std::io::sink())?;
+                self.position += skipped;
+                if skipped < skip {
+                    return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "seek beyond end of stream"));
+                }
+                Ok(self.position)
+            }
+            _ => Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "backward seek on sequential stream")),
+        }
+    }
+}
+
+/// 把文件内偏移 `base` 处开始的数据（例如写入临时文件的数据块）映射回原文件偏移的读取器
+struct OffsetReader<R: Read + Seek> {
+    inner: R,
+    base: u64,
+}
+
+impl<R: Read + Seek> Read for OffsetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Read + Seek> Seek for OffsetReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => SeekFrom::Start(offset.checked_sub(self.base).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek before data block")
+            })?),
+            other => other,
+        };
+        Ok(self.inner.seek(pos)? + self.base)
+    }
+}
@@ -1,10 +1,14 @@
 /// TAR 格式处理器
 use crate::archive::types::*;
 use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
+use crate::archive::formats::compressed_tar::TarEntryWalker;
+use crate::archive::formats::list_index::EntryIndex;
+use crate::archive::formats::tar_header::{TarHeader, TarHeaderParser, TAR_BLOCK_SIZE};
 use crate::storage::traits::StorageClient;
-use std::collections::HashMap;
 use std::sync::Arc;
 
+/// 完整分析时最多解析的条目数，更多条目需要使用分页列表
+const MAX_ANALYZED_ENTRIES: usize = 10000;
 
 pub struct TarHandler;
 
@@ -32,6 +36,50 @@ impl CompressionHandlerDispatcher for TarHandler {
         Self::extract_tar_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx).await
     }
 
+    async fn extract_entry_to_writer(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<u64, String> {
+        Self::extract_tar_entry_to_writer(client, file_path, entry_path, writer, progress_callback, cancel_rx).await
+    }
+
+    async fn test_integrity_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<IntegrityReport, String> {
+        Self::test_tar_integrity(client, file_path, progress_callback, cancel_rx).await
+    }
+
+    async fn extract_entries_to_sink(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        _password: Option<&str>,
+    ) -> Result<(), String> {
+        Self::extract_tar_entries(client, file_path, sink, cancel_rx).await
+    }
+
+    async fn build_entry_index(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+    ) -> Result<EntryIndex, String> {
+        Self::build_tar_entry_index(client, file_path).await
+    }
+
     fn compression_type(&self) -> CompressionType {
         CompressionType::Tar
     }
@@ -94,22 +142,142 @@ impl TarHandler {
         let file_size = client.get_file_size(file_path).await
             .map_err(|e| format!("Failed to get file size: {}", e))?;
 
+        let (entry_info, file_offset) = Self::find_tar_entry(
+            &client,
+            file_path,
+            file_size,
+            entry_path,
+            progress_callback.as_deref(),
+            &mut cancel_rx,
+        ).await?;
+
+        // 找到了目标文件，分块读取其内容
+        let preview_size = max_size.min(entry_info.size as usize);
+
+        let content_data = if let Some(ref callback) = progress_callback {
+            // 分块读取以显示进度
+            let chunk_size = 64 * 1024; // 64KB chunks
+            let mut all_data = Vec::with_capacity(preview_size);
+            let mut read_offset = 0u64;
+
+            while read_offset < preview_size as u64 {
+                // 检查取消信号
+                if let Some(ref mut cancel_rx) = cancel_rx {
+                    if cancel_rx.try_recv().is_ok() {
+                        return Err("download.cancelled".to_string());
+                    }
+                }
+
+                let current_chunk_size = std::cmp::min(chunk_size, preview_size as u64 - read_offset);
+                let chunk = client.read_file_range(file_path, file_offset + read_offset, current_chunk_size)
+                    .await
+                    .map_err(|e| format!("Failed to read file content chunk: {}", e))?;
+
+                all_data.extend_from_slice(&chunk);
+                read_offset += chunk.len() as u64;
+
+                // 更新进度（基于文件内容读取）
+                callback(file_offset + read_offset, file_size);
+            }
+
+            all_data
+        } else {
+            // 直接读取全部内容
+            client.read_file_range(file_path, file_offset, preview_size as u64).await
+                .map_err(|e| format!("Failed to read file content: {}", e))?
+        };
+
+        let _mime_type = detect_mime_type(&content_data);
+
+        let data_len = content_data.len();
+        Ok(PreviewBuilder::new()
+            .content(content_data)
+            .total_size(entry_info.size)
+            .with_truncated(data_len >= max_size || (data_len as u64) < entry_info.size)
+            .build())
+    }
+
+    /// 将TAR条目的数据按范围读取并流式写入 `writer`（TAR不压缩，无需解压）
+    async fn extract_tar_entry_to_writer(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<u64, String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let (entry_info, file_offset) = Self::find_tar_entry(&client, file_path, file_size, entry_path, None, &mut cancel_rx).await?;
+        let data_end = file_offset + entry_info.size;
+        if data_end > file_size {
+            return Err("Unexpected end of TAR data".to_string());
+        }
+
+        copy_range_to_writer(&client, file_path, file_offset, data_end, writer, progress_callback.as_deref(), cancel_rx).await
+    }
+
+    /// 批量解压：顺序读取一遍文件，把选中的条目依次写入 `sink`，选中的条目都写入后停止读取
+    async fn extract_tar_entries(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        mut sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        consume_file_stream_with_client(&client, file_path, 0, file_size, None, cancel_rx, move |reader| {
+            TarEntryWalker::new(reader).extract_selected(&mut sink)
+        }).await
+    }
+
+    /// 完整性测试：顺序读取整个文件，校验每个头部的校验和并确认条目数据完整
+    ///
+    /// TAR 条目数据本身没有校验和，只能检查截断。
+    async fn test_tar_integrity(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<IntegrityReport, String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        consume_file_stream_with_client(&client, file_path, 0, file_size, progress_callback.as_deref(), cancel_rx, |reader| {
+            let mut walker = TarEntryWalker::new(reader).with_strict_headers();
+            let (entries, archive_error) = walker.test_entries();
+            Ok(IntegrityReport::new(entries, archive_error))
+        }).await
+    }
+
+    /// 顺序读取头部查找条目，返回（条目信息，数据起始偏移）
+    async fn find_tar_entry(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        entry_path: &str,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        cancel_rx: &mut Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(ArchiveEntry, u64), String> {
         let mut current_offset = 0u64;
         let mut index = 0;
+        let mut parser = TarHeaderParser::new();
 
         // TAR文件以512字节为一个块
-        const BLOCK_SIZE: u64 = 512;
+        const BLOCK_SIZE: u64 = TAR_BLOCK_SIZE;
 
         while current_offset < file_size {
             // 检查取消信号
             if let Some(ref mut cancel_rx) = cancel_rx {
-                if let Ok(_) = cancel_rx.try_recv() {
+                if cancel_rx.try_recv().is_ok() {
                     return Err("download.cancelled".to_string());
                 }
             }
 
             // 更新进度
-            if let Some(ref callback) = progress_callback {
+            if let Some(callback) = progress_callback {
                 callback(current_offset, file_size);
             }
 
@@ -133,77 +301,33 @@ impl TarHandler {
             }
 
             // 解析TAR头部
-            if let Ok(entry_info) = Self::parse_tar_header(&header_data, index) {
-                // 检查是否是我们要找的文件
-                if entry_info.path == entry_path {
-                    if entry_info.is_dir {
-                        return Err("Cannot preview directory".to_string());
-                    }
-
-                    // 找到了目标文件，分块读取其内容
-                    let file_offset = current_offset + BLOCK_SIZE;
-                    let preview_size = max_size.min(entry_info.size as usize);
-
-                    let content_data = if let Some(ref callback) = progress_callback {
-                        // 分块读取以显示进度
-                        let chunk_size = 64 * 1024; // 64KB chunks
-                        let mut all_data = Vec::with_capacity(preview_size);
-                        let mut read_offset = 0u64;
-                        
-                        while read_offset < preview_size as u64 {
-                            // 检查取消信号
-                             if let Some(ref mut cancel_rx) = cancel_rx {
-                                 if let Ok(_) = cancel_rx.try_recv() {
-                                     return Err("download.cancelled".to_string());
-                                 }
-                             }
-
-                            let current_chunk_size = std::cmp::min(chunk_size, preview_size as u64 - read_offset);
-                            let chunk = client.read_file_range(file_path, file_offset + read_offset, current_chunk_size)
-                                .await
-                                .map_err(|e| format!("Failed to read file content chunk: {}", e))?;
-                            
-                            all_data.extend_from_slice(&chunk);
-                            read_offset += chunk.len() as u64;
-                            
-                            // 更新进度（基于文件内容读取）
-                            let total_progress = current_offset + BLOCK_SIZE + read_offset;
-                            callback(total_progress, file_size);
-                        }
-                        
-                        all_data
-                    } else {
-                        // 直接读取全部内容
-                        client.read_file_range(file_path, file_offset, preview_size as u64).await
-                            .map_err(|e| format!("Failed to read file content: {}", e))?
-                    };
-
-                    let _mime_type = detect_mime_type(&content_data);
-
-                    let data_len = content_data.len();
-                    return Ok(PreviewBuilder::new()
-                        .content(content_data)
-                        .total_size(entry_info.size)
-                        .with_truncated(data_len >= max_size || (data_len as u64) < entry_info.size)
-                        .build());
+            match parser.parse(&header_data, index) {
+                Ok(TarHeader::Extension { type_flag, size }) => {
+                    current_offset = Self::read_extension(client, file_path, &mut parser, current_offset, type_flag, size).await?;
                 }
+                Ok(TarHeader::Entry(entry_info)) => {
+                    // 检查是否是我们要找的文件
+                    if entry_info.path == entry_path {
+                        if entry_info.is_dir {
+                            return Err("Cannot preview directory".to_string());
+                        }
+                        return Ok((entry_info, current_offset + BLOCK_SIZE));
+                    }
 
-                // 计算文件数据的大小（向上舍入到512字节的倍数）
-                let file_size_blocks = (entry_info.size + BLOCK_SIZE - 1) / BLOCK_SIZE;
-                let file_data_size = file_size_blocks * BLOCK_SIZE;
-
-                // 跳过头部和文件数据
-                current_offset += BLOCK_SIZE + file_data_size;
-                index += 1;
+                    // 跳过头部和文件数据（文件数据向上舍入到512字节的倍数）
+                    current_offset += BLOCK_SIZE + entry_info.size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+                    index += 1;
 
-                // 防止无限循环
-                if index >= 10000 {
-                    log::warn!("TAR条目搜索达到限制(10000)，停止搜索");
-                    break;
+                    // 防止无限循环
+                    if index >= 10000 {
+                        log::warn!("TAR条目搜索达到限制(10000)，停止搜索");
+                        break;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("解析TAR头部失败，位置 {}: {}", current_offset, e);
+                    current_offset += BLOCK_SIZE;
                 }
-            } else {
-                log::warn!("解析TAR头部失败，位置 {}", current_offset);
-                current_offset += BLOCK_SIZE;
             }
         }
 
@@ -226,11 +350,69 @@ impl TarHandler {
 
         let mut entries = Vec::new();
         let mut total_uncompressed_size = 0u64;
+
+        Self::walk_tar_headers(&client, file_path, file_size, |entry| {
+            total_uncompressed_size += entry.size;
+            entries.push(entry);
+
+            // 限制条目数量以避免内存问题，更多条目需要使用分页列表
+            if entries.len() >= MAX_ANALYZED_ENTRIES {
+                log::warn!("TAR条目数量达到限制({})，停止分析", MAX_ANALYZED_ENTRIES);
+                return Ok(false);
+            }
+            Ok(true)
+        }).await?;
+
+        log::debug!("流式分析完成，找到 {} 个条目", entries.len());
+
+        let entry_count = entries.len();
+
+        Ok(ArchiveInfoBuilder::new(CompressionType::Tar)
+            .entries(entries)
+            .total_uncompressed_size(total_uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(false)
+            .analysis_status(if entry_count >= MAX_ANALYZED_ENTRIES {
+                AnalysisStatus::Partial { analyzed_entries: entry_count }
+            } else {
+                AnalysisStatus::Complete
+            })
+            .build())
+    }
+
+    /// 遍历全部头部建立列表索引
+    async fn build_tar_entry_index(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<EntryIndex, String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let mut index = EntryIndex::default();
+        Self::walk_tar_headers(&client, file_path, file_size, |entry| {
+            index.push_entry(entry)?;
+            Ok(true)
+        }).await?;
+        Ok(index)
+    }
+
+    /// 逐块读取TAR头部，把每个条目交给 `visitor`，`visitor` 返回 false 时停止
+    async fn walk_tar_headers<F>(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        mut visitor: F,
+    ) -> Result<(), String>
+    where
+        F: FnMut(ArchiveEntry) -> Result<bool, String> + Send,
+    {
         let mut current_offset = 0u64;
         let mut index = 0;
+        let mut parser = TarHeaderParser::new();
 
         // TAR文件以512字节为一个块
-        const BLOCK_SIZE: u64 = 512;
+        const BLOCK_SIZE: u64 = TAR_BLOCK_SIZE;
 
         while current_offset < file_size {
             // 读取TAR头部（512字节）
@@ -254,99 +436,49 @@ impl TarHandler {
             }
 
             // 解析TAR头部
-            if let Ok(entry_info) = Self::parse_tar_header(&header_data, index) {
-                total_uncompressed_size += entry_info.size;
-                entries.push(entry_info);
-
-                // 计算文件数据的大小（向上舍入到512字节的倍数）
-                let file_size_blocks = (entries.last().unwrap().size + BLOCK_SIZE - 1) / BLOCK_SIZE;
-                let file_data_size = file_size_blocks * BLOCK_SIZE;
+            match parser.parse(&header_data, index) {
+                Ok(TarHeader::Extension { type_flag, size }) => {
+                    current_offset = Self::read_extension(client, file_path, &mut parser, current_offset, type_flag, size).await?;
+                }
+                Ok(TarHeader::Entry(entry_info)) => {
+                    // 计算文件数据的大小（向上舍入到512字节的倍数）
+                    let file_size_blocks = entry_info.size.div_ceil(BLOCK_SIZE);
+                    let file_data_size = file_size_blocks * BLOCK_SIZE;
 
-                // 跳过头部和文件数据
-                current_offset += BLOCK_SIZE + file_data_size;
-                index += 1;
+                    // 跳过头部和文件数据
+                    current_offset += BLOCK_SIZE + file_data_size;
+                    index += 1;
 
-                // 限制条目数量以避免内存问题
-                if entries.len() >= 10000 {
-                    log::warn!("TAR条目数量达到限制(10000)，停止分析");
-                    break;
+                    if !visitor(entry_info)? {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("解析TAR头部失败，位置 {}: {}", current_offset, e);
+                    current_offset += BLOCK_SIZE;
                 }
-            } else {
-                log::warn!("解析TAR头部失败，位置 {}", current_offset);
-                current_offset += BLOCK_SIZE;
             }
         }
 
-        log::debug!("流式分析完成，找到 {} 个条目", entries.len());
-
-        let entry_count = entries.len();
-
-        Ok(ArchiveInfoBuilder::new(CompressionType::Tar)
-            .entries(entries)
-            .total_uncompressed_size(total_uncompressed_size)
-            .total_compressed_size(file_size)
-            .supports_streaming(true)
-            .supports_random_access(false)
-            .analysis_status(if entry_count >= 10000 {
-                AnalysisStatus::Partial { analyzed_entries: entry_count }
-            } else {
-                AnalysisStatus::Complete
-            })
-            .build())
+        Ok(())
     }
 
-    /// 解析TAR头部信息
-    fn parse_tar_header(header: &[u8], index: usize) -> Result<ArchiveEntry, String> {
-        if header.len() < 512 {
-            return Err("Header too short".to_string());
-        }
-
-        // 提取文件名（前100字节，以null结尾）
-        let name_bytes = &header[0..100];
-        let name_end = name_bytes.iter().position(|&b| b == 0).unwrap_or(100);
-        let name = String::from_utf8_lossy(&name_bytes[..name_end]).to_string();
-
-        if name.is_empty() {
-            return Err("Empty file name".to_string());
+    /// 读取位于 `header_offset` 的扩展头部数据并交给解析器，返回下一个头部的偏移
+    async fn read_extension(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        parser: &mut TarHeaderParser,
+        header_offset: u64,
+        type_flag: u8,
+        size: u64,
+    ) -> Result<u64, String> {
+        let data = client.read_file_range(file_path, header_offset + TAR_BLOCK_SIZE, size).await
+            .map_err(|e| format!("Failed to read TAR extension header: {}", e))?;
+        if (data.len() as u64) < size {
+            return Err("Unexpected end of TAR data".to_string());
         }
 
-        // 提取文件大小（八进制字符串，位置124-135）
-        let size_bytes = &header[124..136];
-        let size_string = String::from_utf8_lossy(size_bytes);
-        let size_str = size_string.trim_end_matches('\0').trim();
-        let size = u64::from_str_radix(size_str, 8)
-            .map_err(|_| format!("Invalid size field: {}", size_str))?;
-
-        // 提取修改时间（八进制字符串，位置136-147）
-        let mtime_bytes = &header[136..148];
-        let mtime_string = String::from_utf8_lossy(mtime_bytes);
-        let mtime_str = mtime_string.trim_end_matches('\0').trim();
-        let mtime = u64::from_str_radix(mtime_str, 8).unwrap_or(0);
-
-        // 提取文件类型（位置156）
-        let type_flag = header[156];
-        let is_directory = type_flag == b'5' || name.ends_with('/');
-
-        let last_modified = if mtime > 0 {
-            use std::time::{Duration, UNIX_EPOCH};
-            use chrono::{DateTime, Utc};
-            let duration = Duration::from_secs(mtime);
-            let datetime = UNIX_EPOCH + duration;
-            let datetime: DateTime<Utc> = datetime.into();
-            Some(datetime.to_rfc3339())
-        } else {
-            None
-        };
-
-        Ok(ArchiveEntry {
-            path: name,
-            size: size,
-            compressed_size: Some(size), // TAR文件不压缩
-            is_dir: is_directory,
-            modified_time: last_modified,
-            crc32: None,
-            index,
-            metadata: HashMap::new(),
-        })
+        parser.apply_extension(type_flag, &data)?;
+        Ok(header_offset + TAR_BLOCK_SIZE + size.div_ceil(TAR_BLOCK_SIZE) * TAR_BLOCK_SIZE)
     }
 }
@@ -0,0 +1,266 @@
+/// TAR 头部解析
+///
+/// 支持 ustar 前缀字段、base-256 数值、GNU 长文件名/长链接名（`L`/`K`）以及
+/// PAX 扩展头部（`x` 单条目、`g` 全局）。扩展头部本身不作为条目返回，
+/// 调用方读取其数据后交给 `TarHeaderParser::apply_extension`，随后的普通头部会应用这些覆盖值。
+use crate::archive::types::ArchiveEntry;
+use std::collections::HashMap;
+
+/// TAR 块大小
+pub const TAR_BLOCK_SIZE: u64 = 512;
+/// 扩展头部数据的最大长度，防止损坏的头部导致大量内存分配
+const MAX_EXTENSION_SIZE: u64 = 1024 * 1024;
+
+const TYPE_PAX_LOCAL: u8 = b'x';
+const TYPE_PAX_GLOBAL: u8 = b'g';
+const TYPE_GNU_LONG_NAME: u8 = b'L';
+const TYPE_GNU_LONG_LINK: u8 = b'K';
+
+/// 解析一个 512 字节头部的结果
+pub enum TarHeader {
+    /// 扩展头部：读取 `size` 字节数据（按块对齐）后交给 `apply_extension`
+    Extension { type_flag: u8, size: u64 },
+    /// 普通条目（已应用之前的扩展头部）
+    Entry(ArchiveEntry),
+}
+
+/// 有状态的 TAR 头部解析器，保存尚未应用的扩展头部
+#[derive(Default)]
+pub struct TarHeaderParser {
+    pax_global: HashMap<String, String>,
+    pax_local: HashMap<String, String>,
+    gnu_long_name: Option<String>,
+    gnu_long_link: Option<String>,
+}
+
+impl TarHeaderParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解析 512 字节头部
+    pub fn parse(&mut self, header: &[u8], index: usize) -> Result<TarHeader, String> {
+        if header.len() < TAR_BLOCK_SIZE as usize {
+            return Err("Header too short".to_string());
+        }
+
+        Self::verify_checksum(header)?;
+
+        let type_flag = header[156];
+        let size = Self::parse_numeric(&header[124..136])
+            .map_err(|e| format!("Invalid size field: {}", e))?;
+
+        if matches!(type_flag, TYPE_PAX_LOCAL | TYPE_PAX_GLOBAL | TYPE_GNU_LONG_NAME | TYPE_GNU_LONG_LINK) {
+            if size > MAX_EXTENSION_SIZE {
+                return Err(format!("TAR extension header too large: {} bytes", size));
+            }
+            return Ok(TarHeader::Extension { type_flag, size });
+        }
+
+        Ok(TarHeader::Entry(self.build_entry(header, type_flag, size, index)?))
+    }
+
+    /// 应用扩展头部的数据
+    pub fn apply_extension(&mut self, type_flag: u8, data: &[u8]) -> Result<(), String> {
+        match type_flag {
+            TYPE_PAX_LOCAL => Self::parse_pax_records(data, &mut self.pax_local, true),
+            TYPE_PAX_GLOBAL => Self::parse_pax_records(data, &mut self.pax_global, false),
+            TYPE_GNU_LONG_NAME => {
+                self.gnu_long_name = Some(Self::parse_string(data));
+                Ok(())
+            }
+            TYPE_GNU_LONG_LINK => {
+                self.gnu_long_link = Some(Self::parse_string(data));
+                Ok(())
+            }
+            _ => Err(format!("Unknown TAR extension header type: {}", type_flag as char)),
+        }
+    }
+
+    fn build_entry(&mut self, header: &[u8], type_flag: u8, size: u64, index: usize) -> Result<ArchiveEntry, String> {
+        // 单条目扩展只作用于紧随其后的一个条目，其中的空值表示该条目不使用同名的全局记录
+        let mut pax = self.pax_global.clone();
+        for (key, value) in std::mem::take(&mut self.pax_local) {
+            if value.is_empty() {
+                pax.remove(&key);
+            } else {
+                pax.insert(key, value);
+            }
+        }
+        let gnu_long_name = self.gnu_long_name.take();
+        let gnu_long_link = self.gnu_long_link.take();
+
+        let name = match pax.get("path") {
+            Some(path) => path.clone(),
+            None => gnu_long_name.unwrap_or_else(|| Self::header_name(header)),
+        };
+        if name.is_empty() {
+            return Err("Empty file name".to_string());
+        }
+
+        let size = match pax.get("size") {
+            Some(value) => value.parse::<u64>().map_err(|_| format!("Invalid PAX size: {}", value))?,
+            None => size,
+        };
+
+        let mtime = match pax.get("mtime") {
+            // PAX 时间可以带小数部分
+            Some(value) => value.split('.').next().and_then(|secs| secs.parse::<i64>().ok()).unwrap_or(0),
+            None => Self::parse_numeric(&header[136..148]).unwrap_or(0) as i64,
+        };
+
+        let mut metadata = HashMap::new();
+        if let Ok(mode) = Self::parse_numeric(&header[100..108]) {
+            metadata.insert("mode".to_string(), format!("{:04o}", mode & 0o7777));
+        }
+        for (key, range) in [("uid", 108..116), ("gid", 116..124)] {
+            let value = pax.get(key).cloned()
+                .or_else(|| Self::parse_numeric(&header[range]).ok().map(|v| v.to_string()));
+            if let Some(value) = value {
+                metadata.insert(key.to_string(), value);
+            }
+        }
+        if Self::is_ustar(header) {
+            for (key, range) in [("uname", 265..297), ("gname", 297..329)] {
+                let value = pax.get(key).cloned().unwrap_or_else(|| Self::parse_string(&header[range]));
+                if !value.is_empty() {
+                    metadata.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        let entry_type = match type_flag {
+            b'1' => "hardlink",
+            b'2' => "symlink",
+            b'3' => "char_device",
+            b'4' => "block_device",
+            b'5' => "directory",
+            b'6' => "fifo",
+            _ => "file",
+        };
+        metadata.insert("entry_type".to_string(), entry_type.to_string());
+
+        if matches!(type_flag, b'1' | b'2') {
+            let link_target = match pax.get("linkpath") {
+                Some(path) => path.clone(),
+                None => gnu_long_link.unwrap_or_else(|| Self::parse_string(&header[157..257])),
+            };
+            metadata.insert("link_target".to_string(), link_target);
+        }
+
+        let modified_time = if mtime > 0 {
+            chrono::DateTime::from_timestamp(mtime, 0).map(|datetime| datetime.to_rfc3339())
+        } else {
+            None
+        };
+
+        Ok(ArchiveEntry {
+            is_dir: type_flag == b'5' || name.ends_with('/'),
+            path: name,
+            size,
+            compressed_size: Some(size), // TAR文件不压缩
+            modified_time,
+            crc32: None,
+            index,
+            metadata,
+        })
+    }
+
+    /// 头部中的文件名；POSIX ustar 格式下拼接 155 字节的前缀字段
+    fn header_name(header: &[u8]) -> String {
+        let name = Self::parse_string(&header[0..100]);
+        if header[257..263] == *b"ustar\0" {
+            let prefix = Self::parse_string(&header[345..500]);
+            if !prefix.is_empty() {
+                return format!("{}/{}", prefix, name);
+            }
+        }
+        name
+    }
+
+    /// POSIX ustar 或 GNU 格式（两者都有用户名/组名字段）
+    fn is_ustar(header: &[u8]) -> bool {
+        header[257..262] == *b"ustar"
+    }
+
+    /// 校验头部校验和（校验和字段按空格计算），兼容按有符号字节计算的旧实现
+    fn verify_checksum(header: &[u8]) -> Result<(), String> {
+        let expected = Self::parse_numeric(&header[148..156])
+            .map_err(|e| format!("Invalid checksum field: {}", e))?;
+        let unsigned: u64 = header[..512].iter().enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u64 } else { b as u64 })
+            .sum();
+        let signed: i64 = header[..512].iter().enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' as i64 } else { b as i8 as i64 })
+            .sum();
+
+        if expected != unsigned && expected as i64 != signed {
+            return Err(format!("TAR header checksum mismatch: expected {}, actual {}", expected, unsigned));
+        }
+        Ok(())
+    }
+
+    /// 解析数值字段：八进制字符串，或最高位置 1 的 base-256 大端二进制（GNU 扩展，用于超过 8GB 的大小等）
+    fn parse_numeric(field: &[u8]) -> Result<u64, String> {
+        if field.first().is_some_and(|&b| b & 0x80 != 0) {
+            if field[0] == 0xff {
+                return Err("negative base-256 value".to_string());
+            }
+            let mut value = (field[0] & 0x7f) as u64;
+            for &b in &field[1..] {
+                value = value.checked_mul(256)
+                    .and_then(|v| v.checked_add(b as u64))
+                    .ok_or_else(|| "base-256 value overflow".to_string())?;
+            }
+            return Ok(value);
+        }
+
+        let text = String::from_utf8_lossy(field);
+        let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+        if text.is_empty() {
+            return Ok(0);
+        }
+        u64::from_str_radix(text, 8).map_err(|_| text.to_string())
+    }
+
+    /// 以 NUL 结尾的字符串字段
+    fn parse_string(field: &[u8]) -> String {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8_lossy(&field[..end]).to_string()
+    }
+
+    /// 解析 PAX 记录：`<长度> <键>=<值>\n`，长度包含整条记录
+    fn parse_pax_records(data: &[u8], records: &mut HashMap<String, String>, keep_empty: bool) -> Result<(), String> {
+        let mut offset = 0;
+        while offset < data.len() {
+            if data[offset] == 0 {
+                break;
+            }
+
+            let space = data[offset..].iter().position(|&b| b == b' ')
+                .ok_or_else(|| "Invalid PAX record: missing length".to_string())?;
+            let length: usize = std::str::from_utf8(&data[offset..offset + space]).ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| "Invalid PAX record length".to_string())?;
+            if length <= space + 1 || offset + length > data.len() || data[offset + length - 1] != b'\n' {
+                return Err("Invalid PAX record".to_string());
+            }
+
+            let record = &data[offset + space + 1..offset + length - 1];
+            let equals = record.iter().position(|&b| b == b'=')
+                .ok_or_else(|| "Invalid PAX record: missing '='".to_string())?;
+            let key = String::from_utf8_lossy(&record[..equals]).to_string();
+            let value = String::from_utf8_lossy(&record[equals + 1..]).to_string();
+
+            // 全局记录中的空值直接删除该键；单条目记录保留空值，应用时删除同名的全局记录
+            if value.is_empty() && !keep_empty {
+                records.remove(&key);
+            } else {
+                records.insert(key, value);
+            }
+
+            offset += length;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+/// 基于 unrar 数据回调的流式解压
+///
+/// `unrar` crate 的安全封装只能把整个条目读入内存或写入文件。这里直接使用 `unrar_sys`，
+/// 在 `UCM_PROCESSDATA` 回调中把每块解压输出交给调用方，调用方可以随时中止解压
+/// （预览已读够、收到取消信号或写入失败），内存占用与条目大小无关。
+use std::os::raw::c_int;
+use std::path::Path;
+use std::ptr::NonNull;
+use unrar_sys as native;
+
+/// 缺少密码时返回给前端的错误
+pub const PASSWORD_REQUIRED_ERROR: &str = "archive.rar.password.required";
+/// 密码错误时返回给前端的错误
+pub const PASSWORD_INCORRECT_ERROR: &str = "archive.rar.password.incorrect";
+
+/// 接收 unrar 解压输出的调用方
+pub trait EntryVisitor {
+    /// 遇到条目时调用（路径已规范化），返回 true 时解压该条目，之后的输出都属于它
+    fn enter(&mut self, path: &str) -> Result<bool, String>;
+    /// 当前条目的一块解压输出，返回 false 时中止解压
+    fn data(&mut self, data: &[u8]) -> bool;
+    /// 当前条目解压完成，返回 false 时不再处理之后的条目
+    fn leave(&mut self) -> Result<bool, String>;
+}
+
+/// 只解压一个条目的访问者
+struct SingleEntry<'a> {
+    target: &'a str,
+    on_data: &'a mut dyn FnMut(&[u8]) -> bool,
+    found: bool,
+}
+
+impl EntryVisitor for SingleEntry<'_> {
+    fn enter(&mut self, path: &str) -> Result<bool, String> {
+        self.found = path == self.target;
+        Ok(self.found)
+    }
+
+    fn data(&mut self, data: &[u8]) -> bool {
+        (self.on_data)(data)
+    }
+
+    fn leave(&mut self) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+/// 回调的用户数据
+struct CallbackState<'a> {
+    visitor: &'a mut dyn EntryVisitor,
+    stopped: bool,
+    password_missing: bool,
+}
+
+/// 已打开的压缩包句柄，离开作用域时关闭
+struct ArchiveHandle(NonNull<native::Handle>);
+
+impl Drop for ArchiveHandle {
+    fn drop(&mut self) {
+        unsafe {
+            native::RARCloseArchive(self.0.as_ptr());
+        }
+    }
+}
+
+extern "C" fn callback(msg: native::UINT, user_data: native::LPARAM, p1: native::LPARAM, p2: native::LPARAM) -> c_int {
+    if user_data == 0 {
+        return -1;
+    }
+    let state = unsafe { &mut *(user_data as *mut CallbackState) };
+    match msg {
+        native::UCM_PROCESSDATA => {
+            if state.stopped {
+                return -1;
+            }
+            let data = unsafe { std::slice::from_raw_parts(p1 as *const u8, p2 as usize) };
+            if state.visitor.data(data) {
+                0
+            } else {
+                state.stopped = true;
+                -1
+            }
+        }
+        // 下一卷不存在时停止，存在时由下面的默认分支继续
+        native::UCM_CHANGEVOLUME | native::UCM_CHANGEVOLUMEW if p2 == native::RAR_VOL_ASK => -1,
+        // 密码已通过 RARSetPassword 设置，仍然请求说明没有提供密码
+        native::UCM_NEEDPASSWORD | native::UCM_NEEDPASSWORDW => {
+            state.password_missing = true;
+            -1
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "netbsd"))]
+fn native_path(path: &Path) -> Result<std::ffi::CString, String> {
+    std::ffi::CString::new(path.as_os_str().as_encoded_bytes())
+        .map_err(|_| format!("Invalid RAR archive path: {}", path.display()))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "netbsd")))]
+fn native_path(path: &Path) -> Result<widestring::WideCString, String> {
+    widestring::WideCString::from_os_str(path)
+        .map_err(|_| format!("Invalid RAR archive path: {}", path.display()))
+}
+
+/// 在压缩包（分卷时为首卷）中找到 `entry_path`，把它的解压输出逐块交给 `on_data`
+///
+/// `on_data` 返回 false 时中止解压并正常返回；solid 压缩包中目标之前的条目会被解压后丢弃。
+/// `normalize` 把 unrar 返回的文件名转换为与列表一致的条目路径。
+pub fn process_entry(
+    archive_path: &Path,
+    entry_path: &str,
+    password: Option<&str>,
+    normalize: fn(&str) -> String,
+    on_data: &mut dyn FnMut(&[u8]) -> bool,
+) -> Result<(), String> {
+    let mut visitor = SingleEntry { target: entry_path, on_data, found: false };
+    process_entries(archive_path, password, normalize, &mut visitor)?;
+    if !visitor.found {
+        return Err("File not found in archive".to_string());
+    }
+    Ok(())
+}
+
+/// 按压缩包内顺序遍历条目，`visitor` 选中的条目以测试模式解压，输出逐块交给 `visitor`
+///
+/// 未选中的条目跳过（solid 压缩时 unrar 内部仍会解压它们）。`visitor` 中止输出时正常返回。
+pub fn process_entries(
+    archive_path: &Path,
+    password: Option<&str>,
+    normalize: fn(&str) -> String,
+    visitor: &mut dyn EntryVisitor,
+) -> Result<(), String> {
+    let archive_name = native_path(archive_path)?;
+    let mut state = CallbackState { visitor, stopped: false, password_missing: false };
+
+    let mut open_data = native::OpenArchiveDataEx::new(archive_name.as_ptr() as *const _, native::RAR_OM_EXTRACT);
+    let handle = NonNull::new(unsafe { native::RAROpenArchiveEx(&mut open_data as *mut _) } as *mut native::Handle)
+        .map(ArchiveHandle);
+    let handle = match handle {
+        Some(handle) if open_data.open_result as c_int == native::ERAR_SUCCESS => handle,
+        _ => return Err(error_message(open_data.open_result as c_int, "open", password.is_some())),
+    };
+
+    unsafe {
+        native::RARSetCallback(handle.0.as_ptr(), Some(callback), &mut state as *mut CallbackState as native::LPARAM);
+    }
+    let password = password.map(std::ffi::CString::new)
+        .transpose()
+        .map_err(|_| PASSWORD_INCORRECT_ERROR.to_string())?;
+    if let Some(password) = &password {
+        unsafe { native::RARSetPassword(handle.0.as_ptr(), password.as_ptr()) };
+    }
+
+    loop {
+        let mut header = native::HeaderDataEx::default();
+        let code = unsafe { native::RARReadHeaderEx(handle.0.as_ptr(), &mut header as *mut _) };
+        match code {
+            native::ERAR_SUCCESS => {}
+            native::ERAR_END_ARCHIVE => return Ok(()),
+            _ if state.password_missing => return Err(PASSWORD_REQUIRED_ERROR.to_string()),
+            _ => return Err(error_message(code, "read header", password.is_some())),
+        }
+
+        let name = unsafe {
+            widestring::WideCString::from_ptr_truncate(header.filename_w.as_ptr() as *const _, header.filename_w.len())
+        };
+        let selected = state.visitor.enter(&normalize(&name.to_string_lossy()))?;
+
+        // 选中的条目以测试模式解压（输出只经过回调，不写文件）；其余条目跳过
+        let operation = if selected { native::RAR_TEST } else { native::RAR_SKIP };
+        let code = unsafe {
+            native::RARProcessFileW(handle.0.as_ptr(), operation, std::ptr::null(), std::ptr::null())
+        };
+
+        if selected && state.stopped {
+            return Ok(());
+        }
+        if state.password_missing {
+            return Err(PASSWORD_REQUIRED_ERROR.to_string());
+        }
+        if code != native::ERAR_SUCCESS {
+            return Err(error_message(code, "extract entry", password.is_some()));
+        }
+        if selected && !state.visitor.leave()? {
+            return Ok(());
+        }
+    }
+}
+
+/// 把 unrar 错误码转换为错误信息；使用密码时的数据错误通常是密码不正确
+fn error_message(code: c_int, operation: &str, has_password: bool) -> String {
+    match code {
+        native::ERAR_MISSING_PASSWORD => PASSWORD_REQUIRED_ERROR.to_string(),
+        native::ERAR_BAD_PASSWORD => PASSWORD_INCORRECT_ERROR.to_string(),
+        native::ERAR_BAD_DATA if has_password => PASSWORD_INCORRECT_ERROR.to_string(),
+        native::ERAR_BAD_DATA => "Failed to extract RAR entry: CRC error or corrupt data".to_string(),
+        native::ERAR_NO_MEMORY => "Failed to extract RAR entry: out of memory".to_string(),
+        native::ERAR_BAD_ARCHIVE | native::ERAR_UNKNOWN_FORMAT => "Invalid RAR archive".to_string(),
+        native::ERAR_EOPEN => "Failed to open RAR volume".to_string(),
+        native::ERAR_EREAD => "Failed to read RAR archive".to_string(),
+        _ => format!("RAR {} failed with error code {}", operation, code),
+    }
+}
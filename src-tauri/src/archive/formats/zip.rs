@@ -1,12 +1,38 @@
 /// ZIP 格式处理器
 use crate::archive::types::*;
 use crate::archive::formats::{CompressionHandlerDispatcher, common::*};
-use crate::storage::traits::{StorageClient, ProgressCallback};
-use std::collections::HashMap;
-use std::sync::Arc;
+use crate::archive::formats::zip_crypto::{self, ZipEncryption};
+use crate::archive::formats::zip_encoding::ZipNameDecoder;
+use crate::archive::formats::list_index::{EntryIndex, PathIndex};
+use crate::archive::limits::{self, LimitedReader, OutputLimit};
+use crate::storage::traits::StorageClient;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
 
 pub struct ZipHandler;
 
+/// 中央目录文件头签名
+const CD_HEADER_SIGNATURE: u32 = 0x02014b50;
+/// 中央目录记录固定头部长度
+const CD_HEADER_SIZE: usize = 46;
+/// 完整分析时最多解析的条目数，更多条目需要使用分页列表
+const MAX_ANALYZED_ENTRIES: u64 = 10000;
+/// 分页列表按窗口读取中央目录时每次读取的大小
+const CD_WINDOW_SIZE: u64 = 4 * 1024 * 1024;
+/// 中央目录记录的最大长度（固定头部 + 三个变长字段）
+const MAX_CD_RECORD_SIZE: u64 = CD_HEADER_SIZE as u64 + 3 * 65535;
+/// 最多缓存的中央目录路径索引数量
+const MAX_CACHED_PATH_INDEXES: usize = 4;
+
+/// 中央目录路径索引：每个条目的路径和记录在中央目录内的偏移（位置即条目索引）
+type RecordIndex = PathIndex<u64>;
+
+/// 按插入顺序淘汰的路径索引缓存
+type RecordIndexCache = VecDeque<(String, Arc<RecordIndex>)>;
+
+static RECORD_INDEX_CACHE: LazyLock<Mutex<RecordIndexCache>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
 #[async_trait::async_trait]
 impl CompressionHandlerDispatcher for ZipHandler {
     async fn analyze_with_client(
@@ -28,13 +54,80 @@ impl CompressionHandlerDispatcher for ZipHandler {
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
-        Self::extract_zip_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx).await
+        Self::extract_zip_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx, None).await
+    }
+
+    async fn extract_preview_with_password(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_zip_preview_with_progress(client, file_path, entry_path, max_size, progress_callback, cancel_rx, password).await
+    }
+
+    async fn extract_entry_to_writer(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String> {
+        Self::extract_zip_entry_to_writer(client, file_path, entry_path, writer, progress_callback, cancel_rx, password).await
+    }
+
+    async fn test_integrity_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<IntegrityReport, String> {
+        Self::test_zip_integrity(client, file_path, progress_callback, cancel_rx, password).await
+    }
+
+    async fn extract_entries_to_sink(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        Self::extract_zip_entries_to_sink(client, file_path, sink, cancel_rx, password).await
+    }
+
+    async fn build_entry_index(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+    ) -> Result<EntryIndex, String> {
+        Self::build_zip_entry_index(client, file_path).await
     }
 
     fn compression_type(&self) -> CompressionType {
         CompressionType::Zip
     }
 
+    async fn list_entries_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        options: &ArchiveListOptions,
+    ) -> Result<ArchiveListing, String> {
+        Self::list_zip_entries_with_client(client, file_path, options).await
+    }
+
     fn validate_format(&self, data: &[u8]) -> bool {
         data.len() >= 4 && {
             let signature = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
@@ -100,7 +193,7 @@ impl ZipHandler {
                 
                 // 验证这是一个有效的ZIP64 EOCD定位器
                 if i + ZIP64_LOCATOR_SIZE <= data.len() {
-                    // 检查磁盘号是否为0（单文件ZIP）
+                    // ZIP64 EOCD 所在的磁盘号必须在总磁盘数范围内（分卷 ZIP 不在第 0 卷）
                     let disk_number = u32::from_le_bytes([
                         data[i + 4], data[i + 5], data[i + 6], data[i + 7]
                     ]);
@@ -108,7 +201,7 @@ impl ZipHandler {
                         data[i + 16], data[i + 17], data[i + 18], data[i + 19]
                     ]);
                     
-                    if disk_number == 0 && total_disks == 1 {
+                    if disk_number < total_disks {
                         return Some(i);
                     }
                 }
@@ -124,49 +217,48 @@ impl ZipHandler {
         file_path: &str,
         footer_data: &[u8],
         zip64_locator_pos: usize,
-        _file_size: u64,
         start_pos: u64,
+        volume_offsets: Option<&[u64]>,
     ) -> Result<(u64, u64, u64), String> {
         // 从ZIP64 EOCD定位器中读取ZIP64 EOCD记录的偏移量
         if zip64_locator_pos + 16 > footer_data.len() {
             return Err("ZIP64 EOCD locator data insufficient".to_string());
         }
         
+        let zip64_eocd_disk = u32::from_le_bytes([
+            footer_data[zip64_locator_pos + 4], footer_data[zip64_locator_pos + 5],
+            footer_data[zip64_locator_pos + 6], footer_data[zip64_locator_pos + 7]
+        ]);
         let zip64_eocd_offset = u64::from_le_bytes([
             footer_data[zip64_locator_pos + 8], footer_data[zip64_locator_pos + 9],
             footer_data[zip64_locator_pos + 10], footer_data[zip64_locator_pos + 11],
             footer_data[zip64_locator_pos + 12], footer_data[zip64_locator_pos + 13],
             footer_data[zip64_locator_pos + 14], footer_data[zip64_locator_pos + 15]
         ]);
-        
+        let zip64_eocd_offset = Self::volume_position(volume_offsets, zip64_eocd_disk, zip64_eocd_offset)?;
 
-        
         // 检查ZIP64 EOCD记录是否在我们已读取的数据范围内
-        let zip64_eocd_data = if zip64_eocd_offset >= start_pos {
+        let relative_offset = zip64_eocd_offset.checked_sub(start_pos).map(|offset| offset as usize);
+        let (cd_disk, cd_offset, cd_size, total_entries) = match relative_offset {
             // ZIP64 EOCD在我们已读取的footer_data中
-            let relative_offset = (zip64_eocd_offset - start_pos) as usize;
-            if relative_offset + 56 <= footer_data.len() {
-                &footer_data[relative_offset..relative_offset + 56]
-            } else {
+            Some(relative_offset) if relative_offset + 56 <= footer_data.len() => {
+                Self::parse_zip64_eocd_data(&footer_data[relative_offset..relative_offset + 56])?
+            }
+            _ => {
                 // 需要重新读取ZIP64 EOCD记录
                 let zip64_data = client.read_file_range(file_path, zip64_eocd_offset, 56)
                     .await
                     .map_err(|e| format!("Failed to read ZIP64 EOCD record: {}", e))?;
-                return Self::parse_zip64_eocd_data(&zip64_data);
+                Self::parse_zip64_eocd_data(&zip64_data)?
             }
-        } else {
-            // 需要重新读取ZIP64 EOCD记录
-            let zip64_data = client.read_file_range(file_path, zip64_eocd_offset, 56)
-                .await
-                .map_err(|e| format!("Failed to read ZIP64 EOCD record: {}", e))?;
-            return Self::parse_zip64_eocd_data(&zip64_data);
         };
-        
-        Self::parse_zip64_eocd_data(zip64_eocd_data)
+
+        let cd_offset = Self::central_directory_position(volume_offsets, cd_disk, cd_offset)?;
+        Ok((cd_offset, cd_size, total_entries))
     }
     
-    /// 解析ZIP64 EOCD记录数据
-    fn parse_zip64_eocd_data(data: &[u8]) -> Result<(u64, u64, u64), String> {
+    /// 解析ZIP64 EOCD记录数据，返回（中央目录所在磁盘号，中央目录偏移，中央目录大小，条目总数）
+    fn parse_zip64_eocd_data(data: &[u8]) -> Result<(u32, u64, u64, u64), String> {
         if data.len() < 56 {
             return Err(format!("ZIP64 EOCD record too short: {} bytes, need 56", data.len()));
         }
@@ -178,6 +270,8 @@ impl ZipHandler {
         }
         
         // 解析ZIP64 EOCD记录字段
+        let cd_disk = u32::from_le_bytes([data[24], data[25], data[26], data[27]]);
+
         let total_entries = u64::from_le_bytes([
             data[32], data[33], data[34], data[35],
             data[36], data[37], data[38], data[39]
@@ -192,12 +286,58 @@ impl ZipHandler {
             data[48], data[49], data[50], data[51],
             data[52], data[53], data[54], data[55]
         ]);
-        
 
-        
-        Ok((cd_offset, cd_size, total_entries))
+        Ok((cd_disk, cd_offset, cd_size, total_entries))
+    }
+
+    /// 中央目录在分卷组中的偏移
+    ///
+    /// 直接打开分卷 ZIP 的最后一卷（`.zip`）时其他分卷不可用，需要从 `.z01` 打开。
+    fn central_directory_position(volume_offsets: Option<&[u64]>, disk: u32, offset: u64) -> Result<u64, String> {
+        if volume_offsets.is_none() && disk > 0 {
+            log::warn!("ZIP中央目录位于第 {} 卷，但没有作为分卷组打开", disk as u64 + 1);
+            return Err("archive.volume.missing".to_string());
+        }
+        Self::volume_position(volume_offsets, disk, offset)
+    }
+
+    /// 把相对于第 `disk` 卷的偏移换算为分卷拼接数据中的偏移；不是分卷组时原样返回
+    fn volume_position(volume_offsets: Option<&[u64]>, disk: u32, offset: u64) -> Result<u64, String> {
+        match volume_offsets {
+            None => Ok(offset),
+            Some(starts) => starts.get(disk as usize)
+                .map(|start| start + offset)
+                .ok_or_else(|| {
+                    log::warn!("ZIP分卷缺少第 {} 卷", disk as u64 + 1);
+                    "archive.volume.missing".to_string()
+                }),
+        }
     }
     
+    /// 条目修改时间：优先使用扩展时间戳字段（0x5455，UTC 秒），否则使用 DOS 日期时间
+    fn parse_modified_time(extra_data: &[u8], dos_datetime: u32) -> Option<String> {
+        const EXTENDED_TIMESTAMP_FIELD_ID: u16 = 0x5455;
+
+        let mut offset = 0;
+        while offset + 4 <= extra_data.len() {
+            let header_id = u16::from_le_bytes([extra_data[offset], extra_data[offset + 1]]);
+            let data_size = u16::from_le_bytes([extra_data[offset + 2], extra_data[offset + 3]]) as usize;
+            let data = &extra_data[offset + 4..(offset + 4 + data_size).min(extra_data.len())];
+
+            // 标志位 0 表示包含修改时间，中央目录中只保存修改时间
+            if header_id == EXTENDED_TIMESTAMP_FIELD_ID && data.len() >= 5 && data[0] & 0x01 != 0 {
+                let mtime = i32::from_le_bytes([data[1], data[2], data[3], data[4]]) as i64;
+                if let Some(datetime) = chrono::DateTime::from_timestamp(mtime, 0) {
+                    return Some(datetime.to_rfc3339());
+                }
+            }
+
+            offset += 4 + data_size;
+        }
+
+        dos_datetime_to_rfc3339(dos_datetime)
+    }
+
     /// 解析ZIP64扩展字段
     fn parse_zip64_extra_field(
         extra_data: &[u8],
@@ -342,122 +482,194 @@ impl ZipHandler {
 
     /// 解析中央目录数据
     fn parse_central_directory(cd_data: &[u8], total_entries: u64) -> Result<Vec<ArchiveEntry>, String> {
-        const CD_HEADER_SIGNATURE: u32 = 0x02014b50;
-        const MIN_CD_HEADER_SIZE: usize = 46;
-        const MAX_FIELD_SIZE: usize = 65535;
-        const MAX_ENTRIES_LIMIT: u64 = 10000;
-        
         // 预分配容量以提高性能
-        let capacity = std::cmp::min(total_entries as usize, MAX_ENTRIES_LIMIT as usize);
+        let capacity = std::cmp::min(total_entries as usize, MAX_ANALYZED_ENTRIES as usize);
         let mut entries = Vec::with_capacity(capacity);
         let mut offset = 0;
         let mut parsed_entries = 0;
         
         // 限制处理的条目数量，避免无限循环
-        let max_entries = total_entries.min(MAX_ENTRIES_LIMIT);
+        let max_entries = total_entries.min(MAX_ANALYZED_ENTRIES);
+        let decoder = Self::detect_name_decoder(cd_data);
         
-        while offset + MIN_CD_HEADER_SIZE <= cd_data.len() && parsed_entries < max_entries {
-            // 检查中央目录文件头签名
-            let signature = u32::from_le_bytes([
-                cd_data[offset], cd_data[offset + 1],
-                cd_data[offset + 2], cd_data[offset + 3]
-            ]);
+        while offset + CD_HEADER_SIZE <= cd_data.len() && parsed_entries < max_entries {
+            let (entry, record_size) = Self::parse_central_directory_entry(cd_data, offset, parsed_entries as usize, &decoder)?;
+            if let Some(entry) = entry {
+                entries.push(entry);
+            }
+
+            offset += record_size;
+            parsed_entries += 1;
+        }
+        
+        if parsed_entries != total_entries && parsed_entries < max_entries {
+            return Err(format!("Parsed entry count ({}) does not match expected count ({})", parsed_entries, total_entries));
+        }
+        
+        Ok(entries)
+    }
 
-            if signature != CD_HEADER_SIGNATURE {
-                return Err(format!("Invalid central directory file header signature: 0x{:08x}, expected: 0x{:08x}", signature, CD_HEADER_SIGNATURE));
+    /// 根据中央目录开头的文件名检测未标记 UTF-8 的文件名所用的编码
+    ///
+    /// 只采样第一个读取窗口，保证完整分析、分页列表和条目查找得到相同的解码结果。
+    fn detect_name_decoder(cd_data: &[u8]) -> ZipNameDecoder {
+        let sample = &cd_data[..cd_data.len().min(CD_WINDOW_SIZE as usize)];
+        let mut names = Vec::new();
+        let mut offset = 0;
+        while let Ok(record_size) = Self::central_directory_record_size(sample, offset) {
+            if offset + record_size > sample.len() {
+                break;
             }
+            let filename_len = u16::from_le_bytes([sample[offset + 28], sample[offset + 29]]) as usize;
+            names.push(&sample[offset + CD_HEADER_SIZE..offset + CD_HEADER_SIZE + filename_len]);
+            offset += record_size;
+        }
+        ZipNameDecoder::detect(names)
+    }
 
-            let compressed_size_32 = u32::from_le_bytes([
-                cd_data[offset + 20], cd_data[offset + 21],
-                cd_data[offset + 22], cd_data[offset + 23]
-            ]);
+    /// 解码中央目录记录中的文件名，返回（文件名，使用的编码名称）
+    fn decode_record_filename(cd_data: &[u8], offset: usize, decoder: &ZipNameDecoder) -> (String, &'static str) {
+        let flags = u16::from_le_bytes([cd_data[offset + 8], cd_data[offset + 9]]);
+        let filename_len = u16::from_le_bytes([cd_data[offset + 28], cd_data[offset + 29]]) as usize;
+        let extra_len = u16::from_le_bytes([cd_data[offset + 30], cd_data[offset + 31]]) as usize;
+        let name_start = offset + CD_HEADER_SIZE;
+        let extra_end = (name_start + filename_len + extra_len).min(cd_data.len());
+        decoder.decode(
+            &cd_data[name_start..name_start + filename_len],
+            flags,
+            &cd_data[name_start + filename_len..extra_end],
+        )
+    }
 
-            let uncompressed_size_32 = u32::from_le_bytes([
-                cd_data[offset + 24], cd_data[offset + 25],
-                cd_data[offset + 26], cd_data[offset + 27]
-            ]);
+    /// 根据固定头部计算中央目录记录的总长度
+    fn central_directory_record_size(cd_data: &[u8], offset: usize) -> Result<usize, String> {
+        const MAX_FIELD_SIZE: usize = 65535;
 
-            let filename_len = u16::from_le_bytes([
-                cd_data[offset + 28], cd_data[offset + 29]
-            ]) as usize;
+        if offset + CD_HEADER_SIZE > cd_data.len() {
+            return Err(format!("Central directory entry header exceeds data range: offset={}, data_len={}", offset, cd_data.len()));
+        }
 
-            let extra_len = u16::from_le_bytes([
-                cd_data[offset + 30], cd_data[offset + 31]
-            ]) as usize;
+        // 检查中央目录文件头签名
+        let signature = u32::from_le_bytes([
+            cd_data[offset], cd_data[offset + 1],
+            cd_data[offset + 2], cd_data[offset + 3]
+        ]);
 
-            let comment_len = u16::from_le_bytes([
-                cd_data[offset + 32], cd_data[offset + 33]
-            ]) as usize;
-            
-            // 验证字段长度的合理性
-            if filename_len > MAX_FIELD_SIZE || extra_len > MAX_FIELD_SIZE || comment_len > MAX_FIELD_SIZE {
-                return Err(format!("Abnormal central directory entry field length: filename={}, extra={}, comment={}", filename_len, extra_len, comment_len));
-            }
-            
-            // 检查总的记录大小是否合理
-            let total_record_size = MIN_CD_HEADER_SIZE + filename_len + extra_len + comment_len;
-            if offset + total_record_size > cd_data.len() {
-                return Err(format!("Central directory entry exceeds data range: offset={}, size={}, data_len={}", offset, total_record_size, cd_data.len()));
-            }
-            
+        if signature != CD_HEADER_SIGNATURE {
+            return Err(format!("Invalid central directory file header signature: 0x{:08x}, expected: 0x{:08x}", signature, CD_HEADER_SIGNATURE));
+        }
 
+        let filename_len = u16::from_le_bytes([
+            cd_data[offset + 28], cd_data[offset + 29]
+        ]) as usize;
 
-            if filename_len == 0 {
-                // 跳过没有文件名的条目
-                offset += total_record_size;
-                parsed_entries += 1;
-                continue;
-            }
+        let extra_len = u16::from_le_bytes([
+            cd_data[offset + 30], cd_data[offset + 31]
+        ]) as usize;
 
-            // 安全地解析文件名
-            let filename_bytes = &cd_data[offset + MIN_CD_HEADER_SIZE..offset + MIN_CD_HEADER_SIZE + filename_len];
-            let filename = String::from_utf8_lossy(filename_bytes).to_string();
-            
-            // 处理ZIP64扩展字段
-            let (compressed_size, uncompressed_size) = if compressed_size_32 == 0xFFFFFFFF || uncompressed_size_32 == 0xFFFFFFFF {
-                // 需要从扩展字段中读取64位值
-                if extra_len > 0 {
-                    let extra_data = &cd_data[offset + MIN_CD_HEADER_SIZE + filename_len..offset + MIN_CD_HEADER_SIZE + filename_len + extra_len];
-                    Self::parse_zip64_extra_field(extra_data, compressed_size_32, uncompressed_size_32)
-                } else {
-                    (compressed_size_32 as u64, uncompressed_size_32 as u64)
-                }
-            } else {
-                (compressed_size_32 as u64, uncompressed_size_32 as u64)
-            };
+        let comment_len = u16::from_le_bytes([
+            cd_data[offset + 32], cd_data[offset + 33]
+        ]) as usize;
 
-            // 检查是否为目录
-            let is_dir = filename.ends_with('/') || uncompressed_size == 0 && compressed_size == 0;
-
-            entries.push(ArchiveEntry {
-                path: filename,
-                size: uncompressed_size,
-                compressed_size: Some(compressed_size),
-                is_dir,
-                modified_time: None, // 可以从DOS时间字段解析
-                crc32: Some(u32::from_le_bytes([
-                    cd_data[offset + 16], cd_data[offset + 17],
-                    cd_data[offset + 18], cd_data[offset + 19]
-                ])),
-                index: parsed_entries as usize,
-                metadata: HashMap::new(),
-            });
+        // 验证字段长度的合理性
+        if filename_len > MAX_FIELD_SIZE || extra_len > MAX_FIELD_SIZE || comment_len > MAX_FIELD_SIZE {
+            return Err(format!("Abnormal central directory entry field length: filename={}, extra={}, comment={}", filename_len, extra_len, comment_len));
+        }
 
-            offset += total_record_size;
-            parsed_entries += 1;
+        Ok(CD_HEADER_SIZE + filename_len + extra_len + comment_len)
+    }
+
+    /// 解析单条中央目录记录，返回（条目，记录长度）；没有文件名的记录返回 None
+    fn parse_central_directory_entry(
+        cd_data: &[u8],
+        offset: usize,
+        index: usize,
+        decoder: &ZipNameDecoder,
+    ) -> Result<(Option<ArchiveEntry>, usize), String> {
+        let total_record_size = Self::central_directory_record_size(cd_data, offset)?;
+
+        // 检查总的记录大小是否合理
+        if offset + total_record_size > cd_data.len() {
+            return Err(format!("Central directory entry exceeds data range: offset={}, size={}, data_len={}", offset, total_record_size, cd_data.len()));
         }
-        
-        if parsed_entries != total_entries && parsed_entries < max_entries {
-            return Err(format!("Parsed entry count ({}) does not match expected count ({})", parsed_entries, total_entries));
+
+        let compressed_size_32 = u32::from_le_bytes([
+            cd_data[offset + 20], cd_data[offset + 21],
+            cd_data[offset + 22], cd_data[offset + 23]
+        ]);
+
+        let uncompressed_size_32 = u32::from_le_bytes([
+            cd_data[offset + 24], cd_data[offset + 25],
+            cd_data[offset + 26], cd_data[offset + 27]
+        ]);
+
+        let filename_len = u16::from_le_bytes([
+            cd_data[offset + 28], cd_data[offset + 29]
+        ]) as usize;
+
+        let extra_len = u16::from_le_bytes([
+            cd_data[offset + 30], cd_data[offset + 31]
+        ]) as usize;
+
+        if filename_len == 0 {
+            // 跳过没有文件名的条目
+            return Ok((None, total_record_size));
         }
-        
-        Ok(entries)
+
+        // 按 UTF-8 标志位、Unicode Path 扩展字段或旧代码页解码文件名
+        let (filename, name_encoding) = Self::decode_record_filename(cd_data, offset, decoder);
+        let extra_data = &cd_data[offset + CD_HEADER_SIZE + filename_len..offset + CD_HEADER_SIZE + filename_len + extra_len];
+
+        // 处理ZIP64扩展字段
+        let (compressed_size, uncompressed_size) = if compressed_size_32 == 0xFFFFFFFF || uncompressed_size_32 == 0xFFFFFFFF {
+            // 需要从扩展字段中读取64位值
+            if extra_len > 0 {
+                Self::parse_zip64_extra_field(extra_data, compressed_size_32, uncompressed_size_32)
+            } else {
+                (compressed_size_32 as u64, uncompressed_size_32 as u64)
+            }
+        } else {
+            (compressed_size_32 as u64, uncompressed_size_32 as u64)
+        };
+
+        // 检查是否为目录
+        let is_dir = filename.ends_with('/') || uncompressed_size == 0 && compressed_size == 0;
+
+        // 标记加密条目，便于前端在预览前请求密码
+        let mut metadata = HashMap::new();
+        metadata.insert("name_encoding".to_string(), name_encoding.to_string());
+        let flags = u16::from_le_bytes([cd_data[offset + 8], cd_data[offset + 9]]);
+        let compression_method = u16::from_le_bytes([cd_data[offset + 10], cd_data[offset + 11]]);
+        if let Some(encryption) = ZipEncryption::from_entry(flags, compression_method, extra_data) {
+            metadata.insert("encrypted".to_string(), "true".to_string());
+            metadata.insert("encryption".to_string(), encryption.name().to_string());
+        }
+
+        let entry = ArchiveEntry {
+            path: filename,
+            size: uncompressed_size,
+            compressed_size: Some(compressed_size),
+            is_dir,
+            modified_time: Self::parse_modified_time(extra_data, u32::from_le_bytes([
+                cd_data[offset + 12], cd_data[offset + 13],
+                cd_data[offset + 14], cd_data[offset + 15]
+            ])),
+            crc32: Some(u32::from_le_bytes([
+                cd_data[offset + 16], cd_data[offset + 17],
+                cd_data[offset + 18], cd_data[offset + 19]
+            ])),
+            index,
+            metadata,
+        };
+
+        Ok((Some(entry), total_record_size))
     }
 
     fn find_file_in_central_directory(
         cd_data: &[u8],
         target_path: &str,
     ) -> Result<Option<ZipFileInfo>, String> {
+        let decoder = Self::detect_name_decoder(cd_data);
         let mut offset = 0;
 
         while offset + 46 <= cd_data.len() {
@@ -471,25 +683,6 @@ impl ZipHandler {
                 break;
             }
 
-            let compression_method = u16::from_le_bytes([
-                cd_data[offset + 10], cd_data[offset + 11]
-            ]);
-
-            let crc32 = u32::from_le_bytes([
-                cd_data[offset + 16], cd_data[offset + 17],
-                cd_data[offset + 18], cd_data[offset + 19]
-            ]);
-
-            let compressed_size_32 = u32::from_le_bytes([
-                cd_data[offset + 20], cd_data[offset + 21],
-                cd_data[offset + 22], cd_data[offset + 23]
-            ]);
-
-            let uncompressed_size_32 = u32::from_le_bytes([
-                cd_data[offset + 24], cd_data[offset + 25],
-                cd_data[offset + 26], cd_data[offset + 27]
-            ]);
-
             let filename_len = u16::from_le_bytes([
                 cd_data[offset + 28], cd_data[offset + 29]
             ]) as usize;
@@ -502,39 +695,16 @@ impl ZipHandler {
                 cd_data[offset + 32], cd_data[offset + 33]
             ]) as usize;
 
-            let local_header_offset_32 = u32::from_le_bytes([
-                cd_data[offset + 42], cd_data[offset + 43],
-                cd_data[offset + 44], cd_data[offset + 45]
-            ]);
-
             if offset + 46 + filename_len > cd_data.len() {
                 break;
             }
 
-            let filename = String::from_utf8_lossy(
-                &cd_data[offset + 46..offset + 46 + filename_len]
-            ).to_string();
-
-            if filename == target_path {
-                // 处理ZIP64扩展字段
-                let (compressed_size, local_header_offset) = if compressed_size_32 == 0xFFFFFFFF || local_header_offset_32 == 0xFFFFFFFF {
-                    // 需要从扩展字段中读取64位值
-                    if offset + 46 + filename_len + extra_len <= cd_data.len() {
-                        let extra_data = &cd_data[offset + 46 + filename_len..offset + 46 + filename_len + extra_len];
-                        Self::parse_zip64_extra_field_with_offset(extra_data, compressed_size_32, uncompressed_size_32, local_header_offset_32)
-                    } else {
-                        (compressed_size_32 as u64, local_header_offset_32 as u64)
-                    }
-                } else {
-                    (compressed_size_32 as u64, local_header_offset_32 as u64)
-                };
-                
-                return Ok(Some(ZipFileInfo {
-                    compression_method,
-                    compressed_size,
-                    local_header_offset,
-                    crc32,
-                }));
+            // 解码后的文件名或原始文件名字节与目标一致都视为命中
+            let raw_filename = &cd_data[offset + 46..offset + 46 + filename_len];
+            if raw_filename == target_path.as_bytes()
+                || Self::decode_record_filename(cd_data, offset, &decoder).0 == target_path
+            {
+                return Ok(Some(Self::parse_file_info(cd_data, offset)));
             }
 
             offset += 46 + filename_len + extra_len + comment_len;
@@ -543,17 +713,307 @@ impl ZipHandler {
         Ok(None)
     }
 
+    /// 从中央目录记录中读取定位和解压条目所需的信息
+    fn parse_file_info(cd_data: &[u8], offset: usize) -> ZipFileInfo {
+        let flags = u16::from_le_bytes([
+            cd_data[offset + 8], cd_data[offset + 9]
+        ]);
+
+        let compression_method = u16::from_le_bytes([
+            cd_data[offset + 10], cd_data[offset + 11]
+        ]);
+
+        let last_mod_time = u16::from_le_bytes([
+            cd_data[offset + 12], cd_data[offset + 13]
+        ]);
+
+        let crc32 = u32::from_le_bytes([
+            cd_data[offset + 16], cd_data[offset + 17],
+            cd_data[offset + 18], cd_data[offset + 19]
+        ]);
+
+        let compressed_size_32 = u32::from_le_bytes([
+            cd_data[offset + 20], cd_data[offset + 21],
+            cd_data[offset + 22], cd_data[offset + 23]
+        ]);
+
+        let uncompressed_size_32 = u32::from_le_bytes([
+            cd_data[offset + 24], cd_data[offset + 25],
+            cd_data[offset + 26], cd_data[offset + 27]
+        ]);
+
+        let filename_len = u16::from_le_bytes([
+            cd_data[offset + 28], cd_data[offset + 29]
+        ]) as usize;
+
+        let extra_len = u16::from_le_bytes([
+            cd_data[offset + 30], cd_data[offset + 31]
+        ]) as usize;
+
+        let disk_number = u16::from_le_bytes([
+            cd_data[offset + 34], cd_data[offset + 35]
+        ]) as u32;
+
+        let local_header_offset_32 = u32::from_le_bytes([
+            cd_data[offset + 42], cd_data[offset + 43],
+            cd_data[offset + 44], cd_data[offset + 45]
+        ]);
+
+        // 处理ZIP64扩展字段
+        let (compressed_size, local_header_offset) = if compressed_size_32 == 0xFFFFFFFF || local_header_offset_32 == 0xFFFFFFFF {
+            // 需要从扩展字段中读取64位值
+            if offset + 46 + filename_len + extra_len <= cd_data.len() {
+                let extra_data = &cd_data[offset + 46 + filename_len..offset + 46 + filename_len + extra_len];
+                Self::parse_zip64_extra_field_with_offset(extra_data, compressed_size_32, uncompressed_size_32, local_header_offset_32)
+            } else {
+                (compressed_size_32 as u64, local_header_offset_32 as u64)
+            }
+        } else {
+            (compressed_size_32 as u64, local_header_offset_32 as u64)
+        };
+
+        let uncompressed_size = if uncompressed_size_32 == 0xFFFFFFFF
+            && offset + 46 + filename_len + extra_len <= cd_data.len()
+        {
+            let extra_data = &cd_data[offset + 46 + filename_len..offset + 46 + filename_len + extra_len];
+            Self::parse_zip64_extra_field(extra_data, compressed_size_32, uncompressed_size_32).1
+        } else {
+            uncompressed_size_32 as u64
+        };
+
+        let extra_end = (offset + 46 + filename_len + extra_len).min(cd_data.len());
+        let encryption = ZipEncryption::from_entry(
+            flags,
+            compression_method,
+            &cd_data[offset + 46 + filename_len..extra_end],
+        );
+
+        ZipFileInfo {
+            compression_method,
+            compressed_size,
+            uncompressed_size,
+            local_header_offset,
+            disk_number,
+            crc32,
+            flags,
+            last_mod_time,
+            encryption,
+        }
+    }
+
     /// 通过存储客户端分析ZIP文件
     async fn analyze_zip_with_client(
         client: Arc<dyn StorageClient>,
         file_path: &str,
         file_size: u64,
     ) -> Result<ArchiveInfo, String> {
+        const MAX_CD_SIZE: u64 = 500 * 1024 * 1024; // 500MB中央目录大小限制
+
+        let (cd_offset, cd_size, total_entries) = Self::locate_central_directory(&client, file_path, file_size).await?;
+
+        // 验证中央目录大小的合理性
+        if cd_size > MAX_CD_SIZE {
+            return Err(format!("Central directory too large: {} bytes, exceeds 500MB limit", cd_size));
+        }
+
+        // 读取中央目录
+        let cd_data = client.read_file_range(file_path, cd_offset, cd_size)
+            .await
+            .map_err(|e| format!("Failed to read central directory: {}", e))?;
+
+        if cd_data.len() != cd_size as usize {
+            return Err(format!("Central directory data length mismatch: expected {}, actual {}", cd_size, cd_data.len()));
+        }
+
+        // 使用优化的解析方法
+        let entries = Self::parse_central_directory_optimized(&cd_data, total_entries)?;
+        let total_uncompressed_size = entries.iter().map(|e| e.size).sum();
+
+        // 超过解析上限时只返回前面的条目，并报告真实的条目总数
+        let analysis_status = if total_entries > MAX_ANALYZED_ENTRIES {
+            AnalysisStatus::Partial { analyzed_entries: entries.len() }
+        } else {
+            AnalysisStatus::Complete
+        };
+
+        Ok(ArchiveInfoBuilder::new(CompressionType::Zip)
+            .entries(entries)
+            .total_entries(total_entries as usize)
+            .total_uncompressed_size(total_uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(true)
+            .analysis_status(analysis_status)
+            .build())
+    }
+
+    /// 分页列出某一目录层级的条目
+    ///
+    /// 第一次列出时按窗口读取整个中央目录，只记录每个条目的路径和记录位置（按文件版本缓存，
+    /// 所有目录层级共用）；翻页时在索引上计算本页，再只解析本页的文件记录。
+    async fn list_zip_entries_with_client(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        options: &ArchiveListOptions,
+    ) -> Result<ArchiveListing, String> {
+        let file_info = client.get_file_info(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let file_size = file_info.size;
+        let (cd_offset, cd_size, _) = Self::locate_central_directory(&client, file_path, file_size).await?;
+
+        let sample = client.read_file_range(file_path, cd_offset, cd_size.min(CD_WINDOW_SIZE))
+            .await
+            .map_err(|e| format!("Failed to read central directory: {}", e))?;
+        let decoder = Self::detect_name_decoder(&sample);
+        drop(sample);
+
+        let cache_key = format!(
+            "{}:{}:{}:{}:{}",
+            client.protocol(), file_path, file_size, file_info.etag.unwrap_or_default(), decoder.cache_tag()
+        );
+        let index = match Self::get_cached_record_index(&cache_key) {
+            Some(index) => index,
+            None => {
+                let index = Arc::new(
+                    Self::build_record_index(&client, file_path, cd_offset, cd_size, &decoder).await?
+                );
+                Self::cache_record_index(cache_key, index.clone());
+                index
+            }
+        };
+
+        let page = index.page(options);
+        let mut files = Vec::with_capacity(page.file_positions.len());
+
+        // 从本页第一条文件记录开始，只解析本页的记录
+        let positions = &page.file_positions;
+        if let (Some(&first), Some(&last)) = (positions.first(), positions.last()) {
+            let first_offset = *index.item(first);
+            // 最后一条记录的长度未知，按记录最大长度读取
+            let end = (*index.item(last) + MAX_CD_RECORD_SIZE).min(cd_size);
+            let mut next = 0;
+            Self::scan_central_directory(&client, file_path, cd_offset, first_offset, end, |record_offset, record| {
+                let position = positions[next];
+                if record_offset == *index.item(position) {
+                    if let (Some(entry), _) = Self::parse_central_directory_entry(record, 0, position, &decoder)? {
+                        files.push(entry);
+                    }
+                    next += 1;
+                }
+                Ok(next < positions.len())
+            }).await?;
+        }
+
+        Ok(page.into_listing(files))
+    }
+
+    /// 扫描整个中央目录，记录每个条目的路径和记录位置
+    async fn build_record_index(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        cd_offset: u64,
+        cd_size: u64,
+        decoder: &ZipNameDecoder,
+    ) -> Result<RecordIndex, String> {
+        let mut index = RecordIndex::default();
+
+        Self::scan_central_directory(client, file_path, cd_offset, 0, cd_size, |record_offset, record| {
+            let (filename, _) = Self::decode_record_filename(record, 0, decoder);
+            index.push(&filename, record_offset)?;
+            Ok(true)
+        }).await?;
+
+        log::debug!("ZIP中央目录路径索引完成: {}，共 {} 个条目", file_path, index.len());
+        Ok(index)
+    }
+
+    fn get_cached_record_index(key: &str) -> Option<Arc<RecordIndex>> {
+        let cache = RECORD_INDEX_CACHE.lock().ok()?;
+        cache.iter().find(|(cached_key, _)| cached_key == key).map(|(_, index)| index.clone())
+    }
+
+    fn cache_record_index(key: String, index: Arc<RecordIndex>) {
+        let Ok(mut cache) = RECORD_INDEX_CACHE.lock() else {
+            return;
+        };
+
+        cache.retain(|(cached_key, _)| *cached_key != key);
+        cache.push_back((key, index));
+        while cache.len() > MAX_CACHED_PATH_INDEXES {
+            cache.pop_front();
+        }
+    }
+
+    /// 按窗口读取中央目录内 [start, end) 范围，逐条把完整记录交给 `visitor`
+    ///
+    /// `visitor` 参数为（记录在中央目录内的偏移，记录数据），返回 false 时停止扫描。
+    async fn scan_central_directory<F>(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        cd_offset: u64,
+        start: u64,
+        end: u64,
+        mut visitor: F,
+    ) -> Result<(), String>
+    where
+        F: FnMut(u64, &[u8]) -> Result<bool, String> + Send,
+    {
+        let mut buffer: Vec<u8> = Vec::new();
+        // buffer[0] 对应的中央目录偏移
+        let mut buffer_start = start;
+        let mut position = 0;
+        let mut read_offset = start;
+
+        loop {
+            let available = buffer.len() - position;
+            let needed = if available >= CD_HEADER_SIZE {
+                Self::central_directory_record_size(&buffer, position)?
+            } else {
+                CD_HEADER_SIZE
+            };
+
+            if available < needed {
+                if read_offset >= end {
+                    break;
+                }
+
+                // 丢弃已处理的数据后读取下一个窗口
+                buffer.drain(..position);
+                buffer_start += position as u64;
+                position = 0;
+
+                let length = CD_WINDOW_SIZE.min(end - read_offset);
+                let chunk = client.read_file_range(file_path, cd_offset + read_offset, length)
+                    .await
+                    .map_err(|e| format!("Failed to read central directory: {}", e))?;
+                if chunk.is_empty() {
+                    return Err(format!("Central directory ended unexpectedly at offset {}", read_offset));
+                }
+                read_offset += chunk.len() as u64;
+                buffer.extend_from_slice(&chunk);
+                continue;
+            }
+
+            if !visitor(buffer_start + position as u64, &buffer[position..position + needed])? {
+                break;
+            }
+            position += needed;
+        }
+
+        Ok(())
+    }
+
+
+
+    /// 读取EOCD（及ZIP64 EOCD）记录，返回（中央目录偏移，中央目录大小，条目总数）
+    async fn locate_central_directory(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+    ) -> Result<(u64, u64, u64), String> {
         const MIN_ZIP_SIZE: u64 = 22; // 最小ZIP文件大小（EOCD记录）
         const MAX_FOOTER_SIZE: u64 = 65536; // 最多读取64KB的文件尾部
         const MAX_ZIP_SIZE: u64 = 500 * 1024 * 1024 * 1024; // 500GB文件大小限制
-        const MAX_CD_SIZE: u64 = 500 * 1024 * 1024; // 500MB中央目录大小限制
-        const MAX_ENTRIES: u64 = 1_000_000; // 100万个文件数量限制
 
         // 检查文件大小是否足够
         if file_size < MIN_ZIP_SIZE {
@@ -594,41 +1054,23 @@ impl ZipHandler {
         let cd_offset_32 = u32::from_le_bytes([
             eocd_data[16], eocd_data[17], eocd_data[18], eocd_data[19]
         ]);
-        
-        // 验证条目数量的合理性
-        if total_entries > MAX_ENTRIES {
-            return Err(format!("Too many entries in ZIP file: {}, exceeds {} limit", total_entries, MAX_ENTRIES));
-        }
+        let cd_disk = u16::from_le_bytes([eocd_data[6], eocd_data[7]]) as u32;
+        let volume_offsets = client.volume_offsets(file_path);
         
         if cd_size > file_size {
             return Err(format!("Central directory size ({}) exceeds file size ({})", cd_size, file_size));
         }
-
-        // 验证中央目录大小的合理性
-        if cd_size > MAX_CD_SIZE {
-            return Err(format!("Central directory too large: {} bytes, exceeds 500MB limit", cd_size));
-        }
         
         // 检查是否需要处理ZIP64格式
         let (cd_offset, cd_size, total_entries) = if cd_offset_32 == 0xFFFFFFFF || cd_size == 0xFFFFFFFF as u64 || total_entries == 0xFFFF {
             // 查找ZIP64 EOCD定位器
             if let Some(zip64_locator_pos) = Self::find_zip64_eocd(&footer_data, eocd_pos) {
-                let zip64_result = Self::parse_zip64_eocd(client.clone(), file_path, &footer_data, zip64_locator_pos, file_size, start_pos).await?;
-                
-                // 验证ZIP64解析结果的合理性
-                if zip64_result.1 > MAX_CD_SIZE {
-                    return Err(format!("ZIP64 central directory too large: {} bytes, exceeds 500MB limit", zip64_result.1));
-                }
-                if zip64_result.2 > MAX_ENTRIES {
-                    return Err(format!("Too many files in ZIP64: {} files, exceeds {} limit", zip64_result.2, MAX_ENTRIES));
-                }
-                
-                zip64_result
+                Self::parse_zip64_eocd(client.clone(), file_path, &footer_data, zip64_locator_pos, start_pos, volume_offsets.as_deref()).await?
             } else {
                 return Err("ZIP64 format detected but ZIP64 EOCD locator not found, file may be corrupted".to_string());
             }
         } else {
-            (cd_offset_32 as u64, cd_size, total_entries)
+            (Self::central_directory_position(volume_offsets.as_deref(), cd_disk, cd_offset_32 as u64)?, cd_size, total_entries)
         };
         
         // 验证中央目录偏移量的合理性
@@ -640,32 +1082,13 @@ impl ZipHandler {
             return Err(format!("Central directory end position ({}) exceeds file range ({})", cd_offset + cd_size, file_size));
         }
 
-        // 读取中央目录
-        let cd_data = client.read_file_range(file_path, cd_offset, cd_size)
-            .await
-            .map_err(|e| format!("Failed to read central directory: {}", e))?;
-
-        if cd_data.len() != cd_size as usize {
-            return Err(format!("Central directory data length mismatch: expected {}, actual {}", cd_size, cd_data.len()));
-        }
-
-        // 使用优化的解析方法
-        let entries = Self::parse_central_directory_optimized(&cd_data, total_entries)?;
-        let total_uncompressed_size = entries.iter().map(|e| e.size).sum();
+        // 条目数量由EOCD声明，超出解压限制时不再读取中央目录
+        limits::current_policy().check_entries(total_entries)?;
 
-        Ok(ArchiveInfoBuilder::new(CompressionType::Zip)
-            .entries(entries)
-            .total_uncompressed_size(total_uncompressed_size)
-            .total_compressed_size(file_size)
-            .supports_streaming(true)
-            .supports_random_access(true)
-            .analysis_status(AnalysisStatus::Complete)
-            .build())
+        Ok((cd_offset, cd_size, total_entries))
     }
 
-
-
-    /// 通过存储客户端提取ZIP文件预览（支持进度回调和取消信号）
+    /// 通过存储客户端提取ZIP文件预览（支持进度回调、取消信号和加密条目）
     async fn extract_zip_preview_with_progress(
         client: Arc<dyn StorageClient>,
         file_path: &str,
@@ -673,14 +1096,9 @@ impl ZipHandler {
         max_size: usize,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
     ) -> Result<FilePreview, String> {
-        // 先找到文件信息
-        let file_size = client.get_file_size(file_path).await
-            .map_err(|e| format!("Failed to get file size: {}", e))?;
-
-        let file_info = Self::find_file_in_zip_with_client(client.clone(), file_path, file_size, entry_path)
-            .await?
-            .ok_or_else(|| "File not found in archive".to_string())?;
+        let (file_info, data_offset, data_end) = Self::locate_entry_data(&client, file_path, entry_path, password).await?;
 
         // 验证文件大小限制
         if file_info.compressed_size == 0 {
@@ -690,11 +1108,351 @@ impl ZipHandler {
                 .total_size(0)
                 .build());
         }
-        
-        if file_info.compressed_size > 100 * 1024 * 1024 { // 100MB限制
-            return Err(format!("Compressed file too large: {} bytes, exceeds 100MB limit", file_info.compressed_size));
+
+        // 进度按条目压缩数据的范围汇报
+        let compressed_size = file_info.compressed_size;
+        let progress = progress_callback.map(|cb| {
+            move |current: u64, _total: u64| cb(current - data_offset, compressed_size)
+        });
+
+        // 流式读取压缩数据并边读边解压，得到 max_size 字节后立即停止读取
+        let password = password.map(|p| p.to_string());
+        consume_file_stream_with_client(
+            &client,
+            file_path,
+            data_offset,
+            data_end,
+            progress.as_ref().map(|cb| cb as &(dyn Fn(u64, u64) + Send + Sync)),
+            cancel_rx,
+            move |reader| Self::decompress_zip_stream(reader, &file_info, password.as_deref(), max_size),
+        ).await
+    }
+
+    /// 将ZIP条目完整解压并流式写入 `writer`
+    async fn extract_zip_entry_to_writer(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String> {
+        let (file_info, data_offset, data_end) = Self::locate_entry_data(&client, file_path, entry_path, password).await?;
+
+        // 中央目录声明的大小已超出解压限制时不再解压
+        let compressed_size = file_info.compressed_size;
+        OutputLimit::for_compressed_size(compressed_size).check(file_info.uncompressed_size)?;
+
+        let progress = progress_callback.map(|cb| {
+            move |current: u64, _total: u64| cb(current - data_offset, compressed_size)
+        });
+
+        let password = password.map(|p| p.to_string());
+        pipe_to_async_writer(writer, |mut output| consume_file_stream_with_client(
+            &client,
+            file_path,
+            data_offset,
+            data_end,
+            progress.as_ref().map(|cb| cb as &(dyn Fn(u64, u64) + Send + Sync)),
+            cancel_rx,
+            move |reader| {
+                Self::copy_zip_stream(reader, &file_info, password.as_deref(), &mut output)?;
+                output.finish()
+            },
+        )).await
+    }
+
+    /// 完整性测试：读取整个中央目录后，按本地文件头偏移顺序一次性流式读取压缩包，
+    /// 逐条目解压并校验 CRC32 和解压后的大小
+    async fn test_zip_integrity(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<IntegrityReport, String> {
+        let (cd_offset, records) = Self::read_sorted_records(&client, file_path, |_| true).await?;
+
+        log::debug!("开始测试ZIP完整性: {} ({} 个条目)", file_path, records.len());
+
+        let password = password.map(|p| p.to_string());
+        consume_file_stream_with_client(
+            &client,
+            file_path,
+            0,
+            cd_offset,
+            progress_callback.as_deref(),
+            cancel_rx,
+            move |reader| Ok(Self::test_zip_stream(reader, records, password.as_deref())),
+        ).await
+    }
+
+    /// 批量解压：读取一遍中央目录找到选中的条目，再按本地文件头偏移顺序一次性流式读取
+    /// 这些条目所在的范围，逐条目解压写入 `sink`
+    async fn extract_zip_entries_to_sink(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        sink: EntrySink,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<(), String> {
+        let (cd_offset, records) = Self::read_sorted_records(&client, file_path, |path| sink.wants(path)).await?;
+        let Some(start) = records.first().map(|(_, file_info)| file_info.local_header_offset) else {
+            return Ok(());
+        };
+
+        for (_, file_info) in &records {
+            // 加密条目在读取数据前先确认已提供密码
+            if file_info.encryption.is_some() && password.is_none() {
+                return Err(zip_crypto::PASSWORD_REQUIRED_ERROR.to_string());
+            }
+            // 中央目录声明的大小已超出解压限制时不再解压
+            OutputLimit::for_compressed_size(file_info.compressed_size).check(file_info.uncompressed_size)?;
+        }
+
+        log::debug!("开始批量解压ZIP条目: {} ({} 个条目)", file_path, records.len());
+
+        let password = password.map(|p| p.to_string());
+        consume_file_stream_with_client(
+            &client,
+            file_path,
+            start,
+            cd_offset,
+            None,
+            cancel_rx,
+            move |reader| Self::extract_zip_stream(reader, start, records, password.as_deref(), sink),
+        ).await
+    }
+
+    /// 从 `start` 偏移开始顺序读取按偏移排序的条目并写入 `sink`，任一条目失败时中止
+    fn extract_zip_stream<R: std::io::Read>(
+        mut input: R,
+        start: u64,
+        records: Vec<(String, ZipFileInfo)>,
+        password: Option<&str>,
+        mut sink: EntrySink,
+    ) -> Result<(), String> {
+        use std::io::Read;
+
+        const TRUNCATED_ERROR: &str = "Unexpected end of ZIP data";
+        let mut position = start;
+
+        for (path, file_info) in records {
+            if file_info.local_header_offset < position || !sink.wants(&path) {
+                // 重复的路径或与之前条目重叠的数据
+                log::warn!("跳过ZIP条目: {}", path);
+                continue;
+            }
+
+            // 跳过两个条目之间的数据
+            let gap = file_info.local_header_offset - position;
+            let mut local_header = [0u8; 30];
+            if !matches!(std::io::copy(&mut (&mut input).take(gap), &mut std::io::sink()), Ok(n) if n == gap)
+                || input.read_exact(&mut local_header).is_err()
+            {
+                return Err(TRUNCATED_ERROR.to_string());
+            }
+            if u32::from_le_bytes([local_header[0], local_header[1], local_header[2], local_header[3]]) != 0x04034b50 {
+                return Err("Invalid local header".to_string());
+            }
+
+            let name_extra_len = u16::from_le_bytes([local_header[26], local_header[27]]) as u64
+                + u16::from_le_bytes([local_header[28], local_header[29]]) as u64;
+            if !matches!(std::io::copy(&mut (&mut input).take(name_extra_len), &mut std::io::sink()), Ok(n) if n == name_extra_len) {
+                return Err(TRUNCATED_ERROR.to_string());
+            }
+
+            sink.begin_entry(&path)?;
+            let mut data = (&mut input).take(file_info.compressed_size);
+            Self::copy_zip_stream(&mut data, &file_info, password, &mut sink)?;
+            // 解压提前结束时读完该条目剩余的压缩数据
+            std::io::copy(&mut data, &mut std::io::sink())
+                .map_err(|e| format!("Failed to read ZIP data: {}", e))?;
+            if data.limit() > 0 {
+                return Err(TRUNCATED_ERROR.to_string());
+            }
+            sink.end_entry()?;
+
+            position = file_info.local_header_offset + 30 + name_extra_len + file_info.compressed_size;
+            if sink.is_complete() {
+                break;
+            }
         }
 
+        Ok(())
+    }
+
+    /// 读取整个中央目录，返回（中央目录偏移，按本地文件头偏移排序的（路径，条目信息））
+    ///
+    /// 只保留 `filter` 接受的路径；分卷 ZIP 的偏移换算为拼接后文件内的偏移。
+    async fn read_sorted_records(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        mut filter: impl FnMut(&str) -> bool + Send,
+    ) -> Result<(u64, Vec<(String, ZipFileInfo)>), String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let (cd_offset, cd_size, _) = Self::locate_central_directory(client, file_path, file_size).await?;
+
+        let sample = client.read_file_range(file_path, cd_offset, cd_size.min(CD_WINDOW_SIZE))
+            .await
+            .map_err(|e| format!("Failed to read central directory: {}", e))?;
+        let decoder = Self::detect_name_decoder(&sample);
+        drop(sample);
+
+        let volume_offsets = client.volume_offsets(file_path);
+        let mut records = Vec::new();
+        Self::scan_central_directory(client, file_path, cd_offset, 0, cd_size, |_, record| {
+            let filename_len = u16::from_le_bytes([record[28], record[29]]);
+            if filename_len > 0 {
+                let (path, _) = Self::decode_record_filename(record, 0, &decoder);
+                if !filter(&path) {
+                    return Ok(true);
+                }
+                let mut file_info = Self::parse_file_info(record, 0);
+                file_info.local_header_offset = Self::volume_position(
+                    volume_offsets.as_deref(),
+                    file_info.disk_number,
+                    file_info.local_header_offset,
+                )?;
+                records.push((path, file_info));
+            }
+            Ok(true)
+        }).await?;
+        records.sort_by_key(|(_, file_info)| file_info.local_header_offset);
+
+        Ok((cd_offset, records))
+    }
+
+    /// 扫描整个中央目录建立完整的条目索引（用于批量解压时选择条目）
+    async fn build_zip_entry_index(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<EntryIndex, String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let (cd_offset, cd_size, _) = Self::locate_central_directory(&client, file_path, file_size).await?;
+
+        let sample = client.read_file_range(file_path, cd_offset, cd_size.min(CD_WINDOW_SIZE))
+            .await
+            .map_err(|e| format!("Failed to read central directory: {}", e))?;
+        let decoder = Self::detect_name_decoder(&sample);
+        drop(sample);
+
+        let mut index = EntryIndex::default();
+        let mut position = 0;
+        Self::scan_central_directory(&client, file_path, cd_offset, 0, cd_size, |_, record| {
+            if let (Some(entry), _) = Self::parse_central_directory_entry(record, 0, position, &decoder)? {
+                index.push_entry(entry)?;
+            }
+            position += 1;
+            Ok(true)
+        }).await?;
+        Ok(index)
+    }
+
+    /// 顺序读取按偏移排序的条目，单个条目解压失败时跳过其剩余数据继续测试
+    fn test_zip_stream<R: std::io::Read>(
+        mut input: R,
+        records: Vec<(String, ZipFileInfo)>,
+        password: Option<&str>,
+    ) -> IntegrityReport {
+        use std::io::Read;
+
+        const TRUNCATED_ERROR: &str = "Unexpected end of ZIP data";
+        let mut position = 0u64;
+        let mut results = Vec::with_capacity(records.len());
+
+        for (path, file_info) in records {
+            if file_info.local_header_offset < position {
+                results.push(EntryIntegrity::failed(path, 0, "Entry data overlaps a previous entry".to_string()));
+                continue;
+            }
+
+            // 跳过两个条目之间的数据（数据描述符、中央目录未引用的数据等）
+            let gap = file_info.local_header_offset - position;
+            if !matches!(std::io::copy(&mut (&mut input).take(gap), &mut std::io::sink()), Ok(n) if n == gap) {
+                return IntegrityReport::new(results, Some(TRUNCATED_ERROR.to_string()));
+            }
+            position += gap;
+
+            let mut local_header = [0u8; 30];
+            if input.read_exact(&mut local_header).is_err() {
+                return IntegrityReport::new(results, Some(TRUNCATED_ERROR.to_string()));
+            }
+            position += 30;
+
+            if u32::from_le_bytes([local_header[0], local_header[1], local_header[2], local_header[3]]) != 0x04034b50 {
+                results.push(EntryIntegrity::failed(path, 0, "Invalid local file header".to_string()));
+                continue;
+            }
+
+            let name_extra_len = u16::from_le_bytes([local_header[26], local_header[27]]) as u64
+                + u16::from_le_bytes([local_header[28], local_header[29]]) as u64;
+            if !matches!(std::io::copy(&mut (&mut input).take(name_extra_len), &mut std::io::sink()), Ok(n) if n == name_extra_len) {
+                return IntegrityReport::new(results, Some(TRUNCATED_ERROR.to_string()));
+            }
+            position += name_extra_len;
+
+            if file_info.encryption.is_some() && password.is_none() {
+                results.push(EntryIntegrity::failed(path, 0, zip_crypto::PASSWORD_REQUIRED_ERROR.to_string()));
+                continue;
+            }
+
+            let mut data = (&mut input).take(file_info.compressed_size);
+            let mut sink = CountingSink::default();
+            let result = Self::copy_zip_stream(&mut data, &file_info, password, &mut sink)
+                .and_then(|size| if size == file_info.uncompressed_size {
+                    Ok(size)
+                } else {
+                    Err(format!("Size mismatch: expected {}, actual {}", file_info.uncompressed_size, size))
+                });
+
+            // 解压提前结束或失败时读完该条目剩余的压缩数据
+            let drained = std::io::copy(&mut data, &mut std::io::sink());
+            let remaining = data.limit();
+            position += file_info.compressed_size - remaining;
+
+            results.push(match result {
+                Ok(size) => EntryIntegrity::passed(path, size),
+                Err(e) => EntryIntegrity::failed(path, sink.written, e),
+            });
+
+            if drained.is_err() || remaining > 0 {
+                return IntegrityReport::new(results, Some(TRUNCATED_ERROR.to_string()));
+            }
+        }
+
+        IntegrityReport::new(results, None)
+    }
+
+    /// 查找条目并读取本地文件头，返回（条目信息，压缩数据起始偏移，压缩数据结束偏移）
+    async fn locate_entry_data(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        password: Option<&str>,
+    ) -> Result<(ZipFileInfo, u64, u64), String> {
+        let file_size = client.get_file_size(file_path).await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        let mut file_info = Self::find_file_in_zip_with_client(client.clone(), file_path, file_size, entry_path)
+            .await?
+            .ok_or_else(|| "File not found in archive".to_string())?;
+
+        // 加密条目在读取数据前先确认已提供密码
+        if file_info.encryption.is_some() && password.is_none() {
+            return Err(zip_crypto::PASSWORD_REQUIRED_ERROR.to_string());
+        }
+
+        // 分卷 ZIP 的本地文件头偏移相对于所在分卷
+        file_info.local_header_offset = Self::volume_position(
+            client.volume_offsets(file_path).as_deref(),
+            file_info.disk_number,
+            file_info.local_header_offset,
+        )?;
+
         // 读取本地文件头
         let local_header = client.read_file_range(file_path, file_info.local_header_offset, 30)
             .await
@@ -708,24 +1466,12 @@ impl ZipHandler {
         let extra_len = u16::from_le_bytes([local_header[28], local_header[29]]) as u64;
 
         let data_offset = file_info.local_header_offset + 30 + filename_len + extra_len;
-        
-        // 总是读取完整的压缩数据，因为CRC32校验需要完整的解压数据
-        // 只在解压后截取所需的预览大小
-        let progress_cb = progress_callback.map(|cb| {
-            Arc::new(move |current: u64, total: u64| {
-                cb(current, total);
-            }) as ProgressCallback
-        });
-        
-        let compressed_data = client.read_file_range_with_progress(file_path, data_offset, file_info.compressed_size, progress_cb, cancel_rx)
-            .await
-            .map_err(|e| format!("Failed to read compressed data: {}", e))?;
-
-        if compressed_data.len() != file_info.compressed_size as usize {
-            return Err(format!("Read data length ({}) does not match expected length ({})", compressed_data.len(), file_info.compressed_size));
+        let data_end = data_offset + file_info.compressed_size;
+        if data_end > file_size {
+            return Err(format!("Entry data exceeds file range: {} > {}", data_end, file_size));
         }
 
-        Self::decompress_zip_data(&compressed_data, file_info.compression_method, max_size, file_info.crc32)
+        Ok((file_info, data_offset, data_end))
     }
 
     /// 通过存储客户端在ZIP中查找文件
@@ -735,134 +1481,232 @@ impl ZipHandler {
         file_size: u64,
         target_path: &str,
     ) -> Result<Option<ZipFileInfo>, String> {
-        let start_pos;
-        // 读取文件末尾来查找中央目录
-        let footer_size = std::cmp::min(65536, file_size);
-        start_pos = file_size.saturating_sub(footer_size);
+        let (final_cd_offset, final_cd_size, _) = Self::locate_central_directory(&client, file_path, file_size).await?;
 
-        let footer_data = client.read_file_range(file_path, start_pos, footer_size)
+        // 读取中央目录
+        let cd_data = client.read_file_range(file_path, final_cd_offset, final_cd_size)
             .await
-            .map_err(|e| format!("Failed to read file footer: {}", e))?;
+            .map_err(|e| format!("Failed to read central directory: {}", e))?;
 
-        let eocd_pos = Self::find_eocd(&footer_data)
-            .ok_or("Could not find End of Central Directory record")?;
+        Self::find_file_in_central_directory(&cd_data, target_path)
+    }
 
-        let eocd_data = &footer_data[eocd_pos..];
-        if eocd_data.len() < 22 {
-            return Err("Invalid EOCD record".to_string());
+    /// 压缩方法名称，用于错误信息
+    fn compression_method_name(compression_method: u16) -> &'static str {
+        match compression_method {
+            0 => "Store",
+            8 => "Deflate",
+            9 => "Deflate64",
+            12 => "BZIP2",
+            14 => "LZMA",
+            93 => "Zstandard",
+            95 => "XZ",
+            _ => "Unknown",
         }
+    }
 
-        let cd_size = u32::from_le_bytes([
-            eocd_data[12], eocd_data[13], eocd_data[14], eocd_data[15]
-        ]) as u64;
-        let cd_offset = u32::from_le_bytes([
-            eocd_data[16], eocd_data[17], eocd_data[18], eocd_data[19]
-        ]) as u64;
-        
-
-        
-        // 检查是否为ZIP64格式
-        let (final_cd_offset, final_cd_size) = if cd_offset == 0xFFFFFFFF {
-            // 查找ZIP64 EOCD定位器
-            if let Some(zip64_locator_pos) = Self::find_zip64_eocd(&footer_data, eocd_pos) {
-                // 解析ZIP64 EOCD记录
-                let (zip64_cd_offset, zip64_cd_size, _zip64_total_entries) = Self::parse_zip64_eocd(
-                    client.clone(),
-                    file_path,
-                    &footer_data,
-                    zip64_locator_pos,
-                    file_size,
-                    start_pos,
-                ).await?;
-                
-                (zip64_cd_offset, zip64_cd_size)
-            } else {
-                return Err("ZIP64 format detected but ZIP64 EOCD locator not found".to_string());
-            }
-        } else {
-            // 检查偏移量是否合理
-            if (cd_offset as u64) >= file_size {
-                return Err(format!("Invalid central directory offset: {} >= file size {}", cd_offset, file_size));
+    /// 根据压缩方法创建流式解码器
+    fn create_method_decoder<'a, R: std::io::Read + 'a>(
+        input: R,
+        compression_method: u16,
+        uncompressed_size: u64,
+    ) -> Result<Box<dyn std::io::Read + 'a>, String> {
+        use std::io::BufReader;
+
+        let decoder: Box<dyn std::io::Read + 'a> = match compression_method {
+            // 无压缩 - 直接读取原始数据
+            0 => Box::new(input),
+            // Deflate压缩 (原始deflate格式，不包含zlib头部)
+            8 => Box::new(flate2::read::DeflateDecoder::new(input)),
+            // Deflate64 (Windows 资源管理器压缩大文件时使用)
+            9 => Box::new(deflate64::Deflate64Decoder::new(input)),
+            12 => Box::new(bzip2::read::BzDecoder::new(input)),
+            14 => Self::create_lzma_decoder(input, uncompressed_size)?,
+            93 => Box::new(
+                ::zstd::stream::read::Decoder::with_buffer(BufReader::new(input))
+                    .map_err(|e| format!("Failed to create Zstandard decoder: {}", e))?,
+            ),
+            95 => Box::new(xz2::read::XzDecoder::new(input)),
+            _ => {
+                return Err(format!(
+                    "Unsupported compression method: {}. Supports Store(0), Deflate(8), Deflate64(9), BZIP2(12), LZMA(14), Zstandard(93) and XZ(95)",
+                    compression_method
+                ));
             }
-            (cd_offset as u64, cd_size as u64)
         };
 
-        // 读取中央目录
-        let cd_data = client.read_file_range(file_path, final_cd_offset, final_cd_size)
-            .await
-            .map_err(|e| format!("Failed to read central directory: {}", e))?;
+        Ok(decoder)
+    }
 
-        Self::find_file_in_central_directory(&cd_data, target_path)
+    /// 创建 ZIP 内 LZMA 数据的解码器
+    ///
+    /// ZIP 中的 LZMA 数据以 2 字节版本号、2 字节属性长度和 5 字节属性开头，
+    /// 这里将其改写为 .lzma (LZMA_Alone) 头部，并填入中央目录中的解压大小，
+    /// 这样无论数据末尾是否带有 EOS 标记都能正确解码。
+    fn create_lzma_decoder<'a, R: std::io::Read + 'a>(
+        mut input: R,
+        uncompressed_size: u64,
+    ) -> Result<Box<dyn std::io::Read + 'a>, String> {
+        use std::io::{Cursor, Read};
+
+        const LZMA_PROPS_SIZE: usize = 5;
+
+        let mut lzma_header = [0u8; 4];
+        input.read_exact(&mut lzma_header)
+            .map_err(|_| "Invalid LZMA header".to_string())?;
+
+        let props_size = u16::from_le_bytes([lzma_header[2], lzma_header[3]]) as usize;
+        if props_size != LZMA_PROPS_SIZE {
+            return Err(format!("Invalid LZMA properties size: {}", props_size));
+        }
+
+        let mut header = vec![0u8; LZMA_PROPS_SIZE + 8];
+        input.read_exact(&mut header[..LZMA_PROPS_SIZE])
+            .map_err(|_| "Invalid LZMA header".to_string())?;
+        header[LZMA_PROPS_SIZE..].copy_from_slice(&uncompressed_size.to_le_bytes());
+
+        let stream = xz2::stream::Stream::new_lzma_decoder(u64::MAX)
+            .map_err(|e| format!("Failed to create LZMA decoder: {}", e))?;
+
+        Ok(Box::new(xz2::read::XzDecoder::new_stream(Cursor::new(header).chain(input), stream)))
     }
 
-    /// 解压缩ZIP数据
-    fn decompress_zip_data(
-        compressed_data: &[u8],
-        compression_method: u16,
-        max_size: usize,
-        expected_crc32: u32,
-    ) -> Result<FilePreview, String> {
-        // 验证输入参数
-        if compressed_data.is_empty() {
-            return Ok(PreviewBuilder::new()
-                .content(Vec::new())
-                .with_truncated(false)
-                .total_size(0)
-                .build());
+    /// 按加密方式和压缩方法创建条目数据的解密解压读取器，返回（读取器，实际压缩方法，应校验的 CRC32）
+    #[allow(clippy::type_complexity)]
+    fn open_entry_stream<'a, R: std::io::Read + 'a>(
+        input: R,
+        file_info: &ZipFileInfo,
+        password: Option<&str>,
+    ) -> Result<(Box<dyn std::io::Read + 'a>, u16, Option<u32>), String> {
+        use std::io::Read;
+
+        let (reader, compression_method, expected_crc32): (Box<dyn Read + 'a>, u16, Option<u32>) =
+            match (&file_info.encryption, password) {
+                (Some(encryption), Some(password)) => {
+                    // ZipCrypto 校验字节：使用数据描述符时为修改时间高字节，否则为 CRC 最高字节
+                    let check_byte = if file_info.flags & zip_crypto::ZIP_FLAG_DATA_DESCRIPTOR != 0 {
+                        (file_info.last_mod_time >> 8) as u8
+                    } else {
+                        (file_info.crc32 >> 24) as u8
+                    };
+                    let reader = zip_crypto::DecryptingReader::new(
+                        input,
+                        encryption,
+                        password,
+                        check_byte,
+                        file_info.compressed_size,
+                    )?;
+                    (
+                        Box::new(reader),
+                        encryption.actual_compression_method(file_info.compression_method),
+                        encryption.verifies_crc().then_some(file_info.crc32),
+                    )
+                }
+                _ => (Box::new(input), file_info.compression_method, Some(file_info.crc32)),
+            };
+
+        let decoder = Self::create_method_decoder(reader, compression_method, file_info.uncompressed_size)?;
+        let decoder = Box::new(LimitedReader::new(decoder, OutputLimit::for_compressed_size(file_info.compressed_size)));
+        Ok((decoder, compression_method, expected_crc32))
+    }
+
+    /// 流式解密并解压整个ZIP条目写入 `output`，结束后校验 CRC32
+    fn copy_zip_stream<R: std::io::Read, W: std::io::Write>(
+        input: R,
+        file_info: &ZipFileInfo,
+        password: Option<&str>,
+        output: &mut W,
+    ) -> Result<u64, String> {
+        use std::io::Read;
+
+        let (mut decoder, compression_method, expected_crc32) = Self::open_entry_stream(input, file_info, password)?;
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut written = 0u64;
+
+        loop {
+            let n = decoder.read(&mut buffer)
+                .map_err(|e| limits::map_io_error(e, |e| format!(
+                    "{} decompression failed: {}. Data may be corrupted or format incorrect",
+                    Self::compression_method_name(compression_method), e
+                )))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+            output.write_all(&buffer[..n])
+                .map_err(|e| format!("Failed to write data: {}", e))?;
+            written += n as u64;
         }
-        
-        // 解压缩数据
-        let decompressed_data = match compression_method {
-            0 => {
-                // 无压缩 - 直接使用原始数据
-                compressed_data.to_vec()
-            }
-            8 => {
-                // Deflate压缩 (原始deflate格式，不包含zlib头部)
-                use flate2::read::DeflateDecoder;
-                use std::io::{Read, Cursor};
-                
-                let cursor = Cursor::new(compressed_data);
-                let decoder = DeflateDecoder::new(cursor);
-                let mut decompressed = Vec::new();
-                
-                // 限制解压缩后的最大大小以防止内存耗尽
-                let max_decompressed_size = std::cmp::max(max_size * 10, 50 * 1024 * 1024); // 最大50MB或10倍预览大小
-                
-                // 使用带限制的读取
-                let mut limited_reader = decoder.take(max_decompressed_size as u64);
-                limited_reader.read_to_end(&mut decompressed)
-                    .map_err(|e| format!("Deflate decompression failed: {}. Data may be corrupted or format incorrect", e))?;
-                
-                decompressed
+
+        if let Some(expected_crc32) = expected_crc32 {
+            let actual_crc32 = hasher.finalize();
+            if actual_crc32 != expected_crc32 {
+                return Err(format!(
+                    "CRC32 verification failed: expected {:08x}, actual {:08x}. File may be corrupted",
+                    expected_crc32, actual_crc32
+                ));
             }
-            _ => {
-                return Err(format!("Unsupported compression method: {}. Only supports Store(0) and Deflate(8)", compression_method));
+        }
+
+        Ok(written)
+    }
+
+    /// 流式解密并解压ZIP条目，只输出前 max_size 字节
+    ///
+    /// 条目完整解压时校验 CRC32；截断时总大小取自中央目录，不再读取剩余数据。
+    fn decompress_zip_stream<R: std::io::Read>(
+        input: R,
+        file_info: &ZipFileInfo,
+        password: Option<&str>,
+        max_size: usize,
+    ) -> Result<FilePreview, String> {
+        use std::io::Read;
+
+        let (mut decoder, compression_method, expected_crc32) = Self::open_entry_stream(input, file_info, password)?;
+        let mut hasher = crc32fast::Hasher::new();
+        let mut preview_data = Vec::with_capacity(max_size.min(file_info.uncompressed_size as usize).min(1024 * 1024));
+        let mut buffer = vec![0u8; 64 * 1024];
+
+        while preview_data.len() < max_size {
+            let want = buffer.len().min(max_size - preview_data.len());
+            let n = decoder.read(&mut buffer[..want])
+                .map_err(|e| limits::map_io_error(e, |e| format!(
+                    "{} decompression failed: {}. Data may be corrupted or format incorrect",
+                    Self::compression_method_name(compression_method), e
+                )))?;
+            if n == 0 {
+                break;
             }
-        };
+            hasher.update(&buffer[..n]);
+            preview_data.extend_from_slice(&buffer[..n]);
+        }
 
-        // 验证CRC32校验和
-        let actual_crc32 = crc32fast::hash(&decompressed_data);
-        if actual_crc32 != expected_crc32 {
-            return Err(format!(
-                "CRC32 verification failed: expected {:08x}, actual {:08x}. File may be corrupted",
-                expected_crc32, actual_crc32
-            ));
+        // 未达到 max_size 说明已解压到末尾；恰好达到时以中央目录记录的大小判断是否完整
+        let complete = preview_data.len() < max_size || preview_data.len() as u64 >= file_info.uncompressed_size;
+
+        // 验证CRC32校验和（AE-2 加密条目没有 CRC，由 HMAC 校验完整性）
+        if complete {
+            if let Some(expected_crc32) = expected_crc32 {
+                let actual_crc32 = hasher.finalize();
+                if actual_crc32 != expected_crc32 {
+                    return Err(format!(
+                        "CRC32 verification failed: expected {:08x}, actual {:08x}. File may be corrupted",
+                        expected_crc32, actual_crc32
+                    ));
+                }
+            }
         }
 
-        // 截取预览数据
-        let total_size = decompressed_data.len() as u64;
-        let preview_data = if decompressed_data.len() > max_size {
-            decompressed_data[..max_size].to_vec()
+        let total_size = if complete {
+            preview_data.len() as u64
         } else {
-            decompressed_data
+            file_info.uncompressed_size
         };
-        
-        let is_truncated = preview_data.len() < total_size as usize;
 
         Ok(PreviewBuilder::new()
             .content(preview_data)
-            .with_truncated(is_truncated)
+            .with_truncated(!complete)
             .total_size(total_size)
             .build())
     }
@@ -874,6 +1718,12 @@ impl ZipHandler {
 struct ZipFileInfo {
     compression_method: u16,
     compressed_size: u64,
+    uncompressed_size: u64,
     local_header_offset: u64,
+    /// 本地文件头所在的分卷（从 0 开始）
+    disk_number: u32,
     crc32: u32,
+    flags: u16,
+    last_mod_time: u16,
+    encryption: Option<ZipEncryption>,
 }
@@ -0,0 +1,356 @@
+/// ZIP 加密条目解密（传统 ZipCrypto 与 WinZip AES）
+use aes::cipher::{BlockEncrypt, KeyInit};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// 通用标志位：条目已加密
+pub const ZIP_FLAG_ENCRYPTED: u16 = 0x0001;
+/// 通用标志位：CRC 和大小写在数据描述符中
+pub const ZIP_FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+/// WinZip AES 扩展字段标识
+pub const AES_EXTRA_FIELD_ID: u16 = 0x9901;
+/// WinZip AES 条目在头部中使用的压缩方法
+pub const AES_COMPRESSION_METHOD: u16 = 99;
+
+/// 缺少密码时返回给前端的错误
+pub const PASSWORD_REQUIRED_ERROR: &str = "archive.zip.password.required";
+/// 密码校验失败时返回给前端的错误
+pub const PASSWORD_INCORRECT_ERROR: &str = "archive.zip.password.incorrect";
+
+const ZIPCRYPTO_HEADER_SIZE: usize = 12;
+const AES_PASSWORD_VERIFIER_SIZE: usize = 2;
+const AES_AUTH_CODE_SIZE: usize = 10;
+const AES_KDF_ITERATIONS: u32 = 1000;
+const AES_BLOCK_SIZE: usize = 16;
+
+/// 条目的加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZipEncryption {
+    /// 传统 PKWARE 加密
+    ZipCrypto,
+    /// WinZip AES 加密
+    Aes {
+        /// 1 = AE-1（保留 CRC），2 = AE-2（CRC 为 0）
+        vendor_version: u16,
+        /// 1 = 128 位，2 = 192 位，3 = 256 位
+        strength: u8,
+        /// 解密后数据实际使用的压缩方法
+        actual_method: u16,
+    },
+}
+
+impl ZipEncryption {
+    /// 根据通用标志位、压缩方法和扩展字段判断条目的加密方式
+    pub fn from_entry(flags: u16, compression_method: u16, extra_data: &[u8]) -> Option<Self> {
+        if flags & ZIP_FLAG_ENCRYPTED == 0 {
+            return None;
+        }
+
+        if compression_method == AES_COMPRESSION_METHOD {
+            if let Some(field) = Self::find_extra_field(extra_data, AES_EXTRA_FIELD_ID) {
+                if field.len() >= 7 {
+                    return Some(ZipEncryption::Aes {
+                        vendor_version: u16::from_le_bytes([field[0], field[1]]),
+                        strength: field[4],
+                        actual_method: u16::from_le_bytes([field[5], field[6]]),
+                    });
+                }
+            }
+        }
+
+        Some(ZipEncryption::ZipCrypto)
+    }
+
+    /// 加密方式名称，用于条目元数据
+    pub fn name(&self) -> &'static str {
+        match self {
+            ZipEncryption::ZipCrypto => "ZipCrypto",
+            ZipEncryption::Aes { strength: 1, .. } => "AES-128",
+            ZipEncryption::Aes { strength: 2, .. } => "AES-192",
+            ZipEncryption::Aes { .. } => "AES-256",
+        }
+    }
+
+    /// 解密后数据使用的压缩方法
+    pub fn actual_compression_method(&self, compression_method: u16) -> u16 {
+        match self {
+            ZipEncryption::ZipCrypto => compression_method,
+            ZipEncryption::Aes { actual_method, .. } => *actual_method,
+        }
+    }
+
+    /// AE-2 条目的 CRC 字段为 0，完整性由 HMAC 保证
+    pub fn verifies_crc(&self) -> bool {
+        !matches!(self, ZipEncryption::Aes { vendor_version: 2, .. })
+    }
+
+    fn find_extra_field(extra_data: &[u8], field_id: u16) -> Option<&[u8]> {
+        let mut offset = 0;
+        while offset + 4 <= extra_data.len() {
+            let id = u16::from_le_bytes([extra_data[offset], extra_data[offset + 1]]);
+            let size = u16::from_le_bytes([extra_data[offset + 2], extra_data[offset + 3]]) as usize;
+            let end = (offset + 4 + size).min(extra_data.len());
+            if id == field_id {
+                return Some(&extra_data[offset + 4..end]);
+            }
+            offset += 4 + size;
+        }
+        None
+    }
+}
+
+/// 传统 ZipCrypto 流解密器
+pub struct ZipCryptoDecryptor {
+    keys: [u32; 3],
+}
+
+impl ZipCryptoDecryptor {
+    /// 用密码初始化密钥并校验 12 字节加密头
+    ///
+    /// `check_byte` 是 CRC 的最高字节；使用数据描述符的条目则是修改时间的高字节。
+    pub fn new(password: &[u8], header: &[u8], check_byte: u8) -> Result<Self, String> {
+        if header.len() < ZIPCRYPTO_HEADER_SIZE {
+            return Err("Invalid ZipCrypto encryption header".to_string());
+        }
+
+        let mut decryptor = Self {
+            keys: [0x12345678, 0x23456789, 0x34567890],
+        };
+        for &byte in password {
+            decryptor.update_keys(byte);
+        }
+
+        let mut header_buf = [0u8; ZIPCRYPTO_HEADER_SIZE];
+        header_buf.copy_from_slice(&header[..ZIPCRYPTO_HEADER_SIZE]);
+        decryptor.decrypt(&mut header_buf);
+
+        if header_buf[ZIPCRYPTO_HEADER_SIZE - 1] != check_byte {
+            return Err(PASSWORD_INCORRECT_ERROR.to_string());
+        }
+
+        Ok(decryptor)
+    }
+
+    /// 原地解密数据
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            let temp = (self.keys[2] | 2) as u16;
+            let plain = *byte ^ ((temp.wrapping_mul(temp ^ 1)) >> 8) as u8;
+            self.update_keys(plain);
+            *byte = plain;
+        }
+    }
+
+    fn update_keys(&mut self, byte: u8) {
+        self.keys[0] = Self::crc32_update(self.keys[0], byte);
+        self.keys[1] = self.keys[1]
+            .wrapping_add(self.keys[0] & 0xff)
+            .wrapping_mul(134775813)
+            .wrapping_add(1);
+        self.keys[2] = Self::crc32_update(self.keys[2], (self.keys[1] >> 24) as u8);
+    }
+
+    fn crc32_update(crc: u32, byte: u8) -> u32 {
+        let mut value = (crc ^ byte as u32) & 0xff;
+        for _ in 0..8 {
+            value = if value & 1 != 0 { (value >> 1) ^ 0xEDB88320 } else { value >> 1 };
+        }
+        value ^ (crc >> 8)
+    }
+}
+
+enum AesCipher {
+    Aes128(aes::Aes128),
+    Aes192(aes::Aes192),
+    Aes256(aes::Aes256),
+}
+
+impl AesCipher {
+    fn encrypt_block(&self, block: &mut [u8; AES_BLOCK_SIZE]) {
+        let block = aes::Block::from_mut_slice(block);
+        match self {
+            AesCipher::Aes128(cipher) => cipher.encrypt_block(block),
+            AesCipher::Aes192(cipher) => cipher.encrypt_block(block),
+            AesCipher::Aes256(cipher) => cipher.encrypt_block(block),
+        }
+    }
+}
+
+/// WinZip AES 流解密器（AES-CTR，小端计数器从 1 开始，HMAC-SHA1 认证）
+pub struct AesDecryptor {
+    cipher: AesCipher,
+    counter: u128,
+    keystream: [u8; AES_BLOCK_SIZE],
+    keystream_pos: usize,
+    mac: Hmac<Sha1>,
+}
+
+impl AesDecryptor {
+    /// 盐值长度：128/192/256 位分别为 8/12/16 字节
+    pub fn salt_size(strength: u8) -> usize {
+        Self::key_size(strength) / 2
+    }
+
+    /// 数据前的加密头长度（盐值 + 密码校验值）
+    pub fn header_size(strength: u8) -> usize {
+        Self::salt_size(strength) + AES_PASSWORD_VERIFIER_SIZE
+    }
+
+    fn key_size(strength: u8) -> usize {
+        match strength {
+            1 => 16,
+            2 => 24,
+            _ => 32,
+        }
+    }
+
+    /// 用密码派生密钥并校验加密头中的密码校验值
+    pub fn new(password: &[u8], strength: u8, header: &[u8]) -> Result<Self, String> {
+        if !(1..=3).contains(&strength) {
+            return Err(format!("Unsupported AES encryption strength: {}", strength));
+        }
+
+        let key_size = Self::key_size(strength);
+        let salt_size = Self::salt_size(strength);
+        if header.len() < salt_size + AES_PASSWORD_VERIFIER_SIZE {
+            return Err("Invalid AES encryption header".to_string());
+        }
+
+        let salt = &header[..salt_size];
+        let verifier = &header[salt_size..salt_size + AES_PASSWORD_VERIFIER_SIZE];
+
+        // PBKDF2 输出依次为：加密密钥、HMAC 密钥、密码校验值
+        let mut derived = vec![0u8; key_size * 2 + AES_PASSWORD_VERIFIER_SIZE];
+        pbkdf2::pbkdf2_hmac::<Sha1>(password, salt, AES_KDF_ITERATIONS, &mut derived);
+
+        if &derived[key_size * 2..] != verifier {
+            return Err(PASSWORD_INCORRECT_ERROR.to_string());
+        }
+
+        let encryption_key = &derived[..key_size];
+        let cipher = match strength {
+            1 => AesCipher::Aes128(aes::Aes128::new_from_slice(encryption_key).map_err(|e| e.to_string())?),
+            2 => AesCipher::Aes192(aes::Aes192::new_from_slice(encryption_key).map_err(|e| e.to_string())?),
+            _ => AesCipher::Aes256(aes::Aes256::new_from_slice(encryption_key).map_err(|e| e.to_string())?),
+        };
+        let mac = <Hmac<Sha1> as Mac>::new_from_slice(&derived[key_size..key_size * 2])
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            cipher,
+            counter: 0,
+            keystream: [0u8; AES_BLOCK_SIZE],
+            keystream_pos: AES_BLOCK_SIZE,
+            mac,
+        })
+    }
+
+    /// 原地解密数据，同时把密文计入 HMAC
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.mac.update(data);
+
+        for byte in data.iter_mut() {
+            if self.keystream_pos == AES_BLOCK_SIZE {
+                self.counter = self.counter.wrapping_add(1);
+                self.keystream = self.counter.to_le_bytes();
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.keystream_pos = 0;
+            }
+            *byte ^= self.keystream[self.keystream_pos];
+            self.keystream_pos += 1;
+        }
+    }
+
+    /// 校验数据末尾的 10 字节认证码
+    pub fn verify(self, auth_code: &[u8]) -> Result<(), String> {
+        let expected = self.mac.finalize().into_bytes();
+        if auth_code.len() != AES_AUTH_CODE_SIZE || expected[..AES_AUTH_CODE_SIZE] != *auth_code {
+            return Err("AES authentication code mismatch. File may be corrupted".to_string());
+        }
+        Ok(())
+    }
+}
+
+enum EntryDecryptor {
+    ZipCrypto(ZipCryptoDecryptor),
+    Aes(Option<Box<AesDecryptor>>),
+}
+
+/// 边读边解密的条目数据读取器，输出仍为压缩状态的数据
+///
+/// AES 条目在读完全部密文后立即读取并校验认证码；只读取前缀时无法校验。
+pub struct DecryptingReader<R> {
+    inner: R,
+    decryptor: EntryDecryptor,
+    remaining: u64,
+}
+
+impl<R: std::io::Read> DecryptingReader<R> {
+    /// 读取并校验加密头，`encrypted_size` 为条目的压缩大小（含加密头和认证码）
+    pub fn new(
+        mut inner: R,
+        encryption: &ZipEncryption,
+        password: &str,
+        check_byte: u8,
+        encrypted_size: u64,
+    ) -> Result<Self, String> {
+        let (decryptor, overhead) = match encryption {
+            ZipEncryption::ZipCrypto => {
+                let mut header = [0u8; ZIPCRYPTO_HEADER_SIZE];
+                inner.read_exact(&mut header)
+                    .map_err(|_| "Invalid ZipCrypto encryption header".to_string())?;
+                let decryptor = ZipCryptoDecryptor::new(password.as_bytes(), &header, check_byte)?;
+                (EntryDecryptor::ZipCrypto(decryptor), ZIPCRYPTO_HEADER_SIZE)
+            }
+            ZipEncryption::Aes { strength, .. } => {
+                let mut header = vec![0u8; AesDecryptor::header_size(*strength)];
+                inner.read_exact(&mut header)
+                    .map_err(|_| "Invalid AES encryption header".to_string())?;
+                let decryptor = AesDecryptor::new(password.as_bytes(), *strength, &header)?;
+                (EntryDecryptor::Aes(Some(Box::new(decryptor))), header.len() + AES_AUTH_CODE_SIZE)
+            }
+        };
+
+        let remaining = encrypted_size.checked_sub(overhead as u64)
+            .ok_or_else(|| "Encrypted entry is smaller than its encryption header".to_string())?;
+
+        Ok(Self { inner, decryptor, remaining })
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let want = buf.len().min(self.remaining.min(usize::MAX as u64) as usize);
+        let n = self.inner.read(&mut buf[..want])?;
+        if n == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Encrypted entry data ended unexpectedly",
+            ));
+        }
+        self.remaining -= n as u64;
+
+        match &mut self.decryptor {
+            EntryDecryptor::ZipCrypto(decryptor) => decryptor.decrypt(&mut buf[..n]),
+            EntryDecryptor::Aes(decryptor) => {
+                if let Some(aes) = decryptor.as_mut() {
+                    aes.decrypt(&mut buf[..n]);
+                }
+                if self.remaining == 0 {
+                    if let Some(aes) = decryptor.take() {
+                        let mut auth_code = [0u8; AES_AUTH_CODE_SIZE];
+                        self.inner.read_exact(&mut auth_code)?;
+                        aes.verify(&auth_code)
+                            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                    }
+                }
+            }
+        }
+
+        Ok(n)
+    }
+}
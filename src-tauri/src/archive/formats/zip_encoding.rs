@@ -0,0 +1,194 @@
+/// ZIP 文件名编码处理
+///
+/// 解码优先级：通用标志位 11（UTF-8）> Info-ZIP Unicode Path 扩展字段（0x7075）>
+/// 用户选择的编码 > 合法的 UTF-8 > 按整个压缩包检测出的旧代码页（GBK、Shift_JIS 等）> CP437。
+use encoding_rs::Encoding;
+use std::sync::{LazyLock, RwLock};
+
+/// 通用标志位：文件名和注释使用 UTF-8 编码
+pub const ZIP_FLAG_UTF8: u16 = 0x0800;
+/// Info-ZIP Unicode Path 扩展字段标识
+const UNICODE_PATH_EXTRA_FIELD_ID: u16 = 0x7075;
+/// 编码检测最多使用的文件名数量
+const MAX_DETECTION_SAMPLES: usize = 2000;
+
+/// CP437 高位字符（0x80-0xFF）
+const CP437_HIGH: &str = concat!(
+    "ÇüéâäàåçêëèïîìÄÅ",
+    "ÉæÆôöòûùÿÖÜ¢£¥₧ƒ",
+    "áíóúñÑªº¿⌐¬½¼¡«»",
+    "░▒▓│┤╡╢╖╕╣║╗╝╜╛┐",
+    "└┴┬├─┼╞╟╚╔╩╦╠═╬╧",
+    "╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀",
+    "αßΓπΣσµτΦΘΩδ∞φε∩",
+    "≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{00A0}",
+);
+
+static CP437_TABLE: LazyLock<Vec<char>> = LazyLock::new(|| CP437_HIGH.chars().collect());
+
+/// 用户选择的旧代码页，None 表示自动检测
+static USER_LEGACY_ENCODING: LazyLock<RwLock<Option<LegacyEncoding>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// 未设置 UTF-8 标志的文件名所使用的旧代码页
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyEncoding {
+    /// ZIP 规范规定的默认编码
+    Cp437,
+    /// encoding_rs 支持的其他编码（GBK、Shift_JIS、Big5 等）
+    Other(&'static Encoding),
+}
+
+impl LegacyEncoding {
+    /// 根据编码名称解析，支持 `cp437`/`ibm437` 以及 WHATWG 编码标签（如 `gbk`、`shift_jis`）
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim();
+        if ["cp437", "ibm437", "437"].iter().any(|l| label.eq_ignore_ascii_case(l)) {
+            return Some(LegacyEncoding::Cp437);
+        }
+        Encoding::for_label(label.as_bytes()).map(LegacyEncoding::Other)
+    }
+
+    /// 编码名称，用于条目元数据
+    pub fn name(&self) -> &'static str {
+        match self {
+            LegacyEncoding::Cp437 => "CP437",
+            LegacyEncoding::Other(encoding) => encoding.name(),
+        }
+    }
+
+    fn decode(&self, raw: &[u8]) -> String {
+        match self {
+            LegacyEncoding::Cp437 => raw
+                .iter()
+                .map(|&b| if b < 0x80 { b as char } else { CP437_TABLE[(b - 0x80) as usize] })
+                .collect(),
+            LegacyEncoding::Other(encoding) => encoding.decode_without_bom_handling(raw).0.into_owned(),
+        }
+    }
+}
+
+/// 设置未标记 UTF-8 的文件名使用的编码；传入 None 或 `auto` 恢复自动检测
+pub fn set_user_legacy_encoding(label: Option<&str>) -> Result<(), String> {
+    let encoding = match label {
+        None => None,
+        Some(label) if label.trim().is_empty() || label.trim().eq_ignore_ascii_case("auto") => None,
+        Some(label) => Some(
+            LegacyEncoding::from_label(label)
+                .ok_or_else(|| format!("Unsupported filename encoding: {}", label))?,
+        ),
+    };
+
+    let mut current = USER_LEGACY_ENCODING.write()
+        .map_err(|_| "Filename encoding setting is unavailable".to_string())?;
+    *current = encoding;
+    log::debug!("ZIP文件名编码设置为: {}", encoding.map(|e| e.name()).unwrap_or("auto"));
+    Ok(())
+}
+
+fn user_legacy_encoding() -> Option<LegacyEncoding> {
+    USER_LEGACY_ENCODING.read().ok().and_then(|encoding| *encoding)
+}
+
+/// 整个压缩包共用的文件名解码器
+#[derive(Debug, Clone, Copy)]
+pub struct ZipNameDecoder {
+    legacy: LegacyEncoding,
+    user_selected: bool,
+}
+
+impl ZipNameDecoder {
+    /// 根据压缩包中的文件名样本确定旧代码页（用户选择的编码优先）
+    pub fn detect<'a, I>(names: I) -> Self
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        if let Some(legacy) = user_legacy_encoding() {
+            return Self { legacy, user_selected: true };
+        }
+
+        let mut detector = chardetng::EncodingDetector::new();
+        let mut has_legacy_names = false;
+        for name in names
+            .into_iter()
+            .filter(|name| std::str::from_utf8(name).is_err())
+            .take(MAX_DETECTION_SAMPLES)
+        {
+            detector.feed(name, false);
+            detector.feed(b"\n", false);
+            has_legacy_names = true;
+        }
+
+        if !has_legacy_names {
+            return Self { legacy: LegacyEncoding::Cp437, user_selected: false };
+        }
+
+        detector.feed(&[], true);
+        let guess = detector.guess(None, false);
+        // 只有东亚多字节编码可以可靠检测，其余情况按 ZIP 规范使用 CP437
+        let legacy = if [
+            encoding_rs::GBK,
+            encoding_rs::GB18030,
+            encoding_rs::BIG5,
+            encoding_rs::SHIFT_JIS,
+            encoding_rs::EUC_JP,
+            encoding_rs::EUC_KR,
+        ]
+        .contains(&guess)
+        {
+            LegacyEncoding::Other(guess)
+        } else {
+            LegacyEncoding::Cp437
+        };
+
+        log::debug!("检测到ZIP文件名编码: {}", legacy.name());
+        Self { legacy, user_selected: false }
+    }
+
+    /// 解码器标识，用于区分不同编码下的缓存
+    pub fn cache_tag(&self) -> String {
+        let source = if self.user_selected { "user" } else { "auto" };
+        format!("{}@{}", self.legacy.name(), source)
+    }
+
+    /// 解码文件名，返回（文件名，使用的编码名称）
+    pub fn decode(&self, raw: &[u8], flags: u16, extra_data: &[u8]) -> (String, &'static str) {
+        if flags & ZIP_FLAG_UTF8 != 0 {
+            return (String::from_utf8_lossy(raw).into_owned(), "UTF-8");
+        }
+
+        if let Some(name) = Self::unicode_path(raw, extra_data) {
+            return (name, "UTF-8");
+        }
+
+        if !self.user_selected {
+            if let Ok(name) = std::str::from_utf8(raw) {
+                return (name.to_string(), "UTF-8");
+            }
+        }
+
+        (self.legacy.decode(raw), self.legacy.name())
+    }
+
+    /// 读取 Info-ZIP Unicode Path 扩展字段；字段中的 CRC 与原始文件名不一致时忽略
+    fn unicode_path(raw: &[u8], extra_data: &[u8]) -> Option<String> {
+        let mut offset = 0;
+        while offset + 4 <= extra_data.len() {
+            let id = u16::from_le_bytes([extra_data[offset], extra_data[offset + 1]]);
+            let size = u16::from_le_bytes([extra_data[offset + 2], extra_data[offset + 3]]) as usize;
+            let end = (offset + 4 + size).min(extra_data.len());
+
+            if id == UNICODE_PATH_EXTRA_FIELD_ID && end - offset >= 9 {
+                let field = &extra_data[offset + 4..end];
+                let version = field[0];
+                let name_crc32 = u32::from_le_bytes([field[1], field[2], field[3], field[4]]);
+                if version == 1 && name_crc32 == crc32fast::hash(raw) {
+                    return std::str::from_utf8(&field[5..]).ok().map(|name| name.to_string());
+                }
+            }
+
+            offset += 4 + size;
+        }
+        None
+    }
+}
@@ -1,8 +1,14 @@
-use crate::archive::{types::*, formats};
+use crate::archive::{types::*, formats, nested, extract, volumes, limits::{self, DecompressionPolicy}};
 use crate::storage::traits::StorageClient;
 use crate::utils::chunk_size;
 use std::sync::Arc;
 
+/// 通过文件头部检测格式时读取的数据大小
+/// 压缩 TAR 需要解压出第一个 TAR 块才能与单文件压缩区分，因此读取较多数据
+const FORMAT_DETECTION_READ_SIZE: u64 = 64 * 1024;
+/// 按偏移预览未指定大小时读取的数据量（与前端分段加载的块大小一致）
+const DEFAULT_OFFSET_PREVIEW_SIZE: usize = 1024 * 1024;
+
 /// 压缩包处理器的统一入口
 pub struct ArchiveHandler;
 
@@ -12,6 +18,8 @@ impl ArchiveHandler {
     }
 
     /// 分析压缩包结构（统一StorageClient接口）
+    ///
+    /// `file_path` 可以是 `outer.zip!/inner.tar` 形式的嵌套路径，此时分析最内层的压缩包。
     pub async fn analyze_archive_with_client(
         &self,
         client: Arc<dyn StorageClient>,
@@ -19,44 +27,34 @@ impl ArchiveHandler {
         filename: String,
         max_size: Option<usize>,
     ) -> Result<ArchiveInfo, String> {
-        let compression_type = CompressionType::from_filename(&filename);
+        self.analyze_archive_with_progress(client, file_path, filename, max_size, None, None).await
+    }
 
-        // 检查是否支持该格式
-        match compression_type {
-            CompressionType::SevenZip => {
-                return Err("archive.format.7z.not.supported".to_string());
-            }
-            CompressionType::Rar => {
-                return Err("archive.format.rar.not.supported".to_string());
-            }
-            CompressionType::Brotli => {
-                return Err("archive.format.brotli.not.supported".to_string());
-            }
-            CompressionType::Lz4 => {
-                return Err("archive.format.lz4.not.supported".to_string());
-            }
-            CompressionType::Zstd => {
-                return Err("archive.format.zstd.not.supported".to_string());
-            }
-            _ => {}
-        }
+    /// 分析压缩包结构，支持的格式在解析过程中通过 `progress_callback` 按批次发送已解析的条目
+    ///
+    /// `password` 用于打开嵌套路径中的加密内层压缩包。
+    pub async fn analyze_archive_with_progress(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        max_size: Option<usize>,
+        progress_callback: Option<AnalysisProgressCallback>,
+        password: Option<&str>,
+    ) -> Result<ArchiveInfo, String> {
+        let (client, file_path, filename) = Self::resolve_archive(client, file_path, filename, password).await?;
 
-        let handler = if matches!(compression_type, CompressionType::Unknown) {
-            // 通过 StorageClient 读取文件头部来检测格式
-            let header_data = client.read_file_range(&file_path, 0, 512).await
-                .map_err(|e| format!("Failed to read file header: {}", e))?;
-            formats::detect_format_and_get_handler(&header_data)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        } else {
-            formats::get_handler(&compression_type)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        };
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
 
         // 通过 StorageClient 进行流式分析
-        handler.analyze_with_client(client, &file_path, &filename, max_size).await
+        handler.analyze_with_progress(client, &file_path, &filename, max_size, progress_callback).await
     }
 
-    /// 获取文件预览
+    /// 获取文件预览（加密条目需要提供密码）
+    ///
+    /// 支持嵌套路径：`file_path` 或 `entry_path` 中的 `!/` 表示进入内层压缩包，
+    /// 例如 `outer.zip` + `inner.tar!/file.json`；路径本身的 `!` 写作 `!!`（见 `nested::join_nested_path`）。
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_file_preview_with_client<F>(
         &self,
         client: Arc<dyn StorageClient>,
@@ -66,41 +64,15 @@ impl ArchiveHandler {
         max_preview_size: Option<usize>,
         progress_callback: Option<F>,
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
     ) -> Result<FilePreview, String>
     where
         F: Fn(u64, u64) + Send + Sync + 'static,
     {
-        let compression_type = CompressionType::from_filename(&filename);
+        let (client, file_path, filename, entry_path) =
+            Self::resolve_archive_entry(client, file_path, filename, entry_path, password).await?;
 
-        // 检查是否支持该格式
-        match compression_type {
-            CompressionType::SevenZip => {
-                return Err("archive.format.7z.not.supported".to_string());
-            }
-            CompressionType::Rar => {
-                return Err("archive.format.rar.not.supported".to_string());
-            }
-            CompressionType::Brotli => {
-                return Err("archive.format.brotli.not.supported".to_string());
-            }
-            CompressionType::Lz4 => {
-                return Err("archive.format.lz4.not.supported".to_string());
-            }
-            CompressionType::Zstd => {
-                return Err("archive.format.zstd.not.supported".to_string());
-            }
-            _ => {}
-        }
-
-        let handler = if matches!(compression_type, CompressionType::Unknown) {
-            let header_data = client.read_file_range(&file_path, 0, 512).await
-                .map_err(|e| format!("Failed to read file header: {}", e))?;
-            formats::detect_format_and_get_handler(&header_data)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        } else {
-            formats::get_handler(&compression_type)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        };
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
 
         // 如果没有指定大小限制，使用4GB作为最大限制（用于下载完整文件）
         let max_size = max_preview_size.unwrap_or(4 * 1024 * 1024 * 1024); // 默认4GB
@@ -110,9 +82,220 @@ impl ArchiveHandler {
             let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
             boxed
         });
-        handler.extract_preview_with_client(client, &file_path, &entry_path, max_size, boxed_callback, cancel_rx).await
+        handler.extract_preview_with_password(client, &file_path, &entry_path, max_size, boxed_callback, cancel_rx, password).await
+    }
+
+    /// 从条目的解压偏移 `offset` 开始获取预览（目前 GZIP 支持，BGZF 文件只读取相关的块）
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_file_preview_at_offset_with_client<F>(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        entry_path: String,
+        offset: u64,
+        max_preview_size: Option<usize>,
+        progress_callback: Option<F>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<FilePreview, String>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        let (client, file_path, filename, entry_path) =
+            Self::resolve_archive_entry(client, file_path, filename, entry_path, password).await?;
+
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
+        let max_size = max_preview_size.unwrap_or(DEFAULT_OFFSET_PREVIEW_SIZE);
+
+        let boxed_callback = progress_callback.map(|callback| {
+            let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
+            boxed
+        });
+        handler.extract_preview_at_offset(client, &file_path, &entry_path, offset, max_size, boxed_callback, cancel_rx).await
+    }
+
+    /// 将条目完整解压并流式写入 `writer`，返回写入的字节数（用于下载）
+    ///
+    /// 与预览不同，数据边解压边写入，不会把整个条目缓存在内存中。支持嵌套路径。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn extract_entry_to_writer_with_client<F>(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        entry_path: String,
+        writer: &mut (dyn tokio::io::AsyncWrite + Unpin + Send),
+        progress_callback: Option<F>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<u64, String>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        let (client, file_path, filename, entry_path) =
+            Self::resolve_archive_entry(client, file_path, filename, entry_path, password).await?;
+
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
+
+        let boxed_callback = progress_callback.map(|callback| {
+            let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
+            boxed
+        });
+        handler.extract_entry_to_writer(client, &file_path, &entry_path, writer, boxed_callback, cancel_rx, password).await
+    }
+
+    /// 按条目路径或通配符把压缩包中的条目解压到本地目录（`patterns` 为空时解压全部条目）
+    ///
+    /// 保留目录结构和修改时间；进度回调参数为（已写入字节数，选中条目的总字节数）。支持嵌套路径。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn extract_to_directory_with_client<F>(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        patterns: Vec<String>,
+        target_dir: std::path::PathBuf,
+        progress_callback: Option<F>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<ExtractSummary, String>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        let (client, file_path, filename) = Self::resolve_archive(client, file_path, filename, password).await?;
+
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
+
+        let boxed_callback = progress_callback.map(|callback| {
+            let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
+            boxed
+        });
+        extract::extract_entries_to_directory(
+            handler.as_ref(),
+            client,
+            &file_path,
+            &filename,
+            &patterns,
+            &target_dir,
+            boxed_callback.as_deref(),
+            cancel_rx,
+            password,
+        ).await
+    }
+
+    /// 测试压缩包完整性：流式读取全部条目并校验 CRC32、压缩流尾部和头部校验和（支持嵌套路径）
+    ///
+    /// 进度回调参数为（已读取的压缩包字节数，压缩包大小）。
+    pub async fn test_archive_with_client<F>(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        progress_callback: Option<F>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        password: Option<&str>,
+    ) -> Result<IntegrityReport, String>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        let (client, file_path, filename) = Self::resolve_archive(client, file_path, filename, password).await?;
+
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
+
+        let boxed_callback = progress_callback.map(|callback| {
+            let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
+            boxed
+        });
+        handler.test_integrity_with_client(client, &file_path, boxed_callback, cancel_rx, password).await
+    }
+
+
+    /// 分页列出压缩包中某一目录层级的条目（支持嵌套路径，`password` 用于打开加密的内层压缩包）
+    pub async fn list_archive_entries_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        options: ArchiveListOptions,
+        password: Option<&str>,
+    ) -> Result<ArchiveListing, String> {
+        let (client, file_path, filename) = Self::resolve_archive(client, file_path, filename, password).await?;
+
+        let handler = Self::get_handler_for(&client, &file_path, &filename).await?;
+        handler.list_entries_with_client(client, &file_path, &filename, &options).await
+    }
+
+    /// 解析嵌套路径和分卷，返回实际读取的存储客户端、压缩包路径和文件名
+    async fn resolve_archive(
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        password: Option<&str>,
+    ) -> Result<(Arc<dyn StorageClient>, String, String), String> {
+        match nested::split_nested_path(&file_path) {
+            Some(segments) => {
+                let (client, inner_path) = nested::resolve_nested_archive(client, &segments, password)?;
+                Ok((client, inner_path.clone(), inner_path))
+            }
+            None => Self::resolve_volumes(client, file_path, filename).await,
+        }
     }
 
+    /// 与 `resolve_archive` 相同，`entry_path` 也可以包含进入内层压缩包的嵌套路径
+    async fn resolve_archive_entry(
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        entry_path: String,
+        password: Option<&str>,
+    ) -> Result<(Arc<dyn StorageClient>, String, String, String), String> {
+        match nested::split_nested_entry_path(&file_path, &entry_path) {
+            Some((segments, entry_path)) => {
+                let (client, inner_path) = nested::resolve_nested_archive(client, &segments, password)?;
+                Ok((client, inner_path.clone(), inner_path, entry_path))
+            }
+            None => {
+                let (client, file_path, filename) = Self::resolve_volumes(client, file_path, filename).await?;
+                Ok((client, file_path, filename, entry_path))
+            }
+        }
+    }
+
+    /// 文件属于分卷压缩包时，替换为拼接所有分卷的虚拟客户端和逻辑文件名
+    async fn resolve_volumes(
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+    ) -> Result<(Arc<dyn StorageClient>, String, String), String> {
+        match volumes::resolve_volume_set(client.clone(), &file_path).await? {
+            Some((client, logical_path)) => {
+                let filename = logical_path.rsplit('/').next().unwrap_or(&logical_path).to_string();
+                Ok((client, logical_path, filename))
+            }
+            None => Ok((client, file_path, filename)),
+        }
+    }
+
+    /// 根据文件名或文件头部选择格式处理器（路径不再按嵌套路径和分卷解析）
+    pub(crate) async fn get_handler_for(
+        client: &Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+    ) -> Result<Box<dyn formats::CompressionHandlerDispatcher>, String> {
+        let compression_type = CompressionType::from_filename(filename);
+
+        if matches!(compression_type, CompressionType::Unknown) {
+            // 通过 StorageClient 读取文件头部来检测格式
+            let header_data = client.read_file_range(file_path, 0, FORMAT_DETECTION_READ_SIZE).await
+                .map_err(|e| format!("Failed to read file header: {}", e))?;
+            formats::detect_format_and_get_handler(&header_data)
+                .ok_or_else(|| "Unsupported archive format".to_string())
+        } else {
+            formats::get_handler(&compression_type)
+                .ok_or_else(|| "Unsupported archive format".to_string())
+        }
+    }
 
     /// 检查文件是否支持压缩包操作
     pub fn is_supported_archive(&self, filename: &str) -> bool {
@@ -131,13 +314,30 @@ impl ArchiveHandler {
         CompressionType::from_filename(filename)
     }
 
+    /// 设置未标记 UTF-8 的 ZIP 文件名使用的编码（如 `gbk`、`shift_jis`、`cp437`），None 表示自动检测
+    pub fn set_zip_filename_encoding(&self, encoding: Option<&str>) -> Result<(), String> {
+        formats::zip_encoding::set_user_legacy_encoding(encoding)?;
+        formats::list_index::clear_cache();
+        Ok(())
+    }
+
+    /// 获取当前的解压限制
+    pub fn get_decompression_policy(&self) -> DecompressionPolicy {
+        limits::current_policy()
+    }
+
+    /// 设置解压限制（输出大小、压缩比、条目数、嵌套层数）
+    pub fn set_decompression_policy(&self, policy: DecompressionPolicy) -> Result<(), String> {
+        limits::set_policy(policy)
+    }
+
     // 辅助方法
 
 
 
     /// 获取支持的压缩格式列表
     pub fn get_supported_formats(&self) -> Vec<&'static str> {
-        vec!["zip", "tar", "tar.gz", "tgz", "gz", "gzip"]
+        vec!["zip", "tar", "tar.gz", "tgz", "tar.zst", "tar.xz", "tar.bz2", "tar.lz4", "gz", "gzip", "7z", "rar", "zst", "zstd", "lz4", "br"]
     }
 
     /// 格式化文件大小
@@ -0,0 +1,207 @@
+/// 解压安全策略
+///
+/// 所有格式处理器共用同一套限制，防止恶意压缩包（zip 炸弹、超多条目、层层嵌套）耗尽内存、磁盘或 CPU：
+///
+/// - 单个解压流（条目或单文件压缩数据）的输出上限
+/// - 解压输出与压缩数据大小之比的上限（输出较小时不检查，避免误伤高度重复的小文件）
+/// - 单个压缩包的条目数上限
+/// - 嵌套压缩包的层数上限
+///
+/// 超出限制时返回 `archive.limit.*` 错误键，后面以 `:` 连接 JSON 参数（实际值 `value` 和上限 `limit`），
+/// 例如 `archive.limit.entries:{"value":1000001,"limit":1000000}`，前端用参数填充说明文字。
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::{LazyLock, RwLock};
+
+/// 解压输出低于此大小时不检查压缩比
+const MIN_RATIO_CHECKED_OUTPUT: u64 = 64 * 1024 * 1024;
+
+static DECOMPRESSION_POLICY: LazyLock<RwLock<DecompressionPolicy>> =
+    LazyLock::new(|| RwLock::new(DecompressionPolicy::default()));
+
+/// 解压限制配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecompressionPolicy {
+    /// 单个解压流的最大输出字节数
+    pub max_output_bytes: u64,
+    /// 解压输出与压缩数据大小之比的上限
+    pub max_ratio: u64,
+    /// 单个压缩包的最大条目数
+    pub max_entries: u64,
+    /// 嵌套压缩包的最大层数（`outer.zip!/inner.tar` 为 1 层）
+    pub max_nesting_depth: usize,
+}
+
+impl Default for DecompressionPolicy {
+    fn default() -> Self {
+        Self {
+            max_output_bytes: 16 * 1024 * 1024 * 1024,
+            max_ratio: 1000,
+            max_entries: 1_000_000,
+            max_nesting_depth: 4,
+        }
+    }
+}
+
+impl DecompressionPolicy {
+    /// 检查条目数量
+    pub fn check_entries(&self, entries: u64) -> Result<(), LimitExceeded> {
+        if entries > self.max_entries {
+            log::warn!("条目数量 {} 超过限制 {}", entries, self.max_entries);
+            return Err(LimitExceeded::Entries { value: entries, limit: self.max_entries });
+        }
+        Ok(())
+    }
+
+    /// 检查嵌套层数
+    pub fn check_nesting_depth(&self, depth: usize) -> Result<(), LimitExceeded> {
+        if depth > self.max_nesting_depth {
+            log::warn!("嵌套层数 {} 超过限制 {}", depth, self.max_nesting_depth);
+            return Err(LimitExceeded::NestingDepth { value: depth as u64, limit: self.max_nesting_depth as u64 });
+        }
+        Ok(())
+    }
+}
+
+/// 获取当前的解压限制
+pub fn current_policy() -> DecompressionPolicy {
+    DECOMPRESSION_POLICY.read().map(|policy| *policy).unwrap_or_default()
+}
+
+/// 设置解压限制；各项必须大于 0
+pub fn set_policy(policy: DecompressionPolicy) -> Result<(), String> {
+    if policy.max_output_bytes == 0 || policy.max_ratio == 0 || policy.max_entries == 0 || policy.max_nesting_depth == 0 {
+        return Err("Decompression limits must be greater than zero".to_string());
+    }
+
+    let mut current = DECOMPRESSION_POLICY.write()
+        .map_err(|_| "Decompression policy is unavailable".to_string())?;
+    *current = policy;
+    log::debug!("解压限制设置为: {:?}", policy);
+    Ok(())
+}
+
+/// 超出的限制类型，`value` 为实际值（字节数、条目数或层数），`limit` 为对应的上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitExceeded {
+    OutputSize { value: u64, limit: u64 },
+    Ratio { value: u64, limit: u64 },
+    Entries { value: u64, limit: u64 },
+    NestingDepth { value: u64, limit: u64 },
+}
+
+impl LimitExceeded {
+    /// 前端用于显示说明的错误键
+    pub fn error_key(self) -> &'static str {
+        match self {
+            LimitExceeded::OutputSize { .. } => "archive.limit.output_size",
+            LimitExceeded::Ratio { .. } => "archive.limit.ratio",
+            LimitExceeded::Entries { .. } => "archive.limit.entries",
+            LimitExceeded::NestingDepth { .. } => "archive.limit.nesting_depth",
+        }
+    }
+
+    /// （实际值，上限）
+    pub fn values(self) -> (u64, u64) {
+        match self {
+            LimitExceeded::OutputSize { value, limit }
+            | LimitExceeded::Ratio { value, limit }
+            | LimitExceeded::Entries { value, limit }
+            | LimitExceeded::NestingDepth { value, limit } => (value, limit),
+        }
+    }
+}
+
+impl std::fmt::Display for LimitExceeded {
+    /// 错误键加 JSON 参数，例如 `archive.limit.ratio:{"value":70000000,"limit":64000000}`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (value, limit) = self.values();
+        write!(f, "{}:{}", self.error_key(), serde_json::json!({ "value": value, "limit": limit }))
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl From<LimitExceeded> for String {
+    fn from(limit: LimitExceeded) -> Self {
+        limit.to_string()
+    }
+}
+
+impl From<LimitExceeded> for std::io::Error {
+    fn from(limit: LimitExceeded) -> Self {
+        std::io::Error::other(limit)
+    }
+}
+
+/// 错误信息是否为超出解压限制
+pub fn is_limit_error(error: &str) -> bool {
+    error.starts_with("archive.limit.")
+}
+
+/// 把解压过程中的 IO 错误转换为错误信息，超出限制时保留错误键和参数
+pub fn map_io_error(error: std::io::Error, describe: impl FnOnce(std::io::Error) -> String) -> String {
+    match error.get_ref().and_then(|inner| inner.downcast_ref::<LimitExceeded>()) {
+        Some(limit) => limit.to_string(),
+        None => describe(error),
+    }
+}
+
+/// 单个解压流的输出限制
+#[derive(Debug, Clone, Copy)]
+pub struct OutputLimit {
+    max_output_bytes: u64,
+    /// 按压缩比计算的输出上限
+    max_ratio_output: u64,
+}
+
+impl OutputLimit {
+    /// 压缩数据大小为 `compressed_size` 的解压流的输出限制
+    pub fn for_compressed_size(compressed_size: u64) -> Self {
+        let policy = current_policy();
+        Self {
+            max_output_bytes: policy.max_output_bytes,
+            max_ratio_output: compressed_size.saturating_mul(policy.max_ratio).max(MIN_RATIO_CHECKED_OUTPUT),
+        }
+    }
+
+    /// 检查累计输出（或声明的解压大小）
+    pub fn check(&self, output: u64) -> Result<(), LimitExceeded> {
+        if output > self.max_output_bytes {
+            log::warn!("解压输出 {} 字节超过限制 {}", output, self.max_output_bytes);
+            return Err(LimitExceeded::OutputSize { value: output, limit: self.max_output_bytes });
+        }
+        self.check_ratio(output)
+    }
+
+    /// 只检查压缩比（用于多个解压流的总输出，单个流的输出上限不适用于总和）
+    pub fn check_ratio(&self, output: u64) -> Result<(), LimitExceeded> {
+        if output > self.max_ratio_output {
+            log::warn!("解压输出 {} 字节超过压缩比限制 {}", output, self.max_ratio_output);
+            return Err(LimitExceeded::Ratio { value: output, limit: self.max_ratio_output });
+        }
+        Ok(())
+    }
+}
+
+/// 统计解压输出并在超出限制时返回错误的读取器
+pub struct LimitedReader<R> {
+    inner: R,
+    limit: OutputLimit,
+    output: u64,
+}
+
+impl<R> LimitedReader<R> {
+    pub fn new(inner: R, limit: OutputLimit) -> Self {
+        Self { inner, limit, output: 0 }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.output += n as u64;
+        self.limit.check(self.output)?;
+        Ok(n)
+    }
+}
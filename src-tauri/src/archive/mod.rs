@@ -1,3 +1,7 @@
 pub mod types;
 pub mod handlers;
 pub mod formats;
+pub mod nested;
+pub mod volumes;
+pub mod limits;
+pub mod extract;
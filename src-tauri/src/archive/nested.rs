@@ -0,0 +1,493 @@
+/// 嵌套压缩包支持
+///
+/// 把压缩包内的条目包装成只读的虚拟 `StorageClient`，上层处理器即可像访问普通文件一样
+/// 对内层压缩包做范围读取，从而支持 `outer.zip!/inner.tar!/file.json` 形式的路径。
+/// 内层条目首次读取时在后台流式解压到临时文件，读取请求只等待所需的范围写入完成；
+/// 临时文件按外层文件版本缓存，供后续分析和预览复用。
+use crate::archive::handlers::ArchiveHandler;
+use crate::archive::limits;
+use crate::archive::types::CompressionType;
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, StorageCapabilities, StorageClient, StorageError,
+    StorageFile, StorageRequest, StorageResponse,
+};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// 嵌套路径中压缩包与内部条目之间的分隔符
+pub const NESTED_PATH_SEPARATOR: &str = "!/";
+/// 路径片段中的 `!` 写作 `!!`，与分隔符区分
+const ESCAPED_EXCLAMATION: &str = "!!";
+
+/// 内层条目临时文件缓存的总大小上限
+const MAX_CACHED_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// 按插入顺序淘汰的内层条目缓存
+type NestedEntryCache = VecDeque<(String, Arc<SpilledEntry>)>;
+
+static NESTED_ENTRY_CACHE: LazyLock<Mutex<NestedEntryCache>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// 将嵌套路径拆分为各层路径；没有分隔符时返回 None，普通路径按原样使用
+///
+/// 各层路径之间用 `!/` 分隔，路径中的 `!` 写作 `!!`，
+/// 因此条目名本身包含 `!/` 时也不会被误认为分隔符（参见 `join_nested_path`）。
+pub fn split_nested_path(path: &str) -> Option<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '!' {
+            match chars.peek() {
+                Some('!') => {
+                    chars.next();
+                    current.push('!');
+                    continue;
+                }
+                Some('/') => {
+                    chars.next();
+                    segments.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        current.push(c);
+    }
+
+    if segments.is_empty() {
+        return None;
+    }
+    segments.push(current);
+    Some(segments)
+}
+
+/// 将各层路径编码为嵌套路径，是 `split_nested_path` 的逆操作
+pub fn join_nested_path<S: AsRef<str>>(segments: &[S]) -> String {
+    segments.iter()
+        .map(|segment| segment.as_ref().replace('!', ESCAPED_EXCLAMATION))
+        .collect::<Vec<_>>()
+        .join(NESTED_PATH_SEPARATOR)
+}
+
+/// 将嵌套路径的各层逐层解析为最内层压缩包所在的存储客户端和该压缩包的路径
+///
+/// 例如 `["outer.zip", "inner.tar"]` 返回读取 `outer.zip` 中 `inner.tar` 条目的虚拟客户端和 `inner.tar`。
+/// 嵌套层数超过解压限制时返回错误。
+pub fn resolve_nested_archive(
+    client: Arc<dyn StorageClient>,
+    segments: &[String],
+    password: Option<&str>,
+) -> Result<(Arc<dyn StorageClient>, String), String> {
+    limits::current_policy().check_nesting_depth(segments.len().saturating_sub(1))?;
+
+    let mut segments = segments.iter();
+    let mut current_client = client;
+    let mut current_path = segments.next().cloned().unwrap_or_default();
+
+    for entry_path in segments {
+        current_client = Arc::new(ArchiveEntryClient::new(
+            current_client,
+            current_path,
+            entry_path.clone(),
+            password.map(|p| p.to_string()),
+        ));
+        current_path = entry_path.clone();
+    }
+
+    Ok((current_client, current_path))
+}
+
+/// 压缩包路径或条目路径是嵌套路径时，拆分为（最内层压缩包的各层路径，最内层条目路径）
+///
+/// 例如 `outer.zip` + `inner.tar!/file.json` 返回 `(["outer.zip", "inner.tar"], "file.json")`。
+pub fn split_nested_entry_path(file_path: &str, entry_path: &str) -> Option<(Vec<String>, String)> {
+    let file_segments = split_nested_path(file_path);
+    let entry_segments = split_nested_path(entry_path);
+    if file_segments.is_none() && entry_segments.is_none() {
+        return None;
+    }
+
+    let mut segments = file_segments.unwrap_or_else(|| vec![file_path.to_string()]);
+    let mut entry_segments = entry_segments.unwrap_or_else(|| vec![entry_path.to_string()]);
+    let entry = entry_segments.pop().unwrap_or_default();
+    segments.extend(entry_segments);
+    Some((segments, entry))
+}
+
+/// 临时文件的写入状态
+#[derive(Clone)]
+enum SpillState {
+    /// 正在解压，已写入的字节数
+    Writing(u64),
+    /// 解压完成，条目总大小
+    Done(u64),
+    Failed(String),
+}
+
+/// 在后台解压到临时文件的内层条目
+struct SpilledEntry {
+    path: PathBuf,
+    state: tokio::sync::watch::Receiver<SpillState>,
+    cancel_tx: tokio::sync::broadcast::Sender<()>,
+}
+
+impl SpilledEntry {
+    /// 创建临时文件并开始在后台解压条目
+    async fn start(
+        parent: Arc<dyn StorageClient>,
+        archive_path: String,
+        entry_path: String,
+        password: Option<String>,
+    ) -> Result<Arc<Self>, StorageError> {
+        let path = std::env::temp_dir().join(format!("dataset_viewer_nested_{}.tmp", uuid::Uuid::new_v4()));
+        let file = tokio::fs::File::create(&path).await
+            .map_err(|e| StorageError::IoError(format!("Failed to create temp file: {}", e)))?;
+
+        let (state_tx, state) = tokio::sync::watch::channel(SpillState::Writing(0));
+        let (cancel_tx, mut cancel_rx) = tokio::sync::broadcast::channel(1);
+        let temp_path = path.clone();
+
+        log::debug!("解压嵌套压缩包条目到临时文件: {} -> {}", join_nested_path(&[&archive_path, &entry_path]), path.display());
+        tokio::spawn(async move {
+            let mut writer = SpillWriter { file, written: 0, pending: 0, state: state_tx };
+            // 条目路径已是解码后的单层路径，直接交给格式处理器，不再按嵌套路径解析
+            let result = match ArchiveHandler::get_handler_for(&parent, &archive_path, &archive_path).await {
+                Ok(handler) => handler.extract_entry_to_writer(
+                    parent,
+                    &archive_path,
+                    &entry_path,
+                    &mut writer,
+                    None,
+                    Some(&mut cancel_rx),
+                    password.as_deref(),
+                ).await,
+                Err(e) => Err(e),
+            };
+            let result = match result {
+                Ok(total) => tokio::io::AsyncWriteExt::flush(&mut writer).await
+                    .map(|_| total)
+                    .map_err(|e| format!("Failed to write temp file: {}", e)),
+                Err(e) => Err(e),
+            };
+
+            let SpillWriter { file, state, .. } = writer;
+            drop(file);
+            if state.is_closed() {
+                // 条目已被淘汰且没有读取者，删除临时文件
+                let _ = std::fs::remove_file(&temp_path);
+                return;
+            }
+            state.send_replace(match result {
+                Ok(total) => SpillState::Done(total),
+                Err(e) => {
+                    log::warn!("嵌套压缩包条目解压失败: {}", e);
+                    SpillState::Failed(e)
+                }
+            });
+        });
+
+        Ok(Arc::new(Self { path, state, cancel_tx }))
+    }
+
+    /// 等待写入到 `end` 或解压结束，返回当前可读取的字节数
+    async fn wait_for(&self, end: u64) -> Result<u64, StorageError> {
+        let mut state = self.state.clone();
+        loop {
+            let current = state.borrow_and_update().clone();
+            match current {
+                SpillState::Writing(written) if written >= end => return Ok(written),
+                SpillState::Writing(_) => {}
+                SpillState::Done(total) => return Ok(total),
+                SpillState::Failed(e) => return Err(StorageError::RequestFailed(e)),
+            }
+            state.changed().await
+                .map_err(|_| StorageError::RequestFailed("Nested archive extraction stopped".to_string()))?;
+        }
+    }
+
+    /// 解压完成时的条目大小
+    fn finished_size(&self) -> Option<u64> {
+        match *self.state.borrow() {
+            SpillState::Done(total) => Some(total),
+            _ => None,
+        }
+    }
+
+    /// 临时文件当前占用的大小
+    fn written(&self) -> u64 {
+        match *self.state.borrow() {
+            SpillState::Writing(written) | SpillState::Done(written) => written,
+            SpillState::Failed(_) => 0,
+        }
+    }
+
+    async fn read_range(&self, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        let available = self.wait_for(start.saturating_add(length)).await?;
+        let end = start.saturating_add(length).min(available);
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut file = std::fs::File::open(&path)?;
+            file.seek(SeekFrom::Start(start))?;
+            let mut data = vec![0u8; (end - start) as usize];
+            file.read_exact(&mut data)?;
+            Ok(data)
+        })
+        .await
+        .map_err(|e| StorageError::IoError(format!("Temp file read task failed: {}", e)))?
+        .map_err(|e: std::io::Error| StorageError::IoError(format!("Failed to read temp file: {}", e)))
+    }
+}
+
+impl Drop for SpilledEntry {
+    fn drop(&mut self) {
+        let _ = self.cancel_tx.send(());
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// 写入临时文件并发布已写入的字节数
+///
+/// `tokio::fs::File` 的写入在后台完成，因此先等待上一次写入落盘，再发布它的字节数。
+struct SpillWriter {
+    file: tokio::fs::File,
+    written: u64,
+    pending: u64,
+    state: tokio::sync::watch::Sender<SpillState>,
+}
+
+impl SpillWriter {
+    fn poll_commit(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if self.pending > 0 {
+            ready!(Pin::new(&mut self.file).poll_flush(cx))?;
+            self.written += self.pending;
+            self.pending = 0;
+            self.state.send_replace(SpillState::Writing(self.written));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for SpillWriter {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_commit(cx))?;
+        let written = ready!(Pin::new(&mut this.file).poll_write(cx, buf))?;
+        this.pending = written as u64;
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_commit(cx))?;
+        Pin::new(&mut this.file).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_commit(cx))?;
+        Pin::new(&mut this.file).poll_shutdown(cx)
+    }
+}
+
+/// 将压缩包内的单个条目暴露为可范围读取的只读文件
+pub struct ArchiveEntryClient {
+    parent: Arc<dyn StorageClient>,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+    protocol: String,
+    size: tokio::sync::OnceCell<u64>,
+    spill: tokio::sync::OnceCell<Arc<SpilledEntry>>,
+}
+
+impl ArchiveEntryClient {
+    pub fn new(
+        parent: Arc<dyn StorageClient>,
+        archive_path: String,
+        entry_path: String,
+        password: Option<String>,
+    ) -> Self {
+        let protocol = format!("{}+archive", parent.protocol());
+        Self {
+            parent,
+            archive_path,
+            entry_path,
+            password,
+            protocol,
+            size: tokio::sync::OnceCell::new(),
+            spill: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    fn check_path(&self, path: &str) -> Result<(), StorageError> {
+        if path != self.entry_path {
+            return Err(StorageError::RequestFailed(format!(
+                "Path {} is not available in nested archive entry {}",
+                path, self.entry_path
+            )));
+        }
+        Ok(())
+    }
+
+    /// 外层文件的版本标识加上条目路径，用于区分不同外层压缩包中的同名条目
+    async fn version_key(&self) -> String {
+        let (parent_size, parent_etag) = match self.parent.get_file_info(&self.archive_path).await {
+            Ok(file) => (file.size, file.etag.unwrap_or_default()),
+            Err(_) => (0, String::new()),
+        };
+        format!(
+            "{}:{}:{}:{}",
+            self.parent.protocol(),
+            parent_size,
+            parent_etag,
+            join_nested_path(&[&self.archive_path, &self.entry_path])
+        )
+    }
+
+    /// 外层格式的目录中是否记录了准确的条目大小；单文件压缩格式的大小可能是估算值
+    fn records_entry_size(&self) -> bool {
+        !matches!(
+            CompressionType::from_filename(&self.archive_path),
+            CompressionType::Gzip | CompressionType::Brotli | CompressionType::Lz4 | CompressionType::Zstd | CompressionType::Unknown
+        )
+    }
+
+    /// 条目的解压大小：优先读取外层压缩包记录的大小，无需解压条目内容
+    async fn entry_size(&self) -> Result<u64, StorageError> {
+        self.size.get_or_try_init(|| async {
+            if let Some(size) = self.spill.get().and_then(|spill| spill.finished_size()) {
+                return Ok(size);
+            }
+
+            if self.records_entry_size() {
+                let handler = ArchiveHandler::get_handler_for(&self.parent, &self.archive_path, &self.archive_path).await
+                    .map_err(StorageError::RequestFailed)?;
+                let preview = handler.extract_preview_with_password(
+                    self.parent.clone(),
+                    &self.archive_path,
+                    &self.entry_path,
+                    1,
+                    None,
+                    None,
+                    self.password.as_deref(),
+                ).await.map_err(StorageError::RequestFailed)?;
+                return Ok(preview.total_size);
+            }
+
+            // 无法从目录得到准确大小时等待解压完成
+            self.spilled_entry().await?.wait_for(u64::MAX).await
+        }).await.copied()
+    }
+
+    /// 后台解压到临时文件的条目（首次访问时开始解压，之后复用）
+    async fn spilled_entry(&self) -> Result<Arc<SpilledEntry>, StorageError> {
+        self.spill.get_or_try_init(|| async {
+            let key = self.version_key().await;
+            if let Some(spill) = Self::get_cached_entry(&key) {
+                return Ok(spill);
+            }
+
+            let spill = SpilledEntry::start(
+                self.parent.clone(),
+                self.archive_path.clone(),
+                self.entry_path.clone(),
+                self.password.clone(),
+            ).await?;
+            Self::cache_entry(key, spill.clone());
+            Ok(spill)
+        }).await.cloned()
+    }
+
+    fn get_cached_entry(key: &str) -> Option<Arc<SpilledEntry>> {
+        let cache = NESTED_ENTRY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache.iter()
+            .find(|(cached_key, spill)| cached_key == key && !matches!(*spill.state.borrow(), SpillState::Failed(_)))
+            .map(|(_, spill)| spill.clone())
+    }
+
+    /// 缓存临时文件；被淘汰的条目在没有读取者后删除
+    fn cache_entry(key: String, spill: Arc<SpilledEntry>) {
+        let mut cache = NESTED_ENTRY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+
+        cache.retain(|(cached_key, _)| *cached_key != key);
+        cache.push_back((key, spill));
+        while cache.iter().map(|(_, spill)| spill.written()).sum::<u64>() > MAX_CACHED_BYTES && cache.len() > 1 {
+            cache.pop_front();
+        }
+    }
+}
+
+#[async_trait]
+impl StorageClient for ArchiveEntryClient {
+    async fn connect(&mut self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn disconnect(&self) {}
+
+    async fn is_connected(&self) -> bool {
+        self.parent.is_connected().await
+    }
+
+    async fn list_directory(&self, _path: &str, _options: Option<&ListOptions>) -> Result<DirectoryResult, StorageError> {
+        Err(StorageError::ProtocolNotSupported("list_directory on nested archive entry".to_string()))
+    }
+
+    async fn request(&self, _request: &StorageRequest) -> Result<StorageResponse, StorageError> {
+        Err(StorageError::ProtocolNotSupported("request on nested archive entry".to_string()))
+    }
+
+    async fn request_binary(&self, _request: &StorageRequest) -> Result<Vec<u8>, StorageError> {
+        Err(StorageError::ProtocolNotSupported("request_binary on nested archive entry".to_string()))
+    }
+
+    async fn read_file_range(&self, path: &str, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        self.check_path(path)?;
+        self.spilled_entry().await?.read_range(start, length).await
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        self.check_path(path)?;
+        let spill = self.spilled_entry().await?;
+        let size = spill.wait_for(u64::MAX).await?;
+        spill.read_range(0, size).await
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        self.check_path(path)?;
+        self.entry_size().await
+    }
+
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        self.check_path(path)?;
+        let size = self.entry_size().await?;
+        Ok(StorageFile::file_info(path, size, String::new(), Some(self.version_key().await)))
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            supports_range_requests: true,
+            ..StorageCapabilities::default()
+        }
+    }
+
+    fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    fn validate_config(&self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
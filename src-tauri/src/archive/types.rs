@@ -9,6 +9,10 @@ pub enum CompressionType {
     Gzip,
     Tar,
     TarGz,
+    TarZst,
+    TarXz,
+    TarBz2,
+    TarLz4,
     Brotli,
     Lz4,
     Zstd,
@@ -20,6 +24,21 @@ pub enum CompressionType {
 impl CompressionType {
     pub fn from_filename(filename: &str) -> Self {
         let lower = filename.to_lowercase();
+
+        // 分卷后缀：`data.7z.001` 按去掉序号后的文件名识别，`.z01`、`.r00` 分别是 ZIP 和 RAR 分卷
+        if let Some((stem, extension)) = lower.rsplit_once('.') {
+            let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+            if extension.len() >= 3 && is_number(extension) {
+                return Self::from_filename(stem);
+            }
+            if extension.len() >= 3 && extension.starts_with('z') && is_number(&extension[1..]) {
+                return CompressionType::Zip;
+            }
+            if extension.len() >= 3 && extension.starts_with('r') && is_number(&extension[1..]) {
+                return CompressionType::Rar;
+            }
+        }
+
         if lower.ends_with(".zip") {
             CompressionType::Zip
         } else if lower.ends_with(".gz") && !lower.contains(".tar.") {
@@ -28,6 +47,14 @@ impl CompressionType {
             CompressionType::Tar
         } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
             CompressionType::TarGz
+        } else if lower.ends_with(".tar.zst") || lower.ends_with(".tar.zstd") || lower.ends_with(".tzst") {
+            CompressionType::TarZst
+        } else if lower.ends_with(".tar.xz") || lower.ends_with(".txz") {
+            CompressionType::TarXz
+        } else if lower.ends_with(".tar.bz2") || lower.ends_with(".tbz2") || lower.ends_with(".tbz") {
+            CompressionType::TarBz2
+        } else if lower.ends_with(".tar.lz4") {
+            CompressionType::TarLz4
         } else if lower.ends_with(".br") {
             CompressionType::Brotli
         } else if lower.ends_with(".lz4") {
@@ -50,6 +77,10 @@ impl CompressionType {
             CompressionType::Gzip => "gzip",
             CompressionType::Tar => "tar",
             CompressionType::TarGz => "tar.gz",
+            CompressionType::TarZst => "tar.zst",
+            CompressionType::TarXz => "tar.xz",
+            CompressionType::TarBz2 => "tar.bz2",
+            CompressionType::TarLz4 => "tar.lz4",
             CompressionType::Brotli => "brotli",
             CompressionType::Lz4 => "lz4",
             CompressionType::Zstd => "zstd",
@@ -64,15 +95,22 @@ impl CompressionType {
             self,
             CompressionType::Zip |
             CompressionType::TarGz |
+            CompressionType::TarZst |
+            CompressionType::TarXz |
+            CompressionType::TarBz2 |
+            CompressionType::TarLz4 |
             CompressionType::Tar |
-            CompressionType::Gzip
+            CompressionType::Gzip |
+            CompressionType::Zstd |
+            CompressionType::Lz4 |
+            CompressionType::Brotli
         )
-        // 7z 和 RAR 不支持流式处理，需要完整文件下载
+        // 7z 和 RAR 的目录信息位于文件末尾，不支持流式处理
     }
 
     #[allow(dead_code)] // API 保留方法，可能在未来版本使用
     pub fn supports_random_access(&self) -> bool {
-        matches!(self, CompressionType::Zip)
+        matches!(self, CompressionType::Zip | CompressionType::SevenZip)
     }
 }
 
@@ -113,6 +151,54 @@ pub struct ArchiveInfo {
     pub analysis_status: AnalysisStatus,
 }
 
+/// 流式分析过程中的进度更新
+///
+/// 条目按批次发送：`entries` 只包含上次更新之后新解析出的条目，接收方追加显示即可。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisProgress {
+    /// 本批新解析出的条目（只更新读取进度时为空）
+    pub entries: Vec<ArchiveEntry>,
+    /// 已读取的压缩包字节数
+    pub bytes_read: u64,
+    /// 压缩包大小
+    pub total_bytes: u64,
+    /// 分析状态（分析过程中为 `Partial`，`analyzed_entries` 为已发送的条目总数）
+    pub analysis_status: AnalysisStatus,
+}
+
+/// 分析进度回调
+pub type AnalysisProgressCallback = Box<dyn Fn(AnalysisProgress) + Send + Sync>;
+
+/// 分页列出压缩包条目的选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveListOptions {
+    /// 目录前缀（如 `images/train/`），为空时列出根目录
+    pub prefix: Option<String>,
+    /// 跳过的条目数
+    pub offset: Option<usize>,
+    /// 本页最多返回的条目数
+    pub limit: Option<usize>,
+}
+
+/// 单层目录的分页列表结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveListing {
+    /// 规范化后的目录前缀
+    pub prefix: String,
+    /// 本页条目：子目录按名称排序在前，文件按压缩包内顺序在后
+    pub entries: Vec<ArchiveEntry>,
+    /// 当前目录层级的条目总数（子目录 + 文件）
+    pub total_entries: usize,
+    /// 压缩包中的条目总数
+    pub archive_total_entries: usize,
+    /// 本页起始偏移
+    pub offset: usize,
+    /// 是否还有下一页
+    pub has_more: bool,
+    /// 下一页的起始偏移
+    pub next_offset: Option<usize>,
+}
+
 /// 分析状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AnalysisStatus {
@@ -136,6 +222,72 @@ pub struct FilePreview {
     pub preview_size: usize,
 }
 
+/// 解压到本地目录的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractSummary {
+    /// 解压的文件数
+    pub extracted_files: usize,
+    /// 创建的目录数
+    pub extracted_dirs: usize,
+    /// 写入的总字节数
+    pub total_bytes: u64,
+    /// 跳过的条目（不安全路径、符号链接等）及原因
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// 压缩包完整性测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// 逐条目的测试结果
+    pub entries: Vec<EntryIntegrity>,
+    pub passed_entries: usize,
+    pub failed_entries: usize,
+    /// 条目之外的结构性错误（TAR 头部校验和、gzip 尾部、数据截断等），出现后不再继续测试
+    pub archive_error: Option<String>,
+    /// 所有条目通过且没有结构性错误
+    pub is_valid: bool,
+}
+
+impl IntegrityReport {
+    pub fn new(entries: Vec<EntryIntegrity>, archive_error: Option<String>) -> Self {
+        let failed_entries = entries.iter().filter(|entry| !entry.passed).count();
+        Self {
+            passed_entries: entries.len() - failed_entries,
+            failed_entries,
+            is_valid: failed_entries == 0 && archive_error.is_none(),
+            entries,
+            archive_error,
+        }
+    }
+}
+
+/// 单个条目的完整性测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryIntegrity {
+    pub path: String,
+    /// 解压后的大小（失败时为已解压的字节数）
+    pub size: u64,
+    pub passed: bool,
+    pub error: Option<String>,
+}
+
+impl EntryIntegrity {
+    pub fn passed(path: String, size: u64) -> Self {
+        Self { path, size, passed: true, error: None }
+    }
+
+    pub fn failed(path: String, size: u64, error: String) -> Self {
+        Self { path, size, passed: false, error: Some(error) }
+    }
+}
+
+/// 解压时跳过的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedEntry {
+    pub path: String,
+    pub reason: String,
+}
+
 
 
 /// 下载选项
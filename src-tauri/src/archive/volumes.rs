@@ -0,0 +1,423 @@
+/// 分卷压缩包支持
+///
+/// 打开带分卷序号的文件时，列出所在目录一次并按命名规则匹配同目录下的其他分卷，
+/// 把它们按顺序拼接成一个只读的虚拟文件，上层处理器即可像访问单个文件一样对整个分卷组做范围读取。
+/// 探测结果按文件版本缓存。只有分卷专用的后缀才会触发探测：
+///
+/// - `data.7z.001`、`data.7z.002`…：按字节切分（7-Zip、HJSplit 等），逻辑文件名为去掉序号的部分
+/// - `data.z01`、`data.z02`…（最后一卷为 `data.zip`）：ZIP 分卷，偏移相对于所在分卷，由 ZIP 处理器按分卷起始位置换算
+/// - `data.part1.rar`、`data.part2.rar`…以及 `data.r00`、`data.r01`…（第一卷为 `data.rar`）：RAR 分卷，每卷都有独立的块结构
+///
+/// 直接打开 `data.zip` 或 `data.rar` 时按普通文件处理。
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, StorageCapabilities, StorageClient, StorageError,
+    StorageFile, StorageRequest, StorageResponse,
+};
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// 最多探测的分卷数量
+const MAX_VOLUMES: u32 = 10000;
+/// 列出分卷所在目录时每页的条目数
+const LIST_PAGE_SIZE: u32 = 1000;
+/// 最多缓存的探测结果数量
+const MAX_CACHED_SETS: usize = 64;
+
+/// 探测结果：分卷组的逻辑路径和各卷，不是分卷组时为 None
+type VolumeSet = Option<(String, Arc<[Volume]>)>;
+
+static VOLUME_SET_CACHE: LazyLock<Mutex<VecDeque<(String, VolumeSet)>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// 分卷命名方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VolumeScheme {
+    /// `name.001`、`name.002`…
+    Numbered,
+    /// `name.z01`、`name.z02`…，最后一卷为 `name.zip`
+    ZipSplit,
+    /// `name.part1.rar`、`name.part2.rar`…
+    RarParts,
+    /// `name.rar`、`name.r00`、`name.r01`…
+    RarLegacy,
+}
+
+/// 解析后的分卷文件名
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VolumeName {
+    scheme: VolumeScheme,
+    /// 去掉分卷后缀的文件名
+    base: String,
+    /// 分卷在组内的顺序
+    ordinal: u32,
+    /// 文件名中序号之前的部分
+    prefix: String,
+    /// 文件名中序号之后的部分
+    suffix: String,
+    /// 序号的最小位数
+    width: usize,
+    /// 没有序号的那一卷（ZIP 分卷的 `.zip`、旧式 RAR 分卷的 `.rar`）
+    unnumbered: Option<String>,
+}
+
+impl VolumeName {
+    /// 同一分卷组中第 `ordinal` 卷的文件名，沿用当前文件名的大小写和序号位数
+    fn file_name(&self, ordinal: u32) -> String {
+        let number = match self.scheme {
+            VolumeScheme::Numbered | VolumeScheme::ZipSplit | VolumeScheme::RarParts => ordinal + 1,
+            VolumeScheme::RarLegacy if ordinal == 0 => return self.unnumbered.clone().unwrap_or_default(),
+            VolumeScheme::RarLegacy => ordinal - 1,
+        };
+        format!("{}{:0width$}{}", self.prefix, number, self.suffix, width = self.width)
+    }
+}
+
+/// 解析文件名中的分卷后缀，不是分卷专用的后缀时返回 None
+fn parse_volume_name(name: &str) -> Option<VolumeName> {
+    let lower = name.to_ascii_lowercase();
+    let (stem, extension) = lower.rsplit_once('.')?;
+    let base = name[..stem.len()].to_string();
+    let digits = |s: &str, min_len: usize| -> Option<u32> {
+        if s.len() >= min_len && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().ok()
+        } else {
+            None
+        }
+    };
+    // 保持原文件名中扩展名的大小写
+    let letter = &name[stem.len() + 1..stem.len() + 2];
+    let upper = name[stem.len() + 1..].starts_with(|c: char| c.is_ascii_uppercase());
+    let same_case = |extension: &str| if upper { extension.to_ascii_uppercase() } else { extension.to_string() };
+
+    let volume = if let Some(number) = digits(extension, 3) {
+        VolumeName {
+            scheme: VolumeScheme::Numbered,
+            ordinal: number.checked_sub(1)?,
+            prefix: format!("{}.", base),
+            suffix: String::new(),
+            width: extension.len(),
+            unnumbered: None,
+            base,
+        }
+    } else if let Some(number) = extension.strip_prefix('z').and_then(|n| digits(n, 2)) {
+        VolumeName {
+            scheme: VolumeScheme::ZipSplit,
+            ordinal: number.checked_sub(1)?,
+            prefix: format!("{}.{}", base, letter),
+            suffix: String::new(),
+            width: 2,
+            unnumbered: Some(format!("{}.{}", base, same_case("zip"))),
+            base,
+        }
+    } else if extension == "rar" {
+        let (part_stem, number_text) = stem.rsplit_once(".part")?;
+        let number = digits(number_text, 1)?;
+        let base_len = part_stem.len();
+        VolumeName {
+            scheme: VolumeScheme::RarParts,
+            base: name[..base_len].to_string(),
+            ordinal: number.checked_sub(1)?,
+            prefix: name[..base_len + ".part".len()].to_string(),
+            suffix: name[stem.len()..].to_string(),
+            width: number_text.len(),
+            unnumbered: None,
+        }
+    } else if let Some(number) = extension.strip_prefix('r').and_then(|n| digits(n, 2)) {
+        VolumeName {
+            scheme: VolumeScheme::RarLegacy,
+            ordinal: number + 1,
+            prefix: format!("{}.{}", base, letter),
+            suffix: String::new(),
+            width: 2,
+            unnumbered: Some(format!("{}.{}", base, same_case("rar"))),
+            base,
+        }
+    } else {
+        return None;
+    };
+
+    Some(volume)
+}
+
+/// 分卷组的逻辑文件名
+fn logical_name(volume: &VolumeName, first_volume: &str) -> String {
+    match volume.scheme {
+        VolumeScheme::Numbered => volume.base.clone(),
+        VolumeScheme::ZipSplit => volume.unnumbered.clone().unwrap_or_default(),
+        // RAR 分卷按第一卷命名，解压时 unrar 需要从第一卷开始
+        VolumeScheme::RarParts | VolumeScheme::RarLegacy => first_volume.to_string(),
+    }
+}
+
+/// 文件属于分卷压缩包时，返回拼接所有分卷的虚拟客户端和逻辑文件路径
+///
+/// 不是分卷专用后缀、`name.NNN` 没有从 `.001` 开始的分卷或只找到一卷 RAR 时返回 None，按普通文件处理；
+/// 当前文件之前的分卷或 ZIP 分卷的最后一卷缺失时返回错误。
+pub async fn resolve_volume_set(
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+) -> Result<Option<(Arc<dyn StorageClient>, String)>, String> {
+    let (directory, name) = match file_path.rsplit_once('/') {
+        Some((directory, name)) => (format!("{}/", directory), name),
+        None => (String::new(), file_path),
+    };
+    let Some(volume) = parse_volume_name(name) else {
+        return Ok(None);
+    };
+
+    // 当前文件的版本变化时重新探测
+    let file_info = client.get_file_info(file_path).await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let cache_key = format!(
+        "{}:{}:{}:{}:{}",
+        client.protocol(), file_path, file_info.size, file_info.lastmod, file_info.etag.unwrap_or_default()
+    );
+
+    let volume_set = match get_cached_set(&cache_key) {
+        Some(volume_set) => volume_set,
+        None => {
+            let volume_set = probe_volume_set(&client, &directory, &volume).await?;
+            cache_set(cache_key, volume_set.clone());
+            volume_set
+        }
+    };
+
+    Ok(volume_set.map(|(logical_path, volumes)| {
+        let client: Arc<dyn StorageClient> = Arc::new(VolumeSetClient::new(client, logical_path.clone(), volumes));
+        (client, logical_path)
+    }))
+}
+
+/// 从第一卷开始依次匹配分卷，直到某一卷不存在
+async fn probe_volume_set(
+    client: &Arc<dyn StorageClient>,
+    directory: &str,
+    volume: &VolumeName,
+) -> Result<VolumeSet, String> {
+    let mut files = list_volume_files(client, directory, &volume.base).await?;
+    let mut parts = Vec::new();
+    for ordinal in 0..MAX_VOLUMES {
+        let name = volume.file_name(ordinal);
+        match files.remove(&name) {
+            Some(file) => parts.push((name, file)),
+            None => break,
+        }
+    }
+
+    if volume.ordinal as usize >= parts.len() {
+        // `report.2024` 之类的文件名没有 `.001` 时不是分卷
+        if volume.scheme == VolumeScheme::Numbered && parts.is_empty() {
+            return Ok(None);
+        }
+        log::warn!("分卷不连续: {} 缺少第 {} 卷", volume.base, parts.len() + 1);
+        return Err("archive.volume.missing".to_string());
+    }
+
+    // ZIP 分卷的最后一卷（.zip）排在最后
+    if let (VolumeScheme::ZipSplit, Some(name)) = (volume.scheme, &volume.unnumbered) {
+        match files.remove(name) {
+            Some(file) => parts.push((name.clone(), file)),
+            None => {
+                log::warn!("ZIP分卷缺少最后一卷: {}", name);
+                return Err("archive.volume.missing".to_string());
+            }
+        }
+    }
+
+    // 只有一卷时按普通文件处理；`name.001` 仍需要转换为逻辑文件名
+    if parts.len() < 2 && volume.scheme != VolumeScheme::Numbered {
+        return Ok(None);
+    }
+
+    let mut volumes = Vec::with_capacity(parts.len());
+    let mut start = 0u64;
+    for (name, file) in &parts {
+        volumes.push(Volume { path: format!("{}{}", directory, name), start, size: file.size, lastmod: file.lastmod.clone() });
+        start += file.size;
+    }
+
+    let logical_path = format!("{}{}", directory, logical_name(volume, &parts[0].0));
+    log::debug!("识别到分卷压缩包: {} ({} 卷, {} 字节)", logical_path, volumes.len(), start);
+    Ok(Some((logical_path, volumes.into())))
+}
+
+/// 列出分卷所在的目录，返回文件名以 `base` 开头的文件
+///
+/// 列出目录失败时返回错误，不会把无法访问的分卷当作不存在。
+async fn list_volume_files(
+    client: &Arc<dyn StorageClient>,
+    directory: &str,
+    base: &str,
+) -> Result<HashMap<String, StorageFile>, String> {
+    let mut files = HashMap::new();
+    let mut marker = None;
+    loop {
+        let options = ListOptions {
+            page_size: Some(LIST_PAGE_SIZE),
+            marker: marker.take(),
+            prefix: None,
+            recursive: Some(false),
+            sort_by: None,
+            sort_order: None,
+        };
+        let page = client.list_directory(directory, Some(&options)).await
+            .map_err(|e| format!("Failed to list volume directory: {}", e))?;
+        for file in page.files {
+            let name = file.basename.rsplit('/').next().unwrap_or(&file.basename).to_string();
+            if file.file_type != "directory" && name.starts_with(base) {
+                files.insert(name, file);
+            }
+        }
+        match page.next_marker {
+            Some(next_marker) if page.has_more => marker = Some(next_marker),
+            _ => break,
+        }
+    }
+    Ok(files)
+}
+
+fn get_cached_set(key: &str) -> Option<VolumeSet> {
+    let cache = VOLUME_SET_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.iter().find(|(cached_key, _)| cached_key == key).map(|(_, volume_set)| volume_set.clone())
+}
+
+fn cache_set(key: String, volume_set: VolumeSet) {
+    let mut cache = VOLUME_SET_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(cached_key, _)| *cached_key != key);
+    cache.push_back((key, volume_set));
+    while cache.len() > MAX_CACHED_SETS {
+        cache.pop_front();
+    }
+}
+
+/// 分卷组中的一卷
+struct Volume {
+    path: String,
+    /// 在拼接数据中的起始偏移
+    start: u64,
+    size: u64,
+    lastmod: String,
+}
+
+/// 把按顺序拼接的分卷暴露为一个可范围读取的只读文件
+pub struct VolumeSetClient {
+    parent: Arc<dyn StorageClient>,
+    logical_path: String,
+    volumes: Arc<[Volume]>,
+    total_size: u64,
+    protocol: String,
+}
+
+impl VolumeSetClient {
+    fn new(parent: Arc<dyn StorageClient>, logical_path: String, volumes: Arc<[Volume]>) -> Self {
+        let protocol = format!("{}+volumes", parent.protocol());
+        let total_size = volumes.iter().map(|volume| volume.size).sum();
+        Self {
+            parent,
+            logical_path,
+            volumes,
+            total_size,
+            protocol,
+        }
+    }
+
+    fn check_path(&self, path: &str) -> Result<(), StorageError> {
+        if path != self.logical_path {
+            return Err(StorageError::RequestFailed(format!(
+                "Path {} is not available in volume set {}",
+                path, self.logical_path
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageClient for VolumeSetClient {
+    async fn connect(&mut self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn disconnect(&self) {}
+
+    async fn is_connected(&self) -> bool {
+        self.parent.is_connected().await
+    }
+
+    async fn list_directory(&self, _path: &str, _options: Option<&ListOptions>) -> Result<DirectoryResult, StorageError> {
+        Err(StorageError::ProtocolNotSupported("list_directory on volume set".to_string()))
+    }
+
+    async fn request(&self, _request: &StorageRequest) -> Result<StorageResponse, StorageError> {
+        Err(StorageError::ProtocolNotSupported("request on volume set".to_string()))
+    }
+
+    async fn request_binary(&self, _request: &StorageRequest) -> Result<Vec<u8>, StorageError> {
+        Err(StorageError::ProtocolNotSupported("request_binary on volume set".to_string()))
+    }
+
+    /// 把逻辑范围拆分到覆盖它的各个分卷上读取
+    async fn read_file_range(&self, path: &str, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        self.check_path(path)?;
+        let end = start.saturating_add(length).min(self.total_size);
+        let mut data = Vec::with_capacity(end.saturating_sub(start) as usize);
+
+        for volume in self.volumes.iter() {
+            let volume_end = volume.start + volume.size;
+            if volume_end <= start || volume.start >= end {
+                continue;
+            }
+            let read_start = start.max(volume.start) - volume.start;
+            let read_end = end.min(volume_end) - volume.start;
+            let chunk = self.parent.read_file_range(&volume.path, read_start, read_end - read_start).await?;
+            if chunk.len() as u64 != read_end - read_start {
+                return Err(StorageError::RequestFailed(format!(
+                    "Volume {} is shorter than expected", volume.path
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(data)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        self.read_file_range(path, 0, self.total_size).await
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        self.check_path(path)?;
+        Ok(self.total_size)
+    }
+
+    /// 各分卷的路径、大小和修改时间共同决定分卷组的版本
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        self.check_path(path)?;
+        let version = self.volumes.iter()
+            .map(|volume| format!("{}:{}:{}", volume.path, volume.size, volume.lastmod))
+            .collect::<Vec<_>>()
+            .join("|");
+        let lastmod = self.volumes.iter().map(|volume| volume.lastmod.clone()).max().unwrap_or_default();
+        Ok(StorageFile::file_info(path, self.total_size, lastmod, Some(version)))
+    }
+
+    fn volume_offsets(&self, path: &str) -> Option<Vec<u64>> {
+        (path == self.logical_path).then(|| self.volumes.iter().map(|volume| volume.start).collect())
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            supports_range_requests: true,
+            ..StorageCapabilities::default()
+        }
+    }
+
+    fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    fn validate_config(&self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
@@ -10,8 +10,13 @@ use tokio_util;
 use crate::download::{types::*, progress::ProgressTracker};
 use crate::storage::get_storage_manager;
 use crate::archive::handlers::ArchiveHandler;
+use crate::archive::types::{ExtractSummary, IntegrityReport};
+use crate::storage::traits::StorageClient;
 use crate::utils::chunk_size;
 
+/// 通过存储客户端分块下载时每次范围读取的大小，SFTP/FTP 每次读取都有往返开销，块不宜过小
+const STORAGE_DOWNLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
 pub struct DownloadManager {
     active_downloads: Arc<Mutex<HashMap<String, broadcast::Sender<()>>>>,
 }
@@ -128,6 +133,11 @@ impl DownloadManager {
             return self.handle_local_file_download(app, request).await;
         }
 
+        // sftp://、ftp:// 等无法用 HTTP 请求的地址，通过当前存储客户端分块读取
+        if !request.url.starts_with("http://") && !request.url.starts_with("https://") {
+            return self.handle_storage_client_download(app, request).await;
+        }
+
         // 设置下载（文件对话框、取消信号、进度跟踪器）
         let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) =
             self.setup_download(&app, &request.filename, None)?;
@@ -273,6 +283,105 @@ impl DownloadManager {
          )
      }
 
+    /// 处理非 HTTP 协议的下载：通过当前存储客户端按范围分块读取并写入本地文件
+    async fn handle_storage_client_download(
+        &self,
+        app: tauri::AppHandle,
+        request: DownloadRequest,
+    ) -> DownloadResult {
+        let manager_arc = get_storage_manager().await;
+        let manager = manager_arc.read().await;
+        let client = manager.get_current_client()
+            .ok_or_else(|| "No storage client available".to_string())?;
+        drop(manager);
+
+        let file_size = client.get_file_size(&request.url)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        // 设置下载（文件对话框、取消信号、进度跟踪器）
+        let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) =
+            self.setup_download(&app, &request.filename, Some(file_size))?;
+
+        let download_result = self
+            .execute_storage_client_download(
+                &progress_tracker,
+                client,
+                &request.url,
+                &save_path,
+                &request.filename,
+                file_size,
+                &mut cancel_rx,
+            )
+            .await;
+
+        self.handle_download_completion(
+            &request.filename,
+            download_result,
+            &save_path,
+            &progress_tracker,
+        )
+    }
+
+    /// 执行存储客户端分块下载的核心逻辑
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_storage_client_download(
+        &self,
+        progress_tracker: &ProgressTracker,
+        client: Arc<dyn StorageClient>,
+        url: &str,
+        save_path: &std::path::Path,
+        filename: &str,
+        total_size: u64,
+        cancel_rx: &mut broadcast::Receiver<()>,
+    ) -> Result<String, String> {
+        let mut file = tokio::fs::File::create(save_path)
+            .await
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+
+        let mut written: u64 = 0;
+        while written < total_size {
+            // 检查是否收到取消信号
+            if cancel_rx.try_recv().is_ok() {
+                drop(file);
+                let _ = tokio::fs::remove_file(save_path).await;
+                return Err("download.cancelled".to_string());
+            }
+
+            let length = STORAGE_DOWNLOAD_CHUNK_SIZE.min(total_size - written);
+            let data = client.read_file_range(url, written, length)
+                .await
+                .map_err(|e| format!("Failed to read data: {}", e))?;
+
+            // 文件在下载过程中被截断时提前结束，避免无限循环
+            if data.is_empty() {
+                return Err(format!("Unexpected end of file at {} of {} bytes", written, total_size));
+            }
+
+            file.write_all(&data)
+                .await
+                .map_err(|e| format!("Failed to write data: {}", e))?;
+
+            written += data.len() as u64;
+
+            progress_tracker.emit_progress(DownloadProgress {
+                filename: filename.to_string(),
+                downloaded: written,
+                total_size,
+                progress: progress_tracker.calculate_progress(written, total_size),
+            });
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to flush file: {}", e))?;
+
+        Ok(format!(
+            "File downloaded successfully to: {}",
+            save_path.display()
+        ))
+    }
+
     /// 下载压缩包内文件的统一方法，支持取消功能
     pub async fn download_archive_file_with_progress(
         &self,
@@ -281,6 +390,7 @@ impl DownloadManager {
         archive_filename: String,
         entry_path: String,
         entry_filename: String,
+        password: Option<String>,
     ) -> DownloadResult {
         // 设置下载（文件对话框、取消信号、进度跟踪器）
         let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) =
@@ -296,6 +406,7 @@ impl DownloadManager {
                 &entry_filename,
                 &save_path,
                 &mut cancel_rx,
+                password.as_deref(),
             )
             .await;
 
@@ -303,6 +414,134 @@ impl DownloadManager {
         self.handle_download_completion(&entry_filename, result, &save_path, &progress_tracker)
     }
 
+    /// 将压缩包中选中的条目（路径或通配符，为空时全部）解压到本地目录，支持取消功能
+    ///
+    /// 进度事件以压缩包文件名作为标识，取消时同样使用压缩包文件名。
+    /// 已解压的文件保留在目标目录中，只有未完成的文件会被删除。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn extract_archive_to_directory(
+        &self,
+        app: tauri::AppHandle,
+        archive_path: String,
+        archive_filename: String,
+        patterns: Vec<String>,
+        target_dir: String,
+        password: Option<String>,
+    ) -> Result<ExtractSummary, String> {
+        let target_path = std::path::PathBuf::from(&target_dir);
+        let filename = archive_filename.clone();
+        self.run_archive_task(app, &archive_filename, target_dir, "Failed to extract archive", |client, progress_callback, mut cancel_rx| async move {
+            ArchiveHandler::new().extract_to_directory_with_client(
+                client,
+                archive_path,
+                filename,
+                patterns,
+                target_path,
+                Some(progress_callback),
+                Some(&mut cancel_rx),
+                password.as_deref(),
+            ).await
+        }).await
+    }
+
+    /// 测试压缩包完整性，支持取消功能；进度为已读取的压缩包字节数
+    pub async fn test_archive_with_progress(
+        &self,
+        app: tauri::AppHandle,
+        archive_path: String,
+        archive_filename: String,
+        password: Option<String>,
+    ) -> Result<IntegrityReport, String> {
+        let completed_path = archive_path.clone();
+        let filename = archive_filename.clone();
+        self.run_archive_task(app, &archive_filename, completed_path, "Failed to test archive", |client, progress_callback, mut cancel_rx| async move {
+            ArchiveHandler::new().test_archive_with_client(
+                client,
+                archive_path,
+                filename,
+                Some(progress_callback),
+                Some(&mut cancel_rx),
+                password.as_deref(),
+            ).await
+        }).await
+    }
+
+    /// 执行可取消的压缩包任务，以压缩包文件名登记取消信号并发送开始、进度、完成和错误事件
+    async fn run_archive_task<T, F, Fut>(
+        &self,
+        app: tauri::AppHandle,
+        archive_filename: &str,
+        completed_path: String,
+        error_prefix: &str,
+        task: F,
+    ) -> Result<T, String>
+    where
+        F: FnOnce(Arc<dyn StorageClient>, Box<dyn Fn(u64, u64) + Send + Sync>, broadcast::Receiver<()>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, String>>,
+    {
+        // 创建取消信号
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        {
+            let mut downloads = self.active_downloads.lock().unwrap();
+            downloads.insert(archive_filename.to_string(), cancel_tx);
+        }
+
+        let progress_tracker = ProgressTracker::new(app);
+        progress_tracker.emit_started(DownloadStarted {
+            filename: archive_filename.to_string(),
+            total_size: 0,
+        });
+
+        let result = async {
+            let manager_arc = get_storage_manager().await;
+            let manager = manager_arc.read().await;
+            let client = manager.get_current_client()
+                .ok_or_else(|| "No storage client available".to_string())?;
+            drop(manager);
+
+            let progress_tracker_clone = progress_tracker.clone();
+            let archive_filename_clone = archive_filename.to_string();
+            let progress_callback: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(move |processed: u64, total: u64| {
+                progress_tracker_clone.emit_progress(DownloadProgress {
+                    filename: archive_filename_clone.clone(),
+                    downloaded: processed,
+                    total_size: total,
+                    progress: progress_tracker_clone.calculate_progress(processed, total),
+                });
+            });
+
+            task(client, progress_callback, cancel_rx).await
+        }.await;
+
+        // 从管理器中移除任务
+        {
+            let mut downloads = self.active_downloads.lock().unwrap();
+            downloads.remove(archive_filename);
+        }
+
+        match result {
+            Ok(value) => {
+                progress_tracker.emit_completed(DownloadCompleted {
+                    filename: archive_filename.to_string(),
+                    file_path: completed_path,
+                });
+                Ok(value)
+            }
+            Err(error) => {
+                let error = if error.contains("download.cancelled") {
+                    "download.cancelled".to_string()
+                } else {
+                    format!("{}: {}", error_prefix, error)
+                };
+                progress_tracker.emit_error(DownloadError {
+                    filename: archive_filename.to_string(),
+                    error: error.clone(),
+                });
+                Err(error)
+            }
+        }
+    }
+
     fn build_request(
         &self,
         client: &reqwest::Client,
@@ -447,6 +686,7 @@ impl DownloadManager {
     }
 
     /// 执行压缩包文件下载的核心逻辑
+    #[allow(clippy::too_many_arguments)]
     async fn execute_archive_download(
         &self,
         progress_tracker: &ProgressTracker,
@@ -456,6 +696,7 @@ impl DownloadManager {
         entry_filename: &str,
         save_path: &std::path::Path,
         cancel_rx: &mut broadcast::Receiver<()>,
+        password: Option<&str>,
     ) -> Result<String, String> {
         // 检查是否收到取消信号
         if cancel_rx.try_recv().is_ok() {
@@ -495,35 +736,30 @@ impl DownloadManager {
             });
         };
 
-        // 使用文件预览方法提取完整文件内容，并显示提取进度
-        let file_preview = archive_handler.get_file_preview_with_client(
+        // 边解压边写入文件，内存占用与条目大小无关
+        let result = archive_handler.extract_entry_to_writer_with_client(
             client,
             archive_path.to_string(),
             archive_filename.to_string(),
             entry_path.to_string(),
-            Some(4 * 1024 * 1024 * 1024), // 4GB 限制
+            &mut file,
             Some(progress_callback), // 使用进度回调显示提取进度
-            Some(cancel_rx), // 传递取消信号
-        ).await.map_err(|e| {
-            // 如果是取消操作，直接返回取消标识
-            if e.contains("download.cancelled") {
-                "download.cancelled".to_string()
-            } else {
-                format!("Failed to extract file from archive: {}", e)
+            Some(&mut *cancel_rx), // 传递取消信号
+            password, // 加密条目的密码
+        ).await;
+
+        let file_size = match result {
+            Ok(size) => size,
+            Err(e) => {
+                drop(file);
+                // 如果是取消操作，删除部分写入的文件并返回取消标识
+                if e.contains("download.cancelled") {
+                    let _ = tokio::fs::remove_file(save_path).await;
+                    return Err("download.cancelled".to_string());
+                }
+                return Err(format!("Failed to extract file from archive: {}", e));
             }
-        })?;
-
-        let file_data = file_preview.content;
-
-        // 检查取消信号
-        if cancel_rx.try_recv().is_ok() {
-            return Err("download.cancelled".to_string());
-        }
-
-        // 写入文件
-        file.write_all(&file_data)
-            .await
-            .map_err(|e| format!("Failed to write to file: {}", e))?;
+        };
 
         // 刷新文件缓冲区
         file.flush()
@@ -531,7 +767,6 @@ impl DownloadManager {
             .map_err(|e| format!("Failed to flush file: {}", e))?;
 
         // 最终进度报告
-        let file_size = file_data.len() as u64;
         progress_tracker.emit_progress(DownloadProgress {
             filename: entry_filename.to_string(),
             downloaded: file_size,
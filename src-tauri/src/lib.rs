@@ -3,8 +3,8 @@ mod archive;  // 压缩包处理功能 - 前端需要使用
 mod download; // 下载管理功能
 mod utils;    // 通用工具模块
 
-use archive::{handlers::ArchiveHandler, types::*};
-use storage::{StorageRequest, ConnectionConfig, get_storage_manager, ListOptions};
+use archive::{handlers::ArchiveHandler, limits::DecompressionPolicy, types::*};
+use storage::{StorageRequest, ConnectionConfig, StorageCredentials, get_storage_manager, ListOptions};
 use download::{DownloadManager, DownloadRequest};
 use std::sync::{Arc, LazyLock};
 use tauri::Emitter;
@@ -39,6 +39,7 @@ async fn storage_request(
             url: Some(url.clone()),
             access_key: None,
             secret_key: None,
+            session_token: None,
             region: None,
             bucket: None,
             endpoint: None,
@@ -73,6 +74,7 @@ async fn storage_request(
             url: Some(url.clone()),
             access_key: None,
             secret_key: None,
+            session_token: None,
             region: None,
             bucket: None,
             endpoint: None,
@@ -135,12 +137,15 @@ async fn storage_request(
     }
 }
 
+/// 分析压缩包结构；流式分析的格式在解析过程中发送 `archive-analysis-progress` 事件
 #[tauri::command]
 async fn analyze_archive_with_client(
+    app: tauri::AppHandle,
     _protocol: String,
     file_path: String,
     filename: String,
     max_size: Option<usize>,
+    password: Option<String>,
 ) -> Result<ArchiveInfo, String> {
     let manager_arc = get_storage_manager().await;
     let manager = manager_arc.read().await;
@@ -152,12 +157,53 @@ async fn analyze_archive_with_client(
     // 释放读锁后进行分析
     drop(manager);
     
+    // 分析过程中按批次把已解析的条目发送给前端
+    let event_path = file_path.clone();
+    let progress_callback: AnalysisProgressCallback = Box::new(move |progress| {
+        let _ = app.emit("archive-analysis-progress", serde_json::json!({
+            "file_path": event_path,
+            "progress": progress,
+        }));
+    });
+
     // 使用压缩包处理器分析文件
-    ARCHIVE_HANDLER.analyze_archive_with_client(
+    ARCHIVE_HANDLER.analyze_archive_with_progress(
         client,
         file_path,
         filename,
         max_size,
+        Some(progress_callback),
+        password.as_deref(),
+    ).await
+}
+
+/// 分页列出压缩包中某一目录层级的条目
+#[tauri::command]
+async fn list_archive_entries_with_client(
+    _protocol: String,
+    file_path: String,
+    filename: String,
+    prefix: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    password: Option<String>,
+) -> Result<ArchiveListing, String> {
+    let manager_arc = get_storage_manager().await;
+    let manager = manager_arc.read().await;
+
+    // 获取对应的存储客户端
+    let client = manager.get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    // 释放读锁后进行列表
+    drop(manager);
+
+    ARCHIVE_HANDLER.list_archive_entries_with_client(
+        client,
+        file_path,
+        filename,
+        ArchiveListOptions { prefix, offset, limit },
+        password.as_deref(),
     ).await
 }
 
@@ -168,6 +214,8 @@ async fn get_archive_preview_with_client(
     filename: String,
     entry_path: String,
     max_preview_size: Option<usize>,
+    password: Option<String>,
+    offset: Option<u64>,
 ) -> Result<FilePreview, String> {
     let manager_arc = get_storage_manager().await;
     let manager = manager_arc.read().await;
@@ -178,6 +226,21 @@ async fn get_archive_preview_with_client(
 
     // 释放读锁后进行预览
     drop(manager);
+
+    // 指定解压偏移时从该位置开始预览（如 BGZF 文件的随机访问）
+    if let Some(offset) = offset.filter(|&offset| offset > 0) {
+        return ARCHIVE_HANDLER.get_file_preview_at_offset_with_client(
+            client,
+            file_path,
+            filename,
+            entry_path,
+            offset,
+            max_preview_size,
+            None::<fn(u64, u64)>,
+            None,
+            password.as_deref(),
+        ).await;
+    }
     
     // 使用压缩包处理器获取文件预览
     ARCHIVE_HANDLER.get_file_preview_with_client(
@@ -188,6 +251,7 @@ async fn get_archive_preview_with_client(
         max_preview_size,
         None::<fn(u64, u64)>, // 不使用进度回调
         None, // 不使用取消信号
+        password.as_deref(),
     ).await
 }
 
@@ -228,6 +292,18 @@ async fn storage_connect(config: ConnectionConfig) -> Result<bool, String> {
     }
 }
 
+// 替换当前连接的访问凭证（如刷新即将过期的 STS 临时凭证），无需重新连接
+#[tauri::command]
+async fn storage_update_credentials(credentials: StorageCredentials) -> Result<bool, String> {
+    let manager_arc = get_storage_manager().await;
+    let manager = manager_arc.read().await;
+
+    match manager.update_credentials(&credentials) {
+        Ok(_) => Ok(true),
+        Err(e) => Err(format!("Credential update failed: {}", e))
+    }
+}
+
 #[tauri::command]
 async fn storage_disconnect() -> Result<bool, String> {
     let manager_arc = get_storage_manager().await;
@@ -332,6 +408,7 @@ async fn download_archive_file_with_progress(
     archive_filename: String,
     entry_path: String,
     entry_filename: String,
+    password: Option<String>,
 ) -> Result<String, String> {
     // 使用统一的下载管理器来处理压缩包文件下载，支持取消功能
     DOWNLOAD_MANAGER
@@ -341,10 +418,48 @@ async fn download_archive_file_with_progress(
             archive_filename,
             entry_path,
             entry_filename,
+            password,
+        )
+        .await
+}
+
+/// 将压缩包中的条目解压到本地目录
+///
+/// `patterns` 为条目路径或通配符（如 `images/**/*.jpg`），为空时解压整个压缩包。
+#[tauri::command]
+async fn extract_archive_to_directory(
+    app: tauri::AppHandle,
+    archive_path: String,
+    archive_filename: String,
+    patterns: Vec<String>,
+    target_dir: String,
+    password: Option<String>,
+) -> Result<ExtractSummary, String> {
+    DOWNLOAD_MANAGER
+        .extract_archive_to_directory(
+            app,
+            archive_path,
+            archive_filename,
+            patterns,
+            target_dir,
+            password,
         )
         .await
 }
 
+/// 测试压缩包完整性（校验每个条目的 CRC32、gzip 尾部和 TAR 头部校验和），进度通过下载事件发送
+#[tauri::command]
+async fn test_archive_integrity(
+    app: tauri::AppHandle,
+    archive_path: String,
+    archive_filename: String,
+    password: Option<String>,
+) -> Result<IntegrityReport, String> {
+    DOWNLOAD_MANAGER
+        .test_archive_with_progress(app, archive_path, archive_filename, password)
+        .await
+}
+
 // 系统对话框命令
 
 /// 显示文件夹选择对话框
@@ -424,7 +539,8 @@ async fn get_file_preview(
     _headers: std::collections::HashMap<String, String>,
     filename: String,
     entry_path: String,
-    max_preview_size: Option<usize>
+    max_preview_size: Option<usize>,
+    password: Option<String>,
 ) -> Result<FilePreview, String> {
     // 统一使用StorageClient接口进行流式预览
     let manager_arc = get_storage_manager().await;
@@ -443,6 +559,7 @@ async fn get_file_preview(
             max_preview_size,
             None::<fn(u64, u64)>, // 不使用进度回调
             None, // 不使用取消信号
+            password.as_deref(),
         ).await
     } else {
         Err("No storage client available. Please connect to a storage first (Local, WebDAV, OSS, or HuggingFace)".to_string())
@@ -467,7 +584,23 @@ async fn get_compression_info(filename: String) -> Result<CompressionType, Strin
     Ok(ARCHIVE_HANDLER.get_compression_info(&filename))
 }
 
-/// 获取支持的压缩格式列表
+/// 设置 ZIP 旧代码页文件名的编码，传入空值或 "auto" 恢复自动检测
+#[tauri::command]
+async fn set_zip_filename_encoding(encoding: Option<String>) -> Result<(), String> {
+    ARCHIVE_HANDLER.set_zip_filename_encoding(encoding.as_deref())
+}
+
+/// 获取当前的解压限制
+#[tauri::command]
+async fn get_decompression_policy() -> Result<DecompressionPolicy, String> {
+    Ok(ARCHIVE_HANDLER.get_decompression_policy())
+}
+
+/// 设置解压限制，超出限制时相关操作返回 `archive.limit.*` 错误
+#[tauri::command]
+async fn set_decompression_policy(policy: DecompressionPolicy) -> Result<(), String> {
+    ARCHIVE_HANDLER.set_decompression_policy(policy)
+}
 
 /// 获取支持的压缩格式列表
 #[tauri::command]
@@ -519,6 +652,7 @@ pub fn run() {
             storage_request,
             storage_request_binary,
             storage_connect,
+            storage_update_credentials,
             storage_disconnect,
             storage_is_connected,
             storage_get_capabilities,
@@ -528,6 +662,8 @@ pub fn run() {
             download_file_with_progress,
             cancel_download,
             download_archive_file_with_progress,
+            extract_archive_to_directory,
+            test_archive_integrity,
             // 系统对话框命令
             show_folder_dialog,
             // 压缩包处理命令
@@ -537,12 +673,16 @@ pub fn run() {
             supports_streaming,
             get_compression_info,
             get_supported_formats,
+            set_zip_filename_encoding,
+            get_decompression_policy,
+            set_decompression_policy,
             format_file_size,
             get_compression_ratio,
             get_recommended_chunk_size,
             // 新增：通过存储客户端的压缩包处理命令
             analyze_archive_with_client,
             get_archive_preview_with_client,
+            list_archive_entries_with_client,
             handle_android_back_button
         ]);
 
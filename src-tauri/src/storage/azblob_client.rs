@@ -0,0 +1,873 @@
+/// Azure Blob Storage 客户端
+///
+/// 端点形如 `https://account.blob.core.windows.net`；Azurite 等模拟器的端点在路径中包含账户名，
+/// 如 `http://127.0.0.1:10000/devstoreaccount1`。`bucket` 为容器名。支持两种认证方式：
+///
+/// - Shared Key：`access_key` 为账户名（省略时从端点推断），`secret_key` 为 Base64 编码的账户密钥
+/// - SAS：`extra_options.sas_token`，附加在每个请求的查询字符串中
+///
+/// 同时配置时使用 Shared Key。`get_download_url` 用账户密钥生成只读的服务 SAS URL，只有 SAS 时直接附带该 SAS。
+use async_trait::async_trait;
+use base64::Engine;
+use reqwest::Client;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use url::Url;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use futures_util::StreamExt;
+
+use crate::storage::traits::{
+    StorageClient, StorageRequest, StorageResponse, StorageError,
+    ConnectionConfig, StorageCapabilities, DirectoryResult, StorageFile, ListOptions, ProgressCallback,
+    check_range_response,
+};
+
+/// 请求使用的存储服务版本（`x-ms-version`），同时作为生成 SAS 的签名版本
+const API_VERSION: &str = "2021-08-06";
+/// `get_download_url` 生成的 SAS 有效期（秒）
+const DOWNLOAD_URL_EXPIRES: i64 = 3600;
+/// 单次列表请求的最大结果数
+const MAX_LIST_RESULTS: u32 = 5000;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 认证方式
+enum AzureAuth {
+    /// 账户密钥（已 Base64 解码）
+    SharedKey(Vec<u8>),
+    /// SAS 查询字符串（不含开头的 `?`）
+    Sas(String),
+}
+
+pub struct AzureBlobClient {
+    client: Client,
+    connected: AtomicBool,
+    endpoint: Url,
+    account: String,
+    container: String,
+    auth: AzureAuth,
+}
+
+impl AzureBlobClient {
+    pub fn new(config: ConnectionConfig) -> Result<Self, StorageError> {
+        let (endpoint, account, container, auth) = Self::parse_config(&config)?;
+
+        Ok(Self {
+            client: Client::new(),
+            connected: AtomicBool::new(false),
+            endpoint,
+            account,
+            container,
+            auth,
+        })
+    }
+
+    /// 解析端点、账户名、容器和认证方式
+    fn parse_config(config: &ConnectionConfig) -> Result<(Url, String, String, AzureAuth), StorageError> {
+        let raw = config.url.as_deref().unwrap_or_default().trim();
+        if raw.is_empty() {
+            return Err(StorageError::InvalidConfig("Azure Blob endpoint is required".to_string()));
+        }
+        let endpoint = if raw.contains("://") { raw.to_string() } else { format!("https://{}", raw) };
+        let mut endpoint = Url::parse(&endpoint)
+            .map_err(|e| StorageError::InvalidConfig(format!("Invalid Azure Blob endpoint: {}", e)))?;
+        let host = endpoint.host_str()
+            .ok_or_else(|| StorageError::InvalidConfig("Invalid Azure Blob endpoint: missing host".to_string()))?
+            .to_string();
+        endpoint.set_query(None);
+        let trimmed_path = endpoint.path().trim_end_matches('/').to_string();
+        endpoint.set_path(&trimmed_path);
+
+        let container = config.bucket.clone()
+            .filter(|container| !container.is_empty())
+            .ok_or_else(|| StorageError::InvalidConfig("Azure Blob container is required".to_string()))?;
+
+        // 账户名：优先使用配置，其次是 `account.blob.core.windows.net` 的第一段，最后是模拟器端点路径的第一段
+        let account = config.access_key.clone()
+            .filter(|account| !account.is_empty())
+            .or_else(|| {
+                host.split_once(".blob.")
+                    .map(|(account, _)| account.to_string())
+                    .or_else(|| trimmed_path.split('/').find(|segment| !segment.is_empty()).map(str::to_string))
+            })
+            .unwrap_or_default();
+
+        let account_key = config.secret_key.as_deref().map(str::trim).filter(|key| !key.is_empty());
+        let sas_token = config.extra_options.as_ref()
+            .and_then(|options| options.get("sas_token"))
+            .map(|token| token.trim().trim_start_matches('?'))
+            .filter(|token| !token.is_empty());
+
+        let auth = match (account_key, sas_token) {
+            (Some(key), _) => {
+                if account.is_empty() {
+                    return Err(StorageError::InvalidConfig("Azure storage account name is required".to_string()));
+                }
+                let key = base64::engine::general_purpose::STANDARD.decode(key)
+                    .map_err(|_| StorageError::InvalidConfig("Azure account key must be Base64 encoded".to_string()))?;
+                AzureAuth::SharedKey(key)
+            }
+            (None, Some(token)) => AzureAuth::Sas(token.to_string()),
+            (None, None) => return Err(StorageError::InvalidConfig("Azure account key or SAS token is required".to_string())),
+        };
+
+        Ok((endpoint, account, container, auth))
+    }
+
+    /// 保留 `A-Z a-z 0-9 - _ . ~`，其余字节编码为 `%XX`
+    fn uri_encode(value: &str, encode_slash: bool) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+                b'/' if !encode_slash => encoded.push('/'),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    fn hmac_base64(key: &[u8], data: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(key)
+            .expect("HMAC can take key of any size");
+        mac.update(data.as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// 容器或 Blob 的 URL 路径（已编码）；`blob_name` 为空时指向容器
+    fn resource_path(&self, blob_name: &str) -> String {
+        let mut path = format!("{}/{}", self.endpoint.path().trim_end_matches('/'), Self::uri_encode(&self.container, true));
+        if !blob_name.is_empty() {
+            path.push('/');
+            path.push_str(&Self::uri_encode(blob_name, false));
+        }
+        path
+    }
+
+    /// 构建请求 URL；使用 SAS 认证时附加 SAS 查询字符串
+    fn request_url(&self, blob_name: &str, query: &BTreeMap<String, String>) -> String {
+        let mut url = format!(
+            "{}://{}{}",
+            self.endpoint.scheme(),
+            self.endpoint.host_str().unwrap_or_default(),
+            self.endpoint.port().map(|port| format!(":{}", port)).unwrap_or_default()
+        );
+        url.push_str(&self.resource_path(blob_name));
+
+        let mut query_parts: Vec<String> = query.iter()
+            .map(|(key, value)| format!("{}={}", Self::uri_encode(key, true), Self::uri_encode(value, true)))
+            .collect();
+        if let AzureAuth::Sas(token) = &self.auth {
+            query_parts.push(token.clone());
+        }
+        if !query_parts.is_empty() {
+            url.push('?');
+            url.push_str(&query_parts.join("&"));
+        }
+        url
+    }
+
+    /// Shared Key 待签名字符串
+    ///
+    /// `headers` 的键为小写；标准头部按固定顺序各占一行（`Content-Length` 为 0 时留空），
+    /// 之后是排序后的 `x-ms-*` 头部和规范资源（`/账户/路径`，查询参数按名称排序、每个一行）。
+    fn shared_key_string_to_sign(
+        &self,
+        method: &str,
+        path: &str,
+        query: &BTreeMap<String, String>,
+        headers: &BTreeMap<String, String>,
+    ) -> String {
+        let header = |name: &str| headers.get(name).map(String::as_str).unwrap_or_default();
+        let content_length = match header("content-length") {
+            "0" => "",
+            length => length,
+        };
+
+        let mut string_to_sign = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+            method,
+            header("content-encoding"),
+            header("content-language"),
+            content_length,
+            header("content-md5"),
+            header("content-type"),
+            header("date"),
+            header("if-modified-since"),
+            header("if-match"),
+            header("if-none-match"),
+            header("if-unmodified-since"),
+            header("range"),
+        );
+
+        for (name, value) in headers.iter().filter(|(name, _)| name.starts_with("x-ms-")) {
+            string_to_sign.push_str(&format!("{}:{}\n", name, value.trim()));
+        }
+
+        string_to_sign.push_str(&format!("/{}{}", self.account, path));
+        let mut params: Vec<(String, &str)> = query.iter()
+            .map(|(key, value)| (key.to_lowercase(), value.as_str()))
+            .collect();
+        params.sort();
+        for (key, value) in params {
+            string_to_sign.push_str(&format!("\n{}:{}", key, value));
+        }
+
+        string_to_sign
+    }
+
+    /// 签名并发送请求；`headers` 为附加的 `x-ms-*` 头部
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        blob_name: &str,
+        query: &BTreeMap<String, String>,
+        headers: &HashMap<String, String>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let mut request_headers: BTreeMap<String, String> = headers.iter()
+            .map(|(key, value)| (key.to_lowercase(), value.clone()))
+            .collect();
+        request_headers.insert("x-ms-date".to_string(), Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+        request_headers.insert("x-ms-version".to_string(), API_VERSION.to_string());
+
+        if let AzureAuth::SharedKey(key) = &self.auth {
+            let string_to_sign = self.shared_key_string_to_sign(method.as_str(), &self.resource_path(blob_name), query, &request_headers);
+            let signature = Self::hmac_base64(key, &string_to_sign);
+            request_headers.insert("authorization".to_string(), format!("SharedKey {}:{}", self.account, signature));
+        }
+
+        let url = self.request_url(blob_name, query);
+        let mut req_builder = self.client.request(method, &url);
+        for (key, value) in &request_headers {
+            req_builder = req_builder.header(key, value);
+        }
+
+        req_builder.send().await
+            .map_err(|e| StorageError::NetworkError(format!("Azure Blob request failed: {}", e)))
+    }
+
+    /// 请求失败时提取 Azure 错误码（HEAD 请求没有响应体，错误码在 `x-ms-error-code` 头部）
+    async fn error_from_response(context: &str, response: reqwest::Response) -> StorageError {
+        let status = response.status();
+        let header_code = response.headers()
+            .get("x-ms-error-code")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await.unwrap_or_default();
+        let code = Self::xml_element(&body, "Code").or(header_code);
+        let message = Self::xml_element(&body, "Message")
+            .map(|message| message.lines().next().unwrap_or_default().to_string());
+
+        match (code, message) {
+            (Some(code), Some(message)) => StorageError::RequestFailed(format!("{} failed with status {}: {} ({})", context, status, code, message)),
+            (Some(code), None) => StorageError::RequestFailed(format!("{} failed with status {}: {}", context, status, code)),
+            _ => StorageError::RequestFailed(format!("{} failed with status {}", context, status)),
+        }
+    }
+
+    /// 读取 XML 中第一个指定元素的文本
+    fn xml_element(xml: &str, name: &str) -> Option<String> {
+        let start_tag = format!("<{}>", name);
+        let end_tag = format!("</{}>", name);
+        let start = xml.find(&start_tag)? + start_tag.len();
+        let end = xml[start..].find(&end_tag)? + start;
+        Some(xml[start..end].to_string())
+    }
+
+    /// 生成只读的服务 SAS URL（需要账户密钥）
+    pub fn generate_sas_url(&self, blob_name: &str, expires_in_seconds: i64) -> Result<String, StorageError> {
+        Ok(self.generate_sas_url_at(blob_name, expires_in_seconds, &Utc::now()))
+    }
+
+    /// 以 `now` 为起点计算过期时间并生成 SAS URL；只有 SAS 时直接附带该 SAS
+    fn generate_sas_url_at(&self, blob_name: &str, expires_in_seconds: i64, now: &DateTime<Utc>) -> String {
+        let key = match &self.auth {
+            AzureAuth::SharedKey(key) => key,
+            AzureAuth::Sas(_) => return self.request_url(blob_name, &BTreeMap::new()),
+        };
+
+        let expiry = (*now + chrono::Duration::seconds(expires_in_seconds)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let protocol = if self.endpoint.scheme() == "https" { "https" } else { "https,http" };
+        let canonical_resource = format!("/blob/{}/{}/{}", self.account, self.container, blob_name);
+
+        // 字段依次为：权限、开始时间、过期时间、规范资源、存储访问策略、IP、协议、版本、资源类型、
+        // 快照时间、加密范围，以及五个响应头覆盖参数（rscc、rscd、rsce、rscl、rsct）
+        let string_to_sign = [
+            "r", "", expiry.as_str(), canonical_resource.as_str(), "", "", protocol, API_VERSION, "b",
+            "", "", "", "", "", "", "",
+        ].join("\n");
+        let signature = Self::hmac_base64(key, &string_to_sign);
+
+        let mut query = BTreeMap::new();
+        query.insert("sv".to_string(), API_VERSION.to_string());
+        query.insert("se".to_string(), expiry);
+        query.insert("sr".to_string(), "b".to_string());
+        query.insert("sp".to_string(), "r".to_string());
+        query.insert("spr".to_string(), protocol.to_string());
+        query.insert("sig".to_string(), signature);
+
+        self.request_url(blob_name, &query)
+    }
+
+    /// 获取 Blob 属性
+    async fn blob_properties(&self, path: &str) -> Result<reqwest::Response, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let blob_name = self.extract_blob_name(path)?;
+        let response = self.send(reqwest::Method::HEAD, &blob_name, &BTreeMap::new(), &HashMap::new()).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from_response("Get blob properties", response).await);
+        }
+        Ok(response)
+    }
+
+    /// List Blobs 请求；`delimiter` 为 None 时递归列出前缀下的所有 Blob
+    async fn list_blobs(
+        &self,
+        prefix: &str,
+        delimiter: Option<&str>,
+        max_results: Option<u32>,
+        marker: Option<&str>,
+    ) -> Result<String, StorageError> {
+        let mut query = BTreeMap::new();
+        query.insert("restype".to_string(), "container".to_string());
+        query.insert("comp".to_string(), "list".to_string());
+        if !prefix.is_empty() {
+            query.insert("prefix".to_string(), prefix.to_string());
+        }
+        if let Some(delimiter) = delimiter {
+            query.insert("delimiter".to_string(), delimiter.to_string());
+        }
+        if let Some(max_results) = max_results {
+            query.insert("maxresults".to_string(), max_results.to_string());
+        }
+        if let Some(marker) = marker.filter(|marker| !marker.is_empty()) {
+            query.insert("marker".to_string(), marker.to_string());
+        }
+
+        let response = self.send(reqwest::Method::GET, "", &query, &HashMap::new()).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from_response("List request", response).await);
+        }
+
+        response.text().await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read response: {}", e)))
+    }
+
+    /// 解析 List Blobs 响应；`filename` 为相对于 `prefix` 的路径，下一页的标记放在 `next_marker`
+    fn parse_list_blobs_response(&self, xml_content: &str, prefix: &str) -> Result<DirectoryResult, StorageError> {
+        let mut reader = Reader::from_str(xml_content);
+        reader.trim_text(true);
+
+        let mut files = Vec::new();
+        let mut buf = Vec::new();
+        let mut current_blob: Option<StorageFile> = None;
+        let mut current_prefix: Option<String> = None;
+        let mut current_text = String::new();
+        let mut next_marker: Option<String> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) => {
+                    match e.name().as_ref() {
+                        b"Blob" => {
+                            current_blob = Some(StorageFile {
+                                filename: String::new(),
+                                basename: String::new(),
+                                lastmod: String::new(),
+                                size: 0,
+                                file_type: "file".to_string(),
+                                mime: None,
+                                etag: None,
+                            });
+                        }
+                        b"BlobPrefix" => current_prefix = Some(String::new()),
+                        _ => {}
+                    }
+                    current_text.clear();
+                }
+                Ok(Event::Text(e)) => {
+                    current_text = e.unescape().unwrap_or_default().to_string();
+                }
+                Ok(Event::End(ref e)) => {
+                    let element_name_bytes = e.name();
+                    let element_name = String::from_utf8_lossy(element_name_bytes.as_ref());
+
+                    if let Some(ref mut blob) = current_blob {
+                        match element_name.as_ref() {
+                            "Name" => {
+                                let relative_path = current_text.strip_prefix(prefix).unwrap_or(&current_text);
+                                blob.filename = relative_path.to_string();
+                                blob.basename = current_text.rsplit('/').next().unwrap_or(&current_text).to_string();
+                            }
+                            "Last-Modified" => {
+                                blob.lastmod = DateTime::parse_from_rfc2822(&current_text)
+                                    .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+                                    .unwrap_or_else(|_| current_text.clone());
+                            }
+                            "Content-Length" => blob.size = current_text.parse().unwrap_or(0),
+                            "Content-Type" => blob.mime = Some(current_text.clone()).filter(|mime| !mime.is_empty()),
+                            "Etag" => blob.etag = Some(current_text.trim_matches('"').to_string()),
+                            "Blob" => {
+                                // 跳过与前缀同名的目录占位 Blob
+                                if let Some(blob) = current_blob.take().filter(|blob| !blob.filename.is_empty() && !blob.filename.ends_with('/')) {
+                                    files.push(blob);
+                                }
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(ref mut prefix_val) = current_prefix {
+                        match element_name.as_ref() {
+                            "Name" => *prefix_val = current_text.clone(),
+                            "BlobPrefix" => {
+                                if let Some(prefix_path) = current_prefix.take() {
+                                    let relative_path = prefix_path.strip_prefix(prefix).unwrap_or(&prefix_path);
+                                    let dir_name = relative_path.trim_end_matches('/');
+                                    if !dir_name.is_empty() {
+                                        files.push(StorageFile {
+                                            filename: dir_name.to_string(),
+                                            basename: dir_name.rsplit('/').next().unwrap_or(dir_name).to_string(),
+                                            lastmod: chrono::Utc::now().to_rfc3339(),
+                                            size: 0,
+                                            file_type: "directory".to_string(),
+                                            mime: None,
+                                            etag: None,
+                                        });
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    } else if element_name == "NextMarker" && !current_text.is_empty() {
+                        next_marker = Some(current_text.clone());
+                    }
+                    current_text.clear();
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(StorageError::RequestFailed(format!("XML parsing error: {}", e))),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(DirectoryResult {
+            files,
+            has_more: next_marker.is_some(),
+            next_marker,
+            total_count: None,
+            path: prefix.to_string(),
+        })
+    }
+
+    /// 从路径或 `azblob://container/blob` URL 中提取 Blob 名
+    fn extract_blob_name(&self, path: &str) -> Result<String, StorageError> {
+        match path.strip_prefix("azblob://") {
+            Some(rest) => match rest.split_once('/') {
+                Some((_container, blob_name)) => Ok(blob_name.to_string()),
+                None => Err(StorageError::RequestFailed("Invalid Azure Blob URL format".to_string())),
+            },
+            None => Ok(path.trim_start_matches('/').to_string()),
+        }
+    }
+
+    /// 目录路径转换为列表前缀：非根目录以 `/` 结尾
+    fn directory_prefix(path: &str) -> String {
+        let trimmed = path.trim_start_matches('/').trim_end_matches('/');
+        if trimmed.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", trimmed)
+        }
+    }
+}
+
+#[async_trait]
+impl StorageClient for AzureBlobClient {
+    async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        let (endpoint, account, container, auth) = Self::parse_config(config)?;
+        self.endpoint = endpoint;
+        self.account = account;
+        self.container = container;
+        self.auth = auth;
+
+        // 测试连接：列出容器中的一个 Blob，同时验证凭证和容器权限
+        self.list_blobs("", Some("/"), Some(1), None).await
+            .map_err(|e| StorageError::ConnectionFailed(format!("Azure Blob connection test failed: {}", e)))?;
+
+        log::debug!("Azure Blob连接成功: {} (账户 {}, 容器 {}, {})", self.endpoint, self.account, self.container,
+            if matches!(self.auth, AzureAuth::SharedKey(_)) { "Shared Key" } else { "SAS" });
+        self.connected.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn disconnect(&self) {
+        self.connected.store(false, Ordering::Relaxed);
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    async fn request(&self, req: &StorageRequest) -> Result<StorageResponse, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let response = if req.method == "LIST" {
+            let query_params = match &req.body {
+                Some(body) => serde_json::from_str::<serde_json::Value>(body)
+                    .map_err(|e| StorageError::RequestFailed(format!("Invalid list request body: {}", e)))?,
+                None => serde_json::Value::Null,
+            };
+
+            let body = self.list_blobs(
+                query_params.get("prefix").and_then(|v| v.as_str()).unwrap_or_default(),
+                query_params.get("delimiter").and_then(|v| v.as_str()),
+                query_params.get("max-keys").and_then(|v| v.as_u64()).map(|max| max.min(MAX_LIST_RESULTS as u64) as u32),
+                query_params.get("marker").and_then(|v| v.as_str()),
+            ).await?;
+
+            return Ok(StorageResponse {
+                status: 200,
+                headers: HashMap::new(),
+                body,
+                metadata: None,
+            });
+        } else {
+            let method = match req.method.as_str() {
+                "GET" => reqwest::Method::GET,
+                "HEAD" => reqwest::Method::HEAD,
+                _ => return Err(StorageError::RequestFailed(format!("Unsupported method: {}", req.method))),
+            };
+            let blob_name = self.extract_blob_name(&req.url)?;
+            self.send(method, &blob_name, &BTreeMap::new(), &req.headers).await?
+        };
+
+        let status = response.status().as_u16();
+        let headers: HashMap<String, String> = response
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+            .collect();
+
+        let body = response.text().await.unwrap_or_default();
+
+        Ok(StorageResponse {
+            status,
+            headers,
+            body,
+            metadata: None,
+        })
+    }
+
+    async fn request_binary(&self, req: &StorageRequest) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        if req.method != "GET" {
+            return Err(StorageError::RequestFailed(format!("Unsupported binary method: {}", req.method)));
+        }
+        let blob_name = self.extract_blob_name(&req.url)?;
+        let response = self.send(reqwest::Method::GET, &blob_name, &BTreeMap::new(), &req.headers).await?;
+
+        if !response.status().is_success() {
+            return Err(Self::error_from_response("Binary request", response).await);
+        }
+
+        response.bytes().await
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read response body: {}", e)))
+    }
+
+    async fn read_file_range(&self, path: &str, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        self.read_file_range_with_progress(path, start, length, None, None).await
+    }
+
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        log::debug!("Azure Blob读取文件范围: path={}, start={}, length={}", path, start, length);
+
+        let blob_name = self.extract_blob_name(path)?;
+        let end = start + length - 1;
+        let mut headers = HashMap::new();
+        headers.insert("x-ms-range".to_string(), format!("bytes={}-{}", start, end));
+
+        let response = self.send(reqwest::Method::GET, &blob_name, &BTreeMap::new(), &headers).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from_response("Range request", response).await);
+        }
+        let content_range = response.headers().get(reqwest::header::CONTENT_RANGE).and_then(|value| value.to_str().ok());
+        let expected = check_range_response(response.status().as_u16(), content_range, start, end)?;
+
+        let mut result = Vec::with_capacity(expected as usize);
+        let mut downloaded = 0u64;
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk_result) = stream.next().await {
+            if let Some(ref mut cancel_rx) = cancel_rx {
+                if cancel_rx.try_recv().is_ok() {
+                    return Err(StorageError::RequestFailed("download.cancelled".to_string()));
+                }
+            }
+
+            let chunk = chunk_result
+                .map_err(|e| StorageError::RequestFailed(format!("Failed to read chunk: {}", e)))?;
+            result.extend_from_slice(&chunk);
+            downloaded += chunk.len() as u64;
+            if downloaded > expected {
+                return Err(StorageError::RequestFailed(format!(
+                    "Range response is longer than Content-Range ({} bytes)", expected
+                )));
+            }
+
+            if let Some(ref callback) = progress_callback {
+                callback(downloaded, length);
+            }
+        }
+
+        log::debug!("Azure Blob实际读取到 {} 字节，请求 {} 字节", result.len(), length);
+        if downloaded != expected {
+            return Err(StorageError::RequestFailed(format!(
+                "Range response ended after {} of {} bytes", downloaded, expected
+            )));
+        }
+
+        Ok(result)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let blob_name = self.extract_blob_name(path)?;
+        let response = self.send(reqwest::Method::GET, &blob_name, &BTreeMap::new(), &HashMap::new()).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from_response("Get blob", response).await);
+        }
+
+        response.bytes().await
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read file content: {}", e)))
+    }
+
+    async fn list_directory(&self, path: &str, options: Option<&ListOptions>) -> Result<DirectoryResult, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let prefix = Self::directory_prefix(path);
+        let page_size = options.and_then(|o| o.page_size).unwrap_or(1000).min(MAX_LIST_RESULTS);
+        let marker = options.and_then(|o| o.marker.as_deref());
+        let recursive = options.and_then(|o| o.recursive).unwrap_or(false);
+
+        let xml_content = self.list_blobs(
+            &prefix,
+            if recursive { None } else { Some("/") },
+            Some(page_size),
+            marker,
+        ).await?;
+
+        self.parse_list_blobs_response(&xml_content, &prefix)
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        Ok(self.get_file_info(path).await?.size)
+    }
+
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        let response = self.blob_properties(path).await?;
+        let headers = response.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let size = header("content-length")
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| StorageError::RequestFailed("No content-length header".to_string()))?;
+        let lastmod = header("last-modified").unwrap_or_default().to_string();
+        let etag = header("etag").map(|s| s.trim_matches('"').to_string());
+
+        Ok(StorageFile::file_info(path, size, lastmod, etag))
+    }
+
+    fn get_download_url(&self, path: &str) -> Result<String, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        // 生成 1 小时有效期的只读 SAS URL
+        let blob_name = self.extract_blob_name(path)?;
+        self.generate_sas_url(&blob_name, DOWNLOAD_URL_EXPIRES)
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            supports_streaming: true,
+            supports_range_requests: true,
+            supports_multipart_upload: false,
+            supports_metadata: true,
+            supports_encryption: false,
+            supports_directories: true,
+            max_file_size: Some(190 * 1024 * 1024 * 1024 * 1024), // 块 Blob 上限约 190.7TiB
+            supported_methods: vec![
+                "GET".to_string(),
+                "HEAD".to_string(),
+                "LIST".to_string(),
+            ],
+        }
+    }
+
+    fn protocol(&self) -> &str {
+        "azblob"
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        Self::parse_config(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Azurite 公开的默认账户密钥
+    const EMULATOR_KEY: &str = "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn test_client(secret_key: Option<&str>, sas_token: Option<&str>) -> AzureBlobClient {
+        AzureBlobClient::new(ConnectionConfig {
+            protocol: "azblob".to_string(),
+            url: Some("http://127.0.0.1:10000/devstoreaccount1".to_string()),
+            access_key: None,
+            secret_key: secret_key.map(str::to_string),
+            session_token: None,
+            region: None,
+            bucket: Some("box".to_string()),
+            endpoint: None,
+            username: None,
+            password: None,
+            extra_options: sas_token.map(|token| HashMap::from([("sas_token".to_string(), token.to_string())])),
+        }).unwrap()
+    }
+
+    fn test_headers(extra: &[(&str, &str)]) -> BTreeMap<String, String> {
+        let mut headers = BTreeMap::from([
+            ("x-ms-date".to_string(), "Tue, 02 Jan 2024 03:04:05 GMT".to_string()),
+            ("x-ms-version".to_string(), API_VERSION.to_string()),
+        ]);
+        headers.extend(extra.iter().map(|(name, value)| (name.to_string(), value.to_string())));
+        headers
+    }
+
+    fn test_signature(string_to_sign: &str) -> String {
+        let key = base64::engine::general_purpose::STANDARD.decode(EMULATOR_KEY).unwrap();
+        AzureBlobClient::hmac_base64(&key, string_to_sign)
+    }
+
+    #[test]
+    fn account_is_inferred_from_emulator_path() {
+        let client = test_client(Some(EMULATOR_KEY), None);
+        assert_eq!(client.account, "devstoreaccount1");
+        assert_eq!(client.resource_path("dir/a b.txt"), "/devstoreaccount1/box/dir/a%20b.txt");
+    }
+
+    #[test]
+    fn shared_key_string_to_sign_for_range_read() {
+        let client = test_client(Some(EMULATOR_KEY), None);
+        let string_to_sign = client.shared_key_string_to_sign(
+            "GET",
+            &client.resource_path("dir/a b.txt"),
+            &BTreeMap::new(),
+            &test_headers(&[("x-ms-range", "bytes=0-1023")]),
+        );
+
+        assert_eq!(
+            string_to_sign,
+            "GET\n\n\n\n\n\n\n\n\n\n\n\n\
+             x-ms-date:Tue, 02 Jan 2024 03:04:05 GMT\n\
+             x-ms-range:bytes=0-1023\n\
+             x-ms-version:2021-08-06\n\
+             /devstoreaccount1/devstoreaccount1/box/dir/a%20b.txt"
+        );
+        assert_eq!(test_signature(&string_to_sign), "2WD+ZE7BMACUwVZQuZtRbDO3p4uEmvw6SxtH2obOQDs=");
+    }
+
+    #[test]
+    fn shared_key_string_to_sign_for_list_blobs() {
+        let client = test_client(Some(EMULATOR_KEY), None);
+        let query = BTreeMap::from([
+            ("restype".to_string(), "container".to_string()),
+            ("comp".to_string(), "list".to_string()),
+            ("prefix".to_string(), "dir/".to_string()),
+            ("delimiter".to_string(), "/".to_string()),
+            ("maxresults".to_string(), "1".to_string()),
+        ]);
+        let string_to_sign = client.shared_key_string_to_sign("GET", &client.resource_path(""), &query, &test_headers(&[]));
+
+        assert_eq!(
+            string_to_sign,
+            "GET\n\n\n\n\n\n\n\n\n\n\n\n\
+             x-ms-date:Tue, 02 Jan 2024 03:04:05 GMT\n\
+             x-ms-version:2021-08-06\n\
+             /devstoreaccount1/devstoreaccount1/box\n\
+             comp:list\ndelimiter:/\nmaxresults:1\nprefix:dir/\nrestype:container"
+        );
+        assert_eq!(test_signature(&string_to_sign), "zs8mzg40h6UHvHaLUBCfGvVhfv13AaiIRMrbA74ysmI=");
+    }
+
+    #[test]
+    fn shared_key_string_to_sign_blanks_zero_content_length() {
+        let client = test_client(Some(EMULATOR_KEY), None);
+        let headers = |length: &str| {
+            let mut headers = test_headers(&[]);
+            headers.insert("content-length".to_string(), length.to_string());
+            headers
+        };
+
+        let zero = client.shared_key_string_to_sign("GET", "/box", &BTreeMap::new(), &headers("0"));
+        assert!(zero.starts_with("GET\n\n\n\n\n"));
+        let sized = client.shared_key_string_to_sign("GET", "/box", &BTreeMap::new(), &headers("42"));
+        assert!(sized.starts_with("GET\n\n\n42\n\n"));
+    }
+
+    #[test]
+    fn sas_url_known_answer() {
+        let client = test_client(Some(EMULATOR_KEY), None);
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        assert_eq!(
+            client.generate_sas_url_at("dir/a b.txt", 3600, &now),
+            "http://127.0.0.1:10000/devstoreaccount1/box/dir/a%20b.txt\
+             ?se=2024-01-02T04%3A04%3A05Z&sig=Wv4qbD6HvBNpFCYOri0hnfWYdm89iOeUYYdcx2dYddE%3D\
+             &sp=r&spr=https%2Chttp&sr=b&sv=2021-08-06"
+        );
+    }
+
+    #[test]
+    fn sas_token_is_appended_as_is() {
+        let client = test_client(None, Some("?sv=2021-08-06&sp=rl&sig=abc%3D"));
+        assert_eq!(
+            client.generate_sas_url("dir/a b.txt", 3600).unwrap(),
+            "http://127.0.0.1:10000/devstoreaccount1/box/dir/a%20b.txt?sv=2021-08-06&sp=rl&sig=abc%3D"
+        );
+    }
+}
@@ -0,0 +1,1164 @@
+/// FTP / FTPS 存储客户端
+///
+/// 连接地址决定传输加密方式：
+///
+/// - `ftp://host:21/path`：明文 FTP
+/// - `ftpes://host:21/path`：显式 TLS，连接后发送 `AUTH TLS` 升级
+/// - `ftps://host:990/path`：隐式 TLS，建立连接即进行 TLS 握手
+///
+/// `extra_options.tls`（`none` / `explicit` / `implicit`）可覆盖地址中的方式，
+/// `extra_options.accept_invalid_certs` 为 `true` 时不校验服务器证书（自签名证书）。
+/// 未提供用户名时使用匿名登录。
+///
+/// 数据连接始终使用被动模式（优先 EPSV，不支持时使用 PASV），并且总是连接控制连接的服务器地址，
+/// 忽略 PASV 响应中可能是内网的地址。目录列表优先使用 MLSD，服务器不支持时回退到 LIST 并解析 Unix / DOS 格式。
+///
+/// 一个控制连接同一时间只能进行一次传输，客户端维护空闲连接池，并发请求各自使用独立的控制连接。
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Utc};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+use url::Url;
+
+use super::traits::{
+    StorageClient, StorageRequest, StorageResponse, StorageError, DirectoryResult, StorageFile,
+    ListOptions, ConnectionConfig, StorageCapabilities, ProgressCallback,
+};
+use crate::utils::chunk_size;
+
+/// 建立 TCP 连接的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// 等待服务器响应或数据的超时
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+/// 空闲连接池的最大连接数
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// 传输加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TlsMode {
+    None,
+    /// 显式 TLS（`AUTH TLS`）
+    Explicit,
+    /// 隐式 TLS
+    Implicit,
+}
+
+/// 明文或 TLS 连接
+enum FtpStream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for FtpStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            FtpStream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            FtpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for FtpStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            FtpStream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            FtpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            FtpStream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            FtpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            FtpStream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            FtpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+/// 服务器响应
+#[derive(Debug)]
+struct FtpReply {
+    code: u16,
+    message: String,
+}
+
+impl FtpReply {
+    fn into_error(self, context: &str) -> StorageError {
+        StorageError::RequestFailed(format!("{} failed: {} {}", context, self.code, self.message))
+    }
+}
+
+/// 连接参数，建立新的控制连接时使用
+#[derive(Clone)]
+struct FtpSettings {
+    host: String,
+    port: u16,
+    tls_mode: TlsMode,
+    username: String,
+    password: String,
+    accept_invalid_certs: bool,
+    /// 远程根目录，为空时在连接后使用登录目录
+    root_path: String,
+}
+
+/// 不校验服务器证书，仅在 `accept_invalid_certs` 时使用
+struct NoCertificateVerification;
+
+impl rustls::client::ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// 一个已登录的控制连接
+struct FtpConnection {
+    control: BufReader<FtpStream>,
+    /// 服务器地址，被动模式的数据连接总是连接这个地址
+    peer: IpAddr,
+    /// 数据连接是否需要 TLS（`PROT P`）
+    protected: bool,
+    /// 服务器不支持 EPSV 时改用 PASV
+    use_pasv: bool,
+}
+
+impl FtpConnection {
+    /// 读取一个（可能跨多行的）响应
+    async fn read_reply(&mut self) -> Result<FtpReply, StorageError> {
+        let mut line = String::new();
+        let mut message = String::new();
+        let mut expected_end: Option<String> = None;
+
+        loop {
+            line.clear();
+            let read = tokio::time::timeout(IO_TIMEOUT, self.control.read_line(&mut line)).await
+                .map_err(|_| StorageError::NetworkError("FTP server response timed out".to_string()))?
+                .map_err(|e| StorageError::NetworkError(format!("Failed to read FTP response: {}", e)))?;
+            if read == 0 {
+                return Err(StorageError::NetworkError("FTP server closed the connection".to_string()));
+            }
+
+            let trimmed = line.trim_end_matches(['\r', '\n']);
+            if !message.is_empty() {
+                message.push('\n');
+            }
+            message.push_str(trimmed);
+
+            match &expected_end {
+                // 多行响应以 "ddd " 开头的行结束
+                Some(end) => {
+                    if trimmed.starts_with(end.as_str()) {
+                        break;
+                    }
+                }
+                None => {
+                    if trimmed.len() >= 4 && trimmed.as_bytes()[3] == b'-' {
+                        expected_end = Some(format!("{} ", &trimmed[..3]));
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let code = message.get(..3)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| StorageError::NetworkError(format!("Invalid FTP response: {}", message)))?;
+        let message = message.get(4..).unwrap_or_default().to_string();
+
+        Ok(FtpReply { code, message })
+    }
+
+    /// 发送命令并读取响应
+    async fn command(&mut self, command: &str) -> Result<FtpReply, StorageError> {
+        if command.contains(['\r', '\n']) {
+            return Err(StorageError::RequestFailed("Invalid characters in FTP command".to_string()));
+        }
+
+        if command.starts_with("PASS ") {
+            log::debug!("FTP命令: PASS ****");
+        } else {
+            log::debug!("FTP命令: {}", command);
+        }
+
+        let stream = self.control.get_mut();
+        tokio::time::timeout(IO_TIMEOUT, async {
+            stream.write_all(format!("{}\r\n", command).as_bytes()).await?;
+            stream.flush().await
+        }).await
+            .map_err(|_| StorageError::NetworkError("FTP command timed out".to_string()))?
+            .map_err(|e| StorageError::NetworkError(format!("Failed to send FTP command: {}", e)))?;
+
+        self.read_reply().await
+    }
+
+    /// 发送命令，响应码不在 `expected` 中时返回错误
+    async fn expect(&mut self, command: &str, expected: &[u16]) -> Result<FtpReply, StorageError> {
+        let reply = self.command(command).await?;
+        if expected.contains(&reply.code) {
+            Ok(reply)
+        } else {
+            let context = command.split(' ').next().unwrap_or(command).to_string();
+            Err(reply.into_error(&context))
+        }
+    }
+
+    /// 以被动模式建立数据连接（TCP 部分）
+    async fn open_passive(&mut self) -> Result<TcpStream, StorageError> {
+        let mut port = None;
+
+        if !self.use_pasv {
+            let reply = self.command("EPSV").await?;
+            if reply.code == 229 {
+                port = FtpClient::parse_epsv_port(&reply.message);
+            } else {
+                log::debug!("FTP服务器不支持EPSV，改用PASV: {} {}", reply.code, reply.message);
+                self.use_pasv = true;
+            }
+        }
+
+        if port.is_none() {
+            let reply = self.expect("PASV", &[227]).await?;
+            port = FtpClient::parse_pasv_port(&reply.message);
+        }
+
+        let port = port.ok_or_else(|| StorageError::RequestFailed("Invalid passive mode response".to_string()))?;
+        tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((self.peer, port))).await
+            .map_err(|_| StorageError::NetworkError("FTP data connection timed out".to_string()))?
+            .map_err(|e| StorageError::NetworkError(format!("Failed to open FTP data connection: {}", e)))
+    }
+
+    /// 开始一次传输：建立数据连接并发送传输命令
+    /// TLS 握手在服务器确认（125/150）之后进行，服务器此时才开始在数据连接上握手
+    async fn start_transfer(
+        &mut self,
+        command: &str,
+        connector: Option<&TlsConnector>,
+        host: &str,
+    ) -> Result<FtpStream, StorageError> {
+        let tcp = self.open_passive().await?;
+
+        let reply = self.command(command).await?;
+        if reply.code != 125 && reply.code != 150 {
+            let context = command.split(' ').next().unwrap_or(command).to_string();
+            return Err(reply.into_error(&context));
+        }
+
+        match connector.filter(|_| self.protected) {
+            Some(connector) => {
+                let server_name = FtpClient::server_name(host)?;
+                let stream = tokio::time::timeout(IO_TIMEOUT, connector.connect(server_name, tcp)).await
+                    .map_err(|_| StorageError::NetworkError("FTP data TLS handshake timed out".to_string()))?
+                    .map_err(|e| StorageError::NetworkError(format!("FTP data TLS handshake failed: {}", e)))?;
+                Ok(FtpStream::Tls(Box::new(stream)))
+            }
+            None => Ok(FtpStream::Plain(tcp)),
+        }
+    }
+
+    /// 读取传输完成的响应；`aborted` 为 true 时表示客户端提前关闭了数据连接，接受 4xx 响应
+    async fn finish_transfer(&mut self, aborted: bool) -> Result<(), StorageError> {
+        let reply = self.read_reply().await?;
+        match reply.code {
+            226 | 250 => Ok(()),
+            400..=499 if aborted => Ok(()),
+            _ => Err(reply.into_error("Transfer")),
+        }
+    }
+}
+
+/// 关闭数据连接；TLS 连接先发送 close_notify，部分服务器据此判断传输是否完整
+async fn close_data(mut stream: FtpStream) {
+    let _ = tokio::time::timeout(IO_TIMEOUT, stream.shutdown()).await;
+}
+
+/// 从数据连接读取，超时或出错时返回错误
+/// 对方未发送 TLS close_notify 就关闭连接时按正常结束处理
+async fn read_data(stream: &mut FtpStream, buf: &mut [u8]) -> Result<usize, StorageError> {
+    match tokio::time::timeout(IO_TIMEOUT, stream.read(buf)).await {
+        Ok(Ok(n)) => Ok(n),
+        Ok(Err(e)) if e.kind() == ErrorKind::UnexpectedEof => Ok(0),
+        Ok(Err(e)) => Err(StorageError::NetworkError(format!("Failed to read FTP data: {}", e))),
+        Err(_) => Err(StorageError::NetworkError("FTP data transfer timed out".to_string())),
+    }
+}
+
+pub struct FtpClient {
+    connected: AtomicBool,
+    settings: FtpSettings,
+    tls_connector: Option<TlsConnector>,
+    idle_connections: Mutex<Vec<FtpConnection>>,
+    /// 服务器不支持 MLSD 时改用 LIST
+    mlsd_supported: AtomicBool,
+}
+
+impl FtpClient {
+    pub fn new(config: ConnectionConfig) -> Result<Self, StorageError> {
+        let settings = Self::parse_settings(&config)?;
+        let tls_connector = (settings.tls_mode != TlsMode::None)
+            .then(|| Self::tls_connector(settings.accept_invalid_certs));
+
+        Ok(Self {
+            connected: AtomicBool::new(false),
+            settings,
+            tls_connector,
+            idle_connections: Mutex::new(Vec::new()),
+            mlsd_supported: AtomicBool::new(true),
+        })
+    }
+
+    fn extra_option<'a>(config: &'a ConnectionConfig, key: &str) -> Option<&'a str> {
+        config.extra_options.as_ref()
+            .and_then(|options| options.get(key))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 解析连接地址和选项
+    fn parse_settings(config: &ConnectionConfig) -> Result<FtpSettings, StorageError> {
+        let raw = config.url.as_deref().unwrap_or_default().trim();
+        if raw.is_empty() {
+            return Err(StorageError::InvalidConfig("FTP host is required".to_string()));
+        }
+
+        let with_scheme = if raw.contains("://") { raw.to_string() } else { format!("ftp://{}", raw) };
+        let url = Url::parse(&with_scheme)
+            .map_err(|e| StorageError::InvalidConfig(format!("Invalid FTP address: {}", e)))?;
+
+        let scheme_mode = match url.scheme() {
+            "ftp" => TlsMode::None,
+            "ftpes" => TlsMode::Explicit,
+            "ftps" => TlsMode::Implicit,
+            other => return Err(StorageError::InvalidConfig(format!("Unsupported FTP scheme: {}", other))),
+        };
+        let tls_mode = match Self::extra_option(config, "tls") {
+            None => scheme_mode,
+            Some("none") => TlsMode::None,
+            Some("explicit") => TlsMode::Explicit,
+            Some("implicit") => TlsMode::Implicit,
+            Some(other) => return Err(StorageError::InvalidConfig(format!("Unknown FTP TLS mode: {}", other))),
+        };
+
+        let host = url.host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| StorageError::InvalidConfig("FTP host is required".to_string()))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = url.port().unwrap_or(if tls_mode == TlsMode::Implicit { 990 } else { 21 });
+
+        let decode = |value: &str| urlencoding::decode(value).map(|v| v.into_owned()).unwrap_or_else(|_| value.to_string());
+        let username = config.username.clone()
+            .filter(|u| !u.is_empty())
+            .or_else(|| Some(decode(url.username())).filter(|u| !u.is_empty()))
+            .unwrap_or_else(|| "anonymous".to_string());
+        let password = config.password.clone()
+            .filter(|p| !p.is_empty())
+            .or_else(|| url.password().map(decode))
+            .unwrap_or_else(|| "anonymous@".to_string());
+
+        let accept_invalid_certs = Self::extra_option(config, "accept_invalid_certs") == Some("true");
+        let root_path = decode(url.path()).trim_end_matches('/').to_string();
+
+        Ok(FtpSettings {
+            host,
+            port,
+            tls_mode,
+            username,
+            password,
+            accept_invalid_certs,
+            root_path,
+        })
+    }
+
+    fn tls_connector(accept_invalid_certs: bool) -> TlsConnector {
+        let builder = rustls::ClientConfig::builder().with_safe_defaults();
+        let config = if accept_invalid_certs {
+            builder
+                .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+                .with_no_client_auth()
+        } else {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+                rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    anchor.subject,
+                    anchor.spki,
+                    anchor.name_constraints,
+                )
+            }));
+            builder
+                .with_root_certificates(roots)
+                .with_no_client_auth()
+        };
+
+        TlsConnector::from(Arc::new(config))
+    }
+
+    fn server_name(host: &str) -> Result<rustls::ServerName, StorageError> {
+        rustls::ServerName::try_from(host)
+            .map_err(|e| StorageError::InvalidConfig(format!("Invalid FTP host name: {}", e)))
+    }
+
+    async fn tls_handshake(&self, tcp: TcpStream) -> Result<FtpStream, StorageError> {
+        let connector = self.tls_connector.as_ref()
+            .ok_or_else(|| StorageError::InvalidConfig("FTP TLS is not enabled".to_string()))?;
+        let server_name = Self::server_name(&self.settings.host)?;
+
+        let stream = tokio::time::timeout(IO_TIMEOUT, connector.connect(server_name, tcp)).await
+            .map_err(|_| StorageError::ConnectionFailed("FTP TLS handshake timed out".to_string()))?
+            .map_err(|e| StorageError::ConnectionFailed(format!("FTP TLS handshake failed: {}", e)))?;
+        Ok(FtpStream::Tls(Box::new(stream)))
+    }
+
+    /// 建立新的控制连接并登录
+    async fn open_connection(&self) -> Result<FtpConnection, StorageError> {
+        let settings = &self.settings;
+
+        let tcp = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((settings.host.as_str(), settings.port))).await
+            .map_err(|_| StorageError::ConnectionFailed(format!("Connection to {}:{} timed out", settings.host, settings.port)))?
+            .map_err(|e| StorageError::ConnectionFailed(format!("Failed to connect to {}:{}: {}", settings.host, settings.port, e)))?;
+        let peer = tcp.peer_addr()
+            .map_err(|e| StorageError::ConnectionFailed(format!("Failed to get FTP server address: {}", e)))?
+            .ip();
+
+        let stream = if settings.tls_mode == TlsMode::Implicit {
+            self.tls_handshake(tcp).await?
+        } else {
+            FtpStream::Plain(tcp)
+        };
+
+        let mut connection = FtpConnection {
+            control: BufReader::new(stream),
+            peer,
+            protected: false,
+            use_pasv: false,
+        };
+
+        let greeting = connection.read_reply().await?;
+        if greeting.code != 220 {
+            return Err(StorageError::ConnectionFailed(format!("FTP server refused connection: {} {}", greeting.code, greeting.message)));
+        }
+
+        if settings.tls_mode == TlsMode::Explicit {
+            connection.expect("AUTH TLS", &[234]).await
+                .map_err(|e| StorageError::ConnectionFailed(format!("FTP server does not support TLS: {}", e)))?;
+            let tcp = match connection.control.into_inner() {
+                FtpStream::Plain(tcp) => tcp,
+                FtpStream::Tls(_) => unreachable!("control connection is upgraded only once"),
+            };
+            connection.control = BufReader::new(self.tls_handshake(tcp).await?);
+        }
+
+        let reply = connection.command(&format!("USER {}", settings.username)).await?;
+        let reply = match reply.code {
+            331 | 332 => connection.command(&format!("PASS {}", settings.password)).await?,
+            _ => reply,
+        };
+        if reply.code != 230 && reply.code != 202 {
+            return Err(StorageError::ConnectionFailed(format!("FTP login failed: {} {}", reply.code, reply.message)));
+        }
+
+        if settings.tls_mode != TlsMode::None {
+            connection.expect("PBSZ 0", &[200]).await?;
+            connection.expect("PROT P", &[200]).await?;
+            connection.protected = true;
+        }
+
+        connection.expect("TYPE I", &[200]).await?;
+        // 部分服务器默认不使用 UTF-8 文件名，不支持时忽略
+        let _ = connection.command("OPTS UTF8 ON").await?;
+
+        Ok(connection)
+    }
+
+    /// 取出一个可用的控制连接，空闲连接失效时重新建立
+    async fn acquire(&self) -> Result<FtpConnection, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        loop {
+            let idle = self.idle_connections.lock().unwrap_or_else(|e| e.into_inner()).pop();
+            match idle {
+                Some(mut connection) => {
+                    if matches!(connection.command("NOOP").await, Ok(reply) if reply.code == 200) {
+                        return Ok(connection);
+                    }
+                    log::debug!("FTP空闲连接已失效，丢弃");
+                }
+                None => return self.open_connection().await,
+            }
+        }
+    }
+
+    /// 操作成功后归还控制连接
+    fn release(&self, connection: FtpConnection) {
+        if !self.connected.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut idle = self.idle_connections.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(connection);
+        }
+    }
+
+    /// 解析 EPSV 响应 `Entering Extended Passive Mode (|||port|)`
+    fn parse_epsv_port(message: &str) -> Option<u16> {
+        let start = message.find('(')?;
+        let end = message[start..].find(')')? + start;
+        let inner = &message[start + 1..end];
+        let delimiter = inner.chars().next()?;
+        inner.split(delimiter).nth(3)?.parse().ok()
+    }
+
+    /// 解析 PASV 响应 `Entering Passive Mode (h1,h2,h3,h4,p1,p2)`，只取端口
+    fn parse_pasv_port(message: &str) -> Option<u16> {
+        let start = message.find(|c: char| c.is_ascii_digit())?;
+        let numbers: Vec<u16> = message[start..]
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .take(6)
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        if numbers.len() != 6 || numbers[4] > 255 || numbers[5] > 255 {
+            return None;
+        }
+        Some(numbers[4] * 256 + numbers[5])
+    }
+
+    /// 把请求路径转换为远程绝对路径
+    /// 支持相对根目录的路径和 `ftp://host/path` 形式的完整地址
+    fn remote_path(&self, path: &str) -> String {
+        if let Some((_, rest)) = path.split_once("://") {
+            let absolute = rest.find('/').map(|i| &rest[i..]).unwrap_or("/");
+            return urlencoding::decode(absolute).map(|p| p.into_owned()).unwrap_or_else(|_| absolute.to_string());
+        }
+
+        let relative = path.trim_start_matches('/');
+        let root = self.settings.root_path.trim_end_matches('/');
+        if relative.is_empty() {
+            if root.is_empty() { "/".to_string() } else { root.to_string() }
+        } else {
+            format!("{}/{}", root, relative)
+        }
+    }
+
+    /// 解析 `YYYYMMDDHHMMSS[.sss]` 格式的时间（MLSD 的 modify、MDTM 响应）
+    fn parse_ftp_timestamp(value: &str) -> Option<String> {
+        let value = value.get(..14)?;
+        NaiveDateTime::parse_from_str(value, "%Y%m%d%H%M%S")
+            .ok()
+            .map(|dt| dt.and_utc().to_rfc3339())
+    }
+
+    /// 解析 MLSD / MLST 的一行：`type=file;size=1024;modify=20240102030405; name`
+    fn parse_mlsd_line(line: &str) -> Option<StorageFile> {
+        let (facts, name) = line.split_once(' ')?;
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut file_type = None;
+        let mut size = 0u64;
+        let mut lastmod = None;
+        for fact in facts.split(';') {
+            let Some((key, value)) = fact.split_once('=') else { continue };
+            match key.to_ascii_lowercase().as_str() {
+                "type" => file_type = Some(value.to_ascii_lowercase()),
+                "size" => size = value.parse().unwrap_or(0),
+                "modify" => lastmod = Self::parse_ftp_timestamp(value),
+                _ => {}
+            }
+        }
+
+        // 跳过当前目录和上级目录
+        let is_directory = match file_type.as_deref() {
+            Some("cdir") | Some("pdir") => return None,
+            Some("dir") => true,
+            Some(other) => other.ends_with("=dir"),
+            None => false,
+        };
+
+        let basename = name.rsplit('/').next().unwrap_or(name).to_string();
+        Some(StorageFile {
+            filename: basename.clone(),
+            basename,
+            lastmod: lastmod.unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string()),
+            size: if is_directory { 0 } else { size },
+            file_type: if is_directory { "directory" } else { "file" }.to_string(),
+            mime: None,
+            etag: None,
+        })
+    }
+
+    /// 解析 LIST 的一行，支持 Unix `ls -l` 格式和 DOS/IIS 格式
+    fn parse_list_line(line: &str) -> Option<StorageFile> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with("total ") {
+            return None;
+        }
+
+        // 按空白切分并记录每段的起始位置，文件名取最后一段起始处到行尾（保留其中的空格）
+        let mut fields = Vec::new();
+        let mut field_start = None;
+        for (i, c) in line.char_indices() {
+            match (c.is_whitespace(), field_start) {
+                (false, None) => field_start = Some(i),
+                (true, Some(start)) => {
+                    fields.push((start, &line[start..i]));
+                    field_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = field_start {
+            fields.push((start, &line[start..]));
+        }
+
+        let first = fields.first()?.1;
+        if first.contains('-') && first.as_bytes().first().is_some_and(u8::is_ascii_digit) {
+            return Self::parse_dos_list_line(line, &fields);
+        }
+
+        // Unix 格式：权限 链接数 所有者 [组] 大小 月 日 时间或年份 文件名
+        let is_directory = first.starts_with('d');
+        let is_symlink = first.starts_with('l');
+        let month_index = (3..fields.len().saturating_sub(3))
+            .find(|&i| Self::month_number(fields[i].1).is_some() && fields[i + 1].1.parse::<u32>().is_ok())?;
+        let size = fields[month_index - 1].1.parse().unwrap_or(0);
+        let (name_start, _) = *fields.get(month_index + 3)?;
+        let mut name = &line[name_start..];
+        if is_symlink {
+            name = name.split(" -> ").next().unwrap_or(name);
+        }
+        if name == "." || name == ".." {
+            return None;
+        }
+
+        let lastmod = Self::parse_unix_list_time(fields[month_index].1, fields[month_index + 1].1, fields[month_index + 2].1);
+        Some(StorageFile {
+            filename: name.to_string(),
+            basename: name.to_string(),
+            lastmod: lastmod.unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string()),
+            size: if is_directory { 0 } else { size },
+            file_type: if is_directory { "directory" } else { "file" }.to_string(),
+            mime: None,
+            etag: None,
+        })
+    }
+
+    /// DOS/IIS 格式：`01-02-24  03:04PM  <DIR>  name` 或 `01-02-24  03:04PM  1234 name`
+    fn parse_dos_list_line(line: &str, fields: &[(usize, &str)]) -> Option<StorageFile> {
+        let (name_start, _) = *fields.get(3)?;
+        let name = &line[name_start..];
+        let is_directory = fields[2].1.eq_ignore_ascii_case("<DIR>");
+        let size = if is_directory { 0 } else { fields[2].1.parse().ok()? };
+
+        let lastmod = ["%m-%d-%y %I:%M%p", "%m-%d-%Y %I:%M%p", "%m-%d-%y %H:%M", "%m-%d-%Y %H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(&format!("{} {}", fields[0].1, fields[1].1), format).ok())
+            .map(|dt| dt.and_utc().to_rfc3339());
+
+        Some(StorageFile {
+            filename: name.to_string(),
+            basename: name.to_string(),
+            lastmod: lastmod.unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string()),
+            size,
+            file_type: if is_directory { "directory" } else { "file" }.to_string(),
+            mime: None,
+            etag: None,
+        })
+    }
+
+    fn month_number(name: &str) -> Option<u32> {
+        const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+        MONTHS.iter()
+            .position(|month| name.eq_ignore_ascii_case(month))
+            .map(|index| index as u32 + 1)
+    }
+
+    /// Unix 列表时间：`Jan 2 12:00`（近半年内，省略年份）或 `Jan 2 2023`
+    fn parse_unix_list_time(month: &str, day: &str, time_or_year: &str) -> Option<String> {
+        let month = Self::month_number(month)?;
+        let day: u32 = day.parse().ok()?;
+
+        let datetime = match time_or_year.split_once(':') {
+            Some((hour, minute)) => {
+                let now = Utc::now().naive_utc();
+                let hour: u32 = hour.parse().ok()?;
+                let minute: u32 = minute.parse().ok()?;
+                let this_year = NaiveDate::from_ymd_opt(now.year(), month, day)?.and_hms_opt(hour, minute, 0)?;
+                // 省略年份的时间不会晚于当前时间太多，否则是去年的
+                if this_year > now + chrono::Duration::days(1) {
+                    NaiveDate::from_ymd_opt(now.year() - 1, month, day)?.and_hms_opt(hour, minute, 0)?
+                } else {
+                    this_year
+                }
+            }
+            None => NaiveDate::from_ymd_opt(time_or_year.parse().ok()?, month, day)?.and_hms_opt(0, 0, 0)?,
+        };
+
+        Some(datetime.and_utc().to_rfc3339())
+    }
+
+    /// 在数据连接上执行命令并读取全部数据
+    async fn transfer_to_end(&self, connection: &mut FtpConnection, command: &str) -> Result<Vec<u8>, StorageError> {
+        let mut stream = connection.start_transfer(command, self.tls_connector.as_ref(), &self.settings.host).await?;
+
+        let mut data = Vec::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let n = read_data(&mut stream, &mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buffer[..n]);
+        }
+        close_data(stream).await;
+
+        connection.finish_transfer(false).await?;
+        Ok(data)
+    }
+
+    async fn read_directory(&self, path: &str) -> Result<DirectoryResult, StorageError> {
+        let dir_path = self.remote_path(path);
+        let mut connection = self.acquire().await?;
+
+        let mut files = None;
+        if self.mlsd_supported.load(Ordering::Relaxed) {
+            match self.transfer_to_end(&mut connection, &format!("MLSD {}", dir_path)).await {
+                Ok(data) => {
+                    files = Some(String::from_utf8_lossy(&data)
+                        .lines()
+                        .filter_map(Self::parse_mlsd_line)
+                        .collect::<Vec<_>>());
+                }
+                // 500/502：命令不被识别或未实现
+                Err(StorageError::RequestFailed(message)) if message.starts_with("MLSD failed: 50") => {
+                    log::debug!("FTP服务器不支持MLSD，改用LIST");
+                    self.mlsd_supported.store(false, Ordering::Relaxed);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut files = match files {
+            Some(files) => files,
+            None => {
+                let data = self.transfer_to_end(&mut connection, &format!("LIST {}", dir_path)).await?;
+                String::from_utf8_lossy(&data)
+                    .lines()
+                    .filter_map(Self::parse_list_line)
+                    .collect()
+            }
+        };
+        self.release(connection);
+
+        // 目录优先，然后按名称排序
+        files.sort_by(|a, b| {
+            match (a.file_type.as_str(), b.file_type.as_str()) {
+                ("directory", "file") => std::cmp::Ordering::Less,
+                ("file", "directory") => std::cmp::Ordering::Greater,
+                _ => a.filename.cmp(&b.filename),
+            }
+        });
+
+        Ok(DirectoryResult {
+            files,
+            has_more: false,
+            next_marker: None,
+            total_count: None,
+            path: path.to_string(),
+        })
+    }
+
+    /// 获取文件大小：优先使用 SIZE，服务器不支持时使用 MLST
+    async fn file_size(&self, connection: &mut FtpConnection, remote_path: &str) -> Result<u64, StorageError> {
+        let reply = connection.command(&format!("SIZE {}", remote_path)).await?;
+        if reply.code == 213 {
+            return reply.message.trim().parse()
+                .map_err(|_| StorageError::RequestFailed(format!("Invalid SIZE response: {}", reply.message)));
+        }
+        if reply.code != 500 && reply.code != 502 {
+            return Err(reply.into_error("SIZE"));
+        }
+
+        let reply = connection.expect(&format!("MLST {}", remote_path), &[250]).await?;
+        let file = reply.message.lines()
+            .skip(1)
+            .find_map(|line| Self::parse_mlsd_line(line.trim_start()))
+            .ok_or_else(|| StorageError::RequestFailed("Invalid MLST response".to_string()))?;
+        if file.file_type == "directory" {
+            return Err(StorageError::RequestFailed("Path is a directory, not a file".to_string()));
+        }
+        Ok(file.size)
+    }
+
+    fn json_response(value: &Value) -> Result<StorageResponse, StorageError> {
+        let body = serde_json::to_string(value)
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to serialize response: {}", e)))?;
+
+        Ok(StorageResponse {
+            status: 200,
+            headers: HashMap::new(),
+            body,
+            metadata: None,
+        })
+    }
+
+    /// 读取文本内容供前端预览，`options` 中的 `start` / `length` 指定读取范围
+    async fn read_text(&self, path: &str, options: Option<&Value>) -> Result<StorageResponse, StorageError> {
+        let file_size = self.get_file_size(path).await?;
+        let start = options.and_then(|opts| opts.get("start")).and_then(Value::as_u64).unwrap_or(0).min(file_size);
+        let length = options.and_then(|opts| opts.get("length")).and_then(Value::as_u64)
+            .unwrap_or(file_size - start)
+            .min(file_size - start);
+
+        let data = self.read_file_range(path, start, length).await?;
+        Self::json_response(&serde_json::json!({
+            "content": String::from_utf8_lossy(&data),
+            "size": file_size,
+            "encoding": "utf-8"
+        }))
+    }
+}
+
+#[async_trait]
+impl StorageClient for FtpClient {
+    async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        self.settings = Self::parse_settings(config)?;
+        self.tls_connector = (self.settings.tls_mode != TlsMode::None)
+            .then(|| Self::tls_connector(self.settings.accept_invalid_certs));
+        self.idle_connections.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        self.mlsd_supported.store(true, Ordering::Relaxed);
+
+        let mut connection = self.open_connection().await?;
+
+        // 未指定根目录时使用登录后的当前目录
+        if self.settings.root_path.is_empty() {
+            let reply = connection.expect("PWD", &[257]).await?;
+            self.settings.root_path = reply.message.split('"').nth(1)
+                .map(|path| path.replace("\"\"", "\"").trim_end_matches('/').to_string())
+                .unwrap_or_default();
+        }
+        let root = self.remote_path("");
+        connection.expect(&format!("CWD {}", root), &[250])
+            .await
+            .map_err(|e| StorageError::ConnectionFailed(format!("FTP root path is not accessible: {}", e)))?;
+
+        log::debug!("FTP连接成功: {}@{}:{} (根目录 {}, TLS {:?})",
+            self.settings.username, self.settings.host, self.settings.port, root, self.settings.tls_mode);
+
+        self.connected.store(true, Ordering::Relaxed);
+        self.release(connection);
+        Ok(())
+    }
+
+    async fn disconnect(&self) {
+        self.connected.store(false, Ordering::Relaxed);
+
+        let connections: Vec<FtpConnection> = self.idle_connections.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+            .collect();
+        for mut connection in connections {
+            let _ = connection.command("QUIT").await;
+        }
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    async fn list_directory(&self, path: &str, _options: Option<&ListOptions>) -> Result<DirectoryResult, StorageError> {
+        self.read_directory(path).await
+    }
+
+    async fn request(&self, request: &StorageRequest) -> Result<StorageResponse, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        match request.method.as_str() {
+            "CHECK_ACCESS" => {
+                let mut connection = self.acquire().await?;
+                connection.expect(&format!("CWD {}", self.remote_path("")), &[250]).await?;
+                self.release(connection);
+                Ok(StorageResponse {
+                    status: 200,
+                    headers: HashMap::new(),
+                    body: "OK".to_string(),
+                    metadata: None,
+                })
+            }
+            "LIST_DIRECTORY" => {
+                let result = self.read_directory(&request.url).await?;
+                Self::json_response(&serde_json::to_value(&result)
+                    .map_err(|e| StorageError::RequestFailed(format!("Failed to serialize response: {}", e)))?)
+            }
+            "READ_FILE" => self.read_text(&request.url, request.options.as_ref()).await,
+            "GET_FILE_SIZE" => {
+                let size = self.get_file_size(&request.url).await?;
+                Self::json_response(&serde_json::json!({ "size": size }))
+            }
+            _ => Err(StorageError::RequestFailed(
+                format!("Unsupported method: {}", request.method)
+            )),
+        }
+    }
+
+    async fn request_binary(&self, request: &StorageRequest) -> Result<Vec<u8>, StorageError> {
+        match request.method.as_str() {
+            "READ_FILE_BINARY" => self.read_full_file(&request.url).await,
+            _ => Err(StorageError::RequestFailed(
+                format!("Unsupported binary method: {}", request.method)
+            )),
+        }
+    }
+
+    async fn read_file_range(&self, path: &str, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        self.read_file_range_with_progress(path, start, length, None, None).await
+    }
+
+    /// 按偏移读取：`REST` 设置起始位置后 `RETR`，读够请求的长度后关闭数据连接
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        log::debug!("FTP读取文件范围: path={}, start={}, length={}", path, start, length);
+
+        let remote_path = self.remote_path(path);
+        let mut connection = self.acquire().await?;
+        if start > 0 {
+            connection.expect(&format!("REST {}", start), &[350]).await?;
+        }
+        let mut stream = connection
+            .start_transfer(&format!("RETR {}", remote_path), self.tls_connector.as_ref(), &self.settings.host)
+            .await?;
+
+        let chunk_size = chunk_size::calculate_optimal_chunk_size(length);
+        let mut result = Vec::with_capacity(length as usize);
+        let mut chunk = vec![0u8; chunk_size];
+        let mut reached_end = false;
+
+        while (result.len() as u64) < length {
+            if let Some(ref mut cancel_rx) = cancel_rx {
+                if cancel_rx.try_recv().is_ok() {
+                    // 传输未完成，控制连接状态不确定，直接丢弃
+                    return Err(StorageError::RequestFailed("download.cancelled".to_string()));
+                }
+            }
+
+            let wanted = std::cmp::min(length - result.len() as u64, chunk_size as u64) as usize;
+            let n = read_data(&mut stream, &mut chunk[..wanted]).await?;
+            if n == 0 {
+                reached_end = true;
+                break;
+            }
+            result.extend_from_slice(&chunk[..n]);
+
+            if let Some(ref callback) = progress_callback {
+                callback(result.len() as u64, length);
+            }
+        }
+
+        // 提前关闭数据连接，服务器会以 426/451 结束这次传输
+        close_data(stream).await;
+        connection.finish_transfer(!reached_end).await?;
+        self.release(connection);
+
+        log::debug!("FTP实际读取到 {} 字节，请求 {} 字节", result.len(), length);
+        Ok(result)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let remote_path = self.remote_path(path);
+        let mut connection = self.acquire().await?;
+        let data = self.transfer_to_end(&mut connection, &format!("RETR {}", remote_path)).await?;
+        self.release(connection);
+        Ok(data)
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        let remote_path = self.remote_path(path);
+        let mut connection = self.acquire().await?;
+        let size = self.file_size(&mut connection, &remote_path).await?;
+        self.release(connection);
+        Ok(size)
+    }
+
+    /// FTP 没有 ETag，使用修改时间（MDTM）和大小组合作为版本标识
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        let remote_path = self.remote_path(path);
+        let mut connection = self.acquire().await?;
+        let size = self.file_size(&mut connection, &remote_path).await?;
+        let reply = connection.command(&format!("MDTM {}", remote_path)).await?;
+        self.release(connection);
+
+        if reply.code != 213 {
+            return Ok(StorageFile::file_info(path, size, String::new(), None));
+        }
+        let modified = reply.message.trim();
+        Ok(StorageFile::file_info(
+            path,
+            size,
+            Self::parse_ftp_timestamp(modified).unwrap_or_default(),
+            Some(format!("{}-{:x}", modified, size)),
+        ))
+    }
+
+    fn get_download_url(&self, path: &str) -> Result<String, StorageError> {
+        if path.contains("://") {
+            return Ok(path.to_string());
+        }
+
+        let scheme = match self.settings.tls_mode {
+            TlsMode::None => "ftp",
+            TlsMode::Explicit => "ftpes",
+            TlsMode::Implicit => "ftps",
+        };
+        let encoded_path: Vec<String> = self.remote_path(path).split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect();
+        let host = if self.settings.host.contains(':') { format!("[{}]", self.settings.host) } else { self.settings.host.clone() };
+
+        Ok(format!("{}://{}@{}:{}{}", scheme, urlencoding::encode(&self.settings.username), host, self.settings.port, encoded_path.join("/")))
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            supports_directories: true,
+            supports_metadata: true,
+            supports_streaming: true,
+            supports_range_requests: true,
+            supports_multipart_upload: false,
+            supports_encryption: self.settings.tls_mode != TlsMode::None,
+            max_file_size: None,
+            supported_methods: vec![
+                "READ_FILE".to_string(),
+                "READ_FILE_BINARY".to_string(),
+                "LIST_DIRECTORY".to_string(),
+                "GET_FILE_SIZE".to_string(),
+                "CHECK_ACCESS".to_string(),
+            ],
+        }
+    }
+
+    fn protocol(&self) -> &str {
+        "ftp"
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        Self::parse_settings(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unix_list_lines() {
+        let file = FtpClient::parse_list_line("-rw-r--r--    1 ftp      ftp        123456 Mar 14  2023 report.csv").unwrap();
+        assert_eq!((file.filename.as_str(), file.file_type.as_str(), file.size), ("report.csv", "file", 123456));
+        assert_eq!(file.lastmod, "2023-03-14T00:00:00+00:00");
+
+        let dir = FtpClient::parse_list_line("drwxr-xr-x    2 ftp      ftp          4096 Jan  2 2024 logs").unwrap();
+        assert_eq!((dir.filename.as_str(), dir.file_type.as_str(), dir.size), ("logs", "directory", 0));
+
+        // 没有组字段、文件名带空格
+        let file = FtpClient::parse_list_line("-rw-r--r-- 1 owner 42 Dec 31 2022 my  data file.txt").unwrap();
+        assert_eq!((file.filename.as_str(), file.size), ("my  data file.txt", 42));
+
+        assert!(FtpClient::parse_list_line("total 12").is_none());
+        assert!(FtpClient::parse_list_line("drwxr-xr-x 2 ftp ftp 4096 Jan 2 2024 .").is_none());
+        assert!(FtpClient::parse_list_line("drwxr-xr-x 2 ftp ftp 4096 Jan 2 2024 ..").is_none());
+    }
+
+    #[test]
+    fn parse_unix_symlink_strips_target() {
+        let link = FtpClient::parse_list_line("lrwxrwxrwx    1 ftp      ftp            11 Feb  5  2021 latest data -> releases/v2").unwrap();
+        assert_eq!((link.filename.as_str(), link.file_type.as_str()), ("latest data", "file"));
+    }
+
+    #[test]
+    fn parse_dos_list_lines() {
+        let dir = FtpClient::parse_list_line("01-02-24  03:04PM       <DIR>          Program Files").unwrap();
+        assert_eq!((dir.filename.as_str(), dir.file_type.as_str(), dir.size), ("Program Files", "directory", 0));
+        assert_eq!(dir.lastmod, "2024-01-02T15:04:00+00:00");
+
+        let file = FtpClient::parse_list_line("12-31-2023  11:59          1048576 backup 2023.zip").unwrap();
+        assert_eq!((file.filename.as_str(), file.file_type.as_str(), file.size), ("backup 2023.zip", "file", 1048576));
+        assert_eq!(file.lastmod, "2023-12-31T11:59:00+00:00");
+    }
+
+    #[test]
+    fn parse_mlsd_lines() {
+        let file = FtpClient::parse_mlsd_line("type=file;size=2048;modify=20240102030405.123;perm=r; annual report.pdf").unwrap();
+        assert_eq!((file.filename.as_str(), file.file_type.as_str(), file.size), ("annual report.pdf", "file", 2048));
+        assert_eq!(file.lastmod, "2024-01-02T03:04:05+00:00");
+
+        let dir = FtpClient::parse_mlsd_line("Type=dir;Modify=20231111111111; data").unwrap();
+        assert_eq!((dir.filename.as_str(), dir.file_type.as_str(), dir.size), ("data", "directory", 0));
+
+        // 部分服务器对指向目录的符号链接返回 `OS.unix=dir` 之类的扩展类型
+        let link = FtpClient::parse_mlsd_line("type=OS.unix=dir;size=0; linked").unwrap();
+        assert_eq!(link.file_type, "directory");
+
+        // MLST 返回完整路径，只保留文件名
+        let full = FtpClient::parse_mlsd_line("type=file;size=1; /pub/readme.txt").unwrap();
+        assert_eq!(full.filename, "readme.txt");
+
+        assert!(FtpClient::parse_mlsd_line("type=cdir;modify=20240102030405; .").is_none());
+        assert!(FtpClient::parse_mlsd_line("type=pdir;modify=20240102030405; ..").is_none());
+    }
+
+    #[test]
+    fn parse_passive_ports() {
+        // 传入的是去掉响应码之后的文本
+        assert_eq!(FtpClient::parse_pasv_port("Entering Passive Mode (192,168,1,10,195,80)."), Some(50000));
+        assert_eq!(FtpClient::parse_pasv_port("Entering Passive Mode 10,0,0,1,4,1"), Some(1025));
+        assert_eq!(FtpClient::parse_pasv_port("Entering Passive Mode (10,0,0,1,256,1)"), None);
+        assert_eq!(FtpClient::parse_pasv_port("Entering Passive Mode"), None);
+
+        assert_eq!(FtpClient::parse_epsv_port("Entering Extended Passive Mode (|||50001|)"), Some(50001));
+        assert_eq!(FtpClient::parse_epsv_port("Entering Extended Passive Mode (!!!6446!)"), Some(6446));
+        assert_eq!(FtpClient::parse_epsv_port("Entering Extended Passive Mode (|||port|)"), None);
+        assert_eq!(FtpClient::parse_epsv_port("Entering Extended Passive Mode"), None);
+    }
+}
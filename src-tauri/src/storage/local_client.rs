@@ -428,6 +428,25 @@ impl StorageClient for LocalFileSystemClient {
         Ok(metadata.len())
     }
 
+    /// 本机文件没有 ETag，使用修改时间和大小组合作为版本标识
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let file_path = self.build_safe_path(path)?;
+        let metadata = fs::metadata(&file_path).await
+            .map_err(|e| StorageError::IoError(format!("Failed to get file metadata: {}", e)))?;
+
+        let modified = metadata.modified().ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        let etag = format!("{:x}-{:x}", modified, metadata.len());
+
+        Ok(StorageFile::file_info(path, metadata.len(), Self::format_modification_time(&metadata), Some(etag)))
+    }
+
     fn capabilities(&self) -> StorageCapabilities {
         StorageCapabilities {
             supports_directories: true,
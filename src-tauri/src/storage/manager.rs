@@ -2,10 +2,14 @@ use std::collections::HashMap;
 use tokio::sync::RwLock;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use super::traits::{StorageClient, StorageRequest, StorageResponse, StorageError, ConnectionConfig, StorageCapabilities, DirectoryResult, ListOptions};
+use super::traits::{StorageClient, StorageRequest, StorageResponse, StorageError, ConnectionConfig, StorageCredentials, StorageCapabilities, DirectoryResult, ListOptions};
 use super::webdav_client::WebDAVClient;
 use super::local_client::LocalFileSystemClient;
 use super::oss_client::OSSClient;
+use super::s3_client::S3Client;
+use super::sftp_client::SftpClient;
+use super::ftp_client::FtpClient;
+use super::azblob_client::AzureBlobClient;
 use super::huggingface_client::HuggingFaceClient;
 
 pub struct StorageManager {
@@ -42,6 +46,26 @@ impl StorageManager {
                 client.connect(config).await?;
                 Arc::new(client)
             },
+            "s3" => {
+                let mut client = S3Client::new(config.clone())?;
+                client.connect(config).await?;
+                Arc::new(client)
+            },
+            "sftp" => {
+                let mut client = SftpClient::new(config.clone())?;
+                client.connect(config).await?;
+                Arc::new(client)
+            },
+            "ftp" => {
+                let mut client = FtpClient::new(config.clone())?;
+                client.connect(config).await?;
+                Arc::new(client)
+            },
+            "azblob" => {
+                let mut client = AzureBlobClient::new(config.clone())?;
+                client.connect(config).await?;
+                Arc::new(client)
+            },
             "huggingface" => {
                 let mut client = HuggingFaceClient::new(config.clone())?;
                 client.connect(config).await?;
@@ -140,8 +164,18 @@ impl StorageManager {
 
         client.get_download_url(path)
     }
+
+    /// 替换当前连接的访问凭证，客户端及其连接状态保持不变
+    pub fn update_credentials(&self, credentials: &StorageCredentials) -> Result<(), StorageError> {
+        let client_id = self.active_client.as_ref()
+            .ok_or(StorageError::NotConnected)?;
+        let client = self.clients.get(client_id)
+            .ok_or(StorageError::NotConnected)?;
+
+        client.update_credentials(credentials)
+    }
     pub fn supported_protocols(&self) -> Vec<&str> {
-        vec!["webdav", "local", "oss", "huggingface"]
+        vec!["webdav", "local", "oss", "s3", "sftp", "ftp", "azblob", "huggingface"]
     }
 
     /// 健康检查：验证当前连接是否正常
@@ -3,8 +3,12 @@ pub mod manager;
 pub mod webdav_client;
 pub mod local_client;
 pub mod oss_client;
+pub mod s3_client;
+pub mod sftp_client;
+pub mod ftp_client;
+pub mod azblob_client;
 pub mod huggingface_client;
 
 #[allow(unused_imports)] // 这些类型通过Serde序列化在Tauri命令中使用
-pub use traits::{StorageRequest, ConnectionConfig, ListOptions, DirectoryResult, StorageFile};
+pub use traits::{StorageRequest, ConnectionConfig, StorageCredentials, ListOptions, DirectoryResult, StorageFile};
 pub use manager::get_storage_manager;
@@ -1,10 +1,12 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use std::collections::HashMap;
+use std::sync::RwLock;
 use std::sync::atomic::{AtomicBool, Ordering};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use hmac::{Hmac, Mac};
 use sha1;
+use sha2::{Digest, Sha256};
 use url::Url;
 use urlencoding;
 use quick_xml::Reader;
@@ -14,18 +16,42 @@ use futures_util::StreamExt;
 
 use crate::storage::traits::{
     StorageClient, StorageRequest, StorageResponse, StorageError,
-    ConnectionConfig, StorageCapabilities, DirectoryResult, StorageFile, ListOptions, ProgressCallback
+    ConnectionConfig, StorageCapabilities, DirectoryResult, StorageFile, ListOptions, ProgressCallback,
+    StorageCredentials,
 };
 
+/// V4 签名不对请求体签名
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+/// 临时凭证过期时 OSS 返回的错误码
+const SECURITY_TOKEN_EXPIRED: &str = "<Code>SecurityTokenExpired</Code>";
+
+/// OSS 签名版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignatureVersion {
+    /// HMAC-SHA1 签名（旧版）
+    V1,
+    /// HMAC-SHA256 签名，凭证范围限定到日期和区域
+    V4,
+}
+
+/// 访问凭证；使用 STS 临时凭证时带有安全令牌，连接期间可通过 `update_credentials` 替换
+#[derive(Clone)]
+struct OssCredentials {
+    access_key: String,
+    secret_key: String,
+    security_token: Option<String>,
+}
+
 pub struct OSSClient {
     client: Client,
     config: ConnectionConfig,
     connected: AtomicBool,
     endpoint: String,
-    access_key: String,
-    secret_key: String,
+    credentials: RwLock<OssCredentials>,
     bucket: String,
+    /// V4 签名使用的区域 ID（如 `cn-hangzhou`）
     region: Option<String>,
+    signature_version: SignatureVersion,
 }
 
 impl OSSClient {
@@ -42,20 +68,56 @@ impl OSSClient {
         let bucket = config.bucket.clone()
             .ok_or_else(|| StorageError::InvalidConfig("OSS bucket is required".to_string()))?;
 
-        let region = config.region.clone();
+        let region = Self::resolve_region(config.region.as_deref(), &endpoint);
+        let signature_version = Self::resolve_signature_version(&config, region.as_deref())?;
+        let credentials = RwLock::new(OssCredentials {
+            access_key,
+            secret_key,
+            security_token: config.session_token.clone().filter(|token| !token.is_empty()),
+        });
 
         Ok(Self {
             client: Client::new(),
             config,
             connected: AtomicBool::new(false),
             endpoint,
-            access_key,
-            secret_key,
+            credentials,
             bucket,
             region,
+            signature_version,
         })
     }
 
+    /// V4 签名的区域 ID：优先使用配置（可带 `oss-` 前缀），否则从 `oss-cn-hangzhou.aliyuncs.com` 形式的端点推断
+    fn resolve_region(region: Option<&str>, endpoint: &str) -> Option<String> {
+        if let Some(region) = region.map(str::trim).filter(|region| !region.is_empty()) {
+            return Some(region.strip_prefix("oss-").unwrap_or(region).to_string());
+        }
+
+        let url = Url::parse(endpoint).ok()?;
+        url.host_str()?
+            .split('.')
+            .filter_map(|label| label.strip_prefix("oss-"))
+            .find(|region| !region.starts_with("accelerate"))
+            .map(|region| region.trim_end_matches("-internal").to_string())
+    }
+
+    /// 签名版本由 `extra_options.signature_version`（`v1` / `v4`）指定，未指定时使用 V1，已保存的连接行为不变
+    fn resolve_signature_version(config: &ConnectionConfig, region: Option<&str>) -> Result<SignatureVersion, StorageError> {
+        let version = config.extra_options.as_ref().and_then(|options| options.get("signature_version"));
+        match version.map(|version| version.as_str()) {
+            None | Some("") | Some("v1") => Ok(SignatureVersion::V1),
+            Some("v4") if region.is_some() => Ok(SignatureVersion::V4),
+            Some("v4") => Err(StorageError::InvalidConfig("OSS region is required for V4 signatures".to_string())),
+            Some(other) => Err(StorageError::InvalidConfig(format!("Unknown OSS signature version: {}", other))),
+        }
+    }
+
+    /// 当前凭证的副本
+    fn credentials(&self) -> OssCredentials {
+        self.credentials.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     /// 生成 OSS 签名
     fn generate_signature(
         &self,
@@ -63,6 +125,7 @@ impl OSSClient {
         uri: &str,
         headers: &HashMap<String, String>,
         date: &str,
+        secret_key: &str,
     ) -> String {
         // 构建签名字符串
         let mut string_to_sign = format!("{}\n", method);
@@ -111,7 +174,7 @@ impl OSSClient {
 
         // 计算 HMAC-SHA1 签名
         type HmacSha1 = Hmac<sha1::Sha1>;
-        let mut mac = HmacSha1::new_from_slice(self.secret_key.as_bytes())
+        let mut mac = HmacSha1::new_from_slice(secret_key.as_bytes())
             .expect("HMAC can take key of any size");
         mac.update(string_to_sign.as_bytes());
         let result = mac.finalize();
@@ -121,28 +184,157 @@ impl OSSClient {
         #[cfg(debug_assertions)]
         {
             println!("DEBUG: Generated signature: {}", signature);
-            println!("DEBUG: Secret key length: {}", self.secret_key.len());
+            println!("DEBUG: Secret key length: {}", secret_key.len());
         }
 
         signature
     }
 
+    /// 生成 OSS V4 签名
+    ///
+    /// 规范请求包含 `/bucket/object` 形式的 URI、请求 URL 中的查询参数，以及 `x-oss-*`、`content-type`、`content-md5` 头部。
+    fn generate_signature_v4(
+        &self,
+        method: &str,
+        uri: &str,
+        request_url: &str,
+        headers: &HashMap<String, String>,
+        now: &DateTime<Utc>,
+        secret_key: &str,
+    ) -> String {
+        let canonical_query = Url::parse(request_url)
+            .map(|url| Self::canonical_query(url.query_pairs().map(|(key, value)| (key.into_owned(), value.into_owned())).collect()))
+            .unwrap_or_default();
+
+        let mut signed_headers: Vec<(String, String)> = headers.iter()
+            .map(|(key, value)| (key.to_lowercase(), value.trim().to_string()))
+            .filter(|(key, _)| key.starts_with("x-oss-") || key == "content-type" || key == "content-md5")
+            .collect();
+        signed_headers.sort();
+        let canonical_headers: String = signed_headers.iter()
+            .map(|(key, value)| format!("{}:{}\n", key, value))
+            .collect();
+
+        // 没有附加签名头部（AdditionalHeaders），对应的行为空
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n\n{}",
+            method, self.canonical_uri_v4(uri), canonical_query, canonical_headers, UNSIGNED_PAYLOAD
+        );
+        self.sign_v4(&canonical_request, now, secret_key)
+    }
+
+    /// 按规范请求计算 V4 签名
+    fn sign_v4(&self, canonical_request: &str, now: &DateTime<Utc>, secret_key: &str) -> String {
+        type HmacSha256 = Hmac<Sha256>;
+        let hmac = |key: &[u8], data: &str| -> Vec<u8> {
+            let mut mac = HmacSha256::new_from_slice(key)
+                .expect("HMAC can take key of any size");
+            mac.update(data.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        };
+
+        let string_to_sign = format!(
+            "OSS4-HMAC-SHA256\n{}\n{}\n{}",
+            now.format("%Y%m%dT%H%M%SZ"),
+            self.scope_v4(now),
+            Self::hex_encode(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let date_key = hmac(format!("aliyun_v4{}", secret_key).as_bytes(), &now.format("%Y%m%d").to_string());
+        let region_key = hmac(&date_key, self.region.as_deref().unwrap_or_default());
+        let service_key = hmac(&region_key, "oss");
+        let signing_key = hmac(&service_key, "aliyun_v4_request");
+        Self::hex_encode(&hmac(&signing_key, &string_to_sign))
+    }
+
+    /// V4 凭证范围：`日期/区域/oss/aliyun_v4_request`
+    fn scope_v4(&self, now: &DateTime<Utc>) -> String {
+        format!("{}/{}/oss/aliyun_v4_request", now.format("%Y%m%d"), self.region.as_deref().unwrap_or_default())
+    }
+
+    /// V4 规范 URI：`/bucket/object`，对象键先解码再统一编码，其中的 `/` 不编码
+    fn canonical_uri_v4(&self, uri: &str) -> String {
+        let object_key = self.normalize_uri_for_signing(uri);
+        let path = format!("/{}/{}", self.bucket, object_key.trim_start_matches('/'));
+        Self::uri_encode(&path, false)
+    }
+
+    /// V4 规范查询字符串：键和值编码后按键排序，没有值的参数只保留键
+    fn canonical_query(mut params: Vec<(String, String)>) -> String {
+        params = params.into_iter()
+            .map(|(key, value)| (Self::uri_encode(&key, true), Self::uri_encode(&value, true)))
+            .collect();
+        params.sort();
+        params.iter()
+            .map(|(key, value)| if value.is_empty() { key.clone() } else { format!("{}={}", key, value) })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// 保留 `A-Z a-z 0-9 - _ . ~`，其余字节编码为 `%XX`
+    fn uri_encode(value: &str, encode_slash: bool) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+                b'/' if !encode_slash => encoded.push('/'),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    fn hex_encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
     /// 构建认证头
-    fn build_auth_headers(&self, method: &str, uri: &str, extra_headers: &HashMap<String, String>) -> HashMap<String, String> {
+    ///
+    /// `uri` 为解码后的对象路径（如 `/dir/file.txt`），`request_url` 为实际请求的 URL，V4 签名需要其中的查询参数。
+    fn build_auth_headers(
+        &self,
+        method: &str,
+        uri: &str,
+        request_url: &str,
+        extra_headers: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
         let now = Utc::now();
-        let date = now.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let credentials = self.credentials();
 
         let mut headers = extra_headers.clone();
-        headers.insert("Date".to_string(), date.clone());
         headers.insert("Host".to_string(), self.get_host());
+        if let Some(token) = &credentials.security_token {
+            headers.insert("x-oss-security-token".to_string(), token.clone());
+        }
 
-        let signature = self.generate_signature(method, uri, &headers, &date);
-        let authorization = format!("OSS {}:{}", self.access_key, signature);
+        let authorization = match self.signature_version {
+            SignatureVersion::V1 => {
+                let date = now.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+                headers.insert("Date".to_string(), date.clone());
+                let signature = self.generate_signature(method, uri, &headers, &date, &credentials.secret_key);
+                format!("OSS {}:{}", credentials.access_key, signature)
+            }
+            SignatureVersion::V4 => {
+                headers.insert("x-oss-date".to_string(), now.format("%Y%m%dT%H%M%SZ").to_string());
+                headers.insert("x-oss-content-sha256".to_string(), UNSIGNED_PAYLOAD.to_string());
+                let signature = self.generate_signature_v4(method, uri, request_url, &headers, &now, &credentials.secret_key);
+                format!("OSS4-HMAC-SHA256 Credential={}/{},Signature={}", credentials.access_key, self.scope_v4(&now), signature)
+            }
+        };
 
         headers.insert("Authorization".to_string(), authorization);
         headers
     }
 
+    /// 请求失败时的错误；临时凭证过期时返回 `storage.credentials.expired`，前端据此刷新凭证
+    fn response_error(context: &str, status: reqwest::StatusCode, body: &str) -> StorageError {
+        if body.contains(SECURITY_TOKEN_EXPIRED) {
+            log::warn!("OSS临时凭证已过期: {}", context);
+            return StorageError::RequestFailed("storage.credentials.expired".to_string());
+        }
+        StorageError::RequestFailed(format!("{} failed with status {}: {}", context, status, body))
+    }
+
     /// 获取主机名
     fn get_host(&self) -> String {
         if let Ok(url) = Url::parse(&self.endpoint) {
@@ -307,22 +499,27 @@ impl StorageClient for OSSClient {
         if let Some(endpoint) = &config.url {
             self.endpoint = endpoint.clone();
         }
-        if let Some(access_key) = &config.access_key {
-            self.access_key = access_key.clone();
-        }
-        if let Some(secret_key) = &config.secret_key {
-            self.secret_key = secret_key.clone();
+        {
+            let credentials = self.credentials.get_mut().unwrap_or_else(|e| e.into_inner());
+            if let Some(access_key) = &config.access_key {
+                credentials.access_key = access_key.clone();
+            }
+            if let Some(secret_key) = &config.secret_key {
+                credentials.secret_key = secret_key.clone();
+            }
+            credentials.security_token = config.session_token.clone().filter(|token| !token.is_empty());
         }
         if let Some(bucket) = &config.bucket {
             self.bucket = bucket.clone();
         }
-        self.region = config.region.clone();
+        self.region = Self::resolve_region(config.region.as_deref(), &self.endpoint);
+        self.signature_version = Self::resolve_signature_version(config, self.region.as_deref())?;
 
         // 测试连接 - 直接使用标准化后的端点
         let uri = "/";
-        let headers = self.build_auth_headers("GET", &uri, &HashMap::new());
-
         let url = format!("{}/", self.endpoint.trim_end_matches('/'));
+        let headers = self.build_auth_headers("GET", &uri, &url, &HashMap::new());
+
         let mut req_builder = self.client.get(&url);
 
         for (key, value) in headers {
@@ -338,10 +535,7 @@ impl StorageClient for OSSClient {
         } else {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            Err(StorageError::RequestFailed(format!(
-                "OSS connection test failed with status {}: {}",
-                status, body
-            )))
+            Err(Self::response_error("OSS connection test", status, &body))
         }
     }
 
@@ -364,11 +558,8 @@ impl StorageClient for OSSClient {
         // 对于签名，使用对象键路径
         let signing_uri = self.normalize_uri_for_signing(&format!("/{}", object_key));
 
-        // 构建认证头
-        let signing_method = if req.method == "LIST" { "GET" } else { &req.method };
-        let auth_headers = self.build_auth_headers(signing_method, &signing_uri, &req.headers);
-
         // 发送请求
+        let mut request_url = actual_url.clone();
         let mut req_builder = match req.method.as_str() {
             "GET" => self.client.get(&actual_url),
             "HEAD" => self.client.head(&actual_url),
@@ -402,12 +593,15 @@ impl StorageClient for OSSClient {
                     list_url.push_str(&format!("&continuation-token={}", urlencoding::encode(marker)));
                 }
 
-                self.client.get(&list_url)
+                request_url = list_url;
+                self.client.get(&request_url)
             }
             _ => return Err(StorageError::RequestFailed(format!("Unsupported method: {}", req.method))),
         };
 
-        // 添加认证头
+        // 构建并添加认证头
+        let signing_method = if req.method == "LIST" { "GET" } else { &req.method };
+        let auth_headers = self.build_auth_headers(signing_method, &signing_uri, &request_url, &req.headers);
         for (key, value) in auth_headers {
             req_builder = req_builder.header(&key, &value);
         }
@@ -450,7 +644,7 @@ impl StorageClient for OSSClient {
         // 对于签名，使用对象键路径
         let signing_uri = self.normalize_uri_for_signing(&format!("/{}", object_key));
 
-        let auth_headers = self.build_auth_headers(&req.method, &signing_uri, &req.headers);
+        let auth_headers = self.build_auth_headers(&req.method, &signing_uri, &actual_url, &req.headers);
 
         let mut req_builder = match req.method.as_str() {
             "GET" => self.client.get(&actual_url),
@@ -530,7 +724,7 @@ impl StorageClient for OSSClient {
 
         println!("Range请求头: {}", range_header);
 
-        let auth_headers = self.build_auth_headers("GET", &signing_uri, &headers);
+        let auth_headers = self.build_auth_headers("GET", &signing_uri, &url, &headers);
 
         let mut req_builder = self.client.get(&url);
         for (key, value) in auth_headers {
@@ -546,10 +740,7 @@ impl StorageClient for OSSClient {
         if !status.is_success() {
             let error_body = response.text().await.unwrap_or_default();
             println!("OSS Range请求失败，响应体: {}", error_body);
-            return Err(StorageError::RequestFailed(format!(
-                "Range request failed with status {}: {}",
-                status, error_body
-            )));
+            return Err(Self::response_error("Range request", status, &error_body));
         }
 
         let content_length = response.headers()
@@ -650,7 +841,7 @@ impl StorageClient for OSSClient {
         }
 
         // 对于 list 操作，URI 应该是 /
-        let auth_headers = self.build_auth_headers("GET", "/", &HashMap::new());
+        let auth_headers = self.build_auth_headers("GET", "/", &list_url, &HashMap::new());
 
         let mut req_builder = self.client.get(&list_url);
         for (key, value) in auth_headers {
@@ -663,10 +854,7 @@ impl StorageClient for OSSClient {
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            return Err(StorageError::RequestFailed(format!(
-                "List request failed with status {}: {}",
-                status, body
-            )));
+            return Err(Self::response_error("List request", status, &body));
         }
 
         let xml_content = response.text().await
@@ -709,7 +897,7 @@ impl StorageClient for OSSClient {
             }
         };
 
-        let auth_headers = self.build_auth_headers("GET", &signing_uri, &HashMap::new());
+        let auth_headers = self.build_auth_headers("GET", &signing_uri, &url, &HashMap::new());
 
         let mut req_builder = self.client.get(&url);
         for (key, value) in auth_headers {
@@ -725,10 +913,7 @@ impl StorageClient for OSSClient {
         if !status.is_success() {
             let error_body = response.text().await.unwrap_or_default();
             println!("OSS文件请求失败，响应体: {}", error_body);
-            return Err(StorageError::RequestFailed(format!(
-                "Get file failed with status {}: {}",
-                status, error_body
-            )));
+            return Err(Self::response_error("Get file", status, &error_body));
         }
 
         let content_length = response.headers()
@@ -748,6 +933,10 @@ impl StorageClient for OSSClient {
     }
 
     async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        Ok(self.get_file_info(path).await?.size)
+    }
+
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
         if !self.is_connected().await {
             return Err(StorageError::NotConnected);
         }
@@ -776,7 +965,7 @@ impl StorageClient for OSSClient {
             }
         };
 
-        let auth_headers = self.build_auth_headers("HEAD", &signing_uri, &HashMap::new());
+        let auth_headers = self.build_auth_headers("HEAD", &signing_uri, &url, &HashMap::new());
 
         let mut req_builder = self.client.head(&url);
         for (key, value) in auth_headers {
@@ -793,12 +982,16 @@ impl StorageClient for OSSClient {
             )));
         }
 
-        response
-            .headers()
-            .get("content-length")
-            .and_then(|v| v.to_str().ok())
+        let headers = response.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let size = header("content-length")
             .and_then(|s| s.parse().ok())
-            .ok_or_else(|| StorageError::RequestFailed("No content-length header".to_string()))
+            .ok_or_else(|| StorageError::RequestFailed("No content-length header".to_string()))?;
+        let lastmod = header("last-modified").unwrap_or_default().to_string();
+        let etag = header("etag").map(|s| s.trim_matches('"').to_string());
+
+        Ok(StorageFile::file_info(path, size, lastmod, etag))
     }
 
     fn protocol(&self) -> &str {
@@ -821,6 +1014,22 @@ impl StorageClient for OSSClient {
         Ok(())
     }
 
+    /// 替换访问凭证（如刷新即将过期的 STS 临时凭证），连接保持不变
+    fn update_credentials(&self, credentials: &StorageCredentials) -> Result<(), StorageError> {
+        if credentials.access_key.is_empty() || credentials.secret_key.is_empty() {
+            return Err(StorageError::InvalidConfig("OSS access key and secret key are required".to_string()));
+        }
+
+        let mut current = self.credentials.write().unwrap_or_else(|e| e.into_inner());
+        *current = OssCredentials {
+            access_key: credentials.access_key.clone(),
+            secret_key: credentials.secret_key.clone(),
+            security_token: credentials.session_token.clone().filter(|token| !token.is_empty()),
+        };
+        log::debug!("OSS凭证已更新 (临时凭证: {})", current.security_token.is_some());
+        Ok(())
+    }
+
     fn get_download_url(&self, path: &str) -> Result<String, StorageError> {
         // 从传入的路径/URL 中提取对象键
         let object_key = self.extract_object_key(path)?;
@@ -837,17 +1046,23 @@ impl OSSClient {
             return Err(StorageError::NotConnected);
         }
 
-        // 计算过期时间戳
-        let now = Utc::now().timestamp();
-        let expires = now + expires_in_seconds;
+        let credentials = self.credentials();
 
         // 构建对象 URL
         let object_url = format!("{}/{}", self.endpoint.trim_end_matches('/'),
             urlencoding::encode(object_key));
 
+        if self.signature_version == SignatureVersion::V4 {
+            return Ok(self.generate_download_url_v4(&object_url, object_key, expires_in_seconds, &credentials, &Utc::now()));
+        }
+
+        // 计算过期时间戳
+        let now = Utc::now().timestamp();
+        let expires = now + expires_in_seconds;
+
         // 构建查询参数
         let mut query_params = HashMap::new();
-        query_params.insert("OSSAccessKeyId".to_string(), self.access_key.clone());
+        query_params.insert("OSSAccessKeyId".to_string(), credentials.access_key.clone());
         query_params.insert("Expires".to_string(), expires.to_string());
 
         // 构建待签名字符串
@@ -856,15 +1071,19 @@ impl OSSClient {
         let content_md5 = "";
         let content_type = "";
 
-        // 构建 Canonicalized Resource
-        let canonicalized_resource = format!("/{}{}", self.bucket, uri);
+        // 构建 Canonicalized Resource，临时凭证的安全令牌作为子资源参与签名
+        let mut canonicalized_resource = format!("/{}{}", self.bucket, uri);
+        if let Some(token) = &credentials.security_token {
+            canonicalized_resource.push_str(&format!("?security-token={}", token));
+            query_params.insert("security-token".to_string(), token.clone());
+        }
 
         // 构建签名字符串
         let string_to_sign = format!("{}\n{}\n{}\n{}\n{}",
             method, content_md5, content_type, expires, canonicalized_resource);
 
         // 生成签名
-        let signature = self.sign_string(&string_to_sign);
+        let signature = self.sign_string(&string_to_sign, &credentials.secret_key);
         query_params.insert("Signature".to_string(), signature);
 
         // 构建最终 URL
@@ -876,10 +1095,39 @@ impl OSSClient {
         Ok(format!("{}?{}", object_url, query_string))
     }
 
+    /// 生成 V4 预签名 URL：签名参数放在查询字符串中，除 `x-oss-signature` 外的参数都参与签名
+    fn generate_download_url_v4(
+        &self,
+        object_url: &str,
+        object_key: &str,
+        expires_in_seconds: i64,
+        credentials: &OssCredentials,
+        now: &DateTime<Utc>,
+    ) -> String {
+        let mut query_params = vec![
+            ("x-oss-signature-version".to_string(), "OSS4-HMAC-SHA256".to_string()),
+            ("x-oss-credential".to_string(), format!("{}/{}", credentials.access_key, self.scope_v4(now))),
+            ("x-oss-date".to_string(), now.format("%Y%m%dT%H%M%SZ").to_string()),
+            ("x-oss-expires".to_string(), expires_in_seconds.to_string()),
+        ];
+        if let Some(token) = &credentials.security_token {
+            query_params.push(("x-oss-security-token".to_string(), token.clone()));
+        }
+
+        let canonical_query = Self::canonical_query(query_params);
+        let canonical_request = format!(
+            "GET\n{}\n{}\n\n\n{}",
+            self.canonical_uri_v4(object_key), canonical_query, UNSIGNED_PAYLOAD
+        );
+        let signature = self.sign_v4(&canonical_request, now, &credentials.secret_key);
+
+        format!("{}?{}&x-oss-signature={}", object_url, canonical_query, signature)
+    }
+
     /// 签名字符串
-    fn sign_string(&self, string_to_sign: &str) -> String {
+    fn sign_string(&self, string_to_sign: &str, secret_key: &str) -> String {
         type HmacSha1 = Hmac<sha1::Sha1>;
-        let mut mac = HmacSha1::new_from_slice(self.secret_key.as_bytes())
+        let mut mac = HmacSha1::new_from_slice(secret_key.as_bytes())
             .expect("HMAC can take key of any size");
         mac.update(string_to_sign.as_bytes());
         let result = mac.finalize();
@@ -932,3 +1180,82 @@ impl OSSClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn test_client(signature_version: Option<&str>) -> OSSClient {
+        let extra_options = signature_version.map(|version| {
+            HashMap::from([("signature_version".to_string(), version.to_string())])
+        });
+        OSSClient::new(ConnectionConfig {
+            protocol: "oss".to_string(),
+            url: Some("https://examplebucket.oss-cn-hangzhou.aliyuncs.com".to_string()),
+            access_key: Some("LTAI5tExample".to_string()),
+            secret_key: Some("yourAccessKeySecret".to_string()),
+            session_token: None,
+            region: Some("cn-hangzhou".to_string()),
+            bucket: Some("examplebucket".to_string()),
+            endpoint: None,
+            username: None,
+            password: None,
+            extra_options,
+        }).unwrap()
+    }
+
+    fn test_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 12, 3, 12, 12, 12).unwrap()
+    }
+
+    #[test]
+    fn signature_version_defaults_to_v1() {
+        assert_eq!(test_client(None).signature_version, SignatureVersion::V1);
+        assert_eq!(test_client(Some("v1")).signature_version, SignatureVersion::V1);
+        assert_eq!(test_client(Some("v4")).signature_version, SignatureVersion::V4);
+    }
+
+    #[test]
+    fn canonical_query_encodes_and_sorts() {
+        let query = OSSClient::canonical_query(vec![
+            ("x-oss-expires".to_string(), "86400".to_string()),
+            ("a b".to_string(), "c/d".to_string()),
+            ("acl".to_string(), String::new()),
+        ]);
+        assert_eq!(query, "a%20b=c%2Fd&acl&x-oss-expires=86400");
+    }
+
+    #[test]
+    fn sign_v4_known_answer() {
+        let client = test_client(Some("v4"));
+        let canonical_request = "GET\n/examplebucket/exampleobject\n\n\
+            x-oss-content-sha256:UNSIGNED-PAYLOAD\nx-oss-date:20231203T121212Z\n\nUNSIGNED-PAYLOAD";
+        assert_eq!(
+            client.sign_v4(canonical_request, &test_time(), "yourAccessKeySecret"),
+            "3dfbfd3c38988b4f675ff0398d0d5cbba97725ff7aab3fc6efee5df73f5a2d2a"
+        );
+    }
+
+    #[test]
+    fn presigned_url_v4_known_answer() {
+        let client = test_client(Some("v4"));
+        let credentials = OssCredentials {
+            access_key: "LTAI5tExample".to_string(),
+            secret_key: "yourAccessKeySecret".to_string(),
+            security_token: Some("CAIS+token/x=".to_string()),
+        };
+        let object_key = "dir/a b.txt";
+        let object_url = format!("https://examplebucket.oss-cn-hangzhou.aliyuncs.com/{}", urlencoding::encode(object_key));
+
+        let url = client.generate_download_url_v4(&object_url, object_key, 3600, &credentials, &test_time());
+        assert_eq!(
+            url,
+            "https://examplebucket.oss-cn-hangzhou.aliyuncs.com/dir%2Fa%20b.txt\
+             ?x-oss-credential=LTAI5tExample%2F20231203%2Fcn-hangzhou%2Foss%2Faliyun_v4_request\
+             &x-oss-date=20231203T121212Z&x-oss-expires=3600\
+             &x-oss-security-token=CAIS%2Btoken%2Fx%3D&x-oss-signature-version=OSS4-HMAC-SHA256\
+             &x-oss-signature=0137f3606e1f3a314b259f623b21fafaa2fe03379b7d309452be4503d0b6f328"
+        );
+    }
+}
@@ -0,0 +1,700 @@
+/// SFTP 存储客户端（通过 SSH 访问 Linux 主机上的文件）
+///
+/// 连接地址形如 `sftp://user@host:22/data`，路径部分为根目录，省略时使用登录用户的主目录。
+/// 认证方式：
+///
+/// - 密码：`password`
+/// - 私钥：`extra_options.private_key`（私钥文件路径或 PEM/OpenSSH 格式内容），可选 `extra_options.private_key_passphrase`
+///
+/// 服务器主机密钥按 `known_hosts` 文件校验（默认 `~/.ssh/known_hosts`，可用 `extra_options.known_hosts` 指定）。
+/// `extra_options.host_key_policy` 为 `strict`（默认）时拒绝未知主机；为 `accept_new` 时记录未知主机的密钥，
+/// 但仍拒绝与记录不一致的密钥。
+use async_trait::async_trait;
+use russh::client;
+use russh::keys::{HashAlg, PrivateKey, PrivateKeyWithHashAlg, PublicKey};
+use russh_sftp::client::SftpSession;
+use russh_sftp::protocol::FileAttributes;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use url::Url;
+
+use super::traits::{
+    StorageClient, StorageRequest, StorageResponse, StorageError, DirectoryResult, StorageFile,
+    ListOptions, ConnectionConfig, StorageCapabilities, ProgressCallback,
+};
+use crate::utils::chunk_size;
+
+/// SSH 默认端口
+const DEFAULT_PORT: u16 = 22;
+/// 连接空闲超时
+const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 未知主机密钥的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HostKeyPolicy {
+    /// 只接受 known_hosts 中已记录的密钥
+    Strict,
+    /// 记录未知主机的密钥并接受
+    AcceptNew,
+}
+
+/// SSH 会话事件处理，负责按 known_hosts 校验服务器主机密钥
+struct SshHandler {
+    host: String,
+    port: u16,
+    known_hosts: PathBuf,
+    policy: HostKeyPolicy,
+    /// 拒绝主机密钥的原因，连接失败时用于生成错误信息
+    rejection: Arc<Mutex<Option<String>>>,
+}
+
+impl SshHandler {
+    fn reject(&self, reason: String) -> Result<bool, russh::Error> {
+        log::warn!("SFTP主机密钥校验失败: {}", reason);
+        *self.rejection.lock().unwrap_or_else(|e| e.into_inner()) = Some(reason);
+        Ok(false)
+    }
+}
+
+impl client::Handler for SshHandler {
+    type Error = russh::Error;
+
+    async fn check_server_key(&mut self, server_public_key: &PublicKey) -> Result<bool, Self::Error> {
+        let fingerprint = server_public_key.fingerprint(HashAlg::Sha256);
+
+        match russh::keys::check_known_hosts_path(&self.host, self.port, server_public_key, &self.known_hosts) {
+            Ok(true) => {
+                log::debug!("SFTP主机密钥已校验: {} ({})", self.host, fingerprint);
+                Ok(true)
+            }
+            Ok(false) if self.policy == HostKeyPolicy::AcceptNew => {
+                if let Some(parent) = self.known_hosts.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                match russh::keys::known_hosts::learn_known_hosts_path(&self.host, self.port, server_public_key, &self.known_hosts) {
+                    Ok(()) => {
+                        log::debug!("SFTP已记录新的主机密钥: {} ({})", self.host, fingerprint);
+                        Ok(true)
+                    }
+                    Err(e) => self.reject(format!(
+                        "Failed to record host key {} in {}: {}",
+                        fingerprint, self.known_hosts.display(), e
+                    )),
+                }
+            }
+            Ok(false) => self.reject(format!(
+                "Host key {} for {} is not in {}",
+                fingerprint, self.host, self.known_hosts.display()
+            )),
+            Err(russh::keys::Error::KeyChanged { line }) => self.reject(format!(
+                "Host key {} for {} does not match {} line {}",
+                fingerprint, self.host, self.known_hosts.display(), line
+            )),
+            Err(e) => self.reject(format!(
+                "Failed to read {}: {}",
+                self.known_hosts.display(), e
+            )),
+        }
+    }
+}
+
+pub struct SftpClient {
+    connected: AtomicBool,
+    host: String,
+    port: u16,
+    username: String,
+    /// 远程根目录（绝对路径）
+    root_path: String,
+    session: Option<client::Handle<SshHandler>>,
+    sftp: Option<SftpSession>,
+}
+
+impl SftpClient {
+    pub fn new(config: ConnectionConfig) -> Result<Self, StorageError> {
+        Self::validate(&config)?;
+        let (host, port, username, root_path) = Self::parse_target(&config)?;
+
+        Ok(Self {
+            connected: AtomicBool::new(false),
+            host,
+            port,
+            username,
+            root_path,
+            session: None,
+            sftp: None,
+        })
+    }
+
+    fn validate(config: &ConnectionConfig) -> Result<(), StorageError> {
+        if config.url.as_deref().is_none_or(str::is_empty) {
+            return Err(StorageError::InvalidConfig("SFTP host is required".to_string()));
+        }
+
+        let has_password = config.password.as_deref().is_some_and(|p| !p.is_empty());
+        if !has_password && Self::extra_option(config, "private_key").is_none() {
+            return Err(StorageError::InvalidConfig("SFTP password or private key is required".to_string()));
+        }
+
+        Self::host_key_policy(config)?;
+        Ok(())
+    }
+
+    fn extra_option<'a>(config: &'a ConnectionConfig, key: &str) -> Option<&'a str> {
+        config.extra_options.as_ref()
+            .and_then(|options| options.get(key))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 解析 `sftp://user@host:port/path`（可省略协议和端口），返回主机、端口、用户名和根目录
+    fn parse_target(config: &ConnectionConfig) -> Result<(String, u16, String, String), StorageError> {
+        let raw = config.url.as_deref().unwrap_or_default().trim();
+        let with_scheme = if raw.contains("://") { raw.to_string() } else { format!("sftp://{}", raw) };
+        let url = Url::parse(&with_scheme)
+            .map_err(|e| StorageError::InvalidConfig(format!("Invalid SFTP address: {}", e)))?;
+
+        if url.scheme() != "sftp" && url.scheme() != "ssh" {
+            return Err(StorageError::InvalidConfig(format!("Unsupported SFTP scheme: {}", url.scheme())));
+        }
+
+        let host = url.host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| StorageError::InvalidConfig("SFTP host is required".to_string()))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = url.port().unwrap_or(DEFAULT_PORT);
+
+        let url_username = urlencoding::decode(url.username()).map(|u| u.into_owned()).unwrap_or_default();
+        let username = config.username.clone()
+            .filter(|u| !u.is_empty())
+            .or_else(|| Some(url_username).filter(|u| !u.is_empty()))
+            .ok_or_else(|| StorageError::InvalidConfig("SFTP username is required".to_string()))?;
+
+        let root_path = urlencoding::decode(url.path()).map(|p| p.into_owned()).unwrap_or_default();
+
+        Ok((host, port, username, root_path))
+    }
+
+    fn host_key_policy(config: &ConnectionConfig) -> Result<HostKeyPolicy, StorageError> {
+        match Self::extra_option(config, "host_key_policy") {
+            None | Some("strict") => Ok(HostKeyPolicy::Strict),
+            Some("accept_new") => Ok(HostKeyPolicy::AcceptNew),
+            Some(other) => Err(StorageError::InvalidConfig(format!("Unknown host key policy: {}", other))),
+        }
+    }
+
+    /// 展开 `~` 为用户主目录
+    fn expand_home(path: &str) -> Result<PathBuf, StorageError> {
+        if path == "~" || path.starts_with("~/") {
+            let home_dir = dirs::home_dir()
+                .ok_or_else(|| StorageError::ConnectionFailed("Cannot determine home directory".to_string()))?;
+            return Ok(home_dir.join(path.trim_start_matches('~').trim_start_matches('/')));
+        }
+        Ok(PathBuf::from(path))
+    }
+
+    fn known_hosts_path(config: &ConnectionConfig) -> Result<PathBuf, StorageError> {
+        match Self::extra_option(config, "known_hosts") {
+            Some(path) => Self::expand_home(path),
+            None => Self::expand_home("~/.ssh/known_hosts"),
+        }
+    }
+
+    /// 加载私钥：以 `-----BEGIN` 开头的视为私钥内容，否则视为私钥文件路径
+    fn load_private_key(config: &ConnectionConfig, private_key: &str) -> Result<PrivateKey, StorageError> {
+        let passphrase = Self::extra_option(config, "private_key_passphrase");
+
+        let result = if private_key.starts_with("-----BEGIN") {
+            russh::keys::decode_secret_key(private_key, passphrase)
+        } else {
+            russh::keys::load_secret_key(Self::expand_home(private_key)?, passphrase)
+        };
+
+        result.map_err(|e| StorageError::InvalidConfig(format!("Failed to load SFTP private key: {}", e)))
+    }
+
+    /// 建立 SSH 连接、认证并打开 SFTP 子系统
+    async fn open_session(
+        &self,
+        config: &ConnectionConfig,
+    ) -> Result<(client::Handle<SshHandler>, SftpSession), StorageError> {
+        let rejection = Arc::new(Mutex::new(None));
+        let handler = SshHandler {
+            host: self.host.clone(),
+            port: self.port,
+            known_hosts: Self::known_hosts_path(config)?,
+            policy: Self::host_key_policy(config)?,
+            rejection: rejection.clone(),
+        };
+
+        let ssh_config = Arc::new(client::Config {
+            inactivity_timeout: Some(INACTIVITY_TIMEOUT),
+            ..Default::default()
+        });
+
+        let mut session = match client::connect(ssh_config, (self.host.as_str(), self.port), handler).await {
+            Ok(session) => session,
+            Err(e) => {
+                let reason = rejection.lock().unwrap_or_else(|e| e.into_inner()).take();
+                return Err(StorageError::ConnectionFailed(reason.unwrap_or_else(|| format!("SSH connection failed: {}", e))));
+            }
+        };
+
+        // 优先使用私钥认证，失败后再尝试密码
+        let mut authenticated = false;
+        if let Some(private_key) = Self::extra_option(config, "private_key") {
+            let key = Self::load_private_key(config, private_key)?;
+            let hash_alg = session.best_supported_rsa_hash().await
+                .map_err(|e| StorageError::ConnectionFailed(format!("SSH authentication failed: {}", e)))?
+                .flatten();
+            authenticated = session
+                .authenticate_publickey(&self.username, PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg))
+                .await
+                .map_err(|e| StorageError::ConnectionFailed(format!("SSH authentication failed: {}", e)))?
+                .success();
+            log::debug!("SFTP私钥认证{}: {}@{}", if authenticated { "成功" } else { "失败" }, self.username, self.host);
+        }
+        if !authenticated {
+            if let Some(password) = config.password.as_deref().filter(|p| !p.is_empty()) {
+                authenticated = session.authenticate_password(&self.username, password).await
+                    .map_err(|e| StorageError::ConnectionFailed(format!("SSH authentication failed: {}", e)))?
+                    .success();
+            }
+        }
+        if !authenticated {
+            return Err(StorageError::ConnectionFailed(format!("SSH authentication failed for {}", self.username)));
+        }
+
+        let channel = session.channel_open_session().await
+            .map_err(|e| StorageError::ConnectionFailed(format!("Failed to open SSH channel: {}", e)))?;
+        channel.request_subsystem(true, "sftp").await
+            .map_err(|e| StorageError::ConnectionFailed(format!("Failed to start SFTP subsystem: {}", e)))?;
+        let sftp = SftpSession::new(channel.into_stream()).await
+            .map_err(|e| StorageError::ConnectionFailed(format!("Failed to start SFTP session: {}", e)))?;
+
+        Ok((session, sftp))
+    }
+
+    fn sftp(&self) -> Result<&SftpSession, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+        self.sftp.as_ref().ok_or(StorageError::NotConnected)
+    }
+
+    /// 把请求路径转换为远程绝对路径
+    /// 支持相对根目录的路径和 `sftp://host/path` 形式的完整地址
+    fn remote_path(&self, path: &str) -> String {
+        let relative = match path.strip_prefix("sftp://") {
+            Some(rest) => {
+                let absolute = rest.find('/').map(|i| &rest[i..]).unwrap_or("/");
+                return urlencoding::decode(absolute).map(|p| p.into_owned()).unwrap_or_else(|_| absolute.to_string());
+            }
+            None => path.trim_start_matches('/'),
+        };
+
+        let root = self.root_path.trim_end_matches('/');
+        if relative.is_empty() {
+            if root.is_empty() { "/".to_string() } else { root.to_string() }
+        } else {
+            format!("{}/{}", root, relative)
+        }
+    }
+
+    /// 格式化文件修改时间
+    fn format_modification_time(attributes: &FileAttributes) -> String {
+        attributes.mtime
+            .and_then(|seconds| chrono::DateTime::from_timestamp(seconds as i64, 0))
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+    }
+
+    async fn stat(&self, path: &str) -> Result<FileAttributes, StorageError> {
+        let remote_path = self.remote_path(path);
+        self.sftp()?.metadata(&remote_path).await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to stat {}: {}", remote_path, e)))
+    }
+
+    async fn read_directory(&self, path: &str) -> Result<DirectoryResult, StorageError> {
+        let sftp = self.sftp()?;
+        let dir_path = self.remote_path(path);
+
+        let entries = sftp.read_dir(&dir_path).await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read directory {}: {}", dir_path, e)))?;
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let file_name = entry.file_name();
+            if file_name == "." || file_name == ".." {
+                continue;
+            }
+
+            // 符号链接按其目标的类型和大小显示，目标不存在时按普通文件处理
+            let mut attributes = entry.metadata();
+            if attributes.is_symlink() {
+                let target = format!("{}/{}", dir_path.trim_end_matches('/'), file_name);
+                if let Ok(resolved) = sftp.metadata(&target).await {
+                    attributes = resolved;
+                }
+            }
+
+            let is_directory = attributes.is_dir();
+            files.push(StorageFile {
+                filename: file_name.clone(),
+                basename: file_name,
+                lastmod: Self::format_modification_time(&attributes),
+                size: if is_directory { 0 } else { attributes.size.unwrap_or(0) },
+                file_type: if is_directory { "directory" } else { "file" }.to_string(),
+                mime: None,
+                etag: None,
+            });
+        }
+
+        // 目录优先，然后按名称排序
+        files.sort_by(|a, b| {
+            match (a.file_type.as_str(), b.file_type.as_str()) {
+                ("directory", "file") => std::cmp::Ordering::Less,
+                ("file", "directory") => std::cmp::Ordering::Greater,
+                _ => a.filename.cmp(&b.filename),
+            }
+        });
+
+        Ok(DirectoryResult {
+            files,
+            has_more: false,
+            next_marker: None,
+            total_count: None,
+            path: path.to_string(),
+        })
+    }
+
+    fn json_response(value: &Value) -> Result<StorageResponse, StorageError> {
+        let body = serde_json::to_string(value)
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to serialize response: {}", e)))?;
+
+        Ok(StorageResponse {
+            status: 200,
+            headers: HashMap::new(),
+            body,
+            metadata: None,
+        })
+    }
+
+    /// 读取文本内容供前端预览，`options` 中的 `start` / `length` 指定读取范围
+    async fn read_text(&self, path: &str, options: Option<&Value>) -> Result<StorageResponse, StorageError> {
+        let file_size = self.get_file_size(path).await?;
+        let start = options.and_then(|opts| opts.get("start")).and_then(Value::as_u64).unwrap_or(0).min(file_size);
+        let length = options.and_then(|opts| opts.get("length")).and_then(Value::as_u64)
+            .unwrap_or(file_size - start)
+            .min(file_size - start);
+
+        let data = self.read_file_range(path, start, length).await?;
+        Self::json_response(&serde_json::json!({
+            "content": String::from_utf8_lossy(&data),
+            "size": file_size,
+            "encoding": "utf-8"
+        }))
+    }
+}
+
+#[async_trait]
+impl StorageClient for SftpClient {
+    async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        Self::validate(config)?;
+        let (host, port, username, root_path) = Self::parse_target(config)?;
+        self.host = host;
+        self.port = port;
+        self.username = username;
+
+        let (session, sftp) = self.open_session(config).await?;
+
+        // 根目录解析为绝对路径，未指定时使用登录用户的主目录
+        let root = if root_path.is_empty() { "." } else { root_path.as_str() };
+        self.root_path = sftp.canonicalize(root).await
+            .map_err(|e| StorageError::ConnectionFailed(format!("SFTP root path is not accessible: {}", e)))?;
+
+        let attributes = sftp.metadata(&self.root_path).await
+            .map_err(|e| StorageError::ConnectionFailed(format!("SFTP root path is not accessible: {}", e)))?;
+        if !attributes.is_dir() {
+            return Err(StorageError::ConnectionFailed(format!("Path is not a directory: {}", self.root_path)));
+        }
+
+        log::debug!("SFTP连接成功: {}@{}:{} (根目录 {})", self.username, self.host, self.port, self.root_path);
+
+        self.session = Some(session);
+        self.sftp = Some(sftp);
+        self.connected.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn disconnect(&self) {
+        self.connected.store(false, Ordering::Relaxed);
+
+        if let Some(sftp) = &self.sftp {
+            let _ = sftp.close().await;
+        }
+        if let Some(session) = &self.session {
+            let _ = session.disconnect(russh::Disconnect::ByApplication, "", "en").await;
+        }
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+            && self.session.as_ref().is_some_and(|session| !session.is_closed())
+    }
+
+    async fn list_directory(&self, path: &str, _options: Option<&ListOptions>) -> Result<DirectoryResult, StorageError> {
+        self.read_directory(path).await
+    }
+
+    async fn request(&self, request: &StorageRequest) -> Result<StorageResponse, StorageError> {
+        self.sftp()?;
+
+        match request.method.as_str() {
+            "CHECK_ACCESS" => {
+                let attributes = self.stat("").await?;
+                if !attributes.is_dir() {
+                    return Err(StorageError::RequestFailed("Path not accessible".to_string()));
+                }
+                Ok(StorageResponse {
+                    status: 200,
+                    headers: HashMap::new(),
+                    body: "OK".to_string(),
+                    metadata: None,
+                })
+            }
+            "LIST_DIRECTORY" => {
+                let result = self.read_directory(&request.url).await?;
+                Self::json_response(&serde_json::to_value(&result)
+                    .map_err(|e| StorageError::RequestFailed(format!("Failed to serialize response: {}", e)))?)
+            }
+            "READ_FILE" => self.read_text(&request.url, request.options.as_ref()).await,
+            "GET_FILE_SIZE" => {
+                let size = self.get_file_size(&request.url).await?;
+                Self::json_response(&serde_json::json!({ "size": size }))
+            }
+            _ => Err(StorageError::RequestFailed(
+                format!("Unsupported method: {}", request.method)
+            )),
+        }
+    }
+
+    async fn request_binary(&self, request: &StorageRequest) -> Result<Vec<u8>, StorageError> {
+        match request.method.as_str() {
+            "READ_FILE_BINARY" => self.read_full_file(&request.url).await,
+            _ => Err(StorageError::RequestFailed(
+                format!("Unsupported binary method: {}", request.method)
+            )),
+        }
+    }
+
+    async fn read_file_range(&self, path: &str, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        self.read_file_range_with_progress(path, start, length, None, None).await
+    }
+
+    /// 按偏移读取：SFTP 的读请求本身带有偏移量，定位后只传输请求的范围
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        let sftp = self.sftp()?;
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        log::debug!("SFTP读取文件范围: path={}, start={}, length={}", path, start, length);
+
+        let remote_path = self.remote_path(path);
+        let mut file = sftp.open(&remote_path).await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to open {}: {}", remote_path, e)))?;
+
+        file.seek(std::io::SeekFrom::Start(start)).await
+            .map_err(|e| StorageError::IoError(format!("Failed to seek in file: {}", e)))?;
+
+        let chunk_size = chunk_size::calculate_optimal_chunk_size(length);
+        let mut result = Vec::with_capacity(length as usize);
+        let mut remaining = length;
+        let mut chunk = vec![0u8; chunk_size];
+
+        while remaining > 0 {
+            if let Some(ref mut cancel_rx) = cancel_rx {
+                if cancel_rx.try_recv().is_ok() {
+                    return Err(StorageError::RequestFailed("download.cancelled".to_string()));
+                }
+            }
+
+            let current_chunk_size = std::cmp::min(remaining, chunk_size as u64) as usize;
+            let bytes_read = file.read(&mut chunk[..current_chunk_size]).await
+                .map_err(|e| StorageError::IoError(format!("Failed to read file: {}", e)))?;
+
+            if bytes_read == 0 {
+                // 到达文件末尾
+                break;
+            }
+
+            result.extend_from_slice(&chunk[..bytes_read]);
+            remaining -= bytes_read as u64;
+
+            if let Some(ref callback) = progress_callback {
+                callback(result.len() as u64, length);
+            }
+        }
+
+        log::debug!("SFTP实际读取到 {} 字节，请求 {} 字节", result.len(), length);
+        Ok(result)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let remote_path = self.remote_path(path);
+        self.sftp()?.read(&remote_path).await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read {}: {}", remote_path, e)))
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        let attributes = self.stat(path).await?;
+        if attributes.is_dir() {
+            return Err(StorageError::RequestFailed("Path is a directory, not a file".to_string()));
+        }
+
+        attributes.size
+            .ok_or_else(|| StorageError::RequestFailed("SFTP server did not report file size".to_string()))
+    }
+
+    /// SFTP 没有 ETag，使用修改时间和大小组合作为版本标识
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        let attributes = self.stat(path).await?;
+        if attributes.is_dir() {
+            return Err(StorageError::RequestFailed("Path is a directory, not a file".to_string()));
+        }
+        let size = attributes.size
+            .ok_or_else(|| StorageError::RequestFailed("SFTP server did not report file size".to_string()))?;
+
+        let lastmod = Self::format_modification_time(&attributes);
+        let etag = attributes.mtime.map(|mtime| format!("{:x}-{:x}", mtime, size));
+        Ok(StorageFile::file_info(path, size, lastmod, etag))
+    }
+
+    fn get_download_url(&self, path: &str) -> Result<String, StorageError> {
+        if path.starts_with("sftp://") {
+            return Ok(path.to_string());
+        }
+
+        let remote_path = self.remote_path(path);
+        let encoded_path: Vec<String> = remote_path.split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect();
+        let host = if self.host.contains(':') { format!("[{}]", self.host) } else { self.host.clone() };
+
+        Ok(format!("sftp://{}@{}:{}{}", urlencoding::encode(&self.username), host, self.port, encoded_path.join("/")))
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            supports_directories: true,
+            supports_metadata: true,
+            supports_streaming: true,
+            supports_range_requests: true,
+            supports_multipart_upload: false,
+            supports_encryption: true,
+            max_file_size: None,
+            supported_methods: vec![
+                "READ_FILE".to_string(),
+                "READ_FILE_BINARY".to_string(),
+                "LIST_DIRECTORY".to_string(),
+                "GET_FILE_SIZE".to_string(),
+                "CHECK_ACCESS".to_string(),
+            ],
+        }
+    }
+
+    fn protocol(&self) -> &str {
+        "sftp"
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        Self::validate(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(url: &str, username: Option<&str>) -> ConnectionConfig {
+        ConnectionConfig {
+            protocol: "sftp".to_string(),
+            url: Some(url.to_string()),
+            access_key: None,
+            secret_key: None,
+            session_token: None,
+            region: None,
+            bucket: None,
+            endpoint: None,
+            username: username.map(str::to_string),
+            password: Some("secret".to_string()),
+            extra_options: None,
+        }
+    }
+
+    #[test]
+    fn parse_target_reads_user_port_and_root() {
+        let target = SftpClient::parse_target(&test_config("sftp://alice@example.com:2222/data/logs", None)).unwrap();
+        assert_eq!(target, ("example.com".to_string(), 2222, "alice".to_string(), "/data/logs".to_string()));
+    }
+
+    #[test]
+    fn parse_target_defaults_scheme_port_and_root() {
+        let target = SftpClient::parse_target(&test_config("example.com", Some("bob"))).unwrap();
+        assert_eq!(target, ("example.com".to_string(), 22, "bob".to_string(), String::new()));
+    }
+
+    #[test]
+    fn parse_target_prefers_config_username_and_decodes() {
+        let target = SftpClient::parse_target(&test_config("ssh://a%40corp@[::1]:22/my%20dir", Some("carol"))).unwrap();
+        assert_eq!(target, ("::1".to_string(), 22, "carol".to_string(), "/my dir".to_string()));
+
+        let target = SftpClient::parse_target(&test_config("sftp://a%40corp@host/", None)).unwrap();
+        assert_eq!(target.2, "a@corp");
+    }
+
+    #[test]
+    fn parse_target_rejects_invalid_addresses() {
+        assert!(SftpClient::parse_target(&test_config("ftp://alice@example.com", None)).is_err());
+        assert!(SftpClient::parse_target(&test_config("sftp://example.com", None)).is_err());
+    }
+
+    #[test]
+    fn remote_path_joins_root_and_accepts_full_urls() {
+        let client = SftpClient::new(test_config("sftp://alice@example.com/data/", None)).unwrap();
+        assert_eq!(client.remote_path(""), "/data");
+        assert_eq!(client.remote_path("/logs/app.log"), "/data/logs/app.log");
+        assert_eq!(client.remote_path("sftp://alice@example.com:22/tmp/a%20b.txt"), "/tmp/a b.txt");
+        assert_eq!(client.remote_path("sftp://alice@example.com"), "/");
+
+        let client = SftpClient::new(test_config("sftp://alice@example.com", None)).unwrap();
+        assert_eq!(client.remote_path(""), "/");
+        assert_eq!(client.remote_path("home/alice"), "/home/alice");
+    }
+
+    #[test]
+    fn host_key_policy_defaults_to_strict() {
+        let mut config = test_config("sftp://alice@example.com", None);
+        assert!(matches!(SftpClient::host_key_policy(&config), Ok(HostKeyPolicy::Strict)));
+
+        config.extra_options = Some(HashMap::from([("host_key_policy".to_string(), "accept_new".to_string())]));
+        assert!(matches!(SftpClient::host_key_policy(&config), Ok(HostKeyPolicy::AcceptNew)));
+
+        config.extra_options = Some(HashMap::from([("host_key_policy".to_string(), "none".to_string())]));
+        assert!(SftpClient::host_key_policy(&config).is_err());
+    }
+}
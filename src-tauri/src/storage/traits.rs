@@ -19,6 +19,22 @@ pub struct StorageFile {
     pub etag: Option<String>,
 }
 
+impl StorageFile {
+    /// 构造单个文件元数据查询的结果，`filename` 为查询的路径
+    pub fn file_info(path: &str, size: u64, lastmod: String, etag: Option<String>) -> Self {
+        let basename = path.trim_end_matches('/').rsplit('/').next().unwrap_or(path).to_string();
+        Self {
+            filename: path.to_string(),
+            basename,
+            lastmod,
+            size,
+            file_type: "file".to_string(),
+            mime: None,
+            etag,
+        }
+    }
+}
+
 /// 统一的目录列表结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectoryResult {
@@ -67,6 +83,8 @@ pub struct ConnectionConfig {
     pub url: Option<String>,
     pub access_key: Option<String>,
     pub secret_key: Option<String>,
+    /// 临时凭证（如 STS）附带的安全令牌
+    pub session_token: Option<String>,
     pub region: Option<String>,
     pub bucket: Option<String>,
     pub endpoint: Option<String>,
@@ -75,6 +93,15 @@ pub struct ConnectionConfig {
     pub extra_options: Option<HashMap<String, String>>,
 }
 
+/// 访问凭证，用于在不断开连接的情况下替换即将过期的临时凭证
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCredentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+}
+
 /// 存储客户端错误类型
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum StorageError {
@@ -103,6 +130,30 @@ pub enum StorageError {
     NetworkError(String),
 }
 
+/// 校验 HTTP 范围请求的响应，返回响应中的数据长度
+///
+/// 服务器忽略 `Range` 时会以 200 返回整个文件，不能当作请求的范围使用，因此要求状态码为 206，
+/// 且 `Content-Range` 从请求的起始位置开始、不超过请求的结束位置（文件末尾之后的部分可以缺少）。
+pub fn check_range_response(status: u16, content_range: Option<&str>, start: u64, end: u64) -> Result<u64, StorageError> {
+    if status != 206 {
+        return Err(StorageError::RequestFailed(format!(
+            "Range request returned status {} instead of 206 Partial Content", status
+        )));
+    }
+
+    let range = content_range
+        .and_then(|value| value.trim().strip_prefix("bytes "))
+        .and_then(|value| value.split_once('/'))
+        .and_then(|(range, _total)| range.split_once('-'))
+        .and_then(|(first, last)| Some((first.parse::<u64>().ok()?, last.parse::<u64>().ok()?)));
+    match range {
+        Some((first, last)) if first == start && last >= first && last <= end => Ok(last - first + 1),
+        _ => Err(StorageError::RequestFailed(format!(
+            "Range request returned Content-Range {:?}, expected bytes {}-{}", content_range.unwrap_or(""), start, end
+        ))),
+    }
+}
+
 /// 统一存储客户端接口
 #[async_trait]
 pub trait StorageClient: Send + Sync {
@@ -168,6 +219,25 @@ pub trait StorageClient: Send + Sync {
     /// 获取文件大小
     async fn get_file_size(&self, path: &str) -> Result<u64, StorageError>;
 
+    /// 获取单个文件的元数据，一次请求同时得到大小、修改时间和版本标识（`etag`）
+    /// `etag` 用于判断基于文件内容的缓存是否失效；默认实现只获取大小，`etag` 为 None 表示存储不提供版本标识
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        let size = self.get_file_size(path).await?;
+        Ok(StorageFile::file_info(path, size, String::new(), None))
+    }
+
+    /// 文件由多个分卷拼接而成时，返回每个分卷在拼接数据中的起始偏移
+    /// 默认实现返回 None，表示普通的单个文件
+    fn volume_offsets(&self, _path: &str) -> Option<Vec<u64>> {
+        None
+    }
+
+    /// 替换访问凭证，后续请求使用新凭证签名
+    /// 默认实现返回不支持，适用于没有临时凭证的存储
+    fn update_credentials(&self, _credentials: &StorageCredentials) -> Result<(), StorageError> {
+        Err(StorageError::ProtocolNotSupported("credential refresh".to_string()))
+    }
+
     /// 获取下载 URL（对于需要签名的存储如 OSS）
     fn get_download_url(&self, path: &str) -> Result<String, StorageError> {
         // 默认实现：直接返回路径，适用于不需要签名的存储
@@ -451,37 +451,24 @@ impl StorageClient for WebDAVClient {
     }
 
     async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
-        if !self.connected.load(Ordering::Relaxed) {
-            return Err(StorageError::NotConnected);
-        }
-
-        // 处理协议URL格式
-        let actual_url = self.parse_path_to_url(path)?;
-
-        let mut request = self.client.head(&actual_url);
-        if let Some(auth) = &self.auth_header {
-            request = request.header("Authorization", auth);
-        }
-
-        let response = request.send().await
-            .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(StorageError::RequestFailed(
-                format!("HTTP {}: {}", response.status(), response.status().canonical_reason().unwrap_or("Unknown"))
-            ));
-        }
-
-        // 尝试从 Content-Length 头获取文件大小
-        if let Some(content_length) = response.headers().get("content-length") {
-            if let Ok(length_str) = content_length.to_str() {
-                if let Ok(length) = length_str.parse::<u64>() {
-                    return Ok(length);
-                }
-            }
-        }
+        Ok(self.get_file_info(path).await?.size)
+    }
 
-        Err(StorageError::RequestFailed("Unable to determine file size".to_string()))
+    async fn get_file_info(&self, path: &str) -> Result<StorageFile, StorageError> {
+        let response = self.head_file(path).await?;
+        let headers = response.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let size = header("content-length")
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| StorageError::RequestFailed("Unable to determine file size".to_string()))?;
+        let lastmod = header("last-modified").unwrap_or_default().to_string();
+        // 没有 ETag 时退回到 Last-Modified
+        let etag = header("etag")
+            .or(header("last-modified"))
+            .map(|s| s.trim_start_matches("W/").trim_matches('"').to_string());
+
+        Ok(StorageFile::file_info(path, size, lastmod, etag))
     }
 
     fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
@@ -519,6 +506,32 @@ impl StorageClient for WebDAVClient {
 }
 
 impl WebDAVClient {
+    /// 发送 HEAD 请求获取文件元数据
+    async fn head_file(&self, path: &str) -> Result<reqwest::Response, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        // 处理协议URL格式
+        let actual_url = self.parse_path_to_url(path)?;
+
+        let mut request = self.client.head(&actual_url);
+        if let Some(auth) = &self.auth_header {
+            request = request.header("Authorization", auth);
+        }
+
+        let response = request.send().await
+            .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(
+                format!("HTTP {}: {}", response.status(), response.status().canonical_reason().unwrap_or("Unknown"))
+            ));
+        }
+
+        Ok(response)
+    }
+
     fn parse_webdav_xml(&self, xml_body: &str, current_path: &str) -> Result<Vec<StorageFile>, StorageError> {
         let mut reader = Reader::from_str(xml_body);
         reader.trim_text(true);
@@ -5,6 +5,7 @@ import { FileViewer } from './components/FileViewer';
 import { DownloadProgress } from './components/DownloadProgress';
 import { UpdateNotification, useUpdateNotification } from './components/UpdateNotification';
 import { SplashScreen } from './components/SplashScreen';
+import { CredentialsRefreshDialog } from './components/CredentialsRefreshDialog';
 import { StorageFile } from './types';
 import { StorageServiceManager } from './services/storage';
 import { navigationHistoryService } from './services/navigationHistory';
@@ -210,6 +211,9 @@ function App() {
         onClose={() => setShowDownloadProgress(false)}
       />
 
+      {/* 临时凭证过期时的凭证刷新对话框 */}
+      <CredentialsRefreshDialog />
+
       {/* 更新通知 */}
       {showNotification && (
         <UpdateNotification onClose={hideUpdateDialog} />
@@ -0,0 +1,251 @@
+import React, { useState, useEffect } from 'react';
+import { useTranslation } from 'react-i18next';
+import { ConnectionConfig } from '../../services/storage/types';
+import { StoredConnection } from '../../services/connectionStorage';
+
+interface AzureBlobConnectionFormProps {
+  onConnect: (config: ConnectionConfig) => Promise<void>;
+  connecting: boolean;
+  error?: string;
+  selectedConnection?: StoredConnection | null;
+}
+
+const emptyConfig = {
+  endpoint: '',
+  account: '',
+  accountKey: '',
+  container: '',
+  sasToken: '',
+};
+
+/**
+ * Azure Blob 连接表单组件
+ * 支持账户密钥和 SAS 令牌认证，端点也可以是 Azurite 等模拟器地址
+ */
+export const AzureBlobConnectionForm: React.FC<AzureBlobConnectionFormProps> = ({
+  onConnect,
+  connecting,
+  error: externalError,
+  selectedConnection
+}) => {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState(emptyConfig);
+  const [errors, setErrors] = useState<Record<string, string>>({});
+
+  // 当选中连接变化时，更新表单
+  useEffect(() => {
+    if (selectedConnection && selectedConnection.url.startsWith('azblob://')) {
+      const metadata = selectedConnection.metadata;
+      const [host, container] = selectedConnection.url.replace('azblob://', '').split('/');
+
+      setConfig({
+        endpoint: metadata?.endpoint || `https://${host}`,
+        account: selectedConnection.username,
+        accountKey: selectedConnection.password ? '••••••••' : '',
+        container: metadata?.bucket || container || '',
+        sasToken: metadata?.sasToken || '',
+      });
+    } else if (!selectedConnection) {
+      setConfig(emptyConfig);
+    }
+  }, [selectedConnection]);
+
+  const validateForm = () => {
+    const newErrors: Record<string, string> = {};
+
+    if (!config.endpoint.trim()) {
+      newErrors.endpoint = t('error.azblob.endpoint.required');
+    } else {
+      try {
+        new URL(config.endpoint);
+      } catch {
+        newErrors.endpoint = t('error.endpoint.invalid');
+      }
+    }
+
+    if (!config.container.trim()) {
+      newErrors.container = t('error.azblob.container.required');
+    }
+
+    if (!config.accountKey.trim() && !config.sasToken.trim()) {
+      newErrors.accountKey = t('error.azblob.auth.required');
+    }
+
+    setErrors(newErrors);
+    return Object.keys(newErrors).length === 0;
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!validateForm()) {
+      return;
+    }
+
+    const endpoint = config.endpoint.trim();
+    const defaultName = t('connection.name.azblob', 'Azure({{host}}-{{container}})', {
+      host: new URL(endpoint).host,
+      container: config.container
+    });
+
+    // 如果密钥是占位符（来自已保存的连接），使用真实密钥
+    const actualAccountKey = config.accountKey === '••••••••' && selectedConnection?.password
+      ? selectedConnection.password
+      : config.accountKey.trim();
+
+    const connectionConfig: ConnectionConfig = {
+      type: 'azblob',
+      name: selectedConnection?.name || defaultName,
+      url: endpoint,
+      username: config.account.trim(), // 使用 username 字段存储账户名
+      password: actualAccountKey,      // 使用 password 字段存储账户密钥
+      bucket: config.container.trim(),
+      endpoint,
+      sasToken: config.sasToken.trim() || undefined,
+    };
+
+    try {
+      await onConnect(connectionConfig);
+    } catch (error) {
+      // 错误由父组件处理
+      console.error('Azure Blob connection failed:', error);
+    }
+  };
+
+  const handleInputChange = (field: string, value: string) => {
+    setConfig(prev => ({ ...prev, [field]: value }));
+    if (errors[field]) {
+      setErrors(prev => ({ ...prev, [field]: '' }));
+    }
+  };
+
+  const inputClassName = (field: string) =>
+    `w-full px-3 py-2 border rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white ${
+      errors[field] ? 'border-red-300 dark:border-red-600' : 'border-gray-300'
+    }`;
+
+  const labelClassName = 'block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1';
+
+  return (
+    <form onSubmit={handleSubmit} className="space-y-4">
+      {externalError && (
+        <div className="bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-md p-3">
+          <p className="text-sm text-red-600 dark:text-red-400">{externalError}</p>
+        </div>
+      )}
+
+      <div>
+        <label htmlFor="azblob-endpoint" className={labelClassName}>
+          {t('oss.endpoint')}
+        </label>
+        <input
+          type="url"
+          id="azblob-endpoint"
+          value={config.endpoint}
+          onChange={(e) => handleInputChange('endpoint', e.target.value)}
+          className={inputClassName('endpoint')}
+          placeholder={t('azblob.endpoint.placeholder')}
+          disabled={connecting}
+        />
+        {errors.endpoint && (
+          <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.endpoint}</p>
+        )}
+        <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+          {t('azblob.endpoint.description')}
+        </p>
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="azblob-account" className={labelClassName}>
+            {t('azblob.account')}
+          </label>
+          <input
+            type="text"
+            id="azblob-account"
+            value={config.account}
+            onChange={(e) => handleInputChange('account', e.target.value)}
+            className={inputClassName('account')}
+            placeholder={t('azblob.account.placeholder')}
+            disabled={connecting}
+          />
+        </div>
+
+        <div>
+          <label htmlFor="azblob-container" className={labelClassName}>
+            {t('azblob.container')}
+          </label>
+          <input
+            type="text"
+            id="azblob-container"
+            value={config.container}
+            onChange={(e) => handleInputChange('container', e.target.value)}
+            className={inputClassName('container')}
+            placeholder={t('azblob.container.placeholder')}
+            disabled={connecting}
+          />
+          {errors.container && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.container}</p>
+          )}
+        </div>
+      </div>
+
+      <div>
+        <label htmlFor="azblob-accountKey" className={labelClassName}>
+          {t('azblob.account.key')}
+        </label>
+        <input
+          type="password"
+          id="azblob-accountKey"
+          value={config.accountKey}
+          onChange={(e) => handleInputChange('accountKey', e.target.value)}
+          className={inputClassName('accountKey')}
+          placeholder={t('azblob.account.key.placeholder')}
+          disabled={connecting}
+        />
+        {errors.accountKey && (
+          <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.accountKey}</p>
+        )}
+      </div>
+
+      <div>
+        <label htmlFor="azblob-sasToken" className={labelClassName}>
+          {t('azblob.sas.token')}
+        </label>
+        <input
+          type="password"
+          id="azblob-sasToken"
+          value={config.sasToken}
+          onChange={(e) => handleInputChange('sasToken', e.target.value)}
+          className={inputClassName('sasToken')}
+          placeholder="sv=...&sig=..."
+          disabled={connecting}
+        />
+        <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+          {t('azblob.sas.token.help')}
+        </p>
+      </div>
+
+      <button
+        type="submit"
+        disabled={connecting}
+        className="w-full bg-indigo-600 hover:bg-indigo-700 disabled:bg-indigo-400
+                 text-white font-medium py-2 px-4 rounded-md transition-colors
+                 focus:outline-none focus:ring-2 focus:ring-indigo-500 focus:ring-offset-2
+                 disabled:cursor-not-allowed flex items-center justify-center"
+      >
+        {connecting ? (
+          <>
+            <svg className="animate-spin -ml-1 mr-2 h-4 w-4 text-white" fill="none" viewBox="0 0 24 24">
+              <circle className="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" strokeWidth="4" />
+              <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4zm2 5.291A7.962 7.962 0 014 12H0c0 3.042 1.135 5.824 3 7.938l3-2.647z" />
+            </svg>
+            {t('connecting')}
+          </>
+        ) : (
+          t('connect')
+        )}
+      </button>
+    </form>
+  );
+};
@@ -6,6 +6,10 @@ import { ConnectionSelector } from './ConnectionSelector';
 import { StorageTypeSelector } from '../StorageTypeSelector';
 import { LocalConnectionForm } from './LocalConnectionForm';
 import { OSSConnectionForm } from './OSSConnectionForm';
+import { S3ConnectionForm } from './S3ConnectionForm';
+import { SFTPConnectionForm } from './SFTPConnectionForm';
+import { FTPConnectionForm } from './FTPConnectionForm';
+import { AzureBlobConnectionForm } from './AzureBlobConnectionForm';
 import { WebDAVConnectionForm } from './WebDAVConnectionForm';
 import { HuggingFaceConnectionForm } from './HuggingFaceConnectionForm';
 
@@ -24,6 +28,10 @@ interface ConnectionFormContainerProps {
   onWebDAVConnect: (e: React.FormEvent) => void;
   onLocalConnect: (rootPath: string) => void;
   onOSSConnect: (config: ConnectionConfig) => Promise<void>;
+  onS3Connect: (config: ConnectionConfig) => Promise<void>;
+  onSFTPConnect: (config: ConnectionConfig) => Promise<void>;
+  onFTPConnect: (config: ConnectionConfig) => Promise<void>;
+  onAzureBlobConnect: (config: ConnectionConfig) => Promise<void>;
   onHuggingFaceConnect: (config: ConnectionConfig) => Promise<void>;
   onUrlChange: (value: string) => void;
   onUsernameChange: (value: string) => void;
@@ -46,6 +54,10 @@ export const ConnectionFormContainer: React.FC<ConnectionFormContainerProps> = (
   onWebDAVConnect,
   onLocalConnect,
   onOSSConnect,
+  onS3Connect,
+  onSFTPConnect,
+  onFTPConnect,
+  onAzureBlobConnect,
   onHuggingFaceConnect,
   onUrlChange,
   onUsernameChange,
@@ -127,6 +139,34 @@ export const ConnectionFormContainer: React.FC<ConnectionFormContainerProps> = (
               error={error}
               selectedConnection={selectedStoredConnection}
             />
+          ) : storageType === 's3' ? (
+            <S3ConnectionForm
+              onConnect={onS3Connect}
+              connecting={connecting}
+              error={error}
+              selectedConnection={selectedStoredConnection}
+            />
+          ) : storageType === 'sftp' ? (
+            <SFTPConnectionForm
+              onConnect={onSFTPConnect}
+              connecting={connecting}
+              error={error}
+              selectedConnection={selectedStoredConnection}
+            />
+          ) : storageType === 'ftp' ? (
+            <FTPConnectionForm
+              onConnect={onFTPConnect}
+              connecting={connecting}
+              error={error}
+              selectedConnection={selectedStoredConnection}
+            />
+          ) : storageType === 'azblob' ? (
+            <AzureBlobConnectionForm
+              onConnect={onAzureBlobConnect}
+              connecting={connecting}
+              error={error}
+              selectedConnection={selectedStoredConnection}
+            />
           ) : storageType === 'huggingface' ? (
             <HuggingFaceConnectionForm
               onConnect={onHuggingFaceConnect}
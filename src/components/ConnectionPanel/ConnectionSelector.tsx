@@ -267,6 +267,10 @@ export const ConnectionSelector: React.FC<ConnectionSelectorProps> = ({
                         ? connection.url.replace('file:///', '')
                         : connection.url.startsWith('oss://')
                         ? `OSS: ${connection.username}`
+                        : connection.url.startsWith('s3://')
+                        ? `S3: ${connection.username}`
+                        : connection.url.startsWith('azblob://')
+                        ? `Azure: ${connection.metadata?.bucket}`
                         : `${connection.username}@${new URL(connection.url).hostname}`}
                     </div>
                     {connection.lastConnected && (
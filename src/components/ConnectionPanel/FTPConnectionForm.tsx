@@ -0,0 +1,281 @@
+import React, { useState, useEffect } from 'react';
+import { useTranslation } from 'react-i18next';
+import { ConnectionConfig, FTPTlsMode } from '../../services/storage/types';
+import { FTPStorageClient } from '../../services/storage/FTPStorageClient';
+import { StoredConnection } from '../../services/connectionStorage';
+
+interface FTPConnectionFormProps {
+  onConnect: (config: ConnectionConfig) => Promise<void>;
+  connecting: boolean;
+  error?: string;
+  selectedConnection?: StoredConnection | null;
+}
+
+const emptyConfig = {
+  tlsMode: 'none' as FTPTlsMode,
+  host: '',
+  port: '21',
+  username: '',
+  password: '',
+  rootPath: '',
+  acceptInvalidCerts: false,
+};
+
+const defaultPort = (mode: FTPTlsMode) => (mode === 'implicit' ? '990' : '21');
+
+/**
+ * FTP 连接表单组件
+ * 支持明文 FTP、显式 TLS（FTPES）和隐式 TLS（FTPS），未填写用户名时匿名登录
+ */
+export const FTPConnectionForm: React.FC<FTPConnectionFormProps> = ({
+  onConnect,
+  connecting,
+  error: externalError,
+  selectedConnection
+}) => {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState(emptyConfig);
+  const [errors, setErrors] = useState<Record<string, string>>({});
+
+  // 当选中连接变化时，更新表单
+  useEffect(() => {
+    if (selectedConnection && FTPStorageClient.isFTPUrl(selectedConnection.url)) {
+      const address = FTPStorageClient.parseAddress(selectedConnection.url);
+
+      setConfig({
+        tlsMode: address.tlsMode,
+        host: address.host,
+        port: String(address.port),
+        username: selectedConnection.username || address.username,
+        password: selectedConnection.password ? '••••••••' : '',
+        rootPath: address.rootPath,
+        acceptInvalidCerts: selectedConnection.metadata?.acceptInvalidCerts || false,
+      });
+    } else if (!selectedConnection) {
+      setConfig(emptyConfig);
+    }
+  }, [selectedConnection]);
+
+  const validateForm = () => {
+    const newErrors: Record<string, string> = {};
+
+    if (!config.host.trim()) {
+      newErrors.host = t('error.host.required');
+    }
+
+    const port = Number(config.port);
+    if (!Number.isInteger(port) || port < 1 || port > 65535) {
+      newErrors.port = t('error.port.invalid');
+    }
+
+    setErrors(newErrors);
+    return Object.keys(newErrors).length === 0;
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!validateForm()) {
+      return;
+    }
+
+    const host = config.host.trim();
+    const hostPart = host.includes(':') ? `[${host}]` : host;
+    const rootPath = config.rootPath.trim() ? config.rootPath.trim().replace(/^\/*/, '/').replace(/\/+$/, '') : '';
+    const username = config.username.trim();
+    const defaultName = username
+      ? t('connection.name.ftp', 'FTP({{user}}@{{host}})', { user: username, host })
+      : t('connection.name.ftp.anonymous', 'FTP({{host}})', { host });
+
+    // 如果密码是占位符（来自已保存的连接），使用真实密码
+    const actualPassword = config.password === '••••••••' && selectedConnection?.password
+      ? selectedConnection.password
+      : config.password;
+
+    const connectionConfig: ConnectionConfig = {
+      type: 'ftp',
+      name: selectedConnection?.name || defaultName,
+      url: `${FTPStorageClient.schemeForTlsMode(config.tlsMode)}://${hostPart}:${config.port}${rootPath}`,
+      username,
+      password: actualPassword,
+      acceptInvalidCerts: config.tlsMode !== 'none' && config.acceptInvalidCerts,
+    };
+
+    try {
+      await onConnect(connectionConfig);
+    } catch (error) {
+      // 错误由父组件处理
+      console.error('FTP connection failed:', error);
+    }
+  };
+
+  const handleInputChange = (field: string, value: string) => {
+    setConfig(prev => ({ ...prev, [field]: value }));
+    if (errors[field]) {
+      setErrors(prev => ({ ...prev, [field]: '' }));
+    }
+  };
+
+  // 切换加密方式时，端口仍为默认值则同步为新方式的默认端口
+  const handleTlsModeChange = (mode: FTPTlsMode) => {
+    setConfig(prev => ({
+      ...prev,
+      tlsMode: mode,
+      port: prev.port === defaultPort(prev.tlsMode) ? defaultPort(mode) : prev.port,
+    }));
+  };
+
+  const inputClassName = (field: string) =>
+    `w-full px-3 py-2 border rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white ${
+      errors[field] ? 'border-red-300 dark:border-red-600' : 'border-gray-300'
+    }`;
+
+  const labelClassName = 'block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1';
+
+  return (
+    <form onSubmit={handleSubmit} className="space-y-4">
+      {externalError && (
+        <div className="bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-md p-3">
+          <p className="text-sm text-red-600 dark:text-red-400">{externalError}</p>
+        </div>
+      )}
+
+      <div>
+        <label htmlFor="ftp-tlsMode" className={labelClassName}>
+          {t('ftp.tls.mode')}
+        </label>
+        <select
+          id="ftp-tlsMode"
+          value={config.tlsMode}
+          onChange={(e) => handleTlsModeChange(e.target.value as FTPTlsMode)}
+          className={inputClassName('tlsMode')}
+          disabled={connecting}
+        >
+          <option value="none">{t('ftp.tls.none')}</option>
+          <option value="explicit">{t('ftp.tls.explicit')}</option>
+          <option value="implicit">{t('ftp.tls.implicit')}</option>
+        </select>
+      </div>
+
+      <div className="grid grid-cols-3 gap-4">
+        <div className="col-span-2">
+          <label htmlFor="ftp-host" className={labelClassName}>
+            {t('sftp.host')}
+          </label>
+          <input
+            type="text"
+            id="ftp-host"
+            value={config.host}
+            onChange={(e) => handleInputChange('host', e.target.value)}
+            className={inputClassName('host')}
+            placeholder={t('ftp.host.placeholder')}
+            disabled={connecting}
+          />
+          {errors.host && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.host}</p>
+          )}
+        </div>
+
+        <div>
+          <label htmlFor="ftp-port" className={labelClassName}>
+            {t('sftp.port')}
+          </label>
+          <input
+            type="number"
+            id="ftp-port"
+            value={config.port}
+            onChange={(e) => handleInputChange('port', e.target.value)}
+            className={inputClassName('port')}
+            min={1}
+            max={65535}
+            disabled={connecting}
+          />
+          {errors.port && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.port}</p>
+          )}
+        </div>
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="ftp-username" className={labelClassName}>
+            {t('username')}
+          </label>
+          <input
+            type="text"
+            id="ftp-username"
+            value={config.username}
+            onChange={(e) => handleInputChange('username', e.target.value)}
+            className={inputClassName('username')}
+            placeholder={t('ftp.username.placeholder')}
+            disabled={connecting}
+          />
+        </div>
+
+        <div>
+          <label htmlFor="ftp-password" className={labelClassName}>
+            {t('password')}
+          </label>
+          <input
+            type="password"
+            id="ftp-password"
+            value={config.password}
+            onChange={(e) => handleInputChange('password', e.target.value)}
+            className={inputClassName('password')}
+            placeholder={t('password.placeholder')}
+            disabled={connecting}
+          />
+        </div>
+      </div>
+
+      <div>
+        <label htmlFor="ftp-rootPath" className={labelClassName}>
+          {t('sftp.root.path')}
+        </label>
+        <input
+          type="text"
+          id="ftp-rootPath"
+          value={config.rootPath}
+          onChange={(e) => handleInputChange('rootPath', e.target.value)}
+          className={inputClassName('rootPath')}
+          placeholder={t('ftp.root.path.placeholder')}
+          disabled={connecting}
+        />
+      </div>
+
+      {config.tlsMode !== 'none' && (
+        <label className="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+          <input
+            type="checkbox"
+            checked={config.acceptInvalidCerts}
+            onChange={(e) => setConfig(prev => ({ ...prev, acceptInvalidCerts: e.target.checked }))}
+            className="rounded border-gray-300 text-indigo-600 focus:ring-indigo-500"
+            disabled={connecting}
+          />
+          <span>{t('ftp.accept.invalid.certs')}</span>
+        </label>
+      )}
+
+      <button
+        type="submit"
+        disabled={connecting}
+        className="w-full bg-indigo-600 hover:bg-indigo-700 disabled:bg-indigo-400
+                 text-white font-medium py-2 px-4 rounded-md transition-colors
+                 focus:outline-none focus:ring-2 focus:ring-indigo-500 focus:ring-offset-2
+                 disabled:cursor-not-allowed flex items-center justify-center"
+      >
+        {connecting ? (
+          <>
+            <svg className="animate-spin -ml-1 mr-2 h-4 w-4 text-white" fill="none" viewBox="0 0 24 24">
+              <circle className="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" strokeWidth="4" />
+              <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4zm2 5.291A7.962 7.962 0 014 12H0c0 3.042 1.135 5.824 3 7.938l3-2.647z" />
+            </svg>
+            {t('connecting')}
+          </>
+        ) : (
+          t('connect')
+        )}
+      </button>
+    </form>
+  );
+};
@@ -1,6 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import { useTranslation } from 'react-i18next';
-import { ConnectionConfig } from '../../services/storage/types';
+import { ConnectionConfig, OSSSignatureVersion } from '../../services/storage/types';
 import { StoredConnection } from '../../services/connectionStorage';
 
 interface OSSConnectionFormProps {
@@ -27,6 +27,8 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
     secretKey: '',
     bucket: '',
     region: '',
+    sessionToken: '',
+    signatureVersion: 'v1' as OSSSignatureVersion,
   });
 
   const [errors, setErrors] = useState<Record<string, string>>({});
@@ -52,6 +54,8 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
           secretKey: selectedConnection.password ? '••••••••' : '',
           bucket: bucket || '',
           region: region,
+          sessionToken: selectedConnection.metadata?.sessionToken ? '••••••••' : '',
+          signatureVersion: selectedConnection.metadata?.signatureVersion || 'v1',
         });
       } catch (error) {
         console.error('Failed to parse OSS connection:', error);
@@ -62,6 +66,8 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
           secretKey: selectedConnection.password ? '••••••••' : '',
           bucket: '',
           region: '',
+          sessionToken: '',
+          signatureVersion: 'v1',
         });
       }
     } else if (!selectedConnection) {
@@ -72,6 +78,8 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
         secretKey: '',
         bucket: '',
         region: '',
+        sessionToken: '',
+        signatureVersion: 'v1',
       });
     }
   }, [selectedConnection]);
@@ -124,6 +132,10 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
       ? selectedConnection.password
       : config.secretKey;
 
+    const actualSessionToken = config.sessionToken === '••••••••'
+      ? selectedConnection?.metadata?.sessionToken
+      : config.sessionToken.trim();
+
     const connectionConfig: ConnectionConfig = {
       type: 'oss',
       name: selectedConnection?.name || defaultName,
@@ -133,6 +145,8 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
       bucket: config.bucket,      // 添加 bucket 字段
       region: config.region || 'cn-hangzhou', // 添加 region 字段，有默认值
       endpoint: config.endpoint,  // 添加 endpoint 字段
+      sessionToken: actualSessionToken || undefined, // STS 临时凭证的安全令牌
+      signatureVersion: config.signatureVersion,
     };
 
     try {
@@ -266,6 +280,40 @@ export const OSSConnectionForm: React.FC<OSSConnectionFormProps> = ({
           </div>
         </div>
 
+        <div>
+          <label htmlFor="sessionToken" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.security.token')}
+          </label>
+          <input
+            type="password"
+            id="sessionToken"
+            value={config.sessionToken}
+            onChange={(e) => handleInputChange('sessionToken', e.target.value)}
+            className="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white"
+            placeholder={t('oss.security.token.placeholder')}
+            disabled={connecting}
+          />
+          <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+            {t('oss.security.token.help')}
+          </p>
+        </div>
+
+        <div>
+          <label htmlFor="signatureVersion" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.signature.version')}
+          </label>
+          <select
+            id="signatureVersion"
+            value={config.signatureVersion}
+            onChange={(e) => handleInputChange('signatureVersion', e.target.value)}
+            className="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white"
+            disabled={connecting}
+          >
+            <option value="v1">{t('oss.signature.v1')}</option>
+            <option value="v4">{t('oss.signature.v4')}</option>
+          </select>
+        </div>
+
         <button
           type="submit"
           disabled={connecting}
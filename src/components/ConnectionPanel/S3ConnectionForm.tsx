@@ -0,0 +1,275 @@
+import React, { useState, useEffect } from 'react';
+import { useTranslation } from 'react-i18next';
+import { ConnectionConfig, S3AddressingStyle } from '../../services/storage/types';
+import { StoredConnection } from '../../services/connectionStorage';
+
+interface S3ConnectionFormProps {
+  onConnect: (config: ConnectionConfig) => Promise<void>;
+  connecting: boolean;
+  error?: string;
+  selectedConnection?: StoredConnection | null;
+}
+
+const emptyConfig = {
+  endpoint: '',
+  accessKey: '',
+  secretKey: '',
+  bucket: '',
+  region: '',
+  addressingStyle: '' as S3AddressingStyle | '',
+};
+
+/**
+ * S3 连接表单组件
+ * 支持 AWS S3、MinIO、Ceph RGW 等 S3 兼容的对象存储服务
+ */
+export const S3ConnectionForm: React.FC<S3ConnectionFormProps> = ({
+  onConnect,
+  connecting,
+  error: externalError,
+  selectedConnection
+}) => {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState(emptyConfig);
+  const [errors, setErrors] = useState<Record<string, string>>({});
+
+  // 当选中连接变化时，更新表单
+  useEffect(() => {
+    if (selectedConnection && selectedConnection.url.startsWith('s3://')) {
+      const metadata = selectedConnection.metadata;
+      const [host, bucket] = selectedConnection.url.replace('s3://', '').split('/');
+
+      setConfig({
+        endpoint: metadata?.endpoint || `https://${host}`,
+        accessKey: selectedConnection.username,
+        secretKey: selectedConnection.password ? '••••••••' : '',
+        bucket: metadata?.bucket || bucket || '',
+        region: metadata?.region || '',
+        addressingStyle: metadata?.addressingStyle || '',
+      });
+    } else if (!selectedConnection) {
+      setConfig(emptyConfig);
+    }
+  }, [selectedConnection]);
+
+  const validateForm = () => {
+    const newErrors: Record<string, string> = {};
+
+    if (!config.endpoint.trim()) {
+      newErrors.endpoint = t('error.endpoint.required');
+    } else {
+      try {
+        new URL(config.endpoint);
+      } catch {
+        newErrors.endpoint = t('error.endpoint.invalid');
+      }
+    }
+
+    if (!config.accessKey.trim()) {
+      newErrors.accessKey = t('error.access.key.required');
+    }
+
+    if (!config.secretKey.trim()) {
+      newErrors.secretKey = t('error.secret.key.required');
+    }
+
+    if (!config.bucket.trim()) {
+      newErrors.bucket = t('error.bucket.required');
+    }
+
+    setErrors(newErrors);
+    return Object.keys(newErrors).length === 0;
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!validateForm()) {
+      return;
+    }
+
+    const endpoint = config.endpoint.trim();
+    const defaultName = t('connection.name.s3', 'S3({{host}}-{{bucket}})', {
+      host: new URL(endpoint).host,
+      bucket: config.bucket
+    });
+
+    // 如果密码是占位符（来自已保存的连接），使用真实密码
+    const actualSecretKey = config.secretKey === '••••••••' && selectedConnection?.password
+      ? selectedConnection.password
+      : config.secretKey;
+
+    const connectionConfig: ConnectionConfig = {
+      type: 's3',
+      name: selectedConnection?.name || defaultName,
+      url: endpoint,
+      username: config.accessKey, // 使用 username 字段存储 accessKey
+      password: actualSecretKey,  // 使用 password 字段存储 secretKey
+      bucket: config.bucket.trim(),
+      region: config.region.trim() || undefined,
+      endpoint,
+      addressingStyle: config.addressingStyle || undefined,
+    };
+
+    try {
+      await onConnect(connectionConfig);
+    } catch (error) {
+      // 错误由父组件处理
+      console.error('S3 connection failed:', error);
+    }
+  };
+
+  const handleInputChange = (field: string, value: string) => {
+    setConfig(prev => ({ ...prev, [field]: value }));
+    if (errors[field]) {
+      setErrors(prev => ({ ...prev, [field]: '' }));
+    }
+  };
+
+  const inputClassName = (field: string) =>
+    `w-full px-3 py-2 border rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white ${
+      errors[field] ? 'border-red-300 dark:border-red-600' : 'border-gray-300'
+    }`;
+
+  return (
+    <form onSubmit={handleSubmit} className="space-y-4">
+      {externalError && (
+        <div className="bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-md p-3">
+          <p className="text-sm text-red-600 dark:text-red-400">{externalError}</p>
+        </div>
+      )}
+
+      <div>
+        <label htmlFor="s3-endpoint" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+          {t('oss.endpoint')}
+        </label>
+        <input
+          type="url"
+          id="s3-endpoint"
+          value={config.endpoint}
+          onChange={(e) => handleInputChange('endpoint', e.target.value)}
+          className={inputClassName('endpoint')}
+          placeholder={t('s3.endpoint.placeholder')}
+          disabled={connecting}
+        />
+        {errors.endpoint && (
+          <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.endpoint}</p>
+        )}
+        <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+          {t('s3.endpoint.description')}
+        </p>
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="s3-accessKey" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.access.key')}
+          </label>
+          <input
+            type="text"
+            id="s3-accessKey"
+            value={config.accessKey}
+            onChange={(e) => handleInputChange('accessKey', e.target.value)}
+            className={inputClassName('accessKey')}
+            placeholder={t('oss.access.key.placeholder')}
+            disabled={connecting}
+          />
+          {errors.accessKey && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.accessKey}</p>
+          )}
+        </div>
+
+        <div>
+          <label htmlFor="s3-secretKey" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.secret.key')}
+          </label>
+          <input
+            type="password"
+            id="s3-secretKey"
+            value={config.secretKey}
+            onChange={(e) => handleInputChange('secretKey', e.target.value)}
+            className={inputClassName('secretKey')}
+            placeholder={t('oss.secret.key.placeholder')}
+            disabled={connecting}
+          />
+          {errors.secretKey && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.secretKey}</p>
+          )}
+        </div>
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="s3-bucket" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.bucket')}
+          </label>
+          <input
+            type="text"
+            id="s3-bucket"
+            value={config.bucket}
+            onChange={(e) => handleInputChange('bucket', e.target.value)}
+            className={inputClassName('bucket')}
+            placeholder={t('oss.bucket.placeholder')}
+            disabled={connecting}
+          />
+          {errors.bucket && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.bucket}</p>
+          )}
+        </div>
+
+        <div>
+          <label htmlFor="s3-region" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.region.optional')}
+          </label>
+          <input
+            type="text"
+            id="s3-region"
+            value={config.region}
+            onChange={(e) => handleInputChange('region', e.target.value)}
+            className={inputClassName('region')}
+            placeholder={t('s3.region.placeholder')}
+            disabled={connecting}
+          />
+        </div>
+      </div>
+
+      <div>
+        <label htmlFor="s3-addressingStyle" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+          {t('s3.addressing.style')}
+        </label>
+        <select
+          id="s3-addressingStyle"
+          value={config.addressingStyle}
+          onChange={(e) => handleInputChange('addressingStyle', e.target.value)}
+          className={inputClassName('addressingStyle')}
+          disabled={connecting}
+        >
+          <option value="">{t('s3.addressing.auto')}</option>
+          <option value="path">{t('s3.addressing.path')}</option>
+          <option value="virtual">{t('s3.addressing.virtual')}</option>
+        </select>
+      </div>
+
+      <button
+        type="submit"
+        disabled={connecting}
+        className="w-full bg-indigo-600 hover:bg-indigo-700 disabled:bg-indigo-400
+                 text-white font-medium py-2 px-4 rounded-md transition-colors
+                 focus:outline-none focus:ring-2 focus:ring-indigo-500 focus:ring-offset-2
+                 disabled:cursor-not-allowed flex items-center justify-center"
+      >
+        {connecting ? (
+          <>
+            <svg className="animate-spin -ml-1 mr-2 h-4 w-4 text-white" fill="none" viewBox="0 0 24 24">
+              <circle className="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" strokeWidth="4" />
+              <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4zm2 5.291A7.962 7.962 0 014 12H0c0 3.042 1.135 5.824 3 7.938l3-2.647z" />
+            </svg>
+            {t('connecting')}
+          </>
+        ) : (
+          t('connect')
+        )}
+      </button>
+    </form>
+  );
+};
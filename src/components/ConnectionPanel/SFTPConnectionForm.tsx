@@ -0,0 +1,326 @@
+import React, { useState, useEffect } from 'react';
+import { useTranslation } from 'react-i18next';
+import { ConnectionConfig, SFTPHostKeyPolicy } from '../../services/storage/types';
+import { SFTPStorageClient } from '../../services/storage/SFTPStorageClient';
+import { StoredConnection } from '../../services/connectionStorage';
+
+interface SFTPConnectionFormProps {
+  onConnect: (config: ConnectionConfig) => Promise<void>;
+  connecting: boolean;
+  error?: string;
+  selectedConnection?: StoredConnection | null;
+}
+
+const emptyConfig = {
+  host: '',
+  port: '22',
+  username: '',
+  password: '',
+  rootPath: '',
+  privateKey: '',
+  privateKeyPassphrase: '',
+  knownHosts: '',
+  hostKeyPolicy: 'strict' as SFTPHostKeyPolicy,
+};
+
+/**
+ * SFTP 连接表单组件
+ * 支持密码和私钥认证，主机密钥按 known_hosts 校验
+ */
+export const SFTPConnectionForm: React.FC<SFTPConnectionFormProps> = ({
+  onConnect,
+  connecting,
+  error: externalError,
+  selectedConnection
+}) => {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState(emptyConfig);
+  const [errors, setErrors] = useState<Record<string, string>>({});
+
+  // 当选中连接变化时，更新表单
+  useEffect(() => {
+    if (selectedConnection && selectedConnection.url.startsWith('sftp://')) {
+      const metadata = selectedConnection.metadata;
+      const address = SFTPStorageClient.parseAddress(selectedConnection.url);
+
+      setConfig({
+        host: address.host,
+        port: String(address.port),
+        username: selectedConnection.username || address.username,
+        password: selectedConnection.password ? '••••••••' : '',
+        rootPath: address.rootPath,
+        privateKey: metadata?.privateKey || '',
+        privateKeyPassphrase: metadata?.privateKeyPassphrase || '',
+        knownHosts: metadata?.knownHosts || '',
+        hostKeyPolicy: metadata?.hostKeyPolicy || 'strict',
+      });
+    } else if (!selectedConnection) {
+      setConfig(emptyConfig);
+    }
+  }, [selectedConnection]);
+
+  const validateForm = () => {
+    const newErrors: Record<string, string> = {};
+
+    if (!config.host.trim()) {
+      newErrors.host = t('error.host.required');
+    }
+
+    const port = Number(config.port);
+    if (!Number.isInteger(port) || port < 1 || port > 65535) {
+      newErrors.port = t('error.port.invalid');
+    }
+
+    if (!config.username.trim()) {
+      newErrors.username = t('error.username.required');
+    }
+
+    if (!config.password && !config.privateKey.trim()) {
+      newErrors.password = t('error.sftp.auth.required');
+    }
+
+    setErrors(newErrors);
+    return Object.keys(newErrors).length === 0;
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!validateForm()) {
+      return;
+    }
+
+    const host = config.host.trim();
+    const hostPart = host.includes(':') ? `[${host}]` : host;
+    const rootPath = config.rootPath.trim().replace(/^\/*/, '/').replace(/\/+$/, '');
+    const defaultName = t('connection.name.sftp', 'SFTP({{user}}@{{host}})', {
+      user: config.username.trim(),
+      host
+    });
+
+    // 如果密码是占位符（来自已保存的连接），使用真实密码
+    const actualPassword = config.password === '••••••••' && selectedConnection?.password
+      ? selectedConnection.password
+      : config.password;
+
+    const connectionConfig: ConnectionConfig = {
+      type: 'sftp',
+      name: selectedConnection?.name || defaultName,
+      url: `sftp://${hostPart}:${config.port}${rootPath}`,
+      username: config.username.trim(),
+      password: actualPassword,
+      privateKey: config.privateKey.trim() || undefined,
+      privateKeyPassphrase: config.privateKeyPassphrase || undefined,
+      knownHosts: config.knownHosts.trim() || undefined,
+      hostKeyPolicy: config.hostKeyPolicy,
+    };
+
+    try {
+      await onConnect(connectionConfig);
+    } catch (error) {
+      // 错误由父组件处理
+      console.error('SFTP connection failed:', error);
+    }
+  };
+
+  const handleInputChange = (field: string, value: string) => {
+    setConfig(prev => ({ ...prev, [field]: value }));
+    if (errors[field]) {
+      setErrors(prev => ({ ...prev, [field]: '' }));
+    }
+  };
+
+  const inputClassName = (field: string) =>
+    `w-full px-3 py-2 border rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white ${
+      errors[field] ? 'border-red-300 dark:border-red-600' : 'border-gray-300'
+    }`;
+
+  const labelClassName = 'block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1';
+
+  return (
+    <form onSubmit={handleSubmit} className="space-y-4">
+      {externalError && (
+        <div className="bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-md p-3">
+          <p className="text-sm text-red-600 dark:text-red-400">{externalError}</p>
+        </div>
+      )}
+
+      <div className="grid grid-cols-3 gap-4">
+        <div className="col-span-2">
+          <label htmlFor="sftp-host" className={labelClassName}>
+            {t('sftp.host')}
+          </label>
+          <input
+            type="text"
+            id="sftp-host"
+            value={config.host}
+            onChange={(e) => handleInputChange('host', e.target.value)}
+            className={inputClassName('host')}
+            placeholder={t('sftp.host.placeholder')}
+            disabled={connecting}
+          />
+          {errors.host && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.host}</p>
+          )}
+        </div>
+
+        <div>
+          <label htmlFor="sftp-port" className={labelClassName}>
+            {t('sftp.port')}
+          </label>
+          <input
+            type="number"
+            id="sftp-port"
+            value={config.port}
+            onChange={(e) => handleInputChange('port', e.target.value)}
+            className={inputClassName('port')}
+            min={1}
+            max={65535}
+            disabled={connecting}
+          />
+          {errors.port && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.port}</p>
+          )}
+        </div>
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="sftp-username" className={labelClassName}>
+            {t('username')}
+          </label>
+          <input
+            type="text"
+            id="sftp-username"
+            value={config.username}
+            onChange={(e) => handleInputChange('username', e.target.value)}
+            className={inputClassName('username')}
+            placeholder={t('username.placeholder')}
+            disabled={connecting}
+          />
+          {errors.username && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.username}</p>
+          )}
+        </div>
+
+        <div>
+          <label htmlFor="sftp-password" className={labelClassName}>
+            {t('password')}
+          </label>
+          <input
+            type="password"
+            id="sftp-password"
+            value={config.password}
+            onChange={(e) => handleInputChange('password', e.target.value)}
+            className={inputClassName('password')}
+            placeholder={t('sftp.password.placeholder')}
+            disabled={connecting}
+          />
+          {errors.password && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">{errors.password}</p>
+          )}
+        </div>
+      </div>
+
+      <div>
+        <label htmlFor="sftp-rootPath" className={labelClassName}>
+          {t('sftp.root.path')}
+        </label>
+        <input
+          type="text"
+          id="sftp-rootPath"
+          value={config.rootPath}
+          onChange={(e) => handleInputChange('rootPath', e.target.value)}
+          className={inputClassName('rootPath')}
+          placeholder={t('sftp.root.path.placeholder')}
+          disabled={connecting}
+        />
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="sftp-privateKey" className={labelClassName}>
+            {t('sftp.private.key')}
+          </label>
+          <input
+            type="text"
+            id="sftp-privateKey"
+            value={config.privateKey}
+            onChange={(e) => handleInputChange('privateKey', e.target.value)}
+            className={inputClassName('privateKey')}
+            placeholder={t('sftp.private.key.placeholder')}
+            disabled={connecting}
+          />
+        </div>
+
+        <div>
+          <label htmlFor="sftp-privateKeyPassphrase" className={labelClassName}>
+            {t('sftp.private.key.passphrase')}
+          </label>
+          <input
+            type="password"
+            id="sftp-privateKeyPassphrase"
+            value={config.privateKeyPassphrase}
+            onChange={(e) => handleInputChange('privateKeyPassphrase', e.target.value)}
+            className={inputClassName('privateKeyPassphrase')}
+            disabled={connecting}
+          />
+        </div>
+      </div>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        <div>
+          <label htmlFor="sftp-knownHosts" className={labelClassName}>
+            {t('sftp.known.hosts')}
+          </label>
+          <input
+            type="text"
+            id="sftp-knownHosts"
+            value={config.knownHosts}
+            onChange={(e) => handleInputChange('knownHosts', e.target.value)}
+            className={inputClassName('knownHosts')}
+            placeholder="~/.ssh/known_hosts"
+            disabled={connecting}
+          />
+        </div>
+
+        <div>
+          <label htmlFor="sftp-hostKeyPolicy" className={labelClassName}>
+            {t('sftp.host.key.policy')}
+          </label>
+          <select
+            id="sftp-hostKeyPolicy"
+            value={config.hostKeyPolicy}
+            onChange={(e) => handleInputChange('hostKeyPolicy', e.target.value)}
+            className={inputClassName('hostKeyPolicy')}
+            disabled={connecting}
+          >
+            <option value="strict">{t('sftp.host.key.policy.strict')}</option>
+            <option value="accept_new">{t('sftp.host.key.policy.accept_new')}</option>
+          </select>
+        </div>
+      </div>
+
+      <button
+        type="submit"
+        disabled={connecting}
+        className="w-full bg-indigo-600 hover:bg-indigo-700 disabled:bg-indigo-400
+                 text-white font-medium py-2 px-4 rounded-md transition-colors
+                 focus:outline-none focus:ring-2 focus:ring-indigo-500 focus:ring-offset-2
+                 disabled:cursor-not-allowed flex items-center justify-center"
+      >
+        {connecting ? (
+          <>
+            <svg className="animate-spin -ml-1 mr-2 h-4 w-4 text-white" fill="none" viewBox="0 0 24 24">
+              <circle className="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" strokeWidth="4" />
+              <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4zm2 5.291A7.962 7.962 0 014 12H0c0 3.042 1.135 5.824 3 7.938l3-2.647z" />
+            </svg>
+            {t('connecting')}
+          </>
+        ) : (
+          t('connect')
+        )}
+      </button>
+    </form>
+  );
+};
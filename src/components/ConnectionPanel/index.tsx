@@ -40,6 +40,10 @@ export const ConnectionPanel: React.FC<ConnectionPanelProps> = ({ onConnect }) =
             onWebDAVConnect={connectionLogic.handleWebDAVConnect}
             onLocalConnect={connectionLogic.handleLocalConnect}
             onOSSConnect={connectionLogic.handleOSSConnect}
+            onS3Connect={connectionLogic.handleS3Connect}
+            onSFTPConnect={connectionLogic.handleSFTPConnect}
+            onFTPConnect={connectionLogic.handleFTPConnect}
+            onAzureBlobConnect={connectionLogic.handleAzureBlobConnect}
             onHuggingFaceConnect={connectionLogic.handleHuggingFaceConnect}
             onUrlChange={connectionLogic.handleUrlChange}
             onUsernameChange={connectionLogic.handleUsernameChange}
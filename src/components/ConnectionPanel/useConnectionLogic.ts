@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { useTranslation } from 'react-i18next';
 import { StorageClientType, ConnectionConfig } from '../../services/storage/types';
-import { StorageServiceManager } from '../../services/storage';
+import { StorageServiceManager, FTPStorageClient } from '../../services/storage';
 import { StoredConnection } from '../../services/connectionStorage';
 
 export const useConnectionLogic = (onConnect: () => void) => {
@@ -53,6 +53,18 @@ export const useConnectionLogic = (onConnect: () => void) => {
         } else if (defaultConnection.url.startsWith('oss://')) {
           setStorageType('oss');
           handleSelectStoredConnection(defaultConnection);
+        } else if (defaultConnection.url.startsWith('s3://')) {
+          setStorageType('s3');
+          handleSelectStoredConnection(defaultConnection);
+        } else if (defaultConnection.url.startsWith('sftp://')) {
+          setStorageType('sftp');
+          handleSelectStoredConnection(defaultConnection);
+        } else if (FTPStorageClient.isFTPUrl(defaultConnection.url)) {
+          setStorageType('ftp');
+          handleSelectStoredConnection(defaultConnection);
+        } else if (defaultConnection.url.startsWith('azblob://')) {
+          setStorageType('azblob');
+          handleSelectStoredConnection(defaultConnection);
         } else if (defaultConnection.url.startsWith('huggingface://')) {
           setStorageType('huggingface');
           handleSelectStoredConnection(defaultConnection);
@@ -76,6 +88,14 @@ export const useConnectionLogic = (onConnect: () => void) => {
       setDefaultLocalPath(localPath);
     } else if (connection.url.startsWith('oss://')) {
       setStorageType('oss');
+    } else if (connection.url.startsWith('s3://')) {
+      setStorageType('s3');
+    } else if (connection.url.startsWith('sftp://')) {
+      setStorageType('sftp');
+    } else if (FTPStorageClient.isFTPUrl(connection.url)) {
+      setStorageType('ftp');
+    } else if (connection.url.startsWith('azblob://')) {
+      setStorageType('azblob');
     } else if (connection.url.startsWith('huggingface://')) {
       setStorageType('huggingface');
     } else {
@@ -129,6 +149,22 @@ export const useConnectionLogic = (onConnect: () => void) => {
     await handleConnect(config);
   };
 
+  const handleS3Connect = async (config: ConnectionConfig) => {
+    await handleConnect(config);
+  };
+
+  const handleSFTPConnect = async (config: ConnectionConfig) => {
+    await handleConnect(config);
+  };
+
+  const handleFTPConnect = async (config: ConnectionConfig) => {
+    await handleConnect(config);
+  };
+
+  const handleAzureBlobConnect = async (config: ConnectionConfig) => {
+    await handleConnect(config);
+  };
+
   const handleHuggingFaceConnect = async (config: ConnectionConfig) => {
     await handleConnect(config);
   };
@@ -158,7 +194,7 @@ export const useConnectionLogic = (onConnect: () => void) => {
     setError('');
 
     if (type === 'webdav') {
-      if (selectedStoredConnection && !selectedStoredConnection.url.startsWith('file:///') && !selectedStoredConnection.url.startsWith('oss://') && !selectedStoredConnection.url.startsWith('huggingface://')) {
+      if (selectedStoredConnection && !selectedStoredConnection.url.startsWith('file:///') && !selectedStoredConnection.url.startsWith('oss://') && !selectedStoredConnection.url.startsWith('s3://') && !selectedStoredConnection.url.startsWith('sftp://') && !FTPStorageClient.isFTPUrl(selectedStoredConnection.url) && !selectedStoredConnection.url.startsWith('azblob://') && !selectedStoredConnection.url.startsWith('huggingface://')) {
         // 保持 WebDAV 连接选择
       } else {
         setSelectedStoredConnection(null);
@@ -184,6 +220,22 @@ export const useConnectionLogic = (onConnect: () => void) => {
       if (!selectedStoredConnection || !selectedStoredConnection.url.startsWith('oss://')) {
         setSelectedStoredConnection(null);
       }
+    } else if (type === 's3') {
+      if (!selectedStoredConnection || !selectedStoredConnection.url.startsWith('s3://')) {
+        setSelectedStoredConnection(null);
+      }
+    } else if (type === 'sftp') {
+      if (!selectedStoredConnection || !selectedStoredConnection.url.startsWith('sftp://')) {
+        setSelectedStoredConnection(null);
+      }
+    } else if (type === 'ftp') {
+      if (!selectedStoredConnection || !FTPStorageClient.isFTPUrl(selectedStoredConnection.url)) {
+        setSelectedStoredConnection(null);
+      }
+    } else if (type === 'azblob') {
+      if (!selectedStoredConnection || !selectedStoredConnection.url.startsWith('azblob://')) {
+        setSelectedStoredConnection(null);
+      }
     } else if (type === 'huggingface') {
       if (!selectedStoredConnection || !selectedStoredConnection.url.startsWith('huggingface://')) {
         setSelectedStoredConnection(null);
@@ -242,6 +294,10 @@ export const useConnectionLogic = (onConnect: () => void) => {
     handleWebDAVConnect,
     handleLocalConnect,
     handleOSSConnect,
+    handleS3Connect,
+    handleSFTPConnect,
+    handleFTPConnect,
+    handleAzureBlobConnect,
     handleHuggingFaceConnect,
     handleUrlChange,
     handleUsernameChange,
@@ -0,0 +1,157 @@
+import React, { useState, useEffect } from 'react';
+import { KeyRound } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { StorageServiceManager } from '../services/storage';
+import { CREDENTIALS_EXPIRED_EVENT } from '../services/storage/BaseStorageClient';
+
+/**
+ * 临时凭证过期时弹出的凭证刷新对话框
+ * 后端返回 storage.credentials.expired 后输入新的 STS 凭证，替换当前连接的凭证而不断开连接
+ */
+export const CredentialsRefreshDialog: React.FC = () => {
+  const { t } = useTranslation();
+  const [isOpen, setIsOpen] = useState(false);
+  const [accessKey, setAccessKey] = useState('');
+  const [secretKey, setSecretKey] = useState('');
+  const [sessionToken, setSessionToken] = useState('');
+  const [updating, setUpdating] = useState(false);
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    const handleExpired = () => {
+      setError('');
+      setIsOpen(true);
+    };
+
+    window.addEventListener(CREDENTIALS_EXPIRED_EVENT, handleExpired);
+    return () => {
+      window.removeEventListener(CREDENTIALS_EXPIRED_EVENT, handleExpired);
+    };
+  }, []);
+
+  const handleClose = () => {
+    setIsOpen(false);
+    setAccessKey('');
+    setSecretKey('');
+    setSessionToken('');
+    setError('');
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!accessKey.trim() || !secretKey.trim()) {
+      return;
+    }
+
+    setUpdating(true);
+    setError('');
+    try {
+      await StorageServiceManager.updateCredentials({
+        accessKey: accessKey.trim(),
+        secretKey: secretKey.trim(),
+        sessionToken: sessionToken.trim() || undefined,
+      });
+      handleClose();
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      setError(t('credentials.refresh.failed', { error: message }));
+    } finally {
+      setUpdating(false);
+    }
+  };
+
+  if (!isOpen) {
+    return null;
+  }
+
+  const inputClassName = 'w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white';
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50 p-4">
+      <form
+        onSubmit={handleSubmit}
+        className="bg-white dark:bg-gray-800 rounded-lg shadow-xl p-6 w-full max-w-md space-y-4"
+      >
+        <div className="flex items-center space-x-2">
+          <KeyRound className="w-5 h-5 text-indigo-600 dark:text-indigo-400" />
+          <h3 className="font-semibold text-gray-900 dark:text-white">
+            {t('credentials.refresh.title')}
+          </h3>
+        </div>
+
+        <p className="text-sm text-gray-600 dark:text-gray-300">
+          {t('storage.credentials.expired')}
+        </p>
+
+        {error && (
+          <div className="bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-md p-3">
+            <p className="text-sm text-red-600 dark:text-red-400">{error}</p>
+          </div>
+        )}
+
+        <div>
+          <label htmlFor="refresh-accessKey" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.access.key')}
+          </label>
+          <input
+            type="text"
+            id="refresh-accessKey"
+            value={accessKey}
+            onChange={(e) => setAccessKey(e.target.value)}
+            className={inputClassName}
+            placeholder={t('oss.access.key.placeholder')}
+            disabled={updating}
+          />
+        </div>
+
+        <div>
+          <label htmlFor="refresh-secretKey" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.secret.key')}
+          </label>
+          <input
+            type="password"
+            id="refresh-secretKey"
+            value={secretKey}
+            onChange={(e) => setSecretKey(e.target.value)}
+            className={inputClassName}
+            placeholder={t('oss.secret.key.placeholder')}
+            disabled={updating}
+          />
+        </div>
+
+        <div>
+          <label htmlFor="refresh-sessionToken" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            {t('oss.security.token')}
+          </label>
+          <input
+            type="password"
+            id="refresh-sessionToken"
+            value={sessionToken}
+            onChange={(e) => setSessionToken(e.target.value)}
+            className={inputClassName}
+            placeholder={t('oss.security.token.placeholder')}
+            disabled={updating}
+          />
+        </div>
+
+        <div className="flex justify-end space-x-2">
+          <button
+            type="button"
+            onClick={handleClose}
+            disabled={updating}
+            className="px-4 py-2 text-sm text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700 rounded-md"
+          >
+            {t('cancel')}
+          </button>
+          <button
+            type="submit"
+            disabled={updating || !accessKey.trim() || !secretKey.trim()}
+            className="px-4 py-2 text-sm bg-indigo-600 hover:bg-indigo-700 disabled:bg-indigo-400 text-white font-medium rounded-md disabled:cursor-not-allowed"
+          >
+            {updating ? t('connecting') : t('credentials.refresh.submit')}
+          </button>
+        </div>
+      </form>
+    </div>
+  );
+};
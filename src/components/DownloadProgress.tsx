@@ -5,6 +5,7 @@ import { revealItemInDir } from '@tauri-apps/plugin-opener';
 import { X, Download, Check, AlertCircle, StopCircle, FolderOpen } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import { formatFileSize } from '../utils/fileUtils';
+import { translateBackendError } from '../utils/errorTranslation';
 
 interface DownloadProgressProps {
   isVisible: boolean;
@@ -21,16 +22,9 @@ interface DownloadState {
   error?: string;
 }
 
-// 错误信息翻译辅助函数
-const translateDownloadError = (error: string, t: (key: string) => string): string => {
-  // 检查是否是翻译键（以字母开头，包含点号）
-  if (error.match(/^[a-zA-Z][a-zA-Z0-9.]+$/)) {
-    return t(error);
-  }
-  
-  // 否则返回原始错误信息
-  return error;
-};
+// 错误信息翻译辅助函数（翻译键可带 JSON 参数）
+const translateDownloadError = (error: string, t: (key: string, options?: Record<string, unknown>) => string): string =>
+  translateBackendError(error, t);
 
 export const DownloadProgress: React.FC<DownloadProgressProps> = ({ isVisible, onClose }) => {
   const { t } = useTranslation();
@@ -2,7 +2,7 @@ import React, { useState, useEffect, useRef } from 'react';
 import { useTranslation } from 'react-i18next';
 import { ChevronDown, Check, Star, Loader, AlertCircle } from 'lucide-react';
 import { StoredConnection } from '../../services/connectionStorage';
-import { StorageServiceManager } from '../../services/storage';
+import { StorageServiceManager, FTPStorageClient } from '../../services/storage';
 
 interface ConnectionSwitcherProps {
   onConnectionChange?: () => void;
@@ -67,6 +67,10 @@ export const ConnectionSwitcher: React.FC<ConnectionSwitcherProps> = ({
       const config = {
         type: isLocal ? 'local' as const :
               connection.url?.startsWith('oss://') ? 'oss' as const :
+              connection.url?.startsWith('s3://') ? 's3' as const :
+              connection.url?.startsWith('sftp://') ? 'sftp' as const :
+              connection.url && FTPStorageClient.isFTPUrl(connection.url) ? 'ftp' as const :
+              connection.url?.startsWith('azblob://') ? 'azblob' as const :
               connection.metadata?.organization ? 'huggingface' as const :
               'webdav' as const,
         url: isLocal ? undefined : connection.url,
@@ -77,6 +81,15 @@ export const ConnectionSwitcher: React.FC<ConnectionSwitcherProps> = ({
         bucket: connection.metadata?.bucket,
         region: connection.metadata?.region,
         endpoint: connection.metadata?.endpoint,
+        sessionToken: connection.metadata?.sessionToken,
+        signatureVersion: connection.metadata?.signatureVersion,
+        addressingStyle: connection.metadata?.addressingStyle,
+        privateKey: connection.metadata?.privateKey,
+        privateKeyPassphrase: connection.metadata?.privateKeyPassphrase,
+        knownHosts: connection.metadata?.knownHosts,
+        hostKeyPolicy: connection.metadata?.hostKeyPolicy,
+        acceptInvalidCerts: connection.metadata?.acceptInvalidCerts,
+        sasToken: connection.metadata?.sasToken,
         apiToken: connection.metadata?.apiToken,
         organization: connection.metadata?.organization
       };
@@ -204,6 +217,10 @@ export const ConnectionSwitcher: React.FC<ConnectionSwitcherProps> = ({
                   ? connection.url.replace('file:///', '')
                         : connection.url?.startsWith('oss://')
                         ? `OSS: ${connection.username}`
+                        : connection.url?.startsWith('s3://')
+                        ? `S3: ${connection.username}`
+                        : connection.url?.startsWith('azblob://')
+                        ? `Azure: ${connection.metadata?.bucket}`
                         : connection.url && connection.username
                         ? `${connection.username}@${new URL(connection.url).hostname}`
                         : connection.url || ''}
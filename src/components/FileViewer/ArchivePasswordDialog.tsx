@@ -0,0 +1,82 @@
+import React, { useState } from 'react';
+import { KeyRound } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+
+interface ArchivePasswordDialogProps {
+  // 上次输入的密码错误
+  incorrect: boolean;
+  onSubmit: (password: string) => void;
+  onCancel: () => void;
+}
+
+/**
+ * 加密压缩包的密码对话框
+ * 后端返回 archive.zip.password.required 或 archive.zip.password.incorrect 时弹出，输入后用新密码重试
+ */
+export const ArchivePasswordDialog: React.FC<ArchivePasswordDialogProps> = ({
+  incorrect,
+  onSubmit,
+  onCancel
+}) => {
+  const { t } = useTranslation();
+  const [password, setPassword] = useState('');
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    if (password) {
+      onSubmit(password);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50 p-4">
+      <form
+        onSubmit={handleSubmit}
+        className="bg-white dark:bg-gray-800 rounded-lg shadow-xl p-6 w-full max-w-md space-y-4"
+      >
+        <div className="flex items-center space-x-2">
+          <KeyRound className="w-5 h-5 text-indigo-600 dark:text-indigo-400" />
+          <h3 className="font-semibold text-gray-900 dark:text-white">
+            {t('archive.password.title')}
+          </h3>
+        </div>
+
+        {incorrect ? (
+          <div className="bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-md p-3">
+            <p className="text-sm text-red-600 dark:text-red-400">{t('archive.zip.password.incorrect')}</p>
+          </div>
+        ) : (
+          <p className="text-sm text-gray-600 dark:text-gray-300">
+            {t('archive.zip.password.required')}
+          </p>
+        )}
+
+        <input
+          type="password"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          className="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:border-gray-600 dark:text-white"
+          placeholder={t('archive.password.placeholder')}
+          autoFocus
+        />
+
+        <div className="flex justify-end space-x-2">
+          <button
+            type="button"
+            onClick={onCancel}
+            className="px-4 py-2 text-sm text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700 rounded-md"
+          >
+            {t('cancel')}
+          </button>
+          <button
+            type="submit"
+            disabled={!password}
+            className="px-4 py-2 text-sm bg-indigo-600 hover:bg-indigo-700 disabled:bg-indigo-400 text-white font-medium rounded-md disabled:cursor-not-allowed"
+          >
+            {t('archive.password.submit')}
+          </button>
+        </div>
+      </form>
+    </div>
+  );
+};
@@ -1,6 +1,8 @@
-import React, { useState, useEffect, useCallback } from 'react';
+import React, { useState, useEffect, useCallback, useRef } from 'react';
 import { Archive, Copy, AlertCircle, Folder, Download } from 'lucide-react';
-import { ArchiveEntry, ArchiveInfo, FilePreview } from '../../types';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { ArchiveAnalysisProgressEvent, ArchiveEntry, ArchiveInfo, FilePreview } from '../../types';
 import { CompressionService } from '../../services/compression';
 import { StorageServiceManager } from '../../services/storage/StorageManager';
 import { copyToClipboard, showCopyToast } from '../../utils/clipboard';
@@ -14,17 +16,13 @@ import { MediaViewer } from './MediaViewer';
 import { UniversalDataTableViewer } from './UniversalDataTableViewer';
 import { LoadingDisplay, ErrorDisplay, StatusDisplay, UnsupportedFormatDisplay } from '../common';
 import { ManualLoadButton } from './ManualLoadButton';
+import { ArchivePasswordDialog } from './ArchivePasswordDialog';
 import { useTranslation } from 'react-i18next';
+import { translateBackendError } from '../../utils/errorTranslation';
 
-// 错误信息翻译辅助函数
-const translateError = (error: string, t: (key: string) => string): string => {
-  // 检查是否是翻译键（以字母开头，包含点号）
-  if (error.match(/^[a-zA-Z][a-zA-Z0-9.]+$/)) {
-    return t(error);
-  }
-  // 否则返回原始错误信息
-  return error;
-};
+// 错误信息翻译辅助函数（翻译键可带 JSON 参数）
+const translateError = (error: string, t: (key: string, options?: Record<string, unknown>) => string): string =>
+  translateBackendError(error, t);
 
 // 从错误对象中提取错误信息的辅助函数
 const extractErrorMessage = (err: unknown, fallbackKey: string, t: (key: string) => string): string => {
@@ -39,6 +37,10 @@ const extractErrorMessage = (err: unknown, fallbackKey: string, t: (key: string)
   }
 };
 
+// 加密 ZIP 条目缺少密码或密码错误时后端返回的错误
+const PASSWORD_REQUIRED_ERROR = 'archive.zip.password.required';
+const PASSWORD_INCORRECT_ERROR = 'archive.zip.password.incorrect';
+
 interface ArchiveViewerProps {
   url: string;
   headers: Record<string, string>;
@@ -49,6 +51,10 @@ interface ArchiveViewerProps {
 
 // 移除不再需要的LoadMoreProgress接口
 
+// 未标记 UTF-8 的 ZIP 文件名可选的编码（空字符串表示自动检测）
+const ZIP_FILENAME_ENCODINGS = ['', 'gbk', 'big5', 'shift_jis', 'euc-kr', 'cp437'];
+const ZIP_FILENAME_ENCODING_KEY = 'archive-zip-filename-encoding';
+
 export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
   url,
   headers,
@@ -59,6 +65,8 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
   const [archiveInfo, setArchiveInfo] = useState<ArchiveInfo | null>(null);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  // 流式分析进度（分析完成后清空）
+  const [analysisProgress, setAnalysisProgress] = useState<{ bytesRead: number; totalBytes: number } | null>(null);
   const [selectedEntry, setSelectedEntry] = useState<ArchiveEntry | null>(null);
   const [filePreview, setFilePreview] = useState<FilePreview | null>(null);
   const [previewLoading, setPreviewLoading] = useState(false);
@@ -83,6 +91,20 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
     }
   }, [showHidden]);
   
+  // ZIP文件名编码（应用内全局设置，保存在localStorage中）
+  const [zipFilenameEncoding, setZipFilenameEncoding] = useState<string>(() => {
+    try {
+      return localStorage.getItem(ZIP_FILENAME_ENCODING_KEY) ?? '';
+    } catch {
+      return '';
+    }
+  });
+
+  // 加密压缩包的密码（只在当前压缩包内使用，不保存）
+  const passwordRef = useRef<string | undefined>(undefined);
+  // 等待输入密码的操作，输入后用新密码重试
+  const [passwordPrompt, setPasswordPrompt] = useState<{ incorrect: boolean; retry: () => void } | null>(null);
+
   // 文件加载状态管理
   const [fileLoadState, setFileLoadState] = useState({
     isLargeFile: false,
@@ -97,13 +119,78 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
   });
 
   useEffect(() => {
-    loadArchiveInfo();
+    passwordRef.current = undefined;
+    setPasswordPrompt(null);
+    // 先同步保存的文件名编码，再分析压缩包
+    invoke('set_zip_filename_encoding', { encoding: zipFilenameEncoding || null })
+      .catch(err => console.warn('Failed to apply ZIP filename encoding:', err))
+      .finally(() => loadArchiveInfo());
   }, [url, filename]);
 
+  const changeZipFilenameEncoding = async (encoding: string) => {
+    try {
+      await invoke('set_zip_filename_encoding', { encoding: encoding || null });
+      setZipFilenameEncoding(encoding);
+      localStorage.setItem(ZIP_FILENAME_ENCODING_KEY, encoding);
+    } catch (err) {
+      const errorMessage = extractErrorMessage(err, 'error.load.archive', t);
+      setError(translateError(errorMessage, t));
+      return;
+    }
+    setSelectedEntry(null);
+    setFilePreview(null);
+    await loadArchiveInfo();
+  };
+
+  // 错误为需要密码或密码错误时弹出密码对话框，返回是否已处理
+  const promptForPassword = (errorMessage: string, retry: () => void): boolean => {
+    const incorrect = errorMessage.includes(PASSWORD_INCORRECT_ERROR);
+    if (!incorrect && !errorMessage.includes(PASSWORD_REQUIRED_ERROR)) {
+      return false;
+    }
+    setPasswordPrompt({ incorrect, retry });
+    return true;
+  };
+
+  const submitPassword = (password: string) => {
+    const retry = passwordPrompt?.retry;
+    passwordRef.current = password;
+    setPasswordPrompt(null);
+    retry?.();
+  };
+
   const loadArchiveInfo = async () => {
+    // 流式分析的格式在解析过程中按批次发送条目，先显示已解析的部分
+    let finished = false;
+    const unlistenProgress = await listen<ArchiveAnalysisProgressEvent>('archive-analysis-progress', (event) => {
+      const { file_path, progress } = event.payload;
+      if (finished || file_path !== url) {
+        return;
+      }
+      setAnalysisProgress({ bytesRead: progress.bytes_read, totalBytes: progress.total_bytes });
+      if (progress.entries.length === 0) {
+        return;
+      }
+      const batchSize = progress.entries.reduce((sum, entry) => sum + entry.size, 0);
+      setArchiveInfo(prev => {
+        const entries = [...(prev?.entries ?? []), ...progress.entries];
+        return {
+          entries,
+          total_entries: entries.length,
+          compression_type: prev?.compression_type ?? '',
+          total_uncompressed_size: (prev?.total_uncompressed_size ?? 0) + batchSize,
+          total_compressed_size: progress.total_bytes,
+          supports_streaming: true,
+          analysis_status: progress.analysis_status,
+        };
+      });
+      setLoading(false);
+    });
+
     try {
       setLoading(true);
       setError(null);
+      setArchiveInfo(null);
 
       let info: ArchiveInfo;
 
@@ -111,7 +198,7 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
       if (storageClient && storageClient.analyzeArchive) {
         // 使用存储客户端的统一接口
         const maxSize = 1024 * 1024; // 1MB
-        info = await storageClient.analyzeArchive(url, filename, maxSize);
+        info = await storageClient.analyzeArchive(url, filename, maxSize, passwordRef.current);
       } else {
         // 回退到直接的压缩服务接口
         const maxSize = 1024 * 1024; // 1MB
@@ -123,11 +210,16 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
         );
       }
 
+      finished = true;
       setArchiveInfo(info);
     } catch (err) {
+      finished = true;
       const errorMessage = extractErrorMessage(err, 'error.load.archive', t);
       setError(translateError(errorMessage, t));
+      promptForPassword(errorMessage, loadArchiveInfo);
     } finally {
+      unlistenProgress();
+      setAnalysisProgress(null);
       setLoading(false);
     }
   };
@@ -145,7 +237,7 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
 
       if (storageClient && storageClient.analyzeArchive) {
         // 使用存储客户端的统一接口，不限制大小以获取详细信息
-        detailedInfo = await storageClient.analyzeArchive(url, filename);
+        detailedInfo = await storageClient.analyzeArchive(url, filename, undefined, passwordRef.current);
       } else {
         // 回退到直接的压缩服务接口
         detailedInfo = await CompressionService.analyzeArchive(
@@ -159,6 +251,7 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
     } catch (err) {
       const errorMessage = extractErrorMessage(err, 'error.load.details', t);
       setError(translateError(errorMessage, t));
+      promptForPassword(errorMessage, loadDetailedArchiveInfo);
     } finally {
       setLoading(false);
     }
@@ -244,7 +337,9 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
             url,
             filename,
             entry.path,
-            loadSize
+            loadSize,
+            undefined,
+            passwordRef.current
           );
         } else {
           preview = await CompressionService.extractFilePreview(
@@ -252,7 +347,8 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
             headers,
             filename,
             entry.path,
-            loadSize
+            loadSize,
+            passwordRef.current
           );
         }
 
@@ -271,7 +367,9 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
           url,
           filename,
           entry.path,
-          initialLoadSize
+          initialLoadSize,
+          undefined,
+          passwordRef.current
         );
       } else {
         preview = await CompressionService.extractFilePreview(
@@ -279,7 +377,8 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
           headers,
           filename,
           entry.path,
-          initialLoadSize
+          initialLoadSize,
+          passwordRef.current
         );
       }
 
@@ -305,6 +404,7 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
     } catch (err) {
       const errorMessage = extractErrorMessage(err, 'error.preview.file', t);
       setPreviewError(translateError(errorMessage, t));
+      promptForPassword(errorMessage, () => previewFile(entry));
     } finally {
       setPreviewLoading(false);
     }
@@ -352,7 +452,8 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
             filename,
             entry.path,
             chunkSize,
-            nextPosition
+            nextPosition,
+            passwordRef.current
           );
         } catch (offsetError) {
           // 如果不支持偏移量，回退到加载完整文件
@@ -361,7 +462,9 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
             url,
             filename,
             entry.path,
-            fileLoadState.totalSize
+            fileLoadState.totalSize,
+            undefined,
+            passwordRef.current
           );
           if (fullPreview.content && fullPreview.content.length > fileLoadState.loadedContentSize) {
             const remainingContent = fullPreview.content.slice(fileLoadState.loadedContentSize);
@@ -386,7 +489,8 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
           headers,
           filename,
           entry.path,
-          fileLoadState.totalSize
+          fileLoadState.totalSize,
+          passwordRef.current
         );
         if (fullPreview.content && fullPreview.content.length > fileLoadState.loadedContentSize) {
           const remainingContent = fullPreview.content.slice(fileLoadState.loadedContentSize);
@@ -448,7 +552,9 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
           url,
           filename,
           entry.path,
-          entry.size || undefined // 加载完整文件
+          entry.size || undefined, // 加载完整文件
+          undefined,
+          passwordRef.current
         );
       } else {
         fullPreview = await CompressionService.extractFilePreview(
@@ -456,7 +562,8 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
           headers,
           filename,
           entry.path,
-          entry.size || undefined // 加载完整文件
+          entry.size || undefined, // 加载完整文件
+          passwordRef.current
         );
       }
 
@@ -476,6 +583,7 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
     } catch (err) {
       console.error('Failed to load full content:', err);
       setPreviewError(t('error.load.full.content'));
+      promptForPassword(extractErrorMessage(err, 'error.load.full.content', t), () => loadFullContent(entry));
     } finally {
       setFileLoadState(prev => ({ ...prev, manualLoading: false }));
     }
@@ -505,12 +613,16 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
         url,
         filename,
         entry.path,
-        entry.path.split('/').pop() || entry.path
+        entry.path.split('/').pop() || entry.path,
+        passwordRef.current
       );
     } catch (err) {
       console.error('Failed to download file:', err);
       // 如果是用户取消操作，不显示错误弹窗
       const errorMessage = extractErrorMessage(err, 'error.unknown', t);
+      if (promptForPassword(errorMessage, () => downloadFile(entry))) {
+        return;
+      }
       if (errorMessage !== 'download.cancelled') {
         alert(`${t('download.failed')}: ${errorMessage}`);
       }
@@ -528,17 +640,56 @@ export const ArchiveViewer: React.FC<ArchiveViewerProps> = ({
     );
   }
 
+  const passwordDialog = passwordPrompt && (
+    <ArchivePasswordDialog
+      incorrect={passwordPrompt.incorrect}
+      onSubmit={submitPassword}
+      onCancel={() => setPasswordPrompt(null)}
+    />
+  );
+
   if (error) {
     return (
-      <ErrorDisplay
-        message={error}
-        onRetry={loadArchiveInfo}
-      />
+      <>
+        <ErrorDisplay
+          message={error}
+          onRetry={loadArchiveInfo}
+        />
+        {passwordDialog}
+      </>
     );
   }
 
   return (
     <div className="h-full flex flex-col">
+      {passwordDialog}
+      {analysisProgress && archiveInfo && (
+        <div className="px-4 py-1 text-xs text-gray-500 dark:text-gray-400 border-b border-gray-200 dark:border-gray-700">
+          {t('loading.analyzing.partial', {
+            count: archiveInfo.entries.length,
+            percent: analysisProgress.totalBytes > 0
+              ? Math.round(analysisProgress.bytesRead / analysisProgress.totalBytes * 100)
+              : 0,
+          })}
+        </div>
+      )}
+      {archiveInfo?.compression_type === 'Zip' && (
+        <div className="px-4 py-1 flex items-center justify-end space-x-2 text-xs text-gray-500 dark:text-gray-400 border-b border-gray-200 dark:border-gray-700">
+          <label htmlFor="zip-filename-encoding">{t('archive.filename.encoding')}</label>
+          <select
+            id="zip-filename-encoding"
+            value={zipFilenameEncoding}
+            onChange={(e) => changeZipFilenameEncoding(e.target.value)}
+            className="px-1 py-0.5 bg-white dark:bg-gray-800 border border-gray-300 dark:border-gray-600 rounded text-gray-700 dark:text-gray-300"
+          >
+            {ZIP_FILENAME_ENCODINGS.map(encoding => (
+              <option key={encoding} value={encoding}>
+                {encoding ? encoding.toUpperCase() : t('archive.filename.encoding.auto')}
+              </option>
+            ))}
+          </select>
+        </div>
+      )}
       <div className="flex-1 flex min-h-0">
         {/* 使用ArchiveFileBrowser组件 */}
         <div className="w-1/2 border-r border-gray-200 dark:border-gray-700 flex flex-col min-h-0">
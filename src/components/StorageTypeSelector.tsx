@@ -1,6 +1,6 @@
 import React from 'react';
 import { useTranslation } from 'react-i18next';
-import { Server, Folder, Cloud, Bot } from 'lucide-react';
+import { Server, Folder, Cloud, Bot, Database, Terminal, HardDrive, Box } from 'lucide-react';
 import { StorageClientType } from '../services/storage/types';
 import { AndroidBackHandlerService } from '../services/androidBackHandler';
 
@@ -31,6 +31,30 @@ export const StorageTypeSelector: React.FC<StorageTypeSelectorProps> = ({
       icon: Cloud,
       description: t('storage.type.oss.description')
     },
+    {
+      type: 's3' as StorageClientType,
+      label: t('storage.type.s3'),
+      icon: Database,
+      description: t('storage.type.s3.description')
+    },
+    {
+      type: 'sftp' as StorageClientType,
+      label: t('storage.type.sftp'),
+      icon: Terminal,
+      description: t('storage.type.sftp.description')
+    },
+    {
+      type: 'ftp' as StorageClientType,
+      label: t('storage.type.ftp'),
+      icon: HardDrive,
+      description: t('storage.type.ftp.description')
+    },
+    {
+      type: 'azblob' as StorageClientType,
+      label: t('storage.type.azblob'),
+      icon: Box,
+      description: t('storage.type.azblob.description')
+    },
     {
       type: 'huggingface' as StorageClientType,
       label: t('storage.type.huggingface'),
@@ -11,6 +11,7 @@ import {
   AlertTriangle,
   LucideIcon
 } from 'lucide-react';
+import { translateBackendError } from '../../utils/errorTranslation';
 
 type StatusType =
   | 'loading'
@@ -141,16 +142,9 @@ export const LoadingDisplay: React.FC<{
   );
 };
 
-// 错误信息翻译辅助函数
-const translateErrorMessage = (error: string, t: (key: string) => string): string => {
-  // 检查是否是翻译键（以字母开头，包含点号）
-  if (error.match(/^[a-zA-Z][a-zA-Z0-9.]+$/)) {
-    return t(error);
-  }
-  
-  // 否则返回原始错误信息
-  return error;
-};
+// 错误信息翻译辅助函数（翻译键可带 JSON 参数）
+const translateErrorMessage = (error: string, t: (key: string, options?: Record<string, unknown>) => string): string =>
+  translateBackendError(error, t);
 
 export const ErrorDisplay: React.FC<{
   message: string;
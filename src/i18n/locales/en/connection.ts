@@ -24,6 +24,14 @@ export const connection = {
   'storage.type.local.description': 'Browse local file system',
   'storage.type.oss': 'OSS',
   'storage.type.oss.description': 'Connect to Object Storage Service',
+  'storage.type.s3': 'S3',
+  'storage.type.s3.description': 'AWS S3, MinIO, Ceph RGW and other S3-compatible storage',
+  'storage.type.sftp': 'SFTP',
+  'storage.type.sftp.description': 'Connect to a server over SSH',
+  'storage.type.ftp': 'FTP',
+  'storage.type.ftp.description': 'Connect to an FTP / FTPS server',
+  'storage.type.azblob': 'Azure Blob',
+  'storage.type.azblob.description': 'Azure Blob Storage and the Azurite emulator',
   'storage.type.huggingface': 'HuggingFace',
   'storage.type.huggingface.description': 'AI Datasets',
 
@@ -31,6 +39,11 @@ export const connection = {
   'connection.name.webdav': 'WebDAV({{host}})',
   'connection.name.local': 'Local Files({{path}})',
   'connection.name.oss': 'OSS({{host}}-{{bucket}})',
+  'connection.name.s3': 'S3({{host}}-{{bucket}})',
+  'connection.name.sftp': 'SFTP({{user}}@{{host}})',
+  'connection.name.ftp': 'FTP({{user}}@{{host}})',
+  'connection.name.ftp.anonymous': 'FTP({{host}})',
+  'connection.name.azblob': 'Azure({{host}}-{{container}})',
   'connection.name.huggingface': 'Hugging Face({{org}})',
 
   // Form fields
@@ -59,6 +72,54 @@ export const connection = {
   'oss.region': 'Region',
   'oss.region.placeholder': 'e.g.: cn-hangzhou, us-east-1',
   'oss.region.optional': 'Region (Optional)',
+  'oss.security.token': 'Security Token (Optional)',
+  'oss.security.token.placeholder': 'STS SecurityToken',
+  'oss.security.token.help': 'Only required for STS temporary credentials. New credentials can be entered when they expire without reconnecting.',
+  'oss.signature.version': 'Signature Version',
+  'oss.signature.v1': 'V1 (HMAC-SHA1)',
+  'oss.signature.v4': 'V4 (HMAC-SHA256, region required)',
+
+  // S3 specific fields
+  's3.endpoint.placeholder': 'https://s3.us-east-1.amazonaws.com or http://127.0.0.1:9000',
+  's3.endpoint.description': 'Requests are signed with AWS Signature V4 and work with AWS S3, MinIO, Ceph RGW, etc.',
+  's3.region.placeholder': 'Defaults to us-east-1',
+  's3.addressing.style': 'Addressing Style',
+  's3.addressing.auto': 'Auto (virtual-hosted for AWS, path-style otherwise)',
+  's3.addressing.path': 'Path-style (endpoint/bucket/key)',
+  's3.addressing.virtual': 'Virtual-hosted (bucket.endpoint/key)',
+
+  // SFTP specific fields
+  'sftp.host': 'Host',
+  'sftp.host.placeholder': 'sftp.example.com',
+  'sftp.port': 'Port',
+  'sftp.password.placeholder': 'Leave empty to use the private key',
+  'sftp.root.path': 'Root Directory (Optional)',
+  'sftp.root.path.placeholder': 'e.g.: /home/username/data, defaults to /',
+  'sftp.private.key': 'Private Key (Optional)',
+  'sftp.private.key.placeholder': '~/.ssh/id_ed25519 or key content',
+  'sftp.private.key.passphrase': 'Private Key Passphrase (Optional)',
+  'sftp.known.hosts': 'known_hosts File',
+  'sftp.host.key.policy': 'Host Key Verification',
+  'sftp.host.key.policy.strict': 'Strict (host must be in known_hosts)',
+  'sftp.host.key.policy.accept_new': 'Trust on first connection (record new hosts)',
+  'ftp.host.placeholder': 'ftp.example.com',
+  'ftp.username.placeholder': 'Leave empty for anonymous login',
+  'ftp.root.path.placeholder': 'e.g.: /pub/data, defaults to the login directory',
+  'ftp.tls.mode': 'Encryption',
+  'ftp.tls.none': 'None (plain FTP)',
+  'ftp.tls.explicit': 'Explicit TLS (FTPES, AUTH TLS)',
+  'ftp.tls.implicit': 'Implicit TLS (FTPS)',
+  'ftp.accept.invalid.certs': 'Accept self-signed or invalid certificates',
+  'azblob.endpoint.placeholder': 'https://account.blob.core.windows.net',
+  'azblob.endpoint.description': 'For Azurite, include the account name in the path, e.g. http://127.0.0.1:10000/devstoreaccount1',
+  'azblob.account': 'Account Name (Optional)',
+  'azblob.account.placeholder': 'Inferred from the endpoint when empty',
+  'azblob.account.key': 'Account Key',
+  'azblob.account.key.placeholder': 'Base64 account key, leave empty to use a SAS token',
+  'azblob.container': 'Container',
+  'azblob.container.placeholder': 'Container name',
+  'azblob.sas.token': 'SAS Token (Optional)',
+  'azblob.sas.token.help': 'Used only when no account key is entered. The token needs read and list permissions.',
 
   // Form validation errors
   'error.endpoint.required': 'Please enter OSS endpoint',
@@ -66,6 +127,13 @@ export const connection = {
   'error.access.key.required': 'Please enter Access Key',
   'error.secret.key.required': 'Please enter Secret Key',
   'error.bucket.required': 'Please enter Bucket name',
+  'error.host.required': 'Please enter host',
+  'error.port.invalid': 'Please enter a port between 1 and 65535',
+  'error.username.required': 'Please enter username',
+  'error.sftp.auth.required': 'Please enter a password or private key',
+  'error.azblob.endpoint.required': 'Please enter the Azure Blob endpoint',
+  'error.azblob.container.required': 'Please enter the container name',
+  'error.azblob.auth.required': 'Please enter an account key or SAS token',
 
   // Connection management
   'saved.connections': 'Saved Connections',
@@ -102,6 +170,10 @@ export const connection = {
 
   // OSS errors
   'error.oss.connection.failed': 'OSS connection failed',
+  'error.s3.connection.failed': 'S3 connection failed',
+  'error.sftp.connection.failed': 'SFTP connection failed',
+  'error.ftp.connection.failed': 'FTP connection failed',
+  'error.azblob.connection.failed': 'Azure Blob connection failed',
 
   // OSS help information
   'oss.help.credentials.title': 'How to get Access Key:',
@@ -123,6 +195,11 @@ export const connection = {
   'huggingface.help.token.step3': 'Select "Read" permission',
   'error.huggingface.connection.failed': 'Hugging Face connection failed',
 
+  // Credential refresh
+  'credentials.refresh.title': 'Update Access Credentials',
+  'credentials.refresh.submit': 'Update',
+  'credentials.refresh.failed': 'Failed to update credentials: {{error}}',
+
   // Connection switching
   'connection.switch.failed': 'Failed to switch connection',
   'connection.switch.error': 'Error occurred while switching connection: {{error}}',
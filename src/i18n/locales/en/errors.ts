@@ -3,6 +3,7 @@ export const errors = {
   'error.connection.failed': 'Failed to connect to WebDAV server. Please check your credentials.',
   'error.credentials': 'Connection failed. Please verify the server URL and credentials.',
   'error.unknown': 'Unknown error',
+  'storage.credentials.expired': 'The temporary access credentials have expired. Enter new credentials to continue.',
 
   // File operation errors
   'error.load.directory': 'Failed to load directory contents',
@@ -42,4 +43,14 @@ export const errors = {
   'archive.format.lz4.not.supported': 'LZ4 format is not supported yet. Supported formats: ZIP, TAR, TAR.GZ, GZIP',
   'archive.format.zstd.not.supported': 'Zstd format is not supported yet. Supported formats: ZIP, TAR, TAR.GZ, GZIP',
   'archive.format.unsupported': 'Unsupported archive format',
+
+  // Decompression limits
+  'archive.limit.output_size': 'Decompressed data ({{value}} bytes) exceeds the output limit of {{limit}} bytes',
+  'archive.limit.ratio': 'Decompressed data ({{value}} bytes) exceeds the compression ratio limit of {{limit}} bytes for this archive. It may be a decompression bomb.',
+  'archive.limit.entries': 'The archive has {{value}} entries, exceeding the limit of {{limit}}',
+  'archive.limit.nesting_depth': 'Nested archive depth {{value}} exceeds the limit of {{limit}}',
+
+  // Encrypted archives
+  'archive.zip.password.required': 'This archive is encrypted. A password is required.',
+  'archive.zip.password.incorrect': 'Incorrect password',
 };
@@ -69,6 +69,12 @@ export const fileViewer = {
   // Loading status
   'loading.file': 'Loading file "{{filename}}"...',
   'loading.analyzing.archive': 'Analyzing archive...',
+  'loading.analyzing.partial': 'Analyzing archive... {{count}} entries found ({{percent}}%)',
+  'archive.filename.encoding': 'Filename encoding',
+  'archive.filename.encoding.auto': 'Auto detect',
+  'archive.password.title': 'Enter Archive Password',
+  'archive.password.placeholder': 'Archive password',
+  'archive.password.submit': 'OK',
   'loading.preview': 'Loading preview...',
   'loading.more.content': 'Loading more content...',
   'loading.text': 'Loading...',
@@ -24,6 +24,14 @@ export const connection = {
   'storage.type.local.description': '浏览本机文件系统',
   'storage.type.oss': 'OSS',
   'storage.type.oss.description': '连接到对象存储服务',
+  'storage.type.s3': 'S3',
+  'storage.type.s3.description': 'AWS S3、MinIO、Ceph RGW 等 S3 兼容存储',
+  'storage.type.sftp': 'SFTP',
+  'storage.type.sftp.description': '通过 SSH 连接服务器',
+  'storage.type.ftp': 'FTP',
+  'storage.type.ftp.description': '连接 FTP / FTPS 服务器',
+  'storage.type.azblob': 'Azure Blob',
+  'storage.type.azblob.description': 'Azure Blob 存储及 Azurite 模拟器',
   'storage.type.huggingface': 'HuggingFace',
   'storage.type.huggingface.description': 'AI 数据集',
 
@@ -31,6 +39,11 @@ export const connection = {
   'connection.name.webdav': 'WebDAV({{host}})',
   'connection.name.local': '本机文件({{path}})',
   'connection.name.oss': 'OSS({{host}}-{{bucket}})',
+  'connection.name.s3': 'S3({{host}}-{{bucket}})',
+  'connection.name.sftp': 'SFTP({{user}}@{{host}})',
+  'connection.name.ftp': 'FTP({{user}}@{{host}})',
+  'connection.name.ftp.anonymous': 'FTP({{host}})',
+  'connection.name.azblob': 'Azure({{host}}-{{container}})',
   'connection.name.huggingface': 'Hugging Face({{org}})',
 
   // 表单字段
@@ -59,6 +72,54 @@ export const connection = {
   'oss.region': '区域',
   'oss.region.placeholder': '例如：cn-hangzhou、us-east-1',
   'oss.region.optional': '区域 (可选)',
+  'oss.security.token': '安全令牌 (可选)',
+  'oss.security.token.placeholder': 'STS SecurityToken',
+  'oss.security.token.help': '仅使用 STS 临时凭证时需要，凭证过期后可直接输入新凭证，无需重新连接',
+  'oss.signature.version': '签名版本',
+  'oss.signature.v1': 'V1（HMAC-SHA1）',
+  'oss.signature.v4': 'V4（HMAC-SHA256，需要区域）',
+
+  // S3 特定字段
+  's3.endpoint.placeholder': 'https://s3.us-east-1.amazonaws.com 或 http://127.0.0.1:9000',
+  's3.endpoint.description': '使用 AWS Signature V4 签名，支持 AWS S3、MinIO、Ceph RGW 等',
+  's3.region.placeholder': '默认为 us-east-1',
+  's3.addressing.style': '寻址风格',
+  's3.addressing.auto': '自动（AWS 使用虚拟主机风格，其他使用路径风格）',
+  's3.addressing.path': '路径风格（endpoint/bucket/key）',
+  's3.addressing.virtual': '虚拟主机风格（bucket.endpoint/key）',
+
+  // SFTP 特定字段
+  'sftp.host': '主机',
+  'sftp.host.placeholder': 'sftp.example.com',
+  'sftp.port': '端口',
+  'sftp.password.placeholder': '使用私钥时可留空',
+  'sftp.root.path': '根目录 (可选)',
+  'sftp.root.path.placeholder': '例如：/home/username/data，默认为 /',
+  'sftp.private.key': '私钥 (可选)',
+  'sftp.private.key.placeholder': '~/.ssh/id_ed25519 或私钥内容',
+  'sftp.private.key.passphrase': '私钥密码 (可选)',
+  'sftp.known.hosts': 'known_hosts 文件',
+  'sftp.host.key.policy': '主机密钥校验',
+  'sftp.host.key.policy.strict': '严格（主机必须在 known_hosts 中）',
+  'sftp.host.key.policy.accept_new': '首次连接时信任（记录新主机）',
+  'ftp.host.placeholder': 'ftp.example.com',
+  'ftp.username.placeholder': '留空则匿名登录',
+  'ftp.root.path.placeholder': '例如：/pub/data，默认为登录目录',
+  'ftp.tls.mode': '加密方式',
+  'ftp.tls.none': '不加密（明文 FTP）',
+  'ftp.tls.explicit': '显式 TLS（FTPES，AUTH TLS）',
+  'ftp.tls.implicit': '隐式 TLS（FTPS）',
+  'ftp.accept.invalid.certs': '接受自签名或无效证书',
+  'azblob.endpoint.placeholder': 'https://account.blob.core.windows.net',
+  'azblob.endpoint.description': 'Azurite 端点需在路径中包含账户名，例如 http://127.0.0.1:10000/devstoreaccount1',
+  'azblob.account': '账户名 (可选)',
+  'azblob.account.placeholder': '留空时从端点推断',
+  'azblob.account.key': '账户密钥',
+  'azblob.account.key.placeholder': 'Base64 编码的账户密钥，使用 SAS 令牌时可留空',
+  'azblob.container': '容器',
+  'azblob.container.placeholder': '容器名称',
+  'azblob.sas.token': 'SAS 令牌 (可选)',
+  'azblob.sas.token.help': '仅在未填写账户密钥时使用，令牌需要读取和列出权限。',
 
   // 表单验证错误
   'error.endpoint.required': '请输入 OSS 端点地址',
@@ -66,6 +127,13 @@ export const connection = {
   'error.access.key.required': '请输入 Access Key',
   'error.secret.key.required': '请输入 Secret Key',
   'error.bucket.required': '请输入 Bucket 名称',
+  'error.host.required': '请输入主机地址',
+  'error.port.invalid': '请输入 1 到 65535 之间的端口',
+  'error.username.required': '请输入用户名',
+  'error.sftp.auth.required': '请输入密码或私钥',
+  'error.azblob.endpoint.required': '请输入 Azure Blob 端点',
+  'error.azblob.container.required': '请输入容器名称',
+  'error.azblob.auth.required': '请输入账户密钥或 SAS 令牌',
 
   // 连接管理
   'saved.connections': '已保存的连接',
@@ -102,6 +170,10 @@ export const connection = {
 
   // OSS 错误
   'error.oss.connection.failed': 'OSS 连接失败',
+  'error.s3.connection.failed': 'S3 连接失败',
+  'error.sftp.connection.failed': 'SFTP 连接失败',
+  'error.ftp.connection.failed': 'FTP 连接失败',
+  'error.azblob.connection.failed': 'Azure Blob 连接失败',
 
   // OSS 帮助信息
   'oss.help.credentials.title': 'Access Key 获取方式：',
@@ -123,6 +195,11 @@ export const connection = {
   'huggingface.help.token.step3': '选择 "Read" 权限即可',
   'error.huggingface.connection.failed': '连接 Hugging Face 失败',
 
+  // 凭证刷新
+  'credentials.refresh.title': '更新访问凭证',
+  'credentials.refresh.submit': '更新',
+  'credentials.refresh.failed': '更新凭证失败：{{error}}',
+
   // 连接切换
   'connection.switch.failed': '切换连接失败',
   'connection.switch.error': '切换连接时发生错误：{{error}}',
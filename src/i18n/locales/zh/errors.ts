@@ -4,6 +4,7 @@ export const errors = {
   'error.credentials': '连接失败，请验证服务器地址和凭据。',
   'error.oss.connection.failed': 'OSS 连接失败，请检查配置信息。',
   'error.unknown': '未知错误',
+  'storage.credentials.expired': '临时访问凭证已过期，请输入新的凭证后继续',
 
   // 文件操作错误
   'error.load.directory': '加载目录内容失败',
@@ -43,4 +44,14 @@ export const errors = {
   'archive.format.lz4.not.supported': '暂不支持 LZ4 格式。支持的格式：ZIP、TAR、TAR.GZ、GZIP',
   'archive.format.zstd.not.supported': '暂不支持 Zstd 格式。支持的格式：ZIP、TAR、TAR.GZ、GZIP',
   'archive.format.unsupported': '不支持的压缩文件格式',
+
+  // 解压限制
+  'archive.limit.output_size': '解压数据（{{value}} 字节）超过输出上限 {{limit}} 字节',
+  'archive.limit.ratio': '解压数据（{{value}} 字节）超过此压缩包的压缩比上限 {{limit}} 字节，可能是解压炸弹',
+  'archive.limit.entries': '压缩包包含 {{value}} 个条目，超过上限 {{limit}}',
+  'archive.limit.nesting_depth': '嵌套压缩包层数 {{value}} 超过上限 {{limit}}',
+
+  // 加密压缩包
+  'archive.zip.password.required': '压缩包已加密，需要输入密码',
+  'archive.zip.password.incorrect': '密码错误',
 };
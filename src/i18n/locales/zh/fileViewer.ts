@@ -69,6 +69,12 @@ export const fileViewer = {
   // 加载状态
   'loading.file': '正在加载文件 "{{filename}}"...',
   'loading.analyzing.archive': '正在分析压缩文件...',
+  'loading.analyzing.partial': '正在分析压缩文件... 已找到 {{count}} 个条目 ({{percent}}%)',
+  'archive.filename.encoding': '文件名编码',
+  'archive.filename.encoding.auto': '自动检测',
+  'archive.password.title': '输入压缩包密码',
+  'archive.password.placeholder': '压缩包密码',
+  'archive.password.submit': '确定',
   'loading.preview': '加载预览...',
   'loading.more.content': '正在加载更多内容...',
   'loading.text': '加载中...',
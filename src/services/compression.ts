@@ -43,7 +43,8 @@ export class CompressionService {
     headers: Record<string, string>,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    password?: string
   ): Promise<FilePreview> {
     const timeoutMs = 30000; // 30秒
     
@@ -54,6 +55,7 @@ export class CompressionService {
         filename,
         entryPath,
         maxPreviewSize,
+        password,
       }),
       new Promise<never>((_, reject) => {
         setTimeout(() => {
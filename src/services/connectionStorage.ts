@@ -17,6 +17,19 @@ export interface StoredConnection {
     bucket?: string;
     region?: string;
     endpoint?: string;
+    sessionToken?: string;
+    signatureVersion?: 'v1' | 'v4';
+    // S3 特定字段（bucket、region、endpoint 与 OSS 共用）
+    addressingStyle?: 'path' | 'virtual';
+    // SFTP 特定字段（用户、主机、端口和根目录保存在 url 中）
+    privateKey?: string;
+    privateKeyPassphrase?: string;
+    knownHosts?: string;
+    hostKeyPolicy?: 'strict' | 'accept_new';
+    // FTP 特定字段（加密方式由 url 的协议决定）
+    acceptInvalidCerts?: boolean;
+    // Azure Blob 特定字段（bucket、endpoint 与 OSS 共用）
+    sasToken?: string;
     // 其他存储类型可以在此添加字段
     [key: string]: any;
   };
@@ -94,9 +107,17 @@ class ConnectionStorageService {
         const { StorageClientFactory } = await import('./storage/StorageManager');
 
         // 确定连接类型
-        let type: 'webdav' | 'oss' | 'local' = 'webdav';
+        let type: 'webdav' | 'oss' | 's3' | 'sftp' | 'ftp' | 'azblob' | 'local' = 'webdav';
         if (normalizedUrl.startsWith('file:///')) {
           type = 'local';
+        } else if (normalizedUrl.startsWith('s3://')) {
+          type = 's3';
+        } else if (normalizedUrl.startsWith('sftp://')) {
+          type = 'sftp';
+        } else if (/^ftp(es|s)?:\/\//.test(normalizedUrl)) {
+          type = 'ftp';
+        } else if (normalizedUrl.startsWith('azblob://')) {
+          type = 'azblob';
         } else if (normalizedUrl.startsWith('oss://') || this.isOSSEndpoint(normalizedUrl)) {
           type = 'oss';
         }
@@ -159,6 +180,22 @@ class ConnectionStorageService {
     }
   }
 
+  // 更新连接凭证（如刷新后的 STS 临时凭证）
+  updateCredentials(id: string, username: string, password: string, metadata?: StoredConnection['metadata']): void {
+    const connections = this.getStoredConnections();
+    const connection = connections.find(c => c.id === id);
+    if (connection) {
+      connection.username = username;
+      if (connection.password) {
+        connection.password = password;
+      }
+      if (metadata) {
+        connection.metadata = { ...connection.metadata, ...metadata };
+      }
+      localStorage.setItem(this.STORAGE_KEY, JSON.stringify(connections));
+    }
+  }
+
   // 删除连接
   deleteConnection(id: string): void {
     const connections = this.getStoredConnections();
@@ -0,0 +1,257 @@
+import { BaseStorageClient, DEFAULT_TIMEOUTS } from './BaseStorageClient';
+import {
+  ConnectionConfig,
+  DirectoryResult,
+  FileContent,
+  ListOptions,
+  ReadOptions,
+  StorageResponse
+} from './types';
+
+interface AzureBlobConnection {
+  endpoint: string;
+  account: string;
+  container: string;
+  connected: boolean;
+}
+
+/**
+ * Azure Blob Storage 客户端实现
+ * 支持账户密钥（Shared Key）和 SAS 令牌两种认证方式，签名和分页均由后端处理
+ */
+export class AzureBlobStorageClient extends BaseStorageClient {
+  protected protocol = 'azblob';
+  private connection: AzureBlobConnection | null = null;
+
+  /**
+   * 获取连接的显示名称
+   */
+  getDisplayName(): string {
+    if (!this.connection) return 'Azure Blob';
+
+    try {
+      return `${this.connection.container}@${new URL(this.connection.endpoint).host}`;
+    } catch {
+      return `${this.connection.container} (Azure Blob)`;
+    }
+  }
+
+  /**
+   * 根据连接配置生成连接名称
+   */
+  generateConnectionName(config: ConnectionConfig): string {
+    try {
+      const url = config.endpoint || config.url;
+      if (!url) {
+        return 'Azure Blob';
+      }
+
+      // 处理保存的 azblob://host/container 格式
+      if (url.startsWith('azblob://')) {
+        const [host, container] = url.replace('azblob://', '').split('/');
+        return container ? `Azure(${host}-${container})` : `Azure(${host})`;
+      }
+
+      const host = new URL(url).host;
+      return config.bucket ? `Azure(${host}-${config.bucket})` : `Azure(${host})`;
+    } catch (error) {
+      return 'Azure Blob';
+    }
+  }
+
+  async connect(config: ConnectionConfig): Promise<boolean> {
+    if (config.type !== 'azblob') {
+      throw new Error('Invalid connection type for Azure Blob client');
+    }
+
+    // url 或 endpoint 作为端点，bucket 作为容器，username 作为账户名，password 作为账户密钥
+    const endpoint = (config.endpoint || config.url || '').trim();
+    if (!endpoint || !config.bucket || (!config.password && !config.sasToken)) {
+      throw new Error('Azure Blob requires endpoint, container (bucket) and an account key (password) or SAS token');
+    }
+
+    try {
+      // 账户名可省略，由后端从端点推断；同时填写账户密钥和 SAS 时使用账户密钥
+      const success = await this.connectToBackend({
+        protocol: 'azblob',
+        url: endpoint,
+        accessKey: config.username || null,
+        secretKey: config.password || null,
+        bucket: config.bucket,
+        region: null,
+        username: null,
+        password: null,
+        extraOptions: config.sasToken ? { sas_token: config.sasToken } : null,
+      });
+
+      if (success) {
+        this.connection = {
+          endpoint,
+          account: config.username || '',
+          container: config.bucket,
+          connected: true,
+        };
+      }
+
+      return success;
+    } catch (error) {
+      console.error('Azure Blob connection failed:', error);
+      throw new Error(`Azure Blob connection failed: ${error}`);
+    }
+  }
+
+  disconnect(): void {
+    this.disconnectFromBackend();
+    this.connection = null;
+  }
+
+  async listDirectory(path: string, options: ListOptions = {}): Promise<DirectoryResult> {
+    if (!this.connection) {
+      throw new Error('Not connected to Azure Blob');
+    }
+
+    try {
+      // 后端使用 List Blobs，分页标记通过 nextMarker / marker 传递
+      return await this.invokeWithTimeout<DirectoryResult>(
+        'storage_list_directory',
+        {
+          path: this.getBlobName(path),
+          options: {
+            page_size: options.pageSize || 1000,
+            marker: options.marker,
+            prefix: options.prefix,
+            recursive: options.recursive || false,
+            sort_by: options.sortBy || 'name',
+            sort_order: options.sortOrder || 'asc',
+          }
+        },
+        DEFAULT_TIMEOUTS.list
+      );
+    } catch (error) {
+      console.error('Failed to list Azure Blob directory:', error);
+      throw new Error(`Failed to list directory: ${error}`);
+    }
+  }
+
+  async getFileContent(path: string, options: ReadOptions = {}): Promise<FileContent> {
+    if (!this.connection) {
+      throw new Error('Not connected to Azure Blob');
+    }
+
+    const headers: Record<string, string> = {};
+    if (options.start !== undefined || options.length !== undefined) {
+      const start = options.start || 0;
+      const end = options.length ? start + options.length - 1 : '';
+      headers['x-ms-range'] = `bytes=${start}-${end}`;
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'GET',
+        url: this.toProtocolUrl(path),
+        headers,
+        body: undefined,
+        options: undefined
+      });
+
+      return {
+        content: response.body || '',
+        size: parseInt(response.headers['content-length'] || '0'),
+        encoding: 'utf-8',
+      };
+    } catch (error) {
+      console.error('Failed to get Azure Blob file content:', error);
+      throw new Error(`Failed to get file content: ${error}`);
+    }
+  }
+
+  async getFileSize(path: string): Promise<number> {
+    if (!this.connection) {
+      throw new Error('Not connected to Azure Blob');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'HEAD',
+        url: this.toProtocolUrl(path),
+        headers: {},
+        body: undefined,
+        options: undefined
+      });
+
+      return parseInt(response.headers['content-length'] || '0');
+    } catch (error) {
+      console.error('Failed to get Azure Blob file size:', error);
+      throw new Error(`Failed to get file size: ${error}`);
+    }
+  }
+
+  async downloadFile(path: string): Promise<Blob> {
+    if (!this.connection) {
+      throw new Error('Not connected to Azure Blob');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<number[]>('storage_request_binary', {
+        protocol: this.protocol,
+        method: 'GET',
+        url: this.toProtocolUrl(path),
+        headers: {},
+        options: undefined
+      }, DEFAULT_TIMEOUTS.download);
+
+      return new Blob([new Uint8Array(response)]);
+    } catch (error) {
+      console.error('Failed to download Azure Blob file:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 带进度的下载方法，后端通过当前连接分块读取 azblob:// 地址
+   */
+  async downloadFileWithProgress(path: string, filename: string): Promise<string> {
+    if (!this.connection) {
+      throw new Error('Not connected to Azure Blob');
+    }
+
+    try {
+      return await this.downloadWithProgress('GET', this.toProtocolUrl(path), filename);
+    } catch (error) {
+      console.error('Failed to download Azure Blob file with progress:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 将前端路径转换为协议统一的地址格式
+   * Azure Blob 协议格式：azblob://container/path/to/blob
+   */
+  toProtocolUrl(path: string): string {
+    if (!this.connection) {
+      throw new Error('Not connected to Azure Blob');
+    }
+
+    if (path.startsWith('azblob://')) {
+      return path;
+    }
+
+    return `azblob://${this.connection.container}/${this.getBlobName(path)}`;
+  }
+
+  /**
+   * 提取 Blob 名称（移除开头斜杠，清理重复斜杠）
+   */
+  private getBlobName(path: string): string {
+    return path.replace(/^\/+/, '').replace(/\/+/g, '/');
+  }
+
+  /**
+   * 获取认证头（签名由后端完成）
+   */
+  protected getAuthHeaders(): Record<string, string> {
+    return {};
+  }
+}
@@ -5,7 +5,8 @@ import {
   DirectoryResult,
   FileContent,
   ListOptions,
-  ReadOptions
+  ReadOptions,
+  StorageCredentials
 } from './types';
 import { ArchiveInfo, FilePreview } from '../../types';
 
@@ -33,6 +34,16 @@ export const DEFAULT_TIMEOUTS: TimeoutConfig = {
   list: 60000,      // 1分钟
 };
 
+/**
+ * 临时凭证过期时派发的 window 事件，由凭证刷新对话框监听
+ */
+export const CREDENTIALS_EXPIRED_EVENT = 'storage-credentials-expired';
+
+/**
+ * 后端在临时凭证过期时返回的错误键
+ */
+const CREDENTIALS_EXPIRED_ERROR = 'storage.credentials.expired';
+
 /**
  * 统一存储客户端基类
  * 提供所有存储类型的通用接口实现
@@ -65,11 +76,21 @@ export abstract class BaseStorageClient implements StorageClient {
         })
         .catch((error) => {
           clearTimeout(timeoutId);
+          this.notifyIfCredentialsExpired(error);
           reject(error);
         });
     });
   }
 
+  /**
+   * 后端返回凭证过期错误时通知界面刷新凭证
+   */
+  protected notifyIfCredentialsExpired(error: unknown): void {
+    if (String(error).includes(CREDENTIALS_EXPIRED_ERROR)) {
+      window.dispatchEvent(new CustomEvent(CREDENTIALS_EXPIRED_EVENT, { detail: { protocol: this.protocol } }));
+    }
+  }
+
   /**
    * 获取连接的显示名称
    */
@@ -137,12 +158,13 @@ export abstract class BaseStorageClient implements StorageClient {
   async analyzeArchive(
     path: string,
     filename: string,
-    maxSize?: number
+    maxSize?: number,
+    password?: string
   ): Promise<ArchiveInfo> {
     try {
       // 所有存储类型都使用统一的StorageClient流式接口
       console.log(`${this.protocol}存储使用统一流式分析:`, { path, filename });
-      return await this.analyzeArchiveWithClient(path, filename, maxSize);
+      return await this.analyzeArchiveWithClient(path, filename, maxSize, password);
     } catch (error) {
       console.error('Failed to analyze archive:', error);
       throw error;
@@ -156,12 +178,14 @@ export abstract class BaseStorageClient implements StorageClient {
     path: string,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    offset?: number,
+    password?: string
   ): Promise<FilePreview> {
     try {
       // 所有存储类型都使用统一的StorageClient流式接口
       console.log(`${this.protocol}存储使用统一流式预览:`, { path, filename, entryPath });
-      return await this.getArchiveFilePreviewWithClient(path, filename, entryPath, maxPreviewSize);
+      return await this.getArchiveFilePreviewWithClient(path, filename, entryPath, maxPreviewSize, offset, password);
     } catch (error) {
       console.error('Failed to get archive file preview:', error);
       throw error;
@@ -176,15 +200,18 @@ export abstract class BaseStorageClient implements StorageClient {
   protected async analyzeArchiveWithClient(
     path: string,
     filename: string,
-    maxSize?: number
+    maxSize?: number,
+    password?: string
   ): Promise<ArchiveInfo> {
     // 通过Tauri命令调用后端的存储客户端接口
+    // 大型压缩 TAR 需要顺序读完整个文件，分析期间条目通过 archive-analysis-progress 事件逐步显示
     return await this.invokeWithTimeout('analyze_archive_with_client', {
       protocol: this.protocol,
       filePath: path,
       filename,
-      maxSize
-    }, DEFAULT_TIMEOUTS.default);
+      maxSize,
+      password
+    }, DEFAULT_TIMEOUTS.download);
   }
 
   /**
@@ -194,7 +221,9 @@ export abstract class BaseStorageClient implements StorageClient {
     path: string,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    offset?: number,
+    password?: string
   ): Promise<FilePreview> {
     // 通过Tauri命令调用后端的存储客户端接口
     const result = await this.invokeWithTimeout('get_archive_preview_with_client', {
@@ -202,7 +231,9 @@ export abstract class BaseStorageClient implements StorageClient {
       filePath: path,
       filename,
       entryPath,
-      maxPreviewSize
+      maxPreviewSize,
+      offset,
+      password
     }, DEFAULT_TIMEOUTS.default) as FilePreview;
 
     // 确保 content 是 Uint8Array 类型，处理 Tauri 序列化的二进制数据
@@ -249,6 +280,7 @@ export abstract class BaseStorageClient implements StorageClient {
     password?: string | null;
     accessKey?: string | null;
     secretKey?: string | null;
+    sessionToken?: string | null;
     region?: string | null;
     bucket?: string | null;
     endpoint?: string | null;
@@ -281,6 +313,13 @@ export abstract class BaseStorageClient implements StorageClient {
     this.connected = false;
   }
 
+  /**
+   * 替换当前连接的访问凭证，无需重新连接
+   */
+  async updateCredentials(credentials: StorageCredentials): Promise<void> {
+    await this.invokeWithTimeout('storage_update_credentials', { credentials }, DEFAULT_TIMEOUTS.connect);
+  }
+
   /**
    * 获取认证头（子类实现）
    */
@@ -0,0 +1,298 @@
+import { BaseStorageClient, DEFAULT_TIMEOUTS } from './BaseStorageClient';
+import {
+  ConnectionConfig,
+  DirectoryResult,
+  FileContent,
+  FTPTlsMode,
+  ListOptions,
+  ReadOptions,
+  StorageResponse
+} from './types';
+
+interface FTPConnection {
+  scheme: string;
+  host: string;
+  port: number;
+  username: string;
+  rootPath: string;
+  connected: boolean;
+}
+
+const SCHEME_TLS_MODES: Record<string, FTPTlsMode> = {
+  ftp: 'none',
+  ftpes: 'explicit',
+  ftps: 'implicit',
+};
+
+/**
+ * FTP / FTPS 存储客户端实现
+ * 被动模式数据连接、TLS 和目录列表解析均由后端处理，加密方式由地址协议决定
+ */
+export class FTPStorageClient extends BaseStorageClient {
+  protected protocol = 'ftp';
+  private connection: FTPConnection | null = null;
+
+  /**
+   * 判断是否为 ftp://、ftpes:// 或 ftps:// 地址
+   */
+  static isFTPUrl(url: string): boolean {
+    return /^ftp(es|s)?:\/\//.test(url);
+  }
+
+  /**
+   * 加密方式对应的地址协议
+   */
+  static schemeForTlsMode(mode: FTPTlsMode): string {
+    return mode === 'explicit' ? 'ftpes' : mode === 'implicit' ? 'ftps' : 'ftp';
+  }
+
+  /**
+   * 解析 ftp[es|s]://user@host:port/path 格式的地址（省略协议时为明文 FTP）
+   */
+  static parseAddress(address: string): {
+    tlsMode: FTPTlsMode;
+    host: string;
+    port: number;
+    username: string;
+    rootPath: string;
+  } {
+    const trimmed = address.trim();
+    const url = new URL(trimmed.includes('://') ? trimmed : `ftp://${trimmed}`);
+    const scheme = url.protocol.replace(/:$/, '');
+    const tlsMode = SCHEME_TLS_MODES[scheme];
+    if (!tlsMode) {
+      throw new Error(`Unsupported FTP scheme: ${scheme}`);
+    }
+
+    return {
+      tlsMode,
+      host: url.hostname.replace(/^\[|\]$/g, ''),
+      port: url.port ? parseInt(url.port) : tlsMode === 'implicit' ? 990 : 21,
+      username: decodeURIComponent(url.username),
+      rootPath: decodeURIComponent(url.pathname).replace(/\/+$/, ''),
+    };
+  }
+
+  /**
+   * 获取连接的显示名称
+   */
+  getDisplayName(): string {
+    if (!this.connection) return 'FTP';
+    return this.connection.username
+      ? `${this.connection.username}@${this.connection.host}`
+      : this.connection.host;
+  }
+
+  /**
+   * 根据连接配置生成连接名称
+   */
+  generateConnectionName(config: ConnectionConfig): string {
+    try {
+      if (!config.url) {
+        return 'FTP';
+      }
+
+      const { host, username } = FTPStorageClient.parseAddress(config.url);
+      const user = config.username || username;
+      return user ? `FTP(${user}@${host})` : `FTP(${host})`;
+    } catch (error) {
+      return 'FTP';
+    }
+  }
+
+  async connect(config: ConnectionConfig): Promise<boolean> {
+    if (config.type !== 'ftp') {
+      throw new Error('Invalid connection type for FTP client');
+    }
+
+    if (!config.url) {
+      throw new Error('FTP requires a server address');
+    }
+
+    const address = FTPStorageClient.parseAddress(config.url);
+    const username = config.username || address.username;
+
+    try {
+      // 未填写用户名和密码时后端使用匿名登录
+      const success = await this.connectToBackend({
+        protocol: 'ftp',
+        url: config.url.trim(),
+        accessKey: null,
+        secretKey: null,
+        region: null,
+        bucket: null,
+        endpoint: null,
+        username: username || null,
+        password: config.password || null,
+        extraOptions: config.acceptInvalidCerts ? { accept_invalid_certs: 'true' } : null,
+      });
+
+      if (success) {
+        this.connection = {
+          scheme: FTPStorageClient.schemeForTlsMode(address.tlsMode),
+          host: address.host,
+          port: address.port,
+          username,
+          rootPath: address.rootPath,
+          connected: true,
+        };
+      }
+
+      return success;
+    } catch (error) {
+      console.error('FTP connection failed:', error);
+      throw new Error(`FTP connection failed: ${error}`);
+    }
+  }
+
+  disconnect(): void {
+    this.disconnectFromBackend();
+    this.connection = null;
+  }
+
+  async listDirectory(path: string = '', options?: ListOptions): Promise<DirectoryResult> {
+    if (!this.connection) {
+      throw new Error('Not connected to FTP');
+    }
+
+    try {
+      return await this.invokeWithTimeout<DirectoryResult>(
+        'storage_list_directory',
+        {
+          path,
+          options: options ? {
+            pageSize: options.pageSize,
+            marker: options.marker,
+            prefix: options.prefix,
+            recursive: options.recursive,
+            sortBy: options.sortBy,
+            sortOrder: options.sortOrder,
+          } : null,
+        },
+        DEFAULT_TIMEOUTS.list
+      );
+    } catch (error) {
+      console.error('Failed to list FTP directory:', error);
+      throw new Error(`Failed to list directory: ${error}`);
+    }
+  }
+
+  async getFileContent(path: string, options?: ReadOptions): Promise<FileContent> {
+    if (!this.connection) {
+      throw new Error('Not connected to FTP');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'READ_FILE',
+        url: path,
+        headers: {},
+        body: undefined,
+        options: {
+          start: options?.start,
+          length: options?.length
+        }
+      });
+
+      const data = JSON.parse(response.body);
+      return {
+        content: data.content,
+        size: data.size,
+        encoding: data.encoding || 'utf-8'
+      };
+    } catch (error) {
+      console.error('Failed to get FTP file content:', error);
+      throw new Error(`Failed to get file content: ${error}`);
+    }
+  }
+
+  async getFileSize(path: string): Promise<number> {
+    if (!this.connection) {
+      throw new Error('Not connected to FTP');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'GET_FILE_SIZE',
+        url: path,
+        headers: {},
+        body: undefined,
+        options: undefined
+      });
+
+      return JSON.parse(response.body).size;
+    } catch (error) {
+      console.error('Failed to get FTP file size:', error);
+      throw new Error(`Failed to get file size: ${error}`);
+    }
+  }
+
+  async downloadFile(path: string): Promise<Blob> {
+    if (!this.connection) {
+      throw new Error('Not connected to FTP');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<number[]>('storage_request_binary', {
+        protocol: this.protocol,
+        method: 'READ_FILE_BINARY',
+        url: path,
+        headers: {},
+        options: undefined
+      }, DEFAULT_TIMEOUTS.download);
+
+      return new Blob([new Uint8Array(response)]);
+    } catch (error) {
+      console.error('Failed to download FTP file:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 带进度的下载方法，后端通过当前 FTP 连接分块读取 ftp:// 地址
+   */
+  async downloadFileWithProgress(path: string, filename: string): Promise<string> {
+    if (!this.connection) {
+      throw new Error('Not connected to FTP');
+    }
+
+    try {
+      return await this.downloadWithProgress('GET', this.toProtocolUrl(path), filename);
+    } catch (error) {
+      console.error('Failed to download FTP file with progress:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 将前端路径转换为协议统一的地址格式
+   * FTP 协议格式：ftp://user@host:port/absolute/path（ftpes://、ftps:// 同理）
+   */
+  toProtocolUrl(path: string): string {
+    if (!this.connection) {
+      throw new Error('Not connected to FTP');
+    }
+
+    if (FTPStorageClient.isFTPUrl(path)) {
+      return path;
+    }
+
+    const { scheme, host, port, username, rootPath } = this.connection;
+    const relative = path.replace(/^\/+/, '').replace(/\/+/g, '/');
+    const absolute = relative ? `${rootPath}/${relative}` : rootPath || '/';
+    const encodedPath = absolute.split('/').map(segment => encodeURIComponent(segment)).join('/');
+    const hostPart = host.includes(':') ? `[${host}]` : host;
+    const userPart = username ? `${encodeURIComponent(username)}@` : '';
+
+    return `${scheme}://${userPart}${hostPart}:${port}${encodedPath}`;
+  }
+
+  /**
+   * 获取认证头（登录由后端控制连接完成）
+   */
+  protected getAuthHeaders(): Record<string, string> {
+    return {};
+  }
+}
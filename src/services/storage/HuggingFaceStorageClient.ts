@@ -347,7 +347,8 @@ export class HuggingFaceStorageClient extends BaseStorageClient {
   protected async analyzeArchiveWithClient(
     path: string,
     filename: string,
-    maxSize?: number
+    maxSize?: number,
+    password?: string
   ): Promise<ArchiveInfo> {
     // 使用协议URL格式
     // 直接使用传入的路径，因为它已经是协议URL格式
@@ -356,8 +357,9 @@ export class HuggingFaceStorageClient extends BaseStorageClient {
       protocol: this.protocol,
       filePath: path, // 直接使用传入的路径
       filename,
-      maxSize
-    }, DEFAULT_TIMEOUTS.default);
+      maxSize,
+      password
+    }, DEFAULT_TIMEOUTS.download);
   }
 
   /**
@@ -367,7 +369,9 @@ export class HuggingFaceStorageClient extends BaseStorageClient {
     path: string,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    offset?: number,
+    password?: string
   ): Promise<FilePreview> {
     // 直接使用传入的路径，因为它已经是协议URL格式
     // 通过Tauri命令调用后端的存储客户端接口
@@ -376,7 +380,9 @@ export class HuggingFaceStorageClient extends BaseStorageClient {
       filePath: path, // 直接使用传入的路径
       filename,
       entryPath,
-      maxPreviewSize
+      maxPreviewSize,
+      offset,
+      password
     }, DEFAULT_TIMEOUTS.default) as FilePreview;
 
     // 确保 content 是 Uint8Array 类型，处理 Tauri 序列化的二进制数据
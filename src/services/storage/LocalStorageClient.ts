@@ -258,13 +258,14 @@ export class LocalStorageClient extends BaseStorageClient {
   async analyzeArchive(
     path: string,
     filename: string,
-    maxSize?: number
+    maxSize?: number,
+    password?: string
   ): Promise<ArchiveInfo> {
     try {
       // 本地文件使用统一的StorageClient流式分析接口
       console.log('本地文件使用统一流式分析:', { path, filename });
 
-      const result = await this.analyzeArchiveWithClient(path, filename, maxSize);
+      const result = await this.analyzeArchiveWithClient(path, filename, maxSize, password);
 
       return result;
     } catch (error) {
@@ -280,7 +281,9 @@ export class LocalStorageClient extends BaseStorageClient {
     path: string,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    offset?: number,
+    password?: string
   ): Promise<FilePreview> {
     try {
       // 对于本地文件，使用存储客户端接口进行流式预览
@@ -295,7 +298,9 @@ export class LocalStorageClient extends BaseStorageClient {
         path,
         filename,
         entryPath,
-        maxPreviewSize
+        maxPreviewSize,
+        offset,
+        password
       );
     } catch (error) {
       console.error('Failed to get local archive file preview:', error);
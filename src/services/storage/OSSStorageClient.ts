@@ -1,11 +1,11 @@
-import { invoke } from '@tauri-apps/api/core';
-import { BaseStorageClient } from './BaseStorageClient';
+import { BaseStorageClient, DEFAULT_TIMEOUTS } from './BaseStorageClient';
 import {
   ConnectionConfig,
   DirectoryResult,
   FileContent,
   ListOptions,
   ReadOptions,
+  StorageCredentials,
   StorageResponse
 } from './types';
 import { ArchiveInfo, FilePreview } from '../../types';
@@ -221,11 +221,13 @@ export class OSSStorageClient extends BaseStorageClient {
         url: normalizedEndpoint,
         accessKey: accessKey,
         secretKey: secretKey,
+        sessionToken: config.sessionToken || null,
         bucket: bucket,
         region: region,
         username: null,
         password: null,
-        extraOptions: null,
+        // 未指定时后端使用 V1 签名
+        extraOptions: config.signatureVersion ? { signature_version: config.signatureVersion } : null,
       });
 
       if (success) {
@@ -248,6 +250,17 @@ export class OSSStorageClient extends BaseStorageClient {
     }
   }
 
+  /**
+   * 替换访问凭证（如 STS 临时凭证过期后），连接保持不变
+   */
+  async updateCredentials(credentials: StorageCredentials): Promise<void> {
+    await super.updateCredentials(credentials);
+    if (this.connection) {
+      this.connection.accessKey = credentials.accessKey;
+      this.connection.secretKey = credentials.secretKey;
+    }
+  }
+
   disconnect(): void {
     // 使用基类的通用断开连接方法
     this.disconnectFromBackend();
@@ -264,7 +277,7 @@ export class OSSStorageClient extends BaseStorageClient {
 
     try {
       // 直接调用后端的 list_directory 方法，而不是通用的 request 方法
-      const result = await invoke('storage_list_directory', {
+      const result = await this.invokeWithTimeout('storage_list_directory', {
         path: objectKeyPrefix,
         options: {
           page_size: options.pageSize || 1000,
@@ -275,7 +288,7 @@ export class OSSStorageClient extends BaseStorageClient {
           sort_by: options.sortBy || 'name',
           sort_order: options.sortOrder || 'asc',
         }
-      });
+      }, DEFAULT_TIMEOUTS.list);
 
       return result as DirectoryResult;
     } catch (error) {
@@ -302,7 +315,7 @@ export class OSSStorageClient extends BaseStorageClient {
       }
 
       // 使用统一的协议URL格式
-      const response = await invoke<StorageResponse>('storage_request', {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
         protocol: this.protocol,
         method: 'GET',
         url: this.toProtocolUrl(path),
@@ -328,7 +341,7 @@ export class OSSStorageClient extends BaseStorageClient {
     }
 
     try {
-      const response = await invoke<StorageResponse>('storage_request', {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
         protocol: this.protocol,
         method: 'HEAD',
         url: this.toProtocolUrl(path),
@@ -350,13 +363,13 @@ export class OSSStorageClient extends BaseStorageClient {
     }
 
     try {
-      const response = await invoke<number[]>('storage_request_binary', {
+      const response = await this.invokeWithTimeout<number[]>('storage_request_binary', {
         protocol: this.protocol,
         method: 'GET',
         url: this.toProtocolUrl(path),
         headers: this.getAuthHeaders(),
         options: undefined
-      });
+      }, DEFAULT_TIMEOUTS.download);
 
       // 直接使用返回的二进制数据创建 Blob
       const uint8Array = new Uint8Array(response);
@@ -432,14 +445,15 @@ export class OSSStorageClient extends BaseStorageClient {
   async analyzeArchive(
     path: string,
     filename: string,
-    maxSize?: number
+    maxSize?: number,
+    password?: string
   ): Promise<ArchiveInfo> {
     try {
       // OSS使用统一的StorageClient流式分析接口
       console.log('OSS使用统一流式分析:', { originalPath: path, filename });
 
       // 直接传递path，因为它已经是协议URL格式
-      const result = await this.analyzeArchiveWithClient(path, filename, maxSize);
+      const result = await this.analyzeArchiveWithClient(path, filename, maxSize, password);
 
       return result;
     } catch (error) {
@@ -455,7 +469,9 @@ export class OSSStorageClient extends BaseStorageClient {
     path: string,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    offset?: number,
+    password?: string
   ): Promise<FilePreview> {
     try {
       // 对于OSS，使用存储客户端接口而不是HTTP接口进行流式预览
@@ -470,7 +486,9 @@ export class OSSStorageClient extends BaseStorageClient {
         path,
         filename,
         entryPath,
-        maxPreviewSize
+        maxPreviewSize,
+        offset,
+        password
       );
     } catch (error) {
       console.error('Failed to get OSS archive file preview:', error);
@@ -0,0 +1,266 @@
+import { BaseStorageClient, DEFAULT_TIMEOUTS } from './BaseStorageClient';
+import {
+  ConnectionConfig,
+  DirectoryResult,
+  FileContent,
+  ListOptions,
+  ReadOptions,
+  S3AddressingStyle,
+  StorageResponse
+} from './types';
+
+interface S3Connection {
+  endpoint: string;
+  accessKey: string;
+  secretKey: string;
+  bucket: string;
+  region: string;
+  addressingStyle?: S3AddressingStyle;
+  connected: boolean;
+}
+
+/**
+ * S3 兼容对象存储客户端实现
+ * 支持 AWS S3、MinIO、Ceph RGW 等使用 Signature V4 签名的服务，签名和分页均由后端处理
+ */
+export class S3StorageClient extends BaseStorageClient {
+  protected protocol = 's3';
+  private connection: S3Connection | null = null;
+
+  /**
+   * 获取连接的显示名称
+   */
+  getDisplayName(): string {
+    if (!this.connection) return 'S3';
+
+    try {
+      return `${this.connection.bucket}@${new URL(this.connection.endpoint).host}`;
+    } catch {
+      return `${this.connection.bucket} (S3)`;
+    }
+  }
+
+  /**
+   * 根据连接配置生成连接名称
+   */
+  generateConnectionName(config: ConnectionConfig): string {
+    try {
+      const url = config.endpoint || config.url;
+      if (!url) {
+        return 'S3';
+      }
+
+      // 处理保存的 s3://host/bucket 格式
+      if (url.startsWith('s3://')) {
+        const [host, bucket] = url.replace('s3://', '').split('/');
+        return bucket ? `S3(${host}-${bucket})` : `S3(${host})`;
+      }
+
+      const host = new URL(url).host;
+      return config.bucket ? `S3(${host}-${config.bucket})` : `S3(${host})`;
+    } catch (error) {
+      return 'S3';
+    }
+  }
+
+  async connect(config: ConnectionConfig): Promise<boolean> {
+    if (config.type !== 's3') {
+      throw new Error('Invalid connection type for S3 client');
+    }
+
+    // url 或 endpoint 作为端点，username 作为 accessKey，password 作为 secretKey
+    const endpoint = (config.endpoint || config.url || '').trim();
+    if (!endpoint || !config.username || !config.password || !config.bucket) {
+      throw new Error('S3 requires endpoint, accessKey (username), secretKey (password) and bucket');
+    }
+
+    const region = config.region || 'us-east-1';
+
+    try {
+      const success = await this.connectToBackend({
+        protocol: 's3',
+        url: endpoint,
+        accessKey: config.username,
+        secretKey: config.password,
+        bucket: config.bucket,
+        region,
+        username: null,
+        password: null,
+        // 未指定时由后端根据端点选择寻址风格（AWS 域名使用虚拟主机风格，其他端点使用路径风格）
+        extraOptions: config.addressingStyle ? { addressing_style: config.addressingStyle } : null,
+      });
+
+      if (success) {
+        this.connection = {
+          endpoint,
+          accessKey: config.username,
+          secretKey: config.password,
+          bucket: config.bucket,
+          region,
+          addressingStyle: config.addressingStyle,
+          connected: true,
+        };
+      }
+
+      return success;
+    } catch (error) {
+      console.error('S3 connection failed:', error);
+      throw new Error(`S3 connection failed: ${error}`);
+    }
+  }
+
+  disconnect(): void {
+    this.disconnectFromBackend();
+    this.connection = null;
+  }
+
+  async listDirectory(path: string, options: ListOptions = {}): Promise<DirectoryResult> {
+    if (!this.connection) {
+      throw new Error('Not connected to S3');
+    }
+
+    try {
+      // 后端使用 ListObjectsV2，分页标记通过 nextMarker / marker 传递
+      return await this.invokeWithTimeout<DirectoryResult>(
+        'storage_list_directory',
+        {
+          path: this.getObjectKey(path),
+          options: {
+            page_size: options.pageSize || 1000,
+            marker: options.marker,
+            prefix: options.prefix,
+            recursive: options.recursive || false,
+            sort_by: options.sortBy || 'name',
+            sort_order: options.sortOrder || 'asc',
+          }
+        },
+        DEFAULT_TIMEOUTS.list
+      );
+    } catch (error) {
+      console.error('Failed to list S3 directory:', error);
+      throw new Error(`Failed to list directory: ${error}`);
+    }
+  }
+
+  async getFileContent(path: string, options: ReadOptions = {}): Promise<FileContent> {
+    if (!this.connection) {
+      throw new Error('Not connected to S3');
+    }
+
+    const headers: Record<string, string> = {};
+    if (options.start !== undefined || options.length !== undefined) {
+      const start = options.start || 0;
+      const end = options.length ? start + options.length - 1 : '';
+      headers['Range'] = `bytes=${start}-${end}`;
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'GET',
+        url: this.toProtocolUrl(path),
+        headers,
+        body: undefined,
+        options: undefined
+      });
+
+      return {
+        content: response.body || '',
+        size: parseInt(response.headers['content-length'] || '0'),
+        encoding: 'utf-8',
+      };
+    } catch (error) {
+      console.error('Failed to get S3 file content:', error);
+      throw new Error(`Failed to get file content: ${error}`);
+    }
+  }
+
+  async getFileSize(path: string): Promise<number> {
+    if (!this.connection) {
+      throw new Error('Not connected to S3');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'HEAD',
+        url: this.toProtocolUrl(path),
+        headers: {},
+        body: undefined,
+        options: undefined
+      });
+
+      return parseInt(response.headers['content-length'] || '0');
+    } catch (error) {
+      console.error('Failed to get S3 file size:', error);
+      throw new Error(`Failed to get file size: ${error}`);
+    }
+  }
+
+  async downloadFile(path: string): Promise<Blob> {
+    if (!this.connection) {
+      throw new Error('Not connected to S3');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<number[]>('storage_request_binary', {
+        protocol: this.protocol,
+        method: 'GET',
+        url: this.toProtocolUrl(path),
+        headers: {},
+        options: undefined
+      }, DEFAULT_TIMEOUTS.download);
+
+      return new Blob([new Uint8Array(response)]);
+    } catch (error) {
+      console.error('Failed to download S3 file:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 带进度的下载方法，后端会把 s3:// 地址转换为预签名 URL
+   */
+  async downloadFileWithProgress(path: string, filename: string): Promise<string> {
+    if (!this.connection) {
+      throw new Error('Not connected to S3');
+    }
+
+    try {
+      return await this.downloadWithProgress('GET', this.toProtocolUrl(path), filename);
+    } catch (error) {
+      console.error('Failed to download S3 file with progress:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 将前端路径转换为协议统一的地址格式
+   * S3 协议格式：s3://bucket/path/to/file
+   */
+  toProtocolUrl(path: string): string {
+    if (!this.connection) {
+      throw new Error('Not connected to S3');
+    }
+
+    if (path.startsWith('s3://')) {
+      return path;
+    }
+
+    return `s3://${this.connection.bucket}/${this.getObjectKey(path)}`;
+  }
+
+  /**
+   * 提取对象键（移除开头斜杠，清理重复斜杠）
+   */
+  private getObjectKey(path: string): string {
+    return path.replace(/^\/+/, '').replace(/\/+/g, '/');
+  }
+
+  /**
+   * 获取认证头（签名由后端完成）
+   */
+  protected getAuthHeaders(): Record<string, string> {
+    return {};
+  }
+}
@@ -0,0 +1,269 @@
+import { BaseStorageClient, DEFAULT_TIMEOUTS } from './BaseStorageClient';
+import {
+  ConnectionConfig,
+  DirectoryResult,
+  FileContent,
+  ListOptions,
+  ReadOptions,
+  StorageResponse
+} from './types';
+
+interface SFTPConnection {
+  host: string;
+  port: number;
+  username: string;
+  rootPath: string;
+  connected: boolean;
+}
+
+/**
+ * SFTP 存储客户端实现
+ * SSH 连接、主机密钥校验和认证均由后端处理，前端只传递地址和认证信息
+ */
+export class SFTPStorageClient extends BaseStorageClient {
+  protected protocol = 'sftp';
+  private connection: SFTPConnection | null = null;
+
+  /**
+   * 解析 sftp://user@host:port/path 格式的地址（协议、用户名和端口均可省略）
+   */
+  static parseAddress(address: string): { host: string; port: number; username: string; rootPath: string } {
+    const trimmed = address.trim();
+    const url = new URL(trimmed.includes('://') ? trimmed : `sftp://${trimmed}`);
+
+    return {
+      host: url.hostname.replace(/^\[|\]$/g, ''),
+      port: url.port ? parseInt(url.port) : 22,
+      username: decodeURIComponent(url.username),
+      rootPath: decodeURIComponent(url.pathname).replace(/\/+$/, ''),
+    };
+  }
+
+  /**
+   * 获取连接的显示名称
+   */
+  getDisplayName(): string {
+    if (!this.connection) return 'SFTP';
+    return `${this.connection.username}@${this.connection.host}`;
+  }
+
+  /**
+   * 根据连接配置生成连接名称
+   */
+  generateConnectionName(config: ConnectionConfig): string {
+    try {
+      if (!config.url) {
+        return 'SFTP';
+      }
+
+      const { host, username } = SFTPStorageClient.parseAddress(config.url);
+      const user = config.username || username;
+      return user ? `SFTP(${user}@${host})` : `SFTP(${host})`;
+    } catch (error) {
+      return 'SFTP';
+    }
+  }
+
+  async connect(config: ConnectionConfig): Promise<boolean> {
+    if (config.type !== 'sftp') {
+      throw new Error('Invalid connection type for SFTP client');
+    }
+
+    if (!config.url || (!config.password && !config.privateKey)) {
+      throw new Error('SFTP requires an address and a password or private key');
+    }
+
+    const address = SFTPStorageClient.parseAddress(config.url);
+    const username = config.username || address.username;
+    if (!username) {
+      throw new Error('SFTP requires a username');
+    }
+
+    // 可选项只在填写时传递，未填写时使用后端默认值（~/.ssh/known_hosts、strict）
+    const extraOptions: Record<string, string> = {};
+    if (config.privateKey) extraOptions.private_key = config.privateKey;
+    if (config.privateKeyPassphrase) extraOptions.private_key_passphrase = config.privateKeyPassphrase;
+    if (config.knownHosts) extraOptions.known_hosts = config.knownHosts;
+    if (config.hostKeyPolicy) extraOptions.host_key_policy = config.hostKeyPolicy;
+
+    try {
+      const success = await this.connectToBackend({
+        protocol: 'sftp',
+        url: config.url.trim(),
+        accessKey: null,
+        secretKey: null,
+        region: null,
+        bucket: null,
+        endpoint: null,
+        username,
+        password: config.password || null,
+        extraOptions: Object.keys(extraOptions).length > 0 ? extraOptions : null,
+      });
+
+      if (success) {
+        this.connection = {
+          host: address.host,
+          port: address.port,
+          username,
+          rootPath: address.rootPath,
+          connected: true,
+        };
+      }
+
+      return success;
+    } catch (error) {
+      console.error('SFTP connection failed:', error);
+      throw new Error(`SFTP connection failed: ${error}`);
+    }
+  }
+
+  disconnect(): void {
+    this.disconnectFromBackend();
+    this.connection = null;
+  }
+
+  async listDirectory(path: string = '', options?: ListOptions): Promise<DirectoryResult> {
+    if (!this.connection) {
+      throw new Error('Not connected to SFTP');
+    }
+
+    try {
+      return await this.invokeWithTimeout<DirectoryResult>(
+        'storage_list_directory',
+        {
+          path,
+          options: options ? {
+            pageSize: options.pageSize,
+            marker: options.marker,
+            prefix: options.prefix,
+            recursive: options.recursive,
+            sortBy: options.sortBy,
+            sortOrder: options.sortOrder,
+          } : null,
+        },
+        DEFAULT_TIMEOUTS.list
+      );
+    } catch (error) {
+      console.error('Failed to list SFTP directory:', error);
+      throw new Error(`Failed to list directory: ${error}`);
+    }
+  }
+
+  async getFileContent(path: string, options?: ReadOptions): Promise<FileContent> {
+    if (!this.connection) {
+      throw new Error('Not connected to SFTP');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'READ_FILE',
+        url: path,
+        headers: {},
+        body: undefined,
+        options: {
+          start: options?.start,
+          length: options?.length
+        }
+      });
+
+      const data = JSON.parse(response.body);
+      return {
+        content: data.content,
+        size: data.size,
+        encoding: data.encoding || 'utf-8'
+      };
+    } catch (error) {
+      console.error('Failed to get SFTP file content:', error);
+      throw new Error(`Failed to get file content: ${error}`);
+    }
+  }
+
+  async getFileSize(path: string): Promise<number> {
+    if (!this.connection) {
+      throw new Error('Not connected to SFTP');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<StorageResponse>('storage_request', {
+        protocol: this.protocol,
+        method: 'GET_FILE_SIZE',
+        url: path,
+        headers: {},
+        body: undefined,
+        options: undefined
+      });
+
+      return JSON.parse(response.body).size;
+    } catch (error) {
+      console.error('Failed to get SFTP file size:', error);
+      throw new Error(`Failed to get file size: ${error}`);
+    }
+  }
+
+  async downloadFile(path: string): Promise<Blob> {
+    if (!this.connection) {
+      throw new Error('Not connected to SFTP');
+    }
+
+    try {
+      const response = await this.invokeWithTimeout<number[]>('storage_request_binary', {
+        protocol: this.protocol,
+        method: 'READ_FILE_BINARY',
+        url: path,
+        headers: {},
+        options: undefined
+      }, DEFAULT_TIMEOUTS.download);
+
+      return new Blob([new Uint8Array(response)]);
+    } catch (error) {
+      console.error('Failed to download SFTP file:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 带进度的下载方法，后端通过当前 SFTP 连接分块读取 sftp:// 地址
+   */
+  async downloadFileWithProgress(path: string, filename: string): Promise<string> {
+    if (!this.connection) {
+      throw new Error('Not connected to SFTP');
+    }
+
+    try {
+      return await this.downloadWithProgress('GET', this.toProtocolUrl(path), filename);
+    } catch (error) {
+      console.error('Failed to download SFTP file with progress:', error);
+      throw new Error(`Failed to download file: ${error}`);
+    }
+  }
+
+  /**
+   * 将前端路径转换为协议统一的地址格式
+   * SFTP 协议格式：sftp://user@host:port/absolute/path
+   */
+  toProtocolUrl(path: string): string {
+    if (!this.connection) {
+      throw new Error('Not connected to SFTP');
+    }
+
+    if (path.startsWith('sftp://')) {
+      return path;
+    }
+
+    const { host, port, username, rootPath } = this.connection;
+    const relative = path.replace(/^\/+/, '').replace(/\/+/g, '/');
+    const absolute = relative ? `${rootPath}/${relative}` : rootPath || '/';
+    const encodedPath = absolute.split('/').map(segment => encodeURIComponent(segment)).join('/');
+    const hostPart = host.includes(':') ? `[${host}]` : host;
+
+    return `sftp://${encodeURIComponent(username)}@${hostPart}:${port}${encodedPath}`;
+  }
+
+  /**
+   * 获取认证头（认证由后端 SSH 连接完成）
+   */
+  protected getAuthHeaders(): Record<string, string> {
+    return {};
+  }
+}
@@ -2,8 +2,12 @@ import { BaseStorageClient } from './BaseStorageClient';
 import { WebDAVStorageClient } from './WebDAVStorageClient';
 import { LocalStorageClient } from './LocalStorageClient';
 import { OSSStorageClient } from './OSSStorageClient';
+import { S3StorageClient } from './S3StorageClient';
+import { SFTPStorageClient } from './SFTPStorageClient';
+import { FTPStorageClient } from './FTPStorageClient';
+import { AzureBlobStorageClient } from './AzureBlobStorageClient';
 import { HuggingFaceStorageClient } from './HuggingFaceStorageClient';
-import { ConnectionConfig, StorageClientType } from './types';
+import { ConnectionConfig, StorageClientType, StorageCredentials } from './types';
 import { connectionStorage, StoredConnection } from '../connectionStorage';
 
 /**
@@ -24,6 +28,14 @@ export class StorageClientFactory {
         return new LocalStorageClient();
       case 'oss':
         return new OSSStorageClient();
+      case 's3':
+        return new S3StorageClient();
+      case 'sftp':
+        return new SFTPStorageClient();
+      case 'ftp':
+        return new FTPStorageClient();
+      case 'azblob':
+        return new AzureBlobStorageClient();
       case 'huggingface':
         return new HuggingFaceStorageClient();
       default:
@@ -88,7 +100,7 @@ export class StorageClientFactory {
    * 检查是否支持指定的存储类型
    */
   static isSupportedType(type: string): type is StorageClientType {
-    return ['webdav', 'local', 'oss', 'huggingface'].includes(type);
+    return ['webdav', 'local', 'oss', 's3', 'sftp', 'ftp', 'azblob', 'huggingface'].includes(type);
   }
 
   /**
@@ -189,7 +201,82 @@ export class StorageServiceManager {
           metadata: {
             bucket: config.bucket,
             region: config.region,
-            endpoint: config.endpoint || config.url
+            endpoint: config.endpoint || config.url,
+            sessionToken: config.sessionToken,
+            signatureVersion: config.signatureVersion
+          }
+        };
+        await connectionStorage.saveConnection(connectionData, config.name, true);
+        break;
+      }
+
+      case 's3': {
+        const endpointUrl = new URL(config.endpoint || config.url!);
+        connectionData = {
+          url: `s3://${endpointUrl.host}/${config.bucket}`,
+          username: config.username!,
+          password: config.password!,
+          connected: true,
+          metadata: {
+            bucket: config.bucket,
+            region: config.region,
+            endpoint: config.endpoint || config.url,
+            addressingStyle: config.addressingStyle
+          }
+        };
+        await connectionStorage.saveConnection(connectionData, config.name, true);
+        break;
+      }
+
+      case 'sftp': {
+        const address = SFTPStorageClient.parseAddress(config.url!);
+        const username = config.username || address.username;
+        const host = address.host.includes(':') ? `[${address.host}]` : address.host;
+        connectionData = {
+          url: `sftp://${encodeURIComponent(username)}@${host}:${address.port}${address.rootPath}`,
+          username,
+          password: config.password || '',
+          connected: true,
+          metadata: {
+            privateKey: config.privateKey,
+            privateKeyPassphrase: config.privateKeyPassphrase,
+            knownHosts: config.knownHosts,
+            hostKeyPolicy: config.hostKeyPolicy
+          }
+        };
+        await connectionStorage.saveConnection(connectionData, config.name, true);
+        break;
+      }
+
+      case 'ftp': {
+        const address = FTPStorageClient.parseAddress(config.url!);
+        const username = config.username || address.username;
+        const host = address.host.includes(':') ? `[${address.host}]` : address.host;
+        const userPart = username ? `${encodeURIComponent(username)}@` : '';
+        connectionData = {
+          url: `${FTPStorageClient.schemeForTlsMode(address.tlsMode)}://${userPart}${host}:${address.port}${address.rootPath}`,
+          username,
+          password: config.password || '',
+          connected: true,
+          metadata: {
+            acceptInvalidCerts: config.acceptInvalidCerts
+          }
+        };
+        await connectionStorage.saveConnection(connectionData, config.name, true);
+        break;
+      }
+
+      case 'azblob': {
+        const endpointUrl = new URL(config.endpoint || config.url!);
+        connectionData = {
+          url: `azblob://${endpointUrl.host}/${config.bucket}`,
+          username: config.username || '',
+          password: config.password || '',
+          connected: true,
+          metadata: {
+            bucket: config.bucket,
+            endpoint: config.endpoint || config.url,
+            sasToken: config.sasToken
           }
         };
         await connectionStorage.saveConnection(connectionData, config.name, true);
@@ -226,6 +313,26 @@ export class StorageServiceManager {
           return conn.url.startsWith('file:///') && conn.url.includes(config.rootPath || config.url!);
         case 'oss':
           return conn.url.startsWith('oss://') && conn.username === config.username;
+        case 's3':
+          return conn.url.startsWith('s3://') && conn.username === config.username
+            && conn.metadata?.bucket === config.bucket;
+        case 'sftp': {
+          if (!conn.url.startsWith('sftp://')) return false;
+          const stored = SFTPStorageClient.parseAddress(conn.url);
+          const current = SFTPStorageClient.parseAddress(config.url!);
+          return stored.host === current.host && stored.port === current.port
+            && conn.username === (config.username || current.username);
+        }
+        case 'ftp': {
+          if (!FTPStorageClient.isFTPUrl(conn.url)) return false;
+          const stored = FTPStorageClient.parseAddress(conn.url);
+          const current = FTPStorageClient.parseAddress(config.url!);
+          return stored.host === current.host && stored.port === current.port
+            && conn.username === (config.username || current.username);
+        }
+        case 'azblob':
+          return conn.url.startsWith('azblob://') && conn.metadata?.endpoint === (config.endpoint || config.url)
+            && conn.metadata?.bucket === config.bucket;
         case 'huggingface':
           // 匹配 HuggingFace 连接：使用 metadata 中的组织信息
           const storedOrg = conn.metadata?.organization;
@@ -321,6 +428,27 @@ export class StorageServiceManager {
     }
   }
 
+  /**
+   * 替换当前连接的访问凭证（如 STS 临时凭证过期后），连接和浏览状态保持不变
+   */
+  static async updateCredentials(credentials: StorageCredentials): Promise<void> {
+    const client = this.getCurrentClient();
+    const config = this.getCurrentConnection();
+    const storedConnection = this.findMatchingStoredConnection(this.getStoredConnections(), config);
+
+    await client.updateCredentials(credentials);
+
+    config.username = credentials.accessKey;
+    config.password = credentials.secretKey;
+    config.sessionToken = credentials.sessionToken;
+
+    if (storedConnection) {
+      connectionStorage.updateCredentials(storedConnection.id, credentials.accessKey, credentials.secretKey, {
+        sessionToken: credentials.sessionToken
+      });
+    }
+  }
+
   /**
    * 获取存储类型特定的功能信息
    */
@@ -382,6 +510,68 @@ export class StorageServiceManager {
           bucket: bucketName,
           region: region,
           endpoint: endpoint,
+          sessionToken: defaultConnection.metadata?.sessionToken,
+          signatureVersion: defaultConnection.metadata?.signatureVersion,
+          name: defaultConnection.name
+        };
+
+        await this.setCurrentStorage(config);
+        return true;
+      } else if (defaultConnection.url.startsWith('s3://')) {
+        // S3 连接 - 从 metadata 获取信息
+        const config: ConnectionConfig = {
+          type: 's3',
+          url: defaultConnection.metadata?.endpoint,
+          username: defaultConnection.username, // accessKey
+          password: defaultConnection.password || '', // secretKey
+          bucket: defaultConnection.metadata?.bucket,
+          region: defaultConnection.metadata?.region,
+          endpoint: defaultConnection.metadata?.endpoint,
+          addressingStyle: defaultConnection.metadata?.addressingStyle,
+          name: defaultConnection.name
+        };
+
+        await this.setCurrentStorage(config);
+        return true;
+      } else if (defaultConnection.url.startsWith('sftp://')) {
+        // SFTP 连接 - 地址中包含用户、主机、端口和根目录，私钥等信息从 metadata 获取
+        const config: ConnectionConfig = {
+          type: 'sftp',
+          url: defaultConnection.url,
+          username: defaultConnection.username,
+          password: defaultConnection.password || '',
+          privateKey: defaultConnection.metadata?.privateKey,
+          privateKeyPassphrase: defaultConnection.metadata?.privateKeyPassphrase,
+          knownHosts: defaultConnection.metadata?.knownHosts,
+          hostKeyPolicy: defaultConnection.metadata?.hostKeyPolicy,
+          name: defaultConnection.name
+        };
+
+        await this.setCurrentStorage(config);
+        return true;
+      } else if (FTPStorageClient.isFTPUrl(defaultConnection.url)) {
+        // FTP 连接 - 地址中包含加密方式、用户、主机、端口和根目录
+        const config: ConnectionConfig = {
+          type: 'ftp',
+          url: defaultConnection.url,
+          username: defaultConnection.username,
+          password: defaultConnection.password || '',
+          acceptInvalidCerts: defaultConnection.metadata?.acceptInvalidCerts,
+          name: defaultConnection.name
+        };
+
+        await this.setCurrentStorage(config);
+        return true;
+      } else if (defaultConnection.url.startsWith('azblob://')) {
+        // Azure Blob 连接 - 从 metadata 获取端点、容器和 SAS 令牌
+        const config: ConnectionConfig = {
+          type: 'azblob',
+          url: defaultConnection.metadata?.endpoint,
+          username: defaultConnection.username, // 账户名
+          password: defaultConnection.password || '', // 账户密钥
+          bucket: defaultConnection.metadata?.bucket,
+          endpoint: defaultConnection.metadata?.endpoint,
+          sasToken: defaultConnection.metadata?.sasToken,
           name: defaultConnection.name
         };
 
@@ -481,7 +671,8 @@ export class StorageServiceManager {
     archivePath: string,
     archiveFilename: string,
     entryPath: string,
-    entryFilename: string
+    entryFilename: string,
+    password?: string
   ): Promise<string> {
     const { invoke } = await import('@tauri-apps/api/core');
     
@@ -494,6 +685,7 @@ export class StorageServiceManager {
         archiveFilename,
         entryPath,
         entryFilename,
+        password,
       }) as Promise<string>,
       new Promise<never>((_, reject) => {
         setTimeout(() => {
@@ -636,6 +828,34 @@ export class StorageServiceManager {
     return await this.connectWithConfig(config);
   }
 
+  /**
+   * 连接到 S3 兼容对象存储
+   */
+  static async connectToS3(config: ConnectionConfig): Promise<boolean> {
+    return await this.connectWithConfig(config);
+  }
+
+  /**
+   * 连接到 SFTP 服务器
+   */
+  static async connectToSFTP(config: ConnectionConfig): Promise<boolean> {
+    return await this.connectWithConfig(config);
+  }
+
+  /**
+   * 连接到 FTP / FTPS 服务器
+   */
+  static async connectToFTP(config: ConnectionConfig): Promise<boolean> {
+    return await this.connectWithConfig(config);
+  }
+
+  /**
+   * 连接到 Azure Blob Storage
+   */
+  static async connectToAzureBlob(config: ConnectionConfig): Promise<boolean> {
+    return await this.connectWithConfig(config);
+  }
+
   /**
    * 连接到 HuggingFace Hub
    */
@@ -306,7 +306,8 @@ export class WebDAVStorageClient extends BaseStorageClient {
   protected async analyzeArchiveWithClient(
     path: string,
     filename: string,
-    maxSize?: number
+    maxSize?: number,
+    password?: string
   ): Promise<ArchiveInfo> {
     // 直接使用传入的路径，因为它已经是协议URL格式
     // 通过Tauri命令调用后端的存储客户端接口
@@ -314,8 +315,9 @@ export class WebDAVStorageClient extends BaseStorageClient {
       protocol: this.protocol,
       filePath: path, // 直接使用传入的路径
       filename,
-      maxSize
-    }, DEFAULT_TIMEOUTS.default);
+      maxSize,
+      password
+    }, DEFAULT_TIMEOUTS.download);
   }
 
   /**
@@ -325,7 +327,9 @@ export class WebDAVStorageClient extends BaseStorageClient {
     path: string,
     filename: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxPreviewSize?: number,
+    offset?: number,
+    password?: string
   ): Promise<FilePreview> {
     // 直接使用传入的路径，因为它已经是协议URL格式
     // 通过Tauri命令调用后端的存储客户端接口
@@ -334,7 +338,9 @@ export class WebDAVStorageClient extends BaseStorageClient {
       filePath: path, // 直接使用传入的路径
       filename,
       entryPath,
-      maxPreviewSize
+      maxPreviewSize,
+      offset,
+      password
     }, DEFAULT_TIMEOUTS.default) as FilePreview;
 
     // 确保 content 是 Uint8Array 类型，处理 Tauri 序列化的二进制数据
@@ -3,6 +3,10 @@ export { BaseStorageClient } from './BaseStorageClient';
 export { WebDAVStorageClient } from './WebDAVStorageClient';
 export { LocalStorageClient } from './LocalStorageClient';
 export { OSSStorageClient } from './OSSStorageClient';
+export { S3StorageClient } from './S3StorageClient';
+export { SFTPStorageClient } from './SFTPStorageClient';
+export { FTPStorageClient } from './FTPStorageClient';
+export { AzureBlobStorageClient } from './AzureBlobStorageClient';
 export { HuggingFaceStorageClient } from './HuggingFaceStorageClient';
 export { StorageClientFactory, StorageServiceManager } from './StorageManager';
 export * from './types';
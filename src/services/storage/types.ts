@@ -1,5 +1,13 @@
 // 存储类型定义
-export type StorageClientType = 'webdav' | 'oss' | 's3' | 'local' | 'huggingface';
+export type StorageClientType = 'webdav' | 'oss' | 's3' | 'sftp' | 'ftp' | 'azblob' | 'local' | 'huggingface';
+// OSS 签名版本：V1（HMAC-SHA1，默认）或 V4（HMAC-SHA256，需要区域）
+export type OSSSignatureVersion = 'v1' | 'v4';
+// S3 寻址风格：路径风格（MinIO、Ceph RGW 等）或虚拟主机风格（AWS S3）
+export type S3AddressingStyle = 'path' | 'virtual';
+// SFTP 主机密钥策略：strict 只接受 known_hosts 中已有的密钥，accept_new 首次连接时记录新主机的密钥
+export type SFTPHostKeyPolicy = 'strict' | 'accept_new';
+// FTP 传输加密方式，对应地址协议 ftp://（明文）、ftpes://（显式 TLS）、ftps://（隐式 TLS）
+export type FTPTlsMode = 'none' | 'explicit' | 'implicit';
 export interface StorageClient {
   connect(config: ConnectionConfig): Promise<boolean>;
   disconnect(): void;
@@ -25,11 +33,31 @@ export interface ConnectionConfig {
   bucket?: string;  // OSS bucket 名称
   region?: string;  // OSS 区域
   endpoint?: string; // OSS 端点地址（可选，通常从 url 解析）
+  sessionToken?: string; // STS 临时凭证的安全令牌
+  signatureVersion?: OSSSignatureVersion;
+  // S3 特定配置（bucket、region、endpoint 与 OSS 共用）
+  addressingStyle?: S3AddressingStyle; // 未指定时由后端根据端点选择
+  // SFTP 特定配置（url 为 sftp://user@host:port/path，password 为登录密码）
+  privateKey?: string; // 私钥文件路径或私钥内容
+  privateKeyPassphrase?: string;
+  knownHosts?: string; // known_hosts 文件路径，默认 ~/.ssh/known_hosts
+  hostKeyPolicy?: SFTPHostKeyPolicy;
+  // FTP 特定配置（url 为 ftp://、ftpes:// 或 ftps:// 地址，未填写用户名时匿名登录）
+  acceptInvalidCerts?: boolean; // 不校验服务器证书（自签名证书）
+  // Azure Blob 特定配置（url 为端点，bucket 为容器，username 为账户名，password 为账户密钥）
+  sasToken?: string; // 未填写账户密钥时使用的 SAS 令牌
   // HuggingFace 特定配置
   apiToken?: string; // HF API token for private datasets
   organization?: string; // 组织名称 (可选)
 }
 
+// 访问凭证，用于替换即将过期的临时凭证
+export interface StorageCredentials {
+  accessKey: string;
+  secretKey: string;
+  sessionToken?: string;
+}
+
 // 统一的分页选项
 export interface ListOptions {
   pageSize?: number;        // 每页大小（OSS 使用，WebDAV 忽略）
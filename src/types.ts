@@ -76,6 +76,17 @@ interface AnalysisStatus {
   Failed?: { error: string };
 }
 
+// 流式分析过程中按批次发送的进度（archive-analysis-progress 事件）
+export interface ArchiveAnalysisProgressEvent {
+  file_path: string;
+  progress: {
+    entries: ArchiveEntry[];
+    bytes_read: number;
+    total_bytes: number;
+    analysis_status: AnalysisStatus;
+  };
+}
+
 export interface FilePreview {
   content: Uint8Array;
   is_truncated: boolean;
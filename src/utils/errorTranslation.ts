@@ -0,0 +1,39 @@
+type TranslateFunction = (key: string, options?: Record<string, unknown>) => string;
+
+// 后端错误键后可以用 `:` 连接 JSON 参数，例如 `archive.limit.entries:{"value":1000001,"limit":1000000}`
+const ERROR_KEY_PATTERN = /^([a-zA-Z][a-zA-Z0-9._]*\.[a-zA-Z0-9._]+)(?::(\{.*\}))?$/;
+
+const translateKey = (match: RegExpMatchArray, t: TranslateFunction): string => {
+  const [, key, params] = match;
+  if (!params) {
+    return t(key);
+  }
+  try {
+    return t(key, JSON.parse(params));
+  } catch {
+    return t(key);
+  }
+};
+
+/**
+ * 翻译后端返回的错误信息
+ * 错误信息是翻译键（可带 JSON 参数）时返回翻译结果，否则返回原始错误信息。
+ * 存储命令会在错误前加上上下文（如 `Request failed: storage.credentials.expired`），
+ * 此时只有最后一段是已有的翻译键才翻译，避免把 `File not found: data.csv` 之类的信息截断
+ */
+export const translateBackendError = (error: string, t: TranslateFunction): string => {
+  const match = error.match(ERROR_KEY_PATTERN);
+  if (match) {
+    return translateKey(match, t);
+  }
+
+  const contextEnd = error.lastIndexOf(': ');
+  const suffixMatch = contextEnd >= 0 ? error.slice(contextEnd + 2).match(ERROR_KEY_PATTERN) : null;
+  if (suffixMatch) {
+    const translated = translateKey(suffixMatch, t);
+    if (translated !== suffixMatch[1]) {
+      return translated;
+    }
+  }
+  return error;
+};